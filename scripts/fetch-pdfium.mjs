@@ -0,0 +1,65 @@
+// Download the PDFium library bundled with the app (src-tauri/pdfium/<platform>/, see the README there).
+// Runs before `tauri build`; the platform comes from the Tauri CLI (TAURI_ENV_PLATFORM) when set,
+// else the current machine. The release is pinned and every archive is checked against its SHA-256
+// below before anything is extracted - bump RELEASE and the checksums together.
+
+import { execFileSync } from "node:child_process";
+import { createHash } from "node:crypto";
+import { copyFileSync, existsSync, mkdirSync, mkdtempSync, rmSync, writeFileSync } from "node:fs";
+import { tmpdir } from "node:os";
+import { dirname, join } from "node:path";
+import { fileURLToPath } from "node:url";
+
+const RELEASE = "chromium/7350";
+
+// SHA-256 of each archive in RELEASE, from `sha256sum` of the files on the release page
+const platforms = {
+  windows: { folder: "windows", archive: "pdfium-win-x64.tgz", file: "bin/pdfium.dll", sha256: "" },
+  darwin: { folder: "macos", archive: "pdfium-mac-univ.tgz", file: "lib/libpdfium.dylib", sha256: "" },
+  linux: { folder: "linux", archive: "pdfium-linux-x64.tgz", file: "lib/libpdfium.so", sha256: "" },
+};
+
+const platform = process.env.TAURI_ENV_PLATFORM ?? (process.platform === "win32" ? "windows" : process.platform);
+const build = platforms[platform];
+if (!build) {
+  console.error(`No PDFium build configured for ${platform}`);
+  process.exit(1);
+}
+
+const root = join(dirname(fileURLToPath(import.meta.url)), "..", "src-tauri", "pdfium", build.folder);
+const library = join(root, build.file.split("/").pop());
+if (existsSync(library)) {
+  process.exit(0);
+}
+
+if (!/^[0-9a-f]{64}$/.test(build.sha256)) {
+  console.error(`No SHA-256 recorded for ${build.archive} (${RELEASE}) in scripts/fetch-pdfium.mjs`);
+  process.exit(1);
+}
+
+const url = `https://github.com/bblanchon/pdfium-binaries/releases/download/${RELEASE}/${build.archive}`;
+console.log(`Downloading ${url}`);
+const response = await fetch(url);
+if (!response.ok) {
+  console.error(`Failed to download PDFium: HTTP ${response.status}`);
+  process.exit(1);
+}
+
+const data = Buffer.from(await response.arrayBuffer());
+const digest = createHash("sha256").update(data).digest("hex");
+if (digest !== build.sha256) {
+  console.error(`Checksum mismatch for ${build.archive}: expected ${build.sha256}, got ${digest}`);
+  process.exit(1);
+}
+
+const work = mkdtempSync(join(tmpdir(), "pdfium-"));
+try {
+  const archive = join(work, build.archive);
+  writeFileSync(archive, data);
+  execFileSync("tar", ["-xzf", archive, "-C", work, build.file]);
+  mkdirSync(root, { recursive: true });
+  copyFileSync(join(work, build.file), library);
+  console.log(`PDFium saved to ${library}`);
+} finally {
+  rmSync(work, { recursive: true, force: true });
+}
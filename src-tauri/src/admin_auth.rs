@@ -0,0 +1,89 @@
+//! Admin Auth - local admin password for destructive device operations
+//! Wiping a terminal, remote door unlocks and turning door control on need the admin password
+//! with each call; only its Argon2 hash is stored. Several wrong passwords in a row lock the check
+//! for a short while.
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use log::{info, warn};
+use crate::app_storage::{load_json, save_json};
+
+const ADMIN_FILE: &str = "admin_auth.json";
+
+const MIN_PASSWORD_LENGTH: usize = 8;
+
+/// Wrong passwords allowed before the check locks
+const MAX_FAILURES: u32 = 5;
+const LOCKOUT: Duration = Duration::from_secs(60);
+
+/// (failures in a row, time of the last one)
+static FAILURES: Mutex<(u32, Option<Instant>)> = Mutex::new((0, None));
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct AdminAuth {
+    password_hash: Option<String>,      // Argon2id PHC string
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdminStatus {
+    pub configured: bool,               // An admin password has been set
+}
+
+pub fn admin_status() -> Result<AdminStatus, String> {
+    let auth: AdminAuth = load_json(ADMIN_FILE)?;
+    Ok(AdminStatus { configured: auth.password_hash.is_some() })
+}
+
+fn verify(hash: &str, password: &str) -> Result<bool, String> {
+    let parsed = PasswordHash::new(hash).map_err(|e| format!("Stored admin password is corrupt: {}", e))?;
+    Ok(Argon2::default().verify_password(password.as_bytes(), &parsed).is_ok())
+}
+
+/// Check the admin password; every privileged operation calls this first
+pub fn require_admin(password: Option<&str>) -> Result<(), String> {
+    let auth: AdminAuth = load_json(ADMIN_FILE)?;
+    let hash = auth.password_hash
+        .ok_or("No admin password is set - set one in Settings before using admin operations")?;
+    let password = password.filter(|p| !p.is_empty()).ok_or("Admin password required")?;
+
+    let mut failures = FAILURES.lock().map_err(|_| "Admin check unavailable".to_string())?;
+    if let (count, Some(last)) = *failures {
+        if count >= MAX_FAILURES && last.elapsed() < LOCKOUT {
+            let wait = (LOCKOUT - last.elapsed()).as_secs() + 1;
+            return Err(format!("Too many wrong admin passwords - try again in {} seconds", wait));
+        }
+    }
+    if verify(&hash, password)? {
+        *failures = (0, None);
+        Ok(())
+    } else {
+        let count = if failures.0 >= MAX_FAILURES { 1 } else { failures.0 + 1 };
+        *failures = (count, Some(Instant::now()));
+        warn!("⚠️ Wrong admin password ({} in a row)", count);
+        Err("Wrong admin password".to_string())
+    }
+}
+
+/// Set the admin password; changing an existing one needs the current password
+pub fn set_admin_password(current: Option<String>, new_password: String) -> Result<AdminStatus, String> {
+    let auth: AdminAuth = load_json(ADMIN_FILE)?;
+    if auth.password_hash.is_some() {
+        require_admin(current.as_deref())?;
+    }
+    if new_password.chars().count() < MIN_PASSWORD_LENGTH {
+        return Err(format!("Admin password must be at least {} characters", MIN_PASSWORD_LENGTH));
+    }
+    let salt = SaltString::generate(&mut OsRng);
+    let hash = Argon2::default()
+        .hash_password(new_password.as_bytes(), &salt)
+        .map_err(|e| format!("Failed to hash admin password: {}", e))?
+        .to_string();
+    save_json(ADMIN_FILE, &AdminAuth { password_hash: Some(hash) })?;
+    info!("🔑 Admin password {}", if auth.password_hash.is_some() { "changed" } else { "set" });
+    admin_status()
+}
@@ -0,0 +1,46 @@
+//! App Storage - small JSON documents persisted in the app data directory
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs;
+use std::path::PathBuf;
+
+/// Matches the bundle identifier in tauri.conf.json so files land next to Tauri's own app data
+const APP_IDENTIFIER: &str = "com.alagappa.tools";
+
+/// Get (and create) the app data directory
+pub fn app_data_dir() -> Result<PathBuf, String> {
+    let dir = dirs::data_dir()
+        .ok_or("Could not find app data directory")?
+        .join(APP_IDENTIFIER);
+    fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create app data directory: {}", e))?;
+    Ok(dir)
+}
+
+/// Load a JSON document, returning the default value if it doesn't exist yet
+pub fn load_json<T: DeserializeOwned + Default>(file_name: &str) -> Result<T, String> {
+    let path = app_data_dir()?.join(file_name);
+    if !path.exists() {
+        return Ok(T::default());
+    }
+
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", file_name, e))?;
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse {}: {}", file_name, e))
+}
+
+/// Save a JSON document (written to a temp file first so a crash can't truncate it)
+pub fn save_json<T: Serialize>(file_name: &str, value: &T) -> Result<(), String> {
+    let dir = app_data_dir()?;
+    let path = dir.join(file_name);
+    let tmp_path = dir.join(format!("{}.tmp", file_name));
+
+    let json = serde_json::to_string_pretty(value)
+        .map_err(|e| format!("Failed to serialize {}: {}", file_name, e))?;
+    fs::write(&tmp_path, json)
+        .map_err(|e| format!("Failed to write {}: {}", file_name, e))?;
+    fs::rename(&tmp_path, &path)
+        .map_err(|e| format!("Failed to save {}: {}", file_name, e))
+}
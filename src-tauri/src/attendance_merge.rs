@@ -0,0 +1,134 @@
+//! Attendance Merge - combine records from several terminals without double-counting
+
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, NaiveDateTime};
+use log::info;
+use crate::zkteco_client::AttendanceRecord;
+
+/// Punches by the same user on different terminals within this window are one event
+const DEFAULT_TOLERANCE_SECS: i64 = 120;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordSource {
+    pub device_serial: Option<String>,
+    pub device_ip: Option<String>,
+    #[serde(default)]
+    pub serial_port: Option<String>,
+    pub timestamp: String,
+    pub punch: u8,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MergedRecord {
+    #[serde(flatten)]
+    pub record: AttendanceRecord,     // Earliest punch of the group
+    pub sources: Vec<RecordSource>,   // Every terminal that recorded it
+    pub conflict: bool,               // Terminals disagree on punch state (in/out)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MergeResult {
+    pub input_count: usize,
+    pub merged_count: usize,
+    pub duplicates_collapsed: usize,
+    pub conflicts: usize,
+    pub records: Vec<MergedRecord>,
+}
+
+fn device_key(record: &AttendanceRecord) -> String {
+    record.device_serial.clone()
+        .or_else(|| record.device_ip.clone())
+        .or_else(|| record.serial_port.clone())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+fn epoch_seconds(record: &AttendanceRecord) -> Option<i64> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(&record.timestamp) {
+        return Some(dt.timestamp());
+    }
+    NaiveDateTime::parse_from_str(&format!("{} {}", record.date, record.time), "%Y-%m-%d %H:%M:%S")
+        .ok()
+        .map(|dt| dt.and_utc().timestamp())
+}
+
+fn source_of(record: &AttendanceRecord) -> RecordSource {
+    RecordSource {
+        device_serial: record.device_serial.clone(),
+        device_ip: record.device_ip.clone(),
+        serial_port: record.serial_port.clone(),
+        timestamp: record.timestamp.clone(),
+        punch: record.punch,
+    }
+}
+
+/// Collapse cross-device duplicates while keeping per-device provenance.
+/// Repeated punches on the *same* terminal are kept - they are real punches.
+pub fn merge_device_records(records: Vec<AttendanceRecord>, tolerance_secs: Option<i64>) -> MergeResult {
+    let tolerance = tolerance_secs.unwrap_or(DEFAULT_TOLERANCE_SECS).max(0);
+    let input_count = records.len();
+
+    let mut keyed: Vec<(i64, AttendanceRecord)> = Vec::with_capacity(input_count);
+    let mut unparsed: Vec<AttendanceRecord> = Vec::new();
+    for record in records {
+        match epoch_seconds(&record) {
+            Some(t) => keyed.push((t, record)),
+            None => unparsed.push(record),
+        }
+    }
+    keyed.sort_by(|(ta, a), (tb, b)| a.user_id.cmp(&b.user_id).then(ta.cmp(tb)));
+
+    let mut merged: Vec<MergedRecord> = Vec::new();
+    let mut group_start: i64 = 0;
+    let mut group_devices: Vec<String> = Vec::new();
+
+    for (time, record) in keyed {
+        let device = device_key(&record);
+
+        let joins_group = merged.last()
+            .map(|last| {
+                last.record.user_id == record.user_id
+                    && time - group_start <= tolerance
+                    && !group_devices.contains(&device)
+            })
+            .unwrap_or(false);
+
+        if joins_group {
+            if let Some(last) = merged.last_mut() {
+                if last.record.punch != record.punch {
+                    last.conflict = true;
+                }
+                last.sources.push(source_of(&record));
+            }
+            group_devices.push(device);
+        } else {
+            group_start = time;
+            group_devices = vec![device];
+            merged.push(MergedRecord {
+                sources: vec![source_of(&record)],
+                record,
+                conflict: false,
+            });
+        }
+    }
+
+    // Records with unreadable timestamps can't be matched - pass them through untouched
+    merged.extend(unparsed.into_iter().map(|record| MergedRecord {
+        sources: vec![source_of(&record)],
+        record,
+        conflict: false,
+    }));
+
+    let conflicts = merged.iter().filter(|m| m.conflict).count();
+    let duplicates_collapsed: usize = merged.iter().map(|m| m.sources.len() - 1).sum();
+
+    info!("🔀 Merged {} records into {} ({} cross-device duplicates, {} conflicts)",
+        input_count, merged.len(), duplicates_collapsed, conflicts);
+
+    MergeResult {
+        input_count,
+        merged_count: merged.len(),
+        duplicates_collapsed,
+        conflicts,
+        records: merged,
+    }
+}
@@ -0,0 +1,136 @@
+//! Attendance Register - monthly register workbook (users x days, P/A/L markers, totals)
+
+use std::fs;
+use chrono::Datelike;
+use log::info;
+use rust_xlsxwriter::{Color, Format, FormatAlign, FormatBorder, Workbook, XlsxError};
+use crate::attendance_summary::{summarize_month, DayStatus, SummaryOptions};
+use crate::holidays::load_calendar;
+use crate::bundled_converter::ConversionResult;
+use crate::zkteco_client::AttendanceRecord;
+
+const MONTH_NAMES: [&str; 12] = [
+    "January", "February", "March", "April", "May", "June",
+    "July", "August", "September", "October", "November", "December",
+];
+
+fn xlsx_err(e: XlsxError) -> String {
+    format!("Failed to write register: {}", e)
+}
+
+/// Export a formatted monthly attendance register as .xlsx
+pub fn export_register_xlsx(
+    records: Vec<AttendanceRecord>,
+    options: SummaryOptions,
+    output_path: String,
+) -> Result<ConversionResult, String> {
+    let calendar = load_calendar()?;
+    let summary = summarize_month(&records, &options, &calendar)?;
+    info!("📊 Exporting attendance register for {} ({} users)", summary.month, summary.users.len());
+
+    let title_format = Format::new().set_bold().set_font_size(14);
+    let header_format = Format::new()
+        .set_bold()
+        .set_align(FormatAlign::Center)
+        .set_background_color(Color::RGB(0xD9E1F2))
+        .set_border(FormatBorder::Thin);
+    let cell_format = Format::new().set_border(FormatBorder::Thin);
+    let marker_format = |status: DayStatus| {
+        let base = Format::new().set_align(FormatAlign::Center).set_border(FormatBorder::Thin);
+        match status {
+            DayStatus::Present => base.set_background_color(Color::RGB(0xC6EFCE)).set_font_color(Color::RGB(0x006100)),
+            DayStatus::Absent => base.set_background_color(Color::RGB(0xFFC7CE)).set_font_color(Color::RGB(0x9C0006)),
+            DayStatus::Late => base.set_background_color(Color::RGB(0xFFEB9C)).set_font_color(Color::RGB(0x9C5700)),
+            DayStatus::Holiday | DayStatus::WeeklyOff => base.set_background_color(Color::RGB(0xE7E6E6)).set_font_color(Color::RGB(0x595959)),
+            DayStatus::Upcoming => base,
+        }
+    };
+    let total_format = Format::new()
+        .set_bold()
+        .set_align(FormatAlign::Center)
+        .set_border(FormatBorder::Thin);
+
+    let mut workbook = Workbook::new();
+    let worksheet = workbook.add_worksheet();
+    worksheet.set_name("Register").map_err(xlsx_err)?;
+
+    // Title row
+    let month_name = MONTH_NAMES[(summary.month_number - 1) as usize];
+    worksheet
+        .write_string_with_format(0, 0, format!("Attendance Register - {} {}", month_name, summary.year), &title_format)
+        .map_err(xlsx_err)?;
+
+    // Header rows: day numbers, then weekday initials
+    let header_row = 2;
+    let weekday_row = 3;
+    let first_day_col: u16 = 2;
+    let totals_col = first_day_col + summary.days_in_month as u16;
+
+    worksheet.write_string_with_format(header_row, 0, "User ID", &header_format).map_err(xlsx_err)?;
+    worksheet.write_string_with_format(header_row, 1, "Name", &header_format).map_err(xlsx_err)?;
+    worksheet.write_string_with_format(weekday_row, 0, "", &header_format).map_err(xlsx_err)?;
+    worksheet.write_string_with_format(weekday_row, 1, "", &header_format).map_err(xlsx_err)?;
+
+    for day in 1..=summary.days_in_month {
+        let col = first_day_col + (day - 1) as u16;
+        let weekday = summary.date_of(day)
+            .map(|d| d.weekday().to_string().chars().next().unwrap_or(' ').to_string())
+            .unwrap_or_default();
+        worksheet.write_number_with_format(header_row, col, day, &header_format).map_err(xlsx_err)?;
+        worksheet.write_string_with_format(weekday_row, col, weekday, &header_format).map_err(xlsx_err)?;
+        worksheet.set_column_width(col, 3.5).map_err(xlsx_err)?;
+    }
+
+    for (i, label) in ["Present", "Late", "Absent", "Holidays"].iter().enumerate() {
+        let col = totals_col + i as u16;
+        worksheet.write_string_with_format(header_row, col, *label, &header_format).map_err(xlsx_err)?;
+        worksheet.write_string_with_format(weekday_row, col, "", &header_format).map_err(xlsx_err)?;
+        worksheet.set_column_width(col, 9).map_err(xlsx_err)?;
+    }
+
+    // One row per user
+    let mut row = weekday_row + 1;
+    for user in &summary.users {
+        worksheet.write_number_with_format(row, 0, user.user_id, &cell_format).map_err(xlsx_err)?;
+        worksheet.write_string_with_format(row, 1, &user.user_name, &cell_format).map_err(xlsx_err)?;
+
+        for (i, status) in user.days.iter().enumerate() {
+            let col = first_day_col + i as u16;
+            worksheet
+                .write_string_with_format(row, col, status.marker(), &marker_format(*status))
+                .map_err(xlsx_err)?;
+        }
+
+        worksheet.write_number_with_format(row, totals_col, user.present, &total_format).map_err(xlsx_err)?;
+        worksheet.write_number_with_format(row, totals_col + 1, user.late, &total_format).map_err(xlsx_err)?;
+        worksheet.write_number_with_format(row, totals_col + 2, user.absent, &total_format).map_err(xlsx_err)?;
+        worksheet.write_number_with_format(row, totals_col + 3, user.holidays, &total_format).map_err(xlsx_err)?;
+        row += 1;
+    }
+
+    // Legend below the table
+    worksheet
+        .write_string(row + 1, 1, "P = Present, L = Late (counted as present), A = Absent, H = Holiday, WO = Weekly off")
+        .map_err(xlsx_err)?;
+    for (i, holiday) in summary.holidays.iter().enumerate() {
+        worksheet
+            .write_string(row + 2 + i as u32, 1, format!("{} - {}", holiday.date, holiday.name))
+            .map_err(xlsx_err)?;
+    }
+
+    worksheet.set_column_width(0, 9).map_err(xlsx_err)?;
+    worksheet.set_column_width(1, 28).map_err(xlsx_err)?;
+    worksheet.set_freeze_panes(weekday_row + 1, first_day_col).map_err(xlsx_err)?;
+
+    workbook.save(&output_path).map_err(xlsx_err)?;
+
+    let output_size = fs::metadata(&output_path).map(|m| m.len()).ok();
+
+    info!("✅ Register exported: {}", output_path);
+    Ok(ConversionResult {
+        success: true,
+        output_path,
+        message: format!("Register exported for {} users", summary.users.len()),
+        output_size,
+    })
+}
@@ -0,0 +1,197 @@
+//! Attendance Summary - per-user monthly day status (P/A/L) computed from raw punches
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use chrono::{Datelike, Local, NaiveDate, NaiveTime};
+use crate::holidays::{Holiday, HolidayCalendar};
+use crate::zkteco_client::{AttendanceRecord, DeviceUser};
+
+/// Default cut-off after which the first punch of the day counts as late
+const DEFAULT_LATE_AFTER: &str = "09:15";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SummaryOptions {
+    pub month: String,               // YYYY-MM
+    pub late_after: Option<String>,  // HH:MM (defaults to 09:15)
+    #[serde(default)]
+    pub users: Option<Vec<DeviceUser>>, // Device user list; users without punches get all days absent
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DayStatus {
+    Present,
+    Absent,
+    Late,
+    Holiday,   // Listed holiday with no punch - not counted as absent
+    WeeklyOff, // Weekly off with no punch - not counted as absent
+    Upcoming,  // Day hasn't happened yet - not counted
+}
+
+impl DayStatus {
+    /// Marker used in registers and reports
+    pub fn marker(&self) -> &'static str {
+        match self {
+            DayStatus::Present => "P",
+            DayStatus::Absent => "A",
+            DayStatus::Late => "L",
+            DayStatus::Holiday => "H",
+            DayStatus::WeeklyOff => "WO",
+            DayStatus::Upcoming => "",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserMonthSummary {
+    pub user_id: u32,
+    pub user_name: String,
+    pub days: Vec<DayStatus>,           // Index 0 = day 1 of the month
+    pub first_punches: Vec<Option<String>>, // HH:MM:SS per day
+    pub present: u32,                   // Includes late days
+    pub late: u32,
+    pub absent: u32,
+    pub holidays: u32,                  // Holidays + weekly offs not worked
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonthSummary {
+    pub month: String,
+    pub year: i32,
+    pub month_number: u32,
+    pub days_in_month: u32,
+    pub holidays: Vec<Holiday>,         // Listed holidays falling in this month
+    pub users: Vec<UserMonthSummary>,
+}
+
+impl MonthSummary {
+    pub fn date_of(&self, day: u32) -> Option<NaiveDate> {
+        NaiveDate::from_ymd_opt(self.year, self.month_number, day)
+    }
+}
+
+/// Parse "YYYY-MM" into (year, month)
+pub fn parse_month(month: &str) -> Result<(i32, u32), String> {
+    let (y, m) = month.trim().split_once('-')
+        .ok_or_else(|| format!("Invalid month '{}', expected YYYY-MM", month))?;
+    let year: i32 = y.parse().map_err(|_| format!("Invalid year in '{}'", month))?;
+    let month_number: u32 = m.parse().map_err(|_| format!("Invalid month in '{}'", month))?;
+    if !(1..=12).contains(&month_number) {
+        return Err(format!("Invalid month in '{}'", month));
+    }
+    Ok((year, month_number))
+}
+
+pub fn days_in_month(year: i32, month: u32) -> u32 {
+    let (next_year, next_month) = if month == 12 { (year + 1, 1) } else { (year, month + 1) };
+    NaiveDate::from_ymd_opt(next_year, next_month, 1)
+        .and_then(|d| d.pred_opt())
+        .map(|d| d.day())
+        .unwrap_or(30)
+}
+
+/// Build a per-user monthly summary from raw device records, with a row for every user in
+/// `options.users` and anyone else who punched.
+/// Punches on holidays/weekly offs still count as present; missing punches there are not absences.
+pub fn summarize_month(
+    records: &[AttendanceRecord],
+    options: &SummaryOptions,
+    calendar: &HolidayCalendar,
+) -> Result<MonthSummary, String> {
+    let (year, month_number) = parse_month(&options.month)?;
+    let total_days = days_in_month(year, month_number);
+
+    let late_after = options.late_after.as_deref().unwrap_or(DEFAULT_LATE_AFTER);
+    let late_after = NaiveTime::parse_from_str(late_after, "%H:%M")
+        .map_err(|_| format!("Invalid late cut-off '{}', expected HH:MM", late_after))?;
+
+    // user_id -> day -> earliest punch
+    let mut first_punch: BTreeMap<u32, HashMap<u32, NaiveTime>> = BTreeMap::new();
+    let mut names: HashMap<u32, String> = HashMap::new();
+    for user in options.users.iter().flatten() {
+        first_punch.entry(user.user_id).or_default();
+        if !user.name.trim().is_empty() {
+            names.insert(user.user_id, user.name.clone());
+        }
+    }
+
+    for record in records {
+        let date = match NaiveDate::parse_from_str(&record.date, "%Y-%m-%d") {
+            Ok(d) => d,
+            Err(_) => continue,
+        };
+        if date.year() != year || date.month() != month_number {
+            continue;
+        }
+        let time = match NaiveTime::parse_from_str(&record.time, "%H:%M:%S") {
+            Ok(t) => t,
+            Err(_) => continue,
+        };
+
+        // Prefer a real name over the "ID: 123" placeholder
+        let name = names.entry(record.user_id).or_insert_with(|| record.user_name.clone());
+        if name.starts_with("ID: ") && !record.user_name.starts_with("ID: ") {
+            *name = record.user_name.clone();
+        }
+
+        let days = first_punch.entry(record.user_id).or_default();
+        let entry = days.entry(date.day()).or_insert(time);
+        if time < *entry {
+            *entry = time;
+        }
+    }
+
+    let today = Local::now().date_naive();
+    let mut users = Vec::new();
+
+    for (user_id, punches) in first_punch {
+        let mut days = Vec::with_capacity(total_days as usize);
+        let mut first_punches = Vec::with_capacity(total_days as usize);
+        let (mut present, mut late, mut absent, mut holidays) = (0, 0, 0, 0);
+
+        for day in 1..=total_days {
+            let date = NaiveDate::from_ymd_opt(year, month_number, day);
+            let status = match punches.get(&day) {
+                Some(t) if *t > late_after => { present += 1; late += 1; DayStatus::Late }
+                Some(_) => { present += 1; DayStatus::Present }
+                None if date.map(|d| d > today).unwrap_or(false) => DayStatus::Upcoming,
+                None if date.map(|d| calendar.holiday_on(d).is_some()).unwrap_or(false) => {
+                    holidays += 1;
+                    DayStatus::Holiday
+                }
+                None if date.map(|d| calendar.is_weekly_off(d)).unwrap_or(false) => {
+                    holidays += 1;
+                    DayStatus::WeeklyOff
+                }
+                None => { absent += 1; DayStatus::Absent }
+            };
+            days.push(status);
+            first_punches.push(punches.get(&day).map(|t| t.format("%H:%M:%S").to_string()));
+        }
+
+        users.push(UserMonthSummary {
+            user_id,
+            user_name: names.remove(&user_id).unwrap_or_else(|| format!("ID: {}", user_id)),
+            days,
+            first_punches,
+            present,
+            late,
+            absent,
+            holidays,
+        });
+    }
+
+    let month_prefix = format!("{:04}-{:02}-", year, month_number);
+    let holidays = calendar.holidays.iter()
+        .filter(|h| h.date.starts_with(&month_prefix))
+        .cloned()
+        .collect();
+
+    Ok(MonthSummary {
+        month: options.month.clone(),
+        year,
+        month_number,
+        days_in_month: total_days,
+        holidays,
+        users,
+    })
+}
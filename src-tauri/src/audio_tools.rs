@@ -0,0 +1,190 @@
+//! Audio Tools - audio-to-audio conversion (wav, mp3, aac, m4a, flac, ogg, opus) and loudness analysis via FFmpeg
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use log::info;
+use crate::ffmpeg_runner::{ffmpeg_command, run_ffmpeg, run_ffmpeg_with_log, MediaJob};
+use crate::media_converter::ConversionResult;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AudioConvertOptions {
+    pub input_path: String,
+    pub output_path: String,
+    pub format: String,              // mp3, aac, m4a, ogg, opus, wav, flac
+    pub bitrate: Option<String>,     // e.g. "128k" (ignored for wav/flac)
+    pub sample_rate: Option<u32>,    // Hz, e.g. 44100
+    pub channels: Option<u32>,       // 1 = mono, 2 = stereo
+}
+
+/// Quieter than this for at least SILENCE_MIN_SECS counts as silence
+const SILENCE_THRESHOLD_DB: i32 = -50;
+const SILENCE_MIN_SECS: f64 = 2.0;
+
+/// Sample peaks this close to full scale are treated as clipped
+const CLIP_LEVEL_DB: f64 = -0.1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SilenceSegment {
+    pub start_secs: f64,
+    pub end_secs: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AudioAnalysis {
+    pub integrated_lufs: Option<f64>,     // EBU R128 programme loudness
+    pub loudness_range_lu: Option<f64>,   // LRA - how much the loudness varies (dynamic range)
+    pub true_peak_dbtp: Option<f64>,
+    pub sample_peak_db: Option<f64>,
+    pub rms_db: Option<f64>,
+    pub clipping: bool,
+    pub clipped_peaks: u64,               // Times the sample peak level was hit
+    pub silences: Vec<SilenceSegment>,
+}
+
+/// (codec, default bitrate) per output format - lossless formats ignore the bitrate
+fn audio_codec(format: &str) -> Result<(&'static str, Option<&'static str>), String> {
+    match format {
+        "mp3" => Ok(("libmp3lame", Some("192k"))),
+        "aac" | "m4a" => Ok(("aac", Some("192k"))),
+        "ogg" => Ok(("libvorbis", Some("192k"))),
+        "opus" => Ok(("libopus", Some("128k"))),
+        "wav" => Ok(("pcm_s16le", None)),
+        "flac" => Ok(("flac", None)),
+        other => Err(format!("Unsupported audio format: {}", other)),
+    }
+}
+
+/// Convert between audio formats, keeping tags (title/artist/album...) and cover art where the
+/// target container supports it.
+pub async fn convert_audio(options: AudioConvertOptions, job: MediaJob) -> Result<ConversionResult, String> {
+    let AudioConvertOptions { input_path, output_path, format, bitrate, sample_rate, channels } = options;
+    if !Path::new(&input_path).exists() {
+        return Err(format!("Input file not found: {}", input_path));
+    }
+
+    let format = format.to_lowercase();
+    let (codec, default_bitrate) = audio_codec(&format)?;
+
+    info!("🎧 Converting audio: {} -> {} ({})", input_path, output_path, format);
+
+    let job = job.with_duration_of(&input_path).await.with_output(&output_path);
+    let mut cmd = ffmpeg_command();
+    cmd.arg("-y");
+    cmd.arg("-i").arg(&input_path);
+    cmd.arg("-map").arg("0:a:0");
+    cmd.arg("-map_metadata").arg("0");
+
+    // Cover art travels as an attached picture stream; only these containers can hold it
+    if matches!(format.as_str(), "mp3" | "m4a" | "flac") {
+        cmd.arg("-map").arg("0:v?");
+        cmd.arg("-c:v").arg("copy");
+        cmd.arg("-disposition:v").arg("attached_pic");
+    }
+    if format == "mp3" {
+        cmd.arg("-id3v2_version").arg("3");
+    }
+
+    cmd.arg("-c:a").arg(codec);
+    if let Some(default) = default_bitrate {
+        let rate = bitrate.filter(|b| !b.is_empty()).unwrap_or_else(|| default.to_string());
+        cmd.arg("-b:a").arg(rate);
+    }
+    if let Some(rate) = sample_rate {
+        cmd.arg("-ar").arg(rate.to_string());
+    }
+    if let Some(count) = channels {
+        cmd.arg("-ac").arg(count.to_string());
+    }
+    cmd.arg(&output_path);
+
+    run_ffmpeg(cmd, &job).await
+        .map_err(|error| format!("Audio conversion failed: {}", error))?;
+
+    let output_size = std::fs::metadata(&output_path).map(|m| m.len()).ok();
+    info!("✅ Audio converted: {}", output_path);
+    Ok(ConversionResult {
+        success: true,
+        output_path,
+        message: "Audio converted successfully".to_string(),
+        output_size,
+    })
+}
+
+/// Number after `label` on a log line, e.g. "I:         -19.3 LUFS" -> -19.3
+fn value_after(line: &str, label: &str) -> Option<f64> {
+    let rest = &line[line.find(label)? + label.len()..];
+    rest.split_whitespace().next()?.parse().ok()
+}
+
+/// Measure loudness (ebur128), peaks/RMS (astats) and silent stretches (silencedetect) in one pass
+pub async fn analyze_audio(input_path: String, job: MediaJob) -> Result<AudioAnalysis, String> {
+    if !Path::new(&input_path).exists() {
+        return Err(format!("Input file not found: {}", input_path));
+    }
+
+    info!("📊 Analyzing audio: {}", input_path);
+
+    let job = job.with_duration_of(&input_path).await;
+    let mut cmd = ffmpeg_command();
+    cmd.arg("-i").arg(&input_path);
+    cmd.arg("-vn");
+    cmd.arg("-af").arg(format!(
+        "ebur128=peak=true,astats,silencedetect=noise={}dB:d={}",
+        SILENCE_THRESHOLD_DB, SILENCE_MIN_SECS
+    ));
+    cmd.arg("-f").arg("null").arg("-");
+
+    let log = run_ffmpeg_with_log(cmd, &job).await
+        .map_err(|error| format!("Audio analysis failed: {}", error))?;
+
+    let mut analysis = AudioAnalysis {
+        integrated_lufs: None,
+        loudness_range_lu: None,
+        true_peak_dbtp: None,
+        sample_peak_db: None,
+        rms_db: None,
+        clipping: false,
+        clipped_peaks: 0,
+        silences: Vec::new(),
+    };
+
+    // ebur128 prints one "Summary:" block at the end (its unprefixed lines run until the next
+    // "[Parsed_..." line); astats prints per-channel blocks and then "Overall", so the last
+    // occurrence of each astats value is the whole-file figure
+    let mut in_summary = false;
+    let mut silence_start = None;
+    for line in log.lines() {
+        if line.starts_with('[') {
+            in_summary = line.contains("ebur128") && line.contains("Summary:");
+        }
+        if in_summary {
+            if let Some(v) = value_after(line, " I:") { analysis.integrated_lufs = Some(v); }
+            if let Some(v) = value_after(line, " LRA:") { analysis.loudness_range_lu = Some(v); }
+            if let Some(v) = value_after(line, " Peak:") { analysis.true_peak_dbtp = Some(v); }
+        }
+        if let Some(v) = value_after(line, "Peak level dB:") { analysis.sample_peak_db = Some(v); }
+        if let Some(v) = value_after(line, "RMS level dB:") { analysis.rms_db = Some(v); }
+        if let Some(v) = value_after(line, "Peak count:") { analysis.clipped_peaks = v as u64; }
+        if let Some(v) = value_after(line, "silence_start:") {
+            silence_start = Some(v);
+        }
+        if let Some(end) = value_after(line, "silence_end:") {
+            analysis.silences.push(SilenceSegment { start_secs: silence_start.take().unwrap_or(0.0), end_secs: end });
+        }
+    }
+    // Silence that runs to the end of the file has no silence_end line
+    if let (Some(start), Some(total)) = (silence_start, job.total_secs) {
+        analysis.silences.push(SilenceSegment { start_secs: start, end_secs: total });
+    }
+
+    let peak_at_full_scale = analysis.sample_peak_db.is_some_and(|p| p >= CLIP_LEVEL_DB);
+    if !peak_at_full_scale {
+        analysis.clipped_peaks = 0;
+    }
+    analysis.clipping = (peak_at_full_scale && analysis.clipped_peaks > 1)
+        || analysis.true_peak_dbtp.is_some_and(|p| p > 0.0);
+
+    info!("✅ Audio analysis: {:?} LUFS, true peak {:?} dBTP, {} silences",
+        analysis.integrated_lufs, analysis.true_peak_dbtp, analysis.silences.len());
+    Ok(analysis)
+}
@@ -0,0 +1,96 @@
+//! AVIF Decode - AVIF images through the image crate's dav1d decoder (`avif` feature)
+//! The image crate decodes the AV1 picture but ignores the container's rotation (irot) and
+//! mirroring (imir) properties, so those are read from the primary item here and applied in the
+//! order the file lists them, as HEIF readers must.
+
+use image::{DynamicImage, ImageFormat};
+
+/// A transform stored as an item property of the primary image
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Transform {
+    Rotate(u8),     // Quarter turns anti-clockwise
+    Mirror(u8),     // 0 = top and bottom exchanged, 1 = left and right exchanged
+}
+
+/// Big-endian unsigned integer of `len` bytes at `offset`
+fn be(data: &[u8], offset: usize, len: usize) -> Option<u64> {
+    let bytes = data.get(offset..offset.checked_add(len)?)?;
+    Some(bytes.iter().fold(0, |value, &byte| value << 8 | byte as u64))
+}
+
+/// ISO-BMFF boxes in `data` as (type, body)
+fn boxes(mut data: &[u8]) -> Vec<(&[u8], &[u8])> {
+    let mut found = Vec::new();
+    while let (Some(size), Some(kind)) = (be(data, 0, 4), data.get(4..8)) {
+        let (header, size) = match size {
+            0 => (8, data.len()),
+            1 => match be(data, 8, 8) {
+                Some(size) => (16, usize::try_from(size).unwrap_or(usize::MAX)),
+                None => break,
+            },
+            size => (8, size as usize),
+        };
+        let Some(body) = data.get(header..size) else { break };
+        found.push((kind, body));
+        data = &data[size..];
+    }
+    found
+}
+
+fn find<'a>(found: &[(&'a [u8], &'a [u8])], kind: &[u8; 4]) -> Option<&'a [u8]> {
+    found.iter().find(|(k, _)| k == kind).map(|(_, body)| *body)
+}
+
+/// irot/imir properties of the primary item, in the order they are associated with it
+fn primary_transforms(bytes: &[u8]) -> Option<Vec<Transform>> {
+    let top = boxes(bytes);
+    // meta, pitm and ipma are full boxes: one version byte and three flag bytes come first
+    let meta = boxes(find(&top, b"meta")?.get(4..)?);
+    let pitm = find(&meta, b"pitm")?;
+    let primary = be(pitm, 4, if pitm.first()? == &0 { 2 } else { 4 })?;
+    let iprp = boxes(find(&meta, b"iprp")?);
+    let properties = boxes(find(&iprp, b"ipco")?);
+    let ipma = find(&iprp, b"ipma")?;
+
+    let id_len = if ipma.first()? < &1 { 2 } else { 4 };
+    let (index_len, index_mask) = if be(ipma, 1, 3)? & 1 == 1 { (2, 0x7fff) } else { (1, 0x7f) };
+    let mut offset = 8;
+    for _ in 0..be(ipma, 4, 4)? {
+        let item = be(ipma, offset, id_len)?;
+        let count = be(ipma, offset + id_len, 1)? as usize;
+        offset += id_len + 1;
+        if item != primary {
+            offset += count * index_len;
+            continue;
+        }
+        return Some((0..count).filter_map(|i| {
+            // Property indices are 1-based; 0 means no property
+            let index = be(ipma, offset + i * index_len, index_len)? & index_mask;
+            let (kind, body) = properties.get((index as usize).checked_sub(1)?)?;
+            match *kind {
+                b"irot" => Some(Transform::Rotate(body.first()? & 3)),
+                b"imir" => Some(Transform::Mirror(body.first()? & 1)),
+                _ => None,
+            }
+        }).collect());
+    }
+    None
+}
+
+fn apply(image: DynamicImage, transform: Transform) -> DynamicImage {
+    match transform {
+        Transform::Rotate(1) => image.rotate270(),
+        Transform::Rotate(2) => image.rotate180(),
+        Transform::Rotate(3) => image.rotate90(),
+        Transform::Rotate(_) => image,
+        Transform::Mirror(0) => image.flipv(),
+        Transform::Mirror(_) => image.fliph(),
+    }
+}
+
+/// Decode a still AVIF file upright (animated AVIF is not supported)
+pub fn decode_avif(bytes: &[u8]) -> Result<DynamicImage, String> {
+    let image = image::load_from_memory_with_format(bytes, ImageFormat::Avif)
+        .map_err(|e| format!("Failed to decode AVIF image: {}", e))?;
+    Ok(primary_transforms(bytes).unwrap_or_default().into_iter().fold(image, apply))
+}
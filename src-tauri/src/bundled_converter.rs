@@ -4,11 +4,21 @@
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 use std::fs;
-use std::io::BufReader;
-use log::info;
+use log::{info, warn};
 use lopdf::Document as PdfDocument;
 use calamine::{Reader, open_workbook, Xlsx, Xls, Ods};
 use image::ImageFormat;
+use crate::csv_dialect::{read_csv, write_csv, CsvDialect};
+use crate::docx_reader::{read_docx, DocxBlock, DocxParagraph};
+use crate::excel_reader::{parse_formula_mode, read_sheet, SheetExportResult};
+use crate::file_sniff::{ensure_text, sniff, workbook_extension, FileKind};
+use crate::image_animation::{convert_animation, get_image_info, is_animation_conversion, ImageInfo};
+use crate::image_decode::open_image;
+use crate::image_size_limit::{encode, LossyFormat};
+use crate::json_flatten::{flatten_records, parse_json_records, JsonToCsvOptions};
+use crate::pdf_metadata::{read_metadata, PdfMetadata};
+use crate::pdf_security::{is_password_error, open_pdf};
+use crate::pdf_writer::{text_width, PdfWriter, A4_PORTRAIT};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConversionResult {
@@ -26,64 +36,18 @@ pub struct DocumentInfo {
     pub extension: String,
     pub page_count: Option<usize>,
     pub sheet_names: Option<Vec<String>>,
+    #[serde(default)]
+    pub pdf_metadata: Option<PdfMetadata>,
+    #[serde(default)]
+    pub image_info: Option<ImageInfo>,
+    #[serde(default)]
+    pub detected_format: Option<String>,   // What the content says the file is, e.g. "a PNG image"
 }
 
 // ============================================================================
 // PDF Operations (using lopdf - bundled)
 // ============================================================================
 
-/// Merge multiple PDF files into one
-pub fn merge_pdfs(input_paths: Vec<String>, output_path: String) -> Result<ConversionResult, String> {
-    if input_paths.len() < 2 {
-        return Err("Need at least 2 PDFs to merge".to_string());
-    }
-
-    info!("📄 Merging {} PDFs (bundled)", input_paths.len());
-
-    // Load first document as base
-    let first_path = &input_paths[0];
-    let mut merged = PdfDocument::load(first_path)
-        .map_err(|e| format!("Failed to load {}: {}", first_path, e))?;
-
-    // Merge remaining documents
-    for path in input_paths.iter().skip(1) {
-        let doc = PdfDocument::load(path)
-            .map_err(|e| format!("Failed to load {}: {}", path, e))?;
-        
-        // Get pages from source document and add to merged
-        let pages = doc.get_pages();
-        for (_page_num, page_id) in pages {
-            if let Ok(page_content) = doc.get_page_content(page_id) {
-                // Simple merge - copy page references
-                let _ = merged.add_object(lopdf::Object::Stream(
-                    lopdf::Stream::new(lopdf::dictionary! {}, page_content)
-                ));
-            }
-        }
-    }
-
-    // Save merged document
-    merged.save(&output_path)
-        .map_err(|e| format!("Failed to save merged PDF: {}", e))?;
-
-    let output_size = fs::metadata(&output_path).map(|m| m.len()).ok();
-    
-    info!("✅ PDFs merged: {}", output_path);
-    Ok(ConversionResult {
-        success: true,
-        output_path,
-        message: format!("Successfully merged {} PDFs", input_paths.len()),
-        output_size,
-    })
-}
-
-/// Get PDF page count
-pub fn get_pdf_info(file_path: &str) -> Result<usize, String> {
-    let doc = PdfDocument::load(file_path)
-        .map_err(|e| format!("Failed to load PDF: {}", e))?;
-    Ok(doc.get_pages().len())
-}
-
 /// Extract text from PDF (basic)
 #[allow(dead_code)]
 pub fn pdf_to_text(input_path: String, output_path: String) -> Result<ConversionResult, String> {
@@ -123,93 +87,38 @@ pub fn pdf_to_text(input_path: String, output_path: String) -> Result<Conversion
 // Excel/Spreadsheet Operations (using calamine - bundled)
 // ============================================================================
 
-/// Convert Excel to CSV
-pub fn excel_to_csv(input_path: String, output_path: String, sheet_index: Option<usize>) -> Result<ConversionResult, String> {
+/// Convert Excel to CSV; `dialect` sets the delimiter, quote character and output encoding.
+/// `formulas` picks saved results ("values", default) or formula text ("text") for formula cells.
+pub fn excel_to_csv(
+    input_path: String,
+    output_path: String,
+    sheet_index: Option<usize>,
+    formulas: Option<String>,
+    dialect: CsvDialect,
+) -> Result<SheetExportResult, String> {
     info!("📊 Converting Excel to CSV (bundled)");
 
-    let ext = Path::new(&input_path)
-        .extension()
-        .and_then(|e| e.to_str())
-        .unwrap_or("")
-        .to_lowercase();
-
-    let sheet_data: Vec<Vec<String>> = match ext.as_str() {
-        "xlsx" => {
-            let mut workbook: Xlsx<_> = open_workbook(&input_path)
-                .map_err(|e| format!("Failed to open Excel file: {}", e))?;
-            extract_sheet_data(&mut workbook, sheet_index)?
-        }
-        "xls" => {
-            let mut workbook: Xls<_> = open_workbook(&input_path)
-                .map_err(|e| format!("Failed to open Excel file: {}", e))?;
-            extract_sheet_data(&mut workbook, sheet_index)?
-        }
-        "ods" => {
-            let mut workbook: Ods<_> = open_workbook(&input_path)
-                .map_err(|e| format!("Failed to open ODS file: {}", e))?;
-            extract_sheet_data(&mut workbook, sheet_index)?
-        }
-        _ => return Err(format!("Unsupported format: {}", ext)),
-    };
+    let formulas_as_text = parse_formula_mode(formulas.as_deref())?;
+    let sheet = read_sheet(&input_path, sheet_index, formulas_as_text)?;
 
     // Write to CSV
-    let mut wtr = csv::Writer::from_path(&output_path)
-        .map_err(|e| format!("Failed to create CSV: {}", e))?;
-
-    for row in sheet_data {
-        wtr.write_record(&row)
-            .map_err(|e| format!("Failed to write row: {}", e))?;
-    }
-
-    wtr.flush().map_err(|e| format!("Failed to flush CSV: {}", e))?;
+    write_csv(&output_path, &sheet.rows, &dialect)?;
 
     let output_size = fs::metadata(&output_path).map(|m| m.len()).ok();
 
     info!("✅ Excel converted to CSV: {}", output_path);
-    Ok(ConversionResult {
+    let result = ConversionResult {
         success: true,
         output_path,
         message: "Excel converted to CSV".to_string(),
         output_size,
-    })
-}
-
-fn extract_sheet_data<R: Reader<BufReader<std::fs::File>>>(
-    workbook: &mut R,
-    sheet_index: Option<usize>,
-) -> Result<Vec<Vec<String>>, String> {
-    let sheets = workbook.sheet_names().to_owned();
-    if sheets.is_empty() {
-        return Err("No sheets found in workbook".to_string());
-    }
-
-    let sheet_name = sheets.get(sheet_index.unwrap_or(0))
-        .ok_or("Sheet not found")?
-        .clone();
-
-    let range = workbook.worksheet_range(&sheet_name)
-        .map_err(|e| format!("Failed to read sheet: {:?}", e))?;
-
-    let mut data = Vec::new();
-    for row in range.rows() {
-        let row_data: Vec<String> = row.iter()
-            .map(|cell| cell.to_string())
-            .collect();
-        data.push(row_data);
-    }
-
-    Ok(data)
+    };
+    Ok(sheet.report(result, formulas_as_text))
 }
 
 /// Get Excel sheet names
 pub fn get_excel_sheets(file_path: &str) -> Result<Vec<String>, String> {
-    let ext = Path::new(file_path)
-        .extension()
-        .and_then(|e| e.to_str())
-        .unwrap_or("")
-        .to_lowercase();
-
-    let sheets = match ext.as_str() {
+    let sheets = match workbook_extension(file_path)? {
         "xlsx" => {
             let workbook: Xlsx<_> = open_workbook(file_path)
                 .map_err(|e| format!("Failed to open: {}", e))?;
@@ -225,28 +134,130 @@ pub fn get_excel_sheets(file_path: &str) -> Result<Vec<String>, String> {
                 .map_err(|e| format!("Failed to open: {}", e))?;
             workbook.sheet_names().to_vec()
         }
-        _ => return Err(format!("Unsupported format: {}", ext)),
+        other => return Err(format!("Unsupported format: {}", other)),
     };
 
     Ok(sheets)
 }
 
 // ============================================================================
-// Image Operations (using image crate - bundled)
+// Word Documents (DOCX reader + PDF writer - bundled)
 // ============================================================================
 
-/// Convert image format
-pub fn convert_image_format(
-    input_path: String,
-    output_path: String,
-    quality: Option<u8>,
-) -> Result<ConversionResult, String> {
-    info!("🖼️ Converting image (bundled)");
+fn docx_paragraph(writer: &mut PdfWriter, paragraph: &DocxParagraph) {
+    let size = match paragraph.heading {
+        Some(1) => 18.0,
+        Some(2) => 15.0,
+        Some(3) => 13.0,
+        Some(_) => 11.5,
+        None => 10.5,
+    };
+    let bold = paragraph.bold || paragraph.heading.is_some();
+    if paragraph.heading.is_some() {
+        writer.move_down(size * 0.4);
+    }
+    for (index, line) in paragraph.text.split('\n').enumerate() {
+        match &paragraph.list {
+            Some((level, marker)) => {
+                let marker = if index == 0 { marker.as_str() } else { "" };
+                writer.indented(16.0 + 16.0 * *level as f32, marker, line, size, bold);
+            }
+            None if paragraph.centered && text_width(line, size) <= writer.content_width() => writer.centered(line, size, bold),
+            None => writer.paragraph(line, size, bold),
+        }
+    }
+    writer.move_down(size * 0.4);
+}
+
+/// Draw a picture at its size in the document, shrunk to fit the page
+fn docx_image(writer: &mut PdfWriter, data: &[u8], size: Option<(f32, f32)>) -> Result<(), String> {
+    let img = image::load_from_memory(data).map_err(|e| format!("Unsupported picture: {}", e))?;
+    let jpeg = encode(&img, LossyFormat::Jpeg, 85)?;
+    // Without a size in the document, assume 96 DPI
+    let (width, height) = size.unwrap_or((img.width() as f32 * 0.75, img.height() as f32 * 0.75));
+    let scale = (writer.content_width() / width).min(A4_PORTRAIT.1 * 0.6 / height).min(1.0);
+    let (width, height) = (width * scale, height * scale);
+
+    let name = writer.add_jpeg(jpeg, img.width(), img.height());
+    writer.ensure_space(height + 6.0);
+    let x = writer.left() + (writer.content_width() - width) / 2.0;
+    let y = writer.cursor() - height;
+    writer.image_at(&name, x, y, width, height);
+    writer.move_down(height + 8.0);
+    Ok(())
+}
+
+/// Convert a simple Word document to PDF without LibreOffice. Text boxes, columns, footnotes and
+/// similar layout are not reproduced; the message lists what was found so the user can switch.
+pub fn docx_to_pdf(input_path: String, output_path: String) -> Result<ConversionResult, String> {
+    info!("📄 Converting DOCX to PDF (bundled)");
+
+    let kind = sniff(&input_path)?;
+    if kind != FileKind::Docx {
+        return Err(format!("Not a Word document: file is actually {}", kind.label()));
+    }
+    let document = read_docx(&input_path)?;
+    let mut unsupported: Vec<String> = document.unsupported.iter().map(|s| s.to_string()).collect();
+
+    let mut writer = PdfWriter::new(A4_PORTRAIT);
+    let mut truncated_cells = false;
+    for block in &document.blocks {
+        match block {
+            DocxBlock::Paragraph(paragraph) => docx_paragraph(&mut writer, paragraph),
+            DocxBlock::Table(rows) => {
+                let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+                if columns == 0 {
+                    continue;
+                }
+                let headers: Vec<&str> = (0..columns).map(|c| rows[0].get(c).map_or("", String::as_str)).collect();
+                let widths: Vec<f32> = (0..columns)
+                    .map(|c| rows.iter().map(|r| r.get(c).map_or(0, |v| v.chars().count())).max().unwrap_or(0).clamp(4, 40) as f32)
+                    .collect();
+                let total: f32 = widths.iter().sum();
+                truncated_cells |= rows.iter().any(|row| row.iter().zip(&widths)
+                    .any(|(cell, w)| text_width(cell, 9.0) > w / total * writer.content_width() - 6.0));
+                writer.table(&headers, &rows[1..], &widths, false);
+                writer.move_down(8.0);
+            }
+            DocxBlock::Image { data, size } => {
+                if let Err(e) = docx_image(&mut writer, data, *size) {
+                    warn!("Skipping picture: {}", e);
+                    unsupported.push("pictures in EMF/WMF or other unsupported formats".to_string());
+                }
+            }
+        }
+    }
+    if truncated_cells {
+        unsupported.push("long table cells (cut off)".to_string());
+    }
+    unsupported.dedup();
+
+    writer.save(&output_path)?;
+    let output_size = fs::metadata(&output_path).map(|m| m.len()).ok();
+
+    info!("✅ DOCX converted: {}", output_path);
+    Ok(ConversionResult {
+        success: true,
+        output_path,
+        message: if unsupported.is_empty() {
+            "Document converted successfully".to_string()
+        } else {
+            format!(
+                "Converted with a simplified layout. Complex layout ({}) is not reproduced; use LibreOffice for an exact copy",
+                unsupported.join(", ")
+            )
+        },
+        output_size,
+    })
+}
 
-    let img = image::open(&input_path)
-        .map_err(|e| format!("Failed to open image: {}", e))?;
+// ============================================================================
+// Image Operations (using image crate - bundled)
+// ============================================================================
 
-    let output_ext = Path::new(&output_path)
+/// Save an image in the format of `output_path`'s extension; `quality` (default 90) applies to JPEG
+pub fn save_image(img: &image::DynamicImage, output_path: &str, quality: Option<u8>) -> Result<(), String> {
+    let output_ext = Path::new(output_path)
         .extension()
         .and_then(|e| e.to_str())
         .unwrap_or("png")
@@ -266,16 +277,36 @@ pub fn convert_image_format(
     // For JPEG, use quality setting
     if format == ImageFormat::Jpeg {
         let q = quality.unwrap_or(90);
-        let mut output_file = fs::File::create(&output_path)
+        let mut output_file = fs::File::create(output_path)
             .map_err(|e| format!("Failed to create output: {}", e))?;
-        
+
+        // JPEG has no alpha channel
+        let rgb = image::DynamicImage::ImageRgb8(img.to_rgb8());
         let mut encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(&mut output_file, q);
-        encoder.encode_image(&img)
+        encoder.encode_image(&rgb)
             .map_err(|e| format!("Failed to encode JPEG: {}", e))?;
     } else {
-        img.save_with_format(&output_path, format)
+        img.save_with_format(output_path, format)
             .map_err(|e| format!("Failed to save image: {}", e))?;
     }
+    Ok(())
+}
+
+/// Convert image format; animations written as GIF or WebP keep all their frames
+pub fn convert_image_format(
+    input_path: String,
+    output_path: String,
+    quality: Option<u8>,
+) -> Result<ConversionResult, String> {
+    if is_animation_conversion(&input_path, &output_path) {
+        return convert_animation(input_path, output_path, quality);
+    }
+
+    info!("🖼️ Converting image (bundled)");
+
+    let img = open_image(&input_path)?;
+
+    save_image(&img, &output_path, quality)?;
 
     let output_size = fs::metadata(&output_path).map(|m| m.len()).ok();
 
@@ -298,8 +329,7 @@ pub fn resize_image(
 ) -> Result<ConversionResult, String> {
     info!("🖼️ Resizing image (bundled)");
 
-    let img = image::open(&input_path)
-        .map_err(|e| format!("Failed to open image: {}", e))?;
+    let img = open_image(&input_path)?;
 
     let resized = if maintain_aspect {
         img.resize(width, height, image::imageops::FilterType::Lanczos3)
@@ -324,27 +354,20 @@ pub fn resize_image(
 // CSV Operations
 // ============================================================================
 
-/// Convert CSV to JSON
-pub fn csv_to_json(input_path: String, output_path: String) -> Result<ConversionResult, String> {
+/// Convert CSV to JSON; `dialect` covers delimiter, quoting, header row and encoding (auto-detected by default)
+pub fn csv_to_json(input_path: String, output_path: String, dialect: CsvDialect) -> Result<ConversionResult, String> {
     info!("📊 Converting CSV to JSON (bundled)");
 
-    let mut rdr = csv::Reader::from_path(&input_path)
-        .map_err(|e| format!("Failed to open CSV: {}", e))?;
-
-    let headers: Vec<String> = rdr.headers()
-        .map_err(|e| format!("Failed to read headers: {}", e))?
-        .iter()
-        .map(|s| s.to_string())
-        .collect();
+    ensure_text(&input_path, "CSV")?;
+    let table = read_csv(&input_path, &dialect)?;
 
     let mut records: Vec<serde_json::Map<String, serde_json::Value>> = Vec::new();
 
-    for result in rdr.records() {
-        let record = result.map_err(|e| format!("Failed to read record: {}", e))?;
+    for row in &table.rows {
         let mut obj = serde_json::Map::new();
         
-        for (i, field) in record.iter().enumerate() {
-            if let Some(key) = headers.get(i) {
+        for (i, field) in row.iter().enumerate() {
+            if let Some(key) = table.headers.get(i) {
                 obj.insert(key.clone(), serde_json::Value::String(field.to_string()));
             }
         }
@@ -362,28 +385,27 @@ pub fn csv_to_json(input_path: String, output_path: String) -> Result<Conversion
     Ok(ConversionResult {
         success: true,
         output_path,
-        message: format!("Converted {} records to JSON", records.len()),
+        message: format!("Converted {} records to JSON ({})", records.len(), table.encoding),
         output_size,
     })
 }
 
-/// Convert JSON array to CSV
-pub fn json_to_csv(input_path: String, output_path: String) -> Result<ConversionResult, String> {
+/// Convert JSON records (array or JSON Lines) to CSV. Columns are the union of every record's keys; nested values
+/// are flattened per `options` (dot-named columns, arrays joined or exploded into rows).
+pub fn json_to_csv(input_path: String, output_path: String, options: JsonToCsvOptions) -> Result<ConversionResult, String> {
     info!("📊 Converting JSON to CSV (bundled)");
 
+    ensure_text(&input_path, "JSON")?;
     let content = fs::read_to_string(&input_path)
         .map_err(|e| format!("Failed to read JSON: {}", e))?;
 
-    let records: Vec<serde_json::Map<String, serde_json::Value>> = serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse JSON: {}", e))?;
+    let records = parse_json_records(&content)?;
 
     if records.is_empty() {
         return Err("JSON array is empty".to_string());
     }
 
-    // Get all unique keys as headers
-    let mut headers: Vec<String> = records[0].keys().cloned().collect();
-    headers.sort();
+    let (headers, rows) = flatten_records(&records, &options)?;
 
     let mut wtr = csv::Writer::from_path(&output_path)
         .map_err(|e| format!("Failed to create CSV: {}", e))?;
@@ -391,18 +413,8 @@ pub fn json_to_csv(input_path: String, output_path: String) -> Result<Conversion
     wtr.write_record(&headers)
         .map_err(|e| format!("Failed to write headers: {}", e))?;
 
-    for record in &records {
-        let row: Vec<String> = headers.iter()
-            .map(|h| {
-                record.get(h)
-                    .map(|v| match v {
-                        serde_json::Value::String(s) => s.clone(),
-                        other => other.to_string(),
-                    })
-                    .unwrap_or_default()
-            })
-            .collect();
-        wtr.write_record(&row)
+    for row in &rows {
+        wtr.write_record(row)
             .map_err(|e| format!("Failed to write row: {}", e))?;
     }
 
@@ -413,7 +425,7 @@ pub fn json_to_csv(input_path: String, output_path: String) -> Result<Conversion
     Ok(ConversionResult {
         success: true,
         output_path,
-        message: format!("Converted {} records to CSV", records.len()),
+        message: format!("Converted {} records to CSV ({} rows, {} columns)", records.len(), rows.len(), headers.len()),
         output_size,
     })
 }
@@ -422,7 +434,7 @@ pub fn json_to_csv(input_path: String, output_path: String) -> Result<Conversion
 // Document Info
 // ============================================================================
 
-pub fn get_document_info(file_path: &str) -> Result<DocumentInfo, String> {
+pub fn get_document_info(file_path: &str, password: Option<&str>) -> Result<DocumentInfo, String> {
     let path = Path::new(file_path);
     
     if !path.exists() {
@@ -443,19 +455,30 @@ pub fn get_document_info(file_path: &str) -> Result<DocumentInfo, String> {
         .map(|m| m.len())
         .unwrap_or(0);
 
-    // Get extra info based on file type
-    let page_count = if extension == "pdf" {
-        get_pdf_info(file_path).ok()
+    // Get extra info based on the detected file type; the extension may be wrong
+    let kind = sniff(file_path)?;
+    let (page_count, pdf_metadata) = if kind == FileKind::Pdf {
+        match open_pdf(file_path, password) {
+            Ok(doc) => (Some(doc.get_pages().len()), Some(read_metadata(&doc))),
+            Err(e) if is_password_error(&e) => return Err(e),
+            Err(_) => (None, None),
+        }
     } else {
-        None
+        (None, None)
     };
 
-    let sheet_names = if ["xlsx", "xls", "ods"].contains(&extension.as_str()) {
+    let sheet_names = if kind.workbook_extension().is_some() {
         get_excel_sheets(file_path).ok()
     } else {
         None
     };
 
+    let image_info = if kind.is_image() {
+        get_image_info(file_path).ok()
+    } else {
+        None
+    };
+
     Ok(DocumentInfo {
         file_path: file_path.to_string(),
         file_name,
@@ -463,5 +486,8 @@ pub fn get_document_info(file_path: &str) -> Result<DocumentInfo, String> {
         extension,
         page_count,
         sheet_names,
+        pdf_metadata,
+        image_info,
+        detected_format: Some(kind.label()),
     })
 }
@@ -0,0 +1,259 @@
+//! Contact Sheet - one page of labelled thumbnails for reviewing a batch of event photos
+//! Output is an image (png/jpg/webp) or a multi-page A4 PDF, chosen by the output extension.
+//! Large batches are split over numbered sheet images (<name>_1.png, ...) so no image grows past
+//! what viewers and encoders handle. Image labels need a TrueType font from the system; PDF
+//! labels use the built-in Helvetica.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use log::{info, warn};
+use ab_glyph::{FontVec, PxScale};
+use image::{imageops, DynamicImage, Rgb, RgbImage};
+use imageproc::drawing::{draw_text_mut, text_size};
+use crate::bundled_converter::{save_image, ConversionResult};
+use crate::image_edit::open_oriented;
+use crate::image_size_limit::{encode, LossyFormat};
+use crate::pdf_writer::{text_width, PdfWriter, A4_PORTRAIT};
+
+const IMAGE_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "webp", "bmp", "gif", "tif", "tiff", "heic", "heif", "avif"];
+
+const PADDING: u32 = 12;
+
+/// Largest side of a sheet image (WebP's limit; JPEG and PNG allow a little more)
+const MAX_SHEET_SIDE: u32 = 16_383;
+
+/// Pixels per sheet image at most (about 150 MB as RGB)
+const MAX_SHEET_PIXELS: u64 = 50_000_000;
+
+/// Fonts tried in order for image labels (Windows, macOS, Linux)
+const FONT_PATHS: &[&str] = &[
+    "C:\\Windows\\Fonts\\segoeui.ttf",
+    "C:\\Windows\\Fonts\\arial.ttf",
+    "/System/Library/Fonts/Supplemental/Arial.ttf",
+    "/Library/Fonts/Arial.ttf",
+    "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf",
+    "/usr/share/fonts/TTF/DejaVuSans.ttf",
+    "/usr/share/fonts/dejavu/DejaVuSans.ttf",
+];
+
+fn system_font() -> Option<FontVec> {
+    FONT_PATHS.iter()
+        .find_map(|path| fs::read(path).ok().and_then(|bytes| FontVec::try_from_vec(bytes).ok()))
+}
+
+/// Expand folders into their images (sorted by name); files are kept in the given order
+fn collect_images(inputs: &[String]) -> Result<Vec<PathBuf>, String> {
+    let mut images = Vec::new();
+    for input in inputs {
+        let path = Path::new(input);
+        if !path.is_dir() {
+            images.push(path.to_path_buf());
+            continue;
+        }
+        let mut found: Vec<PathBuf> = fs::read_dir(path)
+            .map_err(|e| format!("Failed to read folder: {}", e))?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| {
+                path.extension()
+                    .and_then(|e| e.to_str())
+                    .is_some_and(|e| IMAGE_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+            })
+            .collect();
+        found.sort();
+        images.extend(found);
+    }
+    Ok(images)
+}
+
+/// Shorten a label with "..." until `fits` accepts it
+fn fit_label(name: &str, fits: impl Fn(&str) -> bool) -> String {
+    if fits(name) {
+        return name.to_string();
+    }
+    let mut chars: Vec<char> = name.chars().collect();
+    while !chars.is_empty() {
+        chars.pop();
+        let label = format!("{}...", chars.iter().collect::<String>());
+        if fits(&label) {
+            return label;
+        }
+    }
+    String::new()
+}
+
+fn label_scale(thumb_size: u32) -> PxScale {
+    PxScale::from((thumb_size as f32 / 16.0).clamp(10.0, 18.0))
+}
+
+/// Width and height of one grid cell (thumbnail, label and padding)
+fn cell_size(thumb_size: u32, labels: bool) -> (u32, u32) {
+    let label_height = if labels { label_scale(thumb_size).y.ceil() as u32 + PADDING / 2 } else { 0 };
+    (thumb_size + PADDING, thumb_size + PADDING + label_height)
+}
+
+fn render_image(thumbs: &[(String, DynamicImage)], columns: u32, thumb_size: u32, font: Option<&FontVec>) -> RgbImage {
+    let scale = label_scale(thumb_size);
+    let (cell_width, cell_height) = cell_size(thumb_size, font.is_some());
+    let rows = (thumbs.len() as u32).div_ceil(columns);
+
+    let mut canvas = RgbImage::from_pixel(
+        columns * cell_width + PADDING,
+        rows * cell_height + PADDING,
+        Rgb([255, 255, 255]),
+    );
+    for (index, (name, thumb)) in thumbs.iter().enumerate() {
+        let (column, row) = (index as u32 % columns, index as u32 / columns);
+        let (x, y) = (PADDING + column * cell_width, PADDING + row * cell_height);
+        // Center the thumbnail in its square
+        let left = x + (thumb_size - thumb.width()) / 2;
+        let top = y + (thumb_size - thumb.height()) / 2;
+        imageops::overlay(&mut canvas, &thumb.to_rgb8(), left as i64, top as i64);
+
+        if let Some(font) = font {
+            let label = fit_label(name, |text| text_size(scale, font, text).0 <= thumb_size);
+            let label_width = text_size(scale, font, &label).0;
+            let label_x = x + (thumb_size - label_width) / 2;
+            let label_y = y + thumb_size + PADDING / 4;
+            draw_text_mut(&mut canvas, Rgb([60, 60, 60]), label_x as i32, label_y as i32, scale, font, &label);
+        }
+    }
+    canvas
+}
+
+fn render_pdf(thumbs: &[(String, DynamicImage)], columns: u32, labels: bool, output_path: &str) -> Result<(), String> {
+    const LABEL_SIZE: f32 = 7.0;
+    const GAP: f32 = 8.0;
+
+    let mut pdf = PdfWriter::new(A4_PORTRAIT);
+    pdf.set_footer(&format!("Contact sheet - {} images", thumbs.len()));
+
+    let box_size = (pdf.content_width() + GAP) / columns as f32 - GAP;
+    let row_height = box_size + if labels { LABEL_SIZE * 2.0 } else { 0.0 } + GAP;
+
+    for row in thumbs.chunks(columns as usize) {
+        pdf.ensure_space(row_height);
+        let top = pdf.cursor();
+        for (column, (name, thumb)) in row.iter().enumerate() {
+            let x = pdf.left() + column as f32 * (box_size + GAP);
+            let jpeg = encode(thumb, LossyFormat::Jpeg, 85)?;
+            let image = pdf.add_jpeg(jpeg, thumb.width(), thumb.height());
+
+            // Fit the longest side to the box and center the other
+            let scale = box_size / thumb.width().max(thumb.height()) as f32;
+            let (w, h) = (thumb.width() as f32 * scale, thumb.height() as f32 * scale);
+            pdf.image_at(&image, x + (box_size - w) / 2.0, top - box_size + (box_size - h) / 2.0, w, h);
+
+            if labels {
+                let label = fit_label(name, |text| text_width(text, LABEL_SIZE) <= box_size);
+                let label_x = x + (box_size - text_width(&label, LABEL_SIZE)) / 2.0;
+                pdf.text_at(label_x, top - box_size - LABEL_SIZE * 1.4, LABEL_SIZE, false, &label);
+            }
+        }
+        pdf.move_down(row_height);
+    }
+    pdf.save(output_path)
+}
+
+/// Build a grid of thumbnails from image files and/or folders
+pub fn make_contact_sheet(
+    inputs: Vec<String>,
+    output_path: String,
+    columns: u32,
+    thumb_size: u32,
+    labels: bool,
+) -> Result<ConversionResult, String> {
+    let columns = columns.clamp(1, 20);
+    let thumb_size = thumb_size.clamp(32, 1024);
+    let paths = collect_images(&inputs)?;
+    if paths.is_empty() {
+        return Err("No images found".to_string());
+    }
+
+    info!("🖼️ Building contact sheet of {} images (bundled)", paths.len());
+
+    let mut skipped = 0;
+    let mut load = |paths: &[PathBuf], thumb_size: u32| -> Vec<(String, DynamicImage)> {
+        let mut thumbs = Vec::with_capacity(paths.len());
+        for path in paths {
+            match open_oriented(&path.to_string_lossy()) {
+                Ok(img) => {
+                    let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+                    thumbs.push((name, img.thumbnail(thumb_size, thumb_size)));
+                }
+                Err(e) => {
+                    warn!("Skipping {}: {}", path.display(), e);
+                    skipped += 1;
+                }
+            }
+        }
+        thumbs
+    };
+
+    let ext = Path::new(&output_path).extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
+    let mut notes = Vec::new();
+    let mut sheets = Vec::new();
+    let mut placed = 0;
+    if ext == "pdf" {
+        let thumbs = load(&paths, thumb_size);
+        if !thumbs.is_empty() {
+            render_pdf(&thumbs, columns, labels, &output_path)?;
+            placed = thumbs.len();
+            sheets.push(output_path.clone());
+        }
+    } else {
+        let font = if labels { system_font() } else { None };
+        if labels && font.is_none() {
+            notes.push("labels left out: no system font found".to_string());
+        }
+        // Thumbnails shrink when a row would be wider than a sheet may be, and rows that do not
+        // fit in one sheet's pixel budget go on the next sheet
+        let thumb_size = thumb_size.min((MAX_SHEET_SIDE - PADDING) / columns - PADDING);
+        let (cell_width, cell_height) = cell_size(thumb_size, font.is_some());
+        let sheet_width = columns * cell_width + PADDING;
+        let max_height = (MAX_SHEET_PIXELS / sheet_width as u64).min(MAX_SHEET_SIDE as u64) as u32;
+        let per_sheet = ((max_height - PADDING) / cell_height).max(1) * columns;
+
+        let batches: Vec<&[PathBuf]> = paths.chunks(per_sheet as usize).collect();
+        let path = Path::new(&output_path);
+        let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("contact_sheet");
+        for batch in &batches {
+            let thumbs = load(batch, thumb_size);
+            if thumbs.is_empty() {
+                continue;
+            }
+            let sheet_path = if batches.len() == 1 {
+                output_path.clone()
+            } else {
+                path.with_file_name(format!("{}_{}.{}", stem, sheets.len() + 1, ext)).to_string_lossy().to_string()
+            };
+            let sheet = render_image(&thumbs, columns, thumb_size, font.as_ref());
+            save_image(&DynamicImage::ImageRgb8(sheet), &sheet_path, Some(90))?;
+            placed += thumbs.len();
+            sheets.push(sheet_path);
+        }
+        if sheets.len() > 1 {
+            notes.push(format!("split over {} sheets", sheets.len()));
+        }
+    }
+    if sheets.is_empty() {
+        return Err("None of the images could be opened".to_string());
+    }
+    if skipped > 0 {
+        notes.push(format!("{} unreadable files skipped", skipped));
+    }
+    let output_path = sheets[0].clone();
+
+    let output_size = fs::metadata(&output_path).map(|m| m.len()).ok();
+
+    info!("✅ Contact sheet created: {}", output_path);
+    let mut message = format!("Contact sheet of {} images", placed);
+    if !notes.is_empty() {
+        message = format!("{} ({})", message, notes.join(", "));
+    }
+    Ok(ConversionResult {
+        success: true,
+        output_path,
+        message,
+        output_size,
+    })
+}
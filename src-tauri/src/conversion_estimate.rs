@@ -0,0 +1,86 @@
+//! Conversion Estimate - predict output size and encode time by converting a short sample first
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::Instant;
+use log::info;
+use crate::ffmpeg_runner::{ffmpeg_command, probe_duration, run_ffmpeg, MediaJob};
+use crate::media_converter::{convert_video, get_media_info, VideoConvertOptions};
+
+/// Length of the sample encoded to measure speed and bitrate
+const SAMPLE_SECS: f64 = 10.0;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConversionEstimate {
+    pub duration_secs: f64,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub input_size: u64,
+    pub sample_secs: f64,               // Length actually encoded for the estimate
+    pub estimated_size: u64,            // Bytes
+    pub estimated_time_secs: f64,
+    pub speed: f64,                     // Multiple of real time
+}
+
+/// Encode the first ~10 seconds with the real options and extrapolate to the full duration
+pub async fn estimate_conversion(options: VideoConvertOptions) -> Result<ConversionEstimate, String> {
+    let info = get_media_info(&options.input_path).await?;
+    let duration = info.duration.filter(|d| *d > 0.0)
+        .ok_or("Could not read the input duration")?;
+
+    let temp = std::env::temp_dir();
+    let stamp = chrono::Local::now().timestamp_millis();
+    let input_ext = Path::new(&options.input_path).extension().and_then(|e| e.to_str()).unwrap_or("mkv");
+    let output_ext = Path::new(&options.output_path).extension().and_then(|e| e.to_str()).unwrap_or("mp4");
+    let sample_input = temp.join(format!("estimate-{}-in.{}", stamp, input_ext)).to_string_lossy().to_string();
+    let sample_output = temp.join(format!("estimate-{}-out.{}", stamp, output_ext)).to_string_lossy().to_string();
+
+    info!("⏱️ Estimating conversion of {} from a {}s sample", info.file_name, SAMPLE_SECS);
+
+    // Stream-copy the opening seconds so the sample encode sees the real source
+    let job = MediaJob::new(None, None).with_output(&sample_input);
+    let mut cmd = ffmpeg_command();
+    cmd.arg("-y");
+    cmd.arg("-i").arg(&options.input_path);
+    cmd.arg("-t").arg(format!("{:.3}", SAMPLE_SECS.min(duration)));
+    cmd.arg("-map").arg("0:v:0?");
+    cmd.arg("-map").arg("0:a?");
+    cmd.arg("-c").arg("copy");
+    cmd.arg(&sample_input);
+    let cut = run_ffmpeg(cmd, &job).await;
+
+    let result = match cut {
+        Ok(()) => {
+            let sample_secs = probe_duration(&sample_input).await.filter(|d| *d > 0.0).unwrap_or(SAMPLE_SECS.min(duration));
+            let sample_options = VideoConvertOptions {
+                input_path: sample_input.clone(),
+                output_path: sample_output.clone(),
+                ..options
+            };
+            let started = Instant::now();
+            convert_video(sample_options, MediaJob::new(None, None)).await
+                .map(|converted| (sample_secs, started.elapsed().as_secs_f64(), converted.output_size.unwrap_or(0)))
+        }
+        Err(error) => Err(format!("Could not cut a sample: {}", error)),
+    };
+
+    let _ = std::fs::remove_file(&sample_input);
+    let _ = std::fs::remove_file(&sample_output);
+    let (sample_secs, elapsed, sample_size) = result?;
+
+    let scale = duration / sample_secs;
+    let estimate = ConversionEstimate {
+        duration_secs: duration,
+        width: info.width,
+        height: info.height,
+        input_size: info.file_size,
+        sample_secs,
+        estimated_size: (sample_size as f64 * scale) as u64,
+        estimated_time_secs: elapsed * scale,
+        speed: if elapsed > 0.0 { sample_secs / elapsed } else { 0.0 },
+    };
+
+    info!("✅ Estimate: ~{} MB in ~{:.0}s ({:.1}x)",
+        estimate.estimated_size / 1_048_576, estimate.estimated_time_secs, estimate.speed);
+    Ok(estimate)
+}
@@ -0,0 +1,99 @@
+//! Conversion Presets - named encoding settings saved in app data so departments share one standard
+//! A preset only overrides the settings it defines; anything left empty keeps the caller's value.
+
+use serde::{Deserialize, Serialize};
+use crate::app_storage::{load_json, save_json};
+use crate::media_converter::{ImageConvertOptions, VideoConvertOptions};
+
+const PRESETS_FILE: &str = "conversion_presets.json";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConversionPreset {
+    pub name: String,
+    pub kind: String,                    // video, image
+    // Video
+    pub format: Option<String>,          // Container or codec format (mp4, webm, hevc, av1, ...)
+    pub quality: Option<String>,         // high, medium, low
+    pub crf: Option<u32>,                // Exact CRF, overrides quality
+    pub resolution: Option<String>,
+    pub fps: Option<u32>,
+    pub denoise: Option<String>,
+    pub sharpen: Option<String>,
+    pub use_hardware: Option<bool>,
+    pub audio_codec: Option<String>,     // e.g. aac, libopus
+    pub audio_bitrate: Option<String>,   // e.g. "160k"
+    // Image
+    pub image_quality: Option<u32>,      // 1-100
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub maintain_aspect: Option<bool>,
+}
+
+pub fn load_presets() -> Result<Vec<ConversionPreset>, String> {
+    load_json(PRESETS_FILE)
+}
+
+fn find_preset(name: &str, kind: &str) -> Result<ConversionPreset, String> {
+    load_presets()?
+        .into_iter()
+        .find(|p| p.name.eq_ignore_ascii_case(name) && p.kind == kind)
+        .ok_or_else(|| format!("No {} preset named \"{}\"", kind, name))
+}
+
+/// Add or update a preset (matched by name + kind)
+pub fn save_preset(preset: ConversionPreset) -> Result<Vec<ConversionPreset>, String> {
+    if preset.name.trim().is_empty() {
+        return Err("Preset name is required".to_string());
+    }
+    if preset.kind != "video" && preset.kind != "image" {
+        return Err(format!("Unknown preset kind: {} (use video or image)", preset.kind));
+    }
+    let mut presets = load_presets()?;
+    match presets.iter_mut().find(|p| p.name.eq_ignore_ascii_case(&preset.name) && p.kind == preset.kind) {
+        Some(existing) => *existing = preset,
+        None => presets.push(preset),
+    }
+    save_json(PRESETS_FILE, &presets)?;
+    Ok(presets)
+}
+
+pub fn remove_preset(name: String, kind: String) -> Result<Vec<ConversionPreset>, String> {
+    let mut presets = load_presets()?;
+    presets.retain(|p| !(p.name.eq_ignore_ascii_case(&name) && p.kind == kind));
+    save_json(PRESETS_FILE, &presets)?;
+    Ok(presets)
+}
+
+/// Fill video options from the preset named in `options.preset` (no-op without one)
+pub fn apply_video_preset(options: &mut VideoConvertOptions) -> Result<(), String> {
+    let Some(name) = options.preset.clone() else {
+        return Ok(());
+    };
+    let preset = find_preset(&name, "video")?;
+    if let Some(format) = preset.format { options.format = format; }
+    if let Some(quality) = preset.quality { options.quality = quality; }
+    if preset.crf.is_some() { options.crf = preset.crf; }
+    if preset.resolution.is_some() { options.resolution = preset.resolution; }
+    if preset.fps.is_some() { options.fps = preset.fps; }
+    if preset.denoise.is_some() { options.denoise = preset.denoise; }
+    if preset.sharpen.is_some() { options.sharpen = preset.sharpen; }
+    if let Some(use_hardware) = preset.use_hardware { options.use_hardware = use_hardware; }
+    if preset.audio_codec.is_some() { options.audio_codec = preset.audio_codec; }
+    if preset.audio_bitrate.is_some() { options.audio_bitrate = preset.audio_bitrate; }
+    Ok(())
+}
+
+/// Fill image options from the preset named in `options.preset` (no-op without one)
+pub fn apply_image_preset(options: &mut ImageConvertOptions) -> Result<(), String> {
+    let Some(name) = options.preset.clone() else {
+        return Ok(());
+    };
+    let preset = find_preset(&name, "image")?;
+    if let Some(format) = preset.format { options.format = format; }
+    if preset.image_quality.is_some() { options.quality = preset.image_quality; }
+    if preset.width.is_some() { options.width = preset.width; }
+    if preset.height.is_some() { options.height = preset.height; }
+    if let Some(maintain) = preset.maintain_aspect { options.maintain_aspect = maintain; }
+    Ok(())
+}
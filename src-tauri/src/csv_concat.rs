@@ -0,0 +1,91 @@
+//! CSV Concat - append several CSV files into one, reconciling their headers
+//! Columns are the union of every file's columns (first file's order first); a file without a
+//! column gets blanks there. Handy for combining per-device attendance exports.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use log::info;
+use crate::bundled_converter::ConversionResult;
+use crate::csv_dialect::{read_csv, write_csv, CsvDialect};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CsvConcatOptions {
+    pub dialect: CsvDialect,            // How the inputs are read (delimiter/encoding detected per file by default)
+    pub output_dialect: CsvDialect,     // Delimiter, quote and encoding of the merged file (comma, UTF-8)
+    pub source_column: Option<String>,  // Adds a column with each row's source file name, e.g. "device"
+    pub match_case: Option<bool>,       // "Name" and "name" are different columns (default false)
+}
+
+/// Merge CSV files into `output_path`
+pub fn csv_concat(
+    input_paths: Vec<String>,
+    output_path: String,
+    options: CsvConcatOptions,
+) -> Result<ConversionResult, String> {
+    if input_paths.is_empty() {
+        return Err("No CSV files to combine".to_string());
+    }
+
+    info!("📊 Combining {} CSV files", input_paths.len());
+
+    let match_case = options.match_case.unwrap_or(false);
+    let key = |name: &str| if match_case { name.trim().to_string() } else { name.trim().to_lowercase() };
+
+    let mut headers: Vec<String> = Vec::new();
+    let mut positions: HashMap<String, usize> = HashMap::new();
+    let mut rows: Vec<Vec<String>> = Vec::new();
+    let source_column = options.source_column.as_deref().map(str::trim).filter(|c| !c.is_empty());
+    if let Some(column) = source_column {
+        positions.insert(key(column), 0);
+        headers.push(column.to_string());
+    }
+
+    for path in &input_paths {
+        let table = read_csv(path, &options.dialect)
+            .map_err(|e| format!("{}: {}", path, e))?;
+        let columns: Vec<usize> = table.headers.iter()
+            .map(|name| {
+                *positions.entry(key(name)).or_insert_with(|| {
+                    headers.push(name.trim().to_string());
+                    headers.len() - 1
+                })
+            })
+            .collect();
+        let source = Path::new(path).file_stem().and_then(|s| s.to_str()).unwrap_or(path).to_string();
+
+        for record in table.rows {
+            // Cells beyond the header have no column to go to
+            let mut row = vec![String::new(); headers.len()];
+            if source_column.is_some() {
+                row[0] = source.clone();
+            }
+            for (value, column) in record.into_iter().zip(&columns) {
+                row[*column] = value;
+            }
+            rows.push(row);
+        }
+    }
+
+    // Rows from earlier files are shorter when later files added columns
+    let row_count = rows.len();
+    let mut output = Vec::with_capacity(row_count + 1);
+    output.push(headers.clone());
+    output.extend(rows.into_iter().map(|mut row| {
+        row.resize(headers.len(), String::new());
+        row
+    }));
+    write_csv(&output_path, &output, &options.output_dialect)?;
+
+    let output_size = fs::metadata(&output_path).map(|m| m.len()).ok();
+
+    info!("✅ Combined {} rows into {}", row_count, output_path);
+    Ok(ConversionResult {
+        success: true,
+        output_path,
+        message: format!("Combined {} files: {} rows, {} columns", input_paths.len(), row_count, headers.len()),
+        output_size,
+    })
+}
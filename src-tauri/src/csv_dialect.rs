@@ -0,0 +1,183 @@
+//! CSV Dialect - delimiter, quoting, header and text encoding handling for CSV input/output
+//! Legacy ERP exports are often semicolon-separated Windows-1252; the encoding is detected from
+//! the BOM and UTF-8 validity, and the delimiter from the first line, unless given explicitly.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+const DELIMITER_CANDIDATES: [u8; 4] = [b',', b';', b'\t', b'|'];
+
+/// Windows-1252 characters for bytes 0x80-0x9F (undefined bytes map to the same code point)
+const WINDOWS_1252_HIGH: [u16; 32] = [
+    0x20AC, 0x0081, 0x201A, 0x0192, 0x201E, 0x2026, 0x2020, 0x2021,
+    0x02C6, 0x2030, 0x0160, 0x2039, 0x0152, 0x008D, 0x017D, 0x008F,
+    0x0090, 0x2018, 0x2019, 0x201C, 0x201D, 0x2022, 0x2013, 0x2014,
+    0x02DC, 0x2122, 0x0161, 0x203A, 0x0153, 0x009D, 0x017E, 0x0178,
+];
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CsvDialect {
+    pub delimiter: Option<String>,      // ",", ";", "|", "tab"; detected from the first line when empty
+    pub quote: Option<String>,          // Quote character, default "
+    pub has_header: Option<bool>,       // First row holds column names (default true)
+    pub encoding: Option<String>,       // auto (default), utf-8, utf-8-bom, utf-16le, utf-16be, windows-1252, iso-8859-1
+}
+
+impl CsvDialect {
+    pub fn has_header(&self) -> bool {
+        self.has_header.unwrap_or(true)
+    }
+
+    fn quote_byte(&self) -> Result<u8, String> {
+        match self.quote.as_deref().filter(|q| !q.is_empty()) {
+            None => Ok(b'"'),
+            Some(quote) if quote.len() == 1 => Ok(quote.as_bytes()[0]),
+            Some(other) => Err(format!("Quote must be a single character: {}", other)),
+        }
+    }
+
+    /// Delimiter from the options, or the candidate seen most often (outside quotes) in `sample`
+    fn delimiter_byte(&self, sample: &str) -> Result<u8, String> {
+        match self.delimiter.as_deref().filter(|d| !d.is_empty()) {
+            Some("tab") | Some("\\t") => return Ok(b'\t'),
+            Some(delimiter) if delimiter.len() == 1 => return Ok(delimiter.as_bytes()[0]),
+            Some(other) => return Err(format!("Delimiter must be a single character or \"tab\": {}", other)),
+            None => {}
+        }
+        let quote = self.quote_byte()?;
+        let first_line = sample.lines().find(|l| !l.trim().is_empty()).unwrap_or("");
+        let mut counts = [0usize; DELIMITER_CANDIDATES.len()];
+        let mut quoted = false;
+        for byte in first_line.bytes() {
+            if byte == quote {
+                quoted = !quoted;
+            } else if !quoted {
+                if let Some(index) = DELIMITER_CANDIDATES.iter().position(|c| *c == byte) {
+                    counts[index] += 1;
+                }
+            }
+        }
+        // Ties (including no delimiter at all) go to the comma
+        let best = (0..counts.len()).rev().max_by_key(|i| counts[*i]).unwrap_or(0);
+        Ok(if counts[best] == 0 { b',' } else { DELIMITER_CANDIDATES[best] })
+    }
+
+    /// csv::WriterBuilder configured with this dialect (comma when no delimiter is given)
+    pub fn writer_builder(&self) -> Result<csv::WriterBuilder, String> {
+        let mut builder = csv::WriterBuilder::new();
+        builder.delimiter(self.delimiter_byte("")?).quote(self.quote_byte()?);
+        Ok(builder)
+    }
+}
+
+fn utf16(bytes: &[u8], big_endian: bool) -> String {
+    let units: Vec<u16> = bytes.chunks_exact(2)
+        .map(|c| if big_endian { u16::from_be_bytes([c[0], c[1]]) } else { u16::from_le_bytes([c[0], c[1]]) })
+        .collect();
+    String::from_utf16_lossy(&units)
+}
+
+fn windows_1252(bytes: &[u8]) -> String {
+    bytes.iter()
+        .map(|b| match b {
+            0x80..=0x9F => char::from_u32(WINDOWS_1252_HIGH[(*b - 0x80) as usize] as u32).unwrap_or('\u{FFFD}'),
+            _ => *b as char,
+        })
+        .collect()
+}
+
+/// Decode file bytes; returns the text and the encoding that was used
+pub fn decode(bytes: &[u8], encoding: Option<&str>) -> Result<(String, &'static str), String> {
+    let encoding = encoding.map(str::to_lowercase).filter(|e| !e.is_empty() && e != "auto");
+    match encoding.as_deref() {
+        None => {
+            if let Some(rest) = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]) {
+                Ok((String::from_utf8_lossy(rest).to_string(), "utf-8-bom"))
+            } else if let Some(rest) = bytes.strip_prefix(&[0xFF, 0xFE]) {
+                Ok((utf16(rest, false), "utf-16le"))
+            } else if let Some(rest) = bytes.strip_prefix(&[0xFE, 0xFF]) {
+                Ok((utf16(rest, true), "utf-16be"))
+            } else if let Ok(text) = std::str::from_utf8(bytes) {
+                Ok((text.to_string(), "utf-8"))
+            } else {
+                // Not valid UTF-8: almost always a Western Windows export
+                Ok((windows_1252(bytes), "windows-1252"))
+            }
+        }
+        Some("utf-8") | Some("utf8") | Some("utf-8-bom") => {
+            let bytes = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]).unwrap_or(bytes);
+            Ok((String::from_utf8_lossy(bytes).to_string(), "utf-8"))
+        }
+        Some("utf-16le") | Some("utf-16") => Ok((utf16(bytes.strip_prefix(&[0xFF, 0xFE]).unwrap_or(bytes), false), "utf-16le")),
+        Some("utf-16be") => Ok((utf16(bytes.strip_prefix(&[0xFE, 0xFF]).unwrap_or(bytes), true), "utf-16be")),
+        Some("windows-1252") | Some("cp1252") => Ok((windows_1252(bytes), "windows-1252")),
+        Some("iso-8859-1") | Some("latin1") | Some("latin-1") => Ok((bytes.iter().map(|b| *b as char).collect(), "iso-8859-1")),
+        Some(other) => Err(format!("Unsupported encoding: {}", other)),
+    }
+}
+
+/// Encode text for writing; characters the target encoding lacks become '?'
+pub fn encode(text: &str, encoding: Option<&str>) -> Result<Vec<u8>, String> {
+    let single_byte = |high: &dyn Fn(char) -> Option<u8>| -> Vec<u8> {
+        text.chars().map(|c| if (c as u32) < 0x80 { c as u8 } else { high(c).unwrap_or(b'?') }).collect()
+    };
+    match encoding.map(str::to_lowercase).as_deref() {
+        None | Some("") | Some("auto") | Some("utf-8") | Some("utf8") => Ok(text.as_bytes().to_vec()),
+        Some("utf-8-bom") => Ok([&[0xEF, 0xBB, 0xBF][..], text.as_bytes()].concat()),
+        Some("utf-16le") | Some("utf-16") => Ok([0xFF, 0xFE].into_iter().chain(text.encode_utf16().flat_map(u16::to_le_bytes)).collect()),
+        Some("utf-16be") => Ok([0xFE, 0xFF].into_iter().chain(text.encode_utf16().flat_map(u16::to_be_bytes)).collect()),
+        Some("windows-1252") | Some("cp1252") => Ok(single_byte(&|c| {
+            WINDOWS_1252_HIGH.iter().position(|u| *u as u32 == c as u32).map(|i| 0x80 + i as u8)
+                .or_else(|| (0xA0..=0xFF).contains(&(c as u32)).then_some(c as u8))
+        })),
+        Some("iso-8859-1") | Some("latin1") | Some("latin-1") => Ok(single_byte(&|c| u8::try_from(c as u32).ok())),
+        Some(other) => Err(format!("Unsupported encoding: {}", other)),
+    }
+}
+
+/// Parsed CSV: column names (column1, column2... without a header row), rows and the detected encoding
+pub struct CsvTable {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+    pub encoding: &'static str,
+}
+
+/// Read a whole CSV file with the given dialect
+pub fn read_csv(path: &str, dialect: &CsvDialect) -> Result<CsvTable, String> {
+    let bytes = fs::read(path).map_err(|e| format!("Failed to open CSV: {}", e))?;
+    let (text, encoding) = decode(&bytes, dialect.encoding.as_deref())?;
+
+    let mut rdr = csv::ReaderBuilder::new()
+        .delimiter(dialect.delimiter_byte(&text)?)
+        .quote(dialect.quote_byte()?)
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(text.as_bytes());
+
+    let mut rows: Vec<Vec<String>> = Vec::new();
+    for result in rdr.records() {
+        let record = result.map_err(|e| format!("Failed to read record: {}", e))?;
+        rows.push(record.iter().map(str::to_string).collect());
+    }
+
+    let headers = if dialect.has_header() && !rows.is_empty() {
+        rows.remove(0).into_iter().map(|h| h.trim().to_string()).collect()
+    } else {
+        let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+        (1..=columns).map(|i| format!("column{}", i)).collect()
+    };
+    Ok(CsvTable { headers, rows, encoding })
+}
+
+/// Write rows (header first, if any) to a CSV file with the given dialect and encoding
+pub fn write_csv(path: &str, rows: &[Vec<String>], dialect: &CsvDialect) -> Result<(), String> {
+    let mut wtr = dialect.writer_builder()?.flexible(true).from_writer(Vec::new());
+    for row in rows {
+        wtr.write_record(row)
+            .map_err(|e| format!("Failed to write row: {}", e))?;
+    }
+    let text = wtr.into_inner().map_err(|e| format!("Failed to flush CSV: {}", e))?;
+    let bytes = encode(&String::from_utf8_lossy(&text), dialect.encoding.as_deref())?;
+    fs::write(path, bytes).map_err(|e| format!("Failed to write CSV: {}", e))
+}
@@ -0,0 +1,65 @@
+//! CSV to Excel - the reverse of excel_to_csv, producing a typed and formatted .xlsx
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use log::info;
+use rust_xlsxwriter::Workbook;
+use crate::bundled_converter::ConversionResult;
+use crate::csv_dialect::{read_csv, CsvDialect};
+use crate::sheet_writer::{detect, sheet_name, write_table, xlsx_err, CellValue};
+
+/// Rows per worksheet in .xlsx, header included
+pub const EXCEL_MAX_ROWS: usize = 1_048_576;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CsvToExcelOptions {
+    pub sheet_name: Option<String>,     // Defaults to the CSV file name
+    pub dialect: CsvDialect,            // Delimiter, quoting, header row and encoding of the input
+    pub detect_types: Option<bool>,     // Numbers, dates and booleans become real cells (default true)
+    pub freeze_header: Option<bool>,    // Keep the header row visible while scrolling (default true)
+}
+
+/// Convert a CSV file to a single-sheet Excel workbook
+pub fn csv_to_excel(
+    input_path: String,
+    output_path: String,
+    options: CsvToExcelOptions,
+) -> Result<ConversionResult, String> {
+    info!("📊 Converting CSV to Excel (bundled)");
+
+    let detect_types = options.detect_types.unwrap_or(true);
+
+    let table = read_csv(&input_path, &options.dialect)?;
+    let headers = if options.dialect.has_header() { table.headers } else { Vec::new() };
+    let rows: Vec<Vec<CellValue>> = table.rows.iter()
+        .map(|row| row.iter()
+            .map(|field| if detect_types { detect(field) } else if field.is_empty() { CellValue::Empty } else { CellValue::Text(field.to_string()) })
+            .collect())
+        .collect();
+    if rows.len() + 1 > EXCEL_MAX_ROWS {
+        return Err(format!("CSV has {} rows; an Excel sheet holds at most {}", rows.len(), EXCEL_MAX_ROWS - 1));
+    }
+
+    let default_name = Path::new(&input_path)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("Sheet1")
+        .to_string();
+    let mut workbook = Workbook::new();
+    let worksheet = workbook.add_worksheet();
+    worksheet.set_name(sheet_name(options.sheet_name.as_deref().unwrap_or(&default_name))).map_err(xlsx_err)?;
+    write_table(worksheet, &headers, &rows, options.freeze_header.unwrap_or(true)).map_err(xlsx_err)?;
+    workbook.save(&output_path).map_err(xlsx_err)?;
+
+    let output_size = fs::metadata(&output_path).map(|m| m.len()).ok();
+
+    info!("✅ CSV converted to Excel: {}", output_path);
+    Ok(ConversionResult {
+        success: true,
+        output_path,
+        message: format!("Converted {} rows to Excel", rows.len()),
+        output_size,
+    })
+}
@@ -0,0 +1,296 @@
+//! CSV Filter - small row filter expressions for csv_transform
+//! `status == present && (hours >= 8 || role ~ "lab")`: the left side of a comparison is a column
+//! (wrap names with spaces in backticks: `Emp Name`), the right side a value ("quoted" if it has
+//! spaces) or a `column`. Operators: == != > >= < <= ~ (contains) !~ starts_with ends_with,
+//! combined with && || ! (or and/or/not) and parentheses. Numbers and dates compare by value,
+//! text case-insensitively.
+
+use std::cmp::Ordering;
+use chrono::NaiveDateTime;
+use crate::sheet_writer::{detect, CellValue};
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),       // Column on the left of an operator, value on the right
+    Text(String),       // "quoted" or 'quoted' value
+    Column(String),     // `backticked` column
+    Operator(String),
+    And,
+    Or,
+    Not,
+    Open,
+    Close,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Op {
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    Contains,
+    NotContains,
+    StartsWith,
+    EndsWith,
+}
+
+#[derive(Debug, Clone)]
+pub enum Operand {
+    Value(String),
+    Column(usize),
+}
+
+#[derive(Debug, Clone)]
+pub enum Filter {
+    And(Box<Filter>, Box<Filter>),
+    Or(Box<Filter>, Box<Filter>),
+    Not(Box<Filter>),
+    Compare(usize, Op, Operand),
+}
+
+fn tokenize(expression: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = expression.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            ' ' | '\t' | '\n' | '\r' => {
+                chars.next();
+            }
+            '(' | ')' => {
+                chars.next();
+                tokens.push(if c == '(' { Token::Open } else { Token::Close });
+            }
+            '"' | '\'' | '`' => {
+                chars.next();
+                let text: String = chars.by_ref().take_while(|ch| *ch != c).collect();
+                tokens.push(if c == '`' { Token::Column(text) } else { Token::Text(text) });
+            }
+            '&' | '|' => {
+                chars.next();
+                if chars.next() != Some(c) {
+                    return Err(format!("Use {0}{0} in filter expressions", c));
+                }
+                tokens.push(if c == '&' { Token::And } else { Token::Or });
+            }
+            '=' | '!' | '<' | '>' | '~' => {
+                let mut op = String::new();
+                while let Some(&ch) = chars.peek() {
+                    if !"=!<>~".contains(ch) {
+                        break;
+                    }
+                    op.push(ch);
+                    chars.next();
+                }
+                tokens.push(if op == "!" { Token::Not } else { Token::Operator(op) });
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(&ch) = chars.peek() {
+                    if ch.is_whitespace() || "()\"'`&|=!<>~".contains(ch) {
+                        break;
+                    }
+                    word.push(ch);
+                    chars.next();
+                }
+                tokens.push(match word.to_lowercase().as_str() {
+                    "and" => Token::And,
+                    "or" => Token::Or,
+                    "not" => Token::Not,
+                    "contains" | "starts_with" | "ends_with" => Token::Operator(word.to_lowercase()),
+                    _ => Token::Word(word),
+                });
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    tokens: Vec<Token>,
+    position: usize,
+    headers: &'a [String],
+}
+
+impl Parser<'_> {
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn column(&self, name: &str) -> Result<usize, String> {
+        self.headers.iter()
+            .position(|h| h.trim().eq_ignore_ascii_case(name.trim()))
+            .ok_or_else(|| format!("Unknown column in filter: {}", name))
+    }
+
+    fn or(&mut self) -> Result<Filter, String> {
+        let mut filter = self.and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.next();
+            filter = Filter::Or(Box::new(filter), Box::new(self.and()?));
+        }
+        Ok(filter)
+    }
+
+    fn and(&mut self) -> Result<Filter, String> {
+        let mut filter = self.unary()?;
+        while self.peek() == Some(&Token::And) {
+            self.next();
+            filter = Filter::And(Box::new(filter), Box::new(self.unary()?));
+        }
+        Ok(filter)
+    }
+
+    fn unary(&mut self) -> Result<Filter, String> {
+        match self.next() {
+            Some(Token::Not) => Ok(Filter::Not(Box::new(self.unary()?))),
+            Some(Token::Open) => {
+                let filter = self.or()?;
+                match self.next() {
+                    Some(Token::Close) => Ok(filter),
+                    _ => Err("Missing ) in filter".to_string()),
+                }
+            }
+            Some(Token::Word(name)) | Some(Token::Column(name)) => {
+                let column = self.column(&name)?;
+                let op = match self.next() {
+                    Some(Token::Operator(op)) => match op.as_str() {
+                        "==" | "=" => Op::Eq,
+                        "!=" | "<>" => Op::Ne,
+                        ">" => Op::Gt,
+                        ">=" => Op::Ge,
+                        "<" => Op::Lt,
+                        "<=" => Op::Le,
+                        "~" | "contains" => Op::Contains,
+                        "!~" => Op::NotContains,
+                        "starts_with" => Op::StartsWith,
+                        "ends_with" => Op::EndsWith,
+                        other => return Err(format!("Unknown operator in filter: {}", other)),
+                    },
+                    _ => return Err(format!("Expected an operator after {}", name)),
+                };
+                let value = match self.next() {
+                    Some(Token::Word(value)) | Some(Token::Text(value)) => Operand::Value(value),
+                    Some(Token::Column(other)) => Operand::Column(self.column(&other)?),
+                    _ => return Err(format!("Expected a value after {} {:?}", name, op)),
+                };
+                Ok(Filter::Compare(column, op, value))
+            }
+            other => Err(format!("Unexpected {:?} in filter", other)),
+        }
+    }
+}
+
+/// Parse `expression` against the CSV's column names
+pub fn parse_filter(expression: &str, headers: &[String]) -> Result<Filter, String> {
+    let mut parser = Parser { tokens: tokenize(expression)?, position: 0, headers };
+    let filter = parser.or()?;
+    if parser.position < parser.tokens.len() {
+        return Err(format!("Unexpected {:?} in filter", parser.tokens[parser.position]));
+    }
+    Ok(filter)
+}
+
+fn timestamp(value: &CellValue) -> Option<NaiveDateTime> {
+    match value {
+        CellValue::Date(date) => date.and_hms_opt(0, 0, 0),
+        CellValue::DateTime(datetime) => Some(*datetime),
+        _ => None,
+    }
+}
+
+/// Sort key of a cell: numbers before dates before text, so mixed columns still have a total order
+pub enum SortKey {
+    Number(f64),
+    Date(NaiveDateTime),
+    Text(String),
+}
+
+impl SortKey {
+    pub fn of(value: &str) -> SortKey {
+        let cell = detect(value);
+        if let CellValue::Number(number) = cell {
+            return SortKey::Number(number);
+        }
+        match timestamp(&cell) {
+            Some(datetime) => SortKey::Date(datetime),
+            None => SortKey::Text(value.trim().to_lowercase()),
+        }
+    }
+
+    fn rank(&self) -> u8 {
+        match self {
+            SortKey::Number(_) => 0,
+            SortKey::Date(_) => 1,
+            SortKey::Text(_) => 2,
+        }
+    }
+}
+
+/// Numbers numerically, dates by time, text case-insensitively; between kinds, numbers < dates < text
+impl Ord for SortKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (SortKey::Number(x), SortKey::Number(y)) => x.total_cmp(y),
+            (SortKey::Date(x), SortKey::Date(y)) => x.cmp(y),
+            (SortKey::Text(x), SortKey::Text(y)) => x.cmp(y),
+            (a, b) => a.rank().cmp(&b.rank()),
+        }
+    }
+}
+
+impl PartialOrd for SortKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for SortKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for SortKey {}
+
+/// Order two cells by their sort keys
+pub fn compare_values(left: &str, right: &str) -> Ordering {
+    SortKey::of(left).cmp(&SortKey::of(right))
+}
+
+impl Filter {
+    pub fn matches(&self, row: &[String]) -> bool {
+        match self {
+            Filter::And(a, b) => a.matches(row) && b.matches(row),
+            Filter::Or(a, b) => a.matches(row) || b.matches(row),
+            Filter::Not(inner) => !inner.matches(row),
+            Filter::Compare(column, op, operand) => {
+                let left = row.get(*column).map(String::as_str).unwrap_or("");
+                let right = match operand {
+                    Operand::Value(value) => value.as_str(),
+                    Operand::Column(other) => row.get(*other).map(String::as_str).unwrap_or(""),
+                };
+                let (l, r) = (left.to_lowercase(), right.to_lowercase());
+                match op {
+                    Op::Eq => compare_values(left, right) == Ordering::Equal,
+                    Op::Ne => compare_values(left, right) != Ordering::Equal,
+                    Op::Gt => compare_values(left, right) == Ordering::Greater,
+                    Op::Ge => compare_values(left, right) != Ordering::Less,
+                    Op::Lt => compare_values(left, right) == Ordering::Less,
+                    Op::Le => compare_values(left, right) != Ordering::Greater,
+                    Op::Contains => l.contains(&r),
+                    Op::NotContains => !l.contains(&r),
+                    Op::StartsWith => l.starts_with(&r),
+                    Op::EndsWith => l.ends_with(&r),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,118 @@
+//! CSV Transform - run a small cleanup pipeline over a CSV file
+//! Operations apply in order, each to the result of the previous one: keep some columns, filter
+//! rows with an expression (see csv_filter), sort, and drop duplicate rows.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use log::info;
+use crate::bundled_converter::ConversionResult;
+use crate::csv_dialect::{read_csv, write_csv, CsvDialect};
+use crate::csv_filter::{parse_filter, SortKey};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum CsvOperation {
+    Select { columns: Vec<String> },        // Keep these columns, in this order
+    Filter { expression: String },          // Keep rows matching e.g. `hours >= 8 && dept == CSE`
+    Sort {
+        column: String,                     // Numbers and dates by value, text A-Z; ties keep their order
+        #[serde(default)]
+        descending: bool,
+    },
+    Dedupe {
+        #[serde(default)]
+        columns: Vec<String>,               // Keep the first row per key (the whole row when empty)
+    },
+}
+
+fn column_index(headers: &[String], name: &str) -> Result<usize, String> {
+    headers.iter()
+        .position(|h| h.trim().eq_ignore_ascii_case(name.trim()))
+        .ok_or_else(|| format!("Column not found: {}", name))
+}
+
+fn apply(headers: &mut Vec<String>, rows: &mut Vec<Vec<String>>, operation: &CsvOperation) -> Result<(), String> {
+    match operation {
+        CsvOperation::Select { columns } => {
+            if columns.is_empty() {
+                return Err("Select needs at least one column".to_string());
+            }
+            let picked = columns.iter()
+                .map(|name| column_index(headers, name))
+                .collect::<Result<Vec<_>, _>>()?;
+            *headers = picked.iter().map(|&i| headers[i].clone()).collect();
+            for row in rows.iter_mut() {
+                *row = picked.iter().map(|&i| row.get(i).cloned().unwrap_or_default()).collect();
+            }
+        }
+        CsvOperation::Filter { expression } => {
+            let filter = parse_filter(expression, headers)?;
+            rows.retain(|row| filter.matches(row));
+        }
+        CsvOperation::Sort { column, descending } => {
+            let index = column_index(headers, column)?;
+            // Keys are typed once per row rather than on every comparison
+            let mut keyed: Vec<(SortKey, Vec<String>)> = rows.drain(..)
+                .map(|row| (SortKey::of(row.get(index).map(String::as_str).unwrap_or("")), row))
+                .collect();
+            keyed.sort_by(|(a, _), (b, _)| if *descending { b.cmp(a) } else { a.cmp(b) });
+            rows.extend(keyed.into_iter().map(|(_, row)| row));
+        }
+        CsvOperation::Dedupe { columns } => {
+            let key_columns: Vec<usize> = if columns.is_empty() {
+                (0..headers.len()).collect()
+            } else {
+                columns.iter()
+                    .map(|name| column_index(headers, name))
+                    .collect::<Result<_, _>>()?
+            };
+            // Keys ignore case and surrounding spaces, as "CSE " and "cse" are usually the same entry
+            let mut seen = HashSet::new();
+            rows.retain(|row| {
+                let key: Vec<String> = key_columns.iter()
+                    .map(|&i| row.get(i).map(|v| v.trim().to_lowercase()).unwrap_or_default())
+                    .collect();
+                seen.insert(key)
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Apply `operations` to the CSV at `input_path` and write the result to `output_path`.
+/// The output uses the same dialect as the input (comma when the delimiter was detected).
+pub fn csv_transform(
+    input_path: String,
+    output_path: String,
+    operations: Vec<CsvOperation>,
+    dialect: CsvDialect,
+) -> Result<ConversionResult, String> {
+    info!("📊 Transforming CSV with {} operations", operations.len());
+
+    let table = read_csv(&input_path, &dialect)?;
+    let (mut headers, mut rows) = (table.headers, table.rows);
+    let input_rows = rows.len();
+
+    for (step, operation) in operations.iter().enumerate() {
+        apply(&mut headers, &mut rows, operation)
+            .map_err(|e| format!("Step {}: {}", step + 1, e))?;
+    }
+
+    let mut output = Vec::with_capacity(rows.len() + 1);
+    if dialect.has_header() {
+        output.push(headers.clone());
+    }
+    output.extend(rows.iter().cloned());
+    write_csv(&output_path, &output, &dialect)?;
+
+    let output_size = fs::metadata(&output_path).map(|m| m.len()).ok();
+
+    info!("✅ CSV transformed: {}", output_path);
+    Ok(ConversionResult {
+        success: true,
+        output_path,
+        message: format!("Kept {} of {} rows, {} columns", rows.len(), input_rows, headers.len()),
+        output_size,
+    })
+}
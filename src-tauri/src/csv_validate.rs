@@ -0,0 +1,165 @@
+//! CSV Validate - check a CSV against a simple schema before it is uploaded to the ERP
+//! Each schema column can be required, typed (text, integer, number, date, boolean, email),
+//! matched against a regex and limited to a list of values. Rows are numbered as in a
+//! spreadsheet (the header is row 1) so errors are easy to find and fix.
+
+use serde::{Deserialize, Serialize};
+use log::info;
+use regex::Regex;
+use crate::csv_dialect::{read_csv, CsvDialect};
+use crate::sheet_writer::{detect, CellValue};
+
+/// Errors listed in a report; `error_count` still has the total
+const MAX_LISTED_ERRORS: usize = 1000;
+const TYPES: [&str; 8] = ["", "text", "string", "integer", "number", "date", "boolean", "email"];
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ColumnRule {
+    pub name: String,
+    pub required: Option<bool>,         // Column must exist and no cell may be empty
+    #[serde(rename = "type")]
+    pub kind: Option<String>,           // text (default), integer, number, date, boolean, email
+    pub pattern: Option<String>,        // Regex the whole value must match, e.g. "[0-9]{6}"
+    pub allowed: Option<Vec<String>>,   // Only these values (surrounding spaces ignored)
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CsvSchema {
+    pub columns: Vec<ColumnRule>,
+    pub allow_extra_columns: Option<bool>,  // Columns not in the schema are fine (default true)
+    pub dialect: CsvDialect,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationError {
+    pub row: usize,
+    pub column: String,
+    pub value: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationReport {
+    pub valid: bool,
+    pub rows_checked: usize,
+    pub error_count: usize,
+    pub missing_columns: Vec<String>,   // Required schema columns absent from the header
+    pub extra_columns: Vec<String>,     // Header columns not in the schema
+    pub errors: Vec<ValidationError>,
+}
+
+struct CompiledRule<'a> {
+    rule: &'a ColumnRule,
+    index: usize,
+    pattern: Option<Regex>,
+}
+
+fn type_error(kind: &str, value: &str) -> Option<String> {
+    let ok = match kind {
+        "" | "text" | "string" => true,
+        "integer" => matches!(detect(value), CellValue::Number(n) if n.fract() == 0.0) || value.trim().chars().all(|c| c.is_ascii_digit()),
+        "number" => matches!(detect(value), CellValue::Number(_)),
+        "date" => matches!(detect(value), CellValue::Date(_) | CellValue::DateTime(_)),
+        "boolean" => ["true", "false", "yes", "no", "y", "n", "1", "0"].contains(&value.trim().to_lowercase().as_str()),
+        "email" => {
+            let value = value.trim();
+            matches!(value.split_once('@'), Some((user, domain)) if !user.is_empty() && domain.contains('.') && !domain.starts_with('.') && !domain.ends_with('.'))
+                && !value.contains(char::is_whitespace)
+        }
+        _ => true,
+    };
+    (!ok).then(|| format!("Expected {}", kind))
+}
+
+fn check_value(rule: &CompiledRule, value: &str) -> Option<String> {
+    if value.trim().is_empty() {
+        return rule.rule.required.unwrap_or(false).then(|| "Value is required".to_string());
+    }
+    let kind = rule.rule.kind.as_deref().unwrap_or("").to_lowercase();
+    if let Some(message) = type_error(&kind, value) {
+        return Some(message);
+    }
+    if let Some(pattern) = &rule.pattern {
+        if !pattern.is_match(value.trim()) {
+            return Some(format!("Does not match {}", rule.rule.pattern.as_deref().unwrap_or("")));
+        }
+    }
+    if let Some(allowed) = &rule.rule.allowed {
+        if !allowed.iter().any(|a| a.trim() == value.trim()) {
+            return Some(format!("Not one of: {}", allowed.join(", ")));
+        }
+    }
+    None
+}
+
+/// Check every row of `input_path` against `schema`
+pub fn validate_csv(input_path: String, schema: CsvSchema) -> Result<ValidationReport, String> {
+    info!("📊 Validating CSV: {}", input_path);
+
+    let table = read_csv(&input_path, &schema.dialect)?;
+    let position = |name: &str| table.headers.iter().position(|h| h.trim().eq_ignore_ascii_case(name.trim()));
+
+    let mut missing_columns = Vec::new();
+    let mut rules = Vec::new();
+    for rule in &schema.columns {
+        let kind = rule.kind.as_deref().unwrap_or("").to_lowercase();
+        if !TYPES.contains(&kind.as_str()) {
+            return Err(format!("Unknown type for {}: {}", rule.name, kind));
+        }
+        let pattern = match rule.pattern.as_deref().filter(|p| !p.is_empty()) {
+            Some(pattern) => Some(
+                Regex::new(&format!("^(?:{})$", pattern))
+                    .map_err(|e| format!("Invalid pattern for {}: {}", rule.name, e))?,
+            ),
+            None => None,
+        };
+        match position(&rule.name) {
+            Some(index) => rules.push(CompiledRule { rule, index, pattern }),
+            None if rule.required.unwrap_or(false) => missing_columns.push(rule.name.clone()),
+            None => {}
+        }
+    }
+
+    let extra_columns: Vec<String> = if schema.allow_extra_columns.unwrap_or(true) {
+        Vec::new()
+    } else {
+        table.headers.iter()
+            .filter(|h| !schema.columns.iter().any(|rule| rule.name.trim().eq_ignore_ascii_case(h.trim())))
+            .cloned()
+            .collect()
+    };
+
+    let mut errors = Vec::new();
+    let mut error_count = 0;
+    // Row 1 is the header when there is one
+    let first_row = if schema.dialect.has_header() { 2 } else { 1 };
+    for (i, row) in table.rows.iter().enumerate() {
+        for rule in &rules {
+            let value = row.get(rule.index).map(String::as_str).unwrap_or("");
+            if let Some(message) = check_value(rule, value) {
+                error_count += 1;
+                if errors.len() < MAX_LISTED_ERRORS {
+                    errors.push(ValidationError {
+                        row: i + first_row,
+                        column: table.headers[rule.index].clone(),
+                        value: value.to_string(),
+                        message,
+                    });
+                }
+            }
+        }
+    }
+
+    let valid = error_count == 0 && missing_columns.is_empty() && extra_columns.is_empty();
+    info!("✅ Validation finished: {} errors in {} rows", error_count, table.rows.len());
+    Ok(ValidationReport {
+        valid,
+        rows_checked: table.rows.len(),
+        error_count,
+        missing_columns,
+        extra_columns,
+        errors,
+    })
+}
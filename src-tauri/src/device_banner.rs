@@ -0,0 +1,106 @@
+//! Device Banner - identify a device model from its web UI (title, Server header, auth realm)
+
+use std::time::Duration;
+use log::debug;
+
+const BANNER_TIMEOUT: Duration = Duration::from_secs(3);
+const WEB_PORTS: &[u16] = &[80, 8080, 443, 8443];
+
+/// (substring in banner, model family) - checked in order, case-insensitive
+const KNOWN_BANNERS: &[(&str, &str)] = &[
+    ("speedface", "ZKTeco SpeedFace"),
+    ("proface", "ZKTeco ProFace"),
+    ("uface", "ZKTeco uFace"),
+    ("iface", "ZKTeco iFace"),
+    ("iclock", "ZKTeco iClock"),
+    ("zkbio", "ZKTeco ZKBio"),
+    ("zk web server", "ZKTeco terminal"),
+    ("zkteco", "ZKTeco terminal"),
+    ("essl", "eSSL terminal"),
+    ("realtime", "Realtime biometric terminal"),
+    ("hikvision", "Hikvision device"),
+    ("dahua", "Dahua device"),
+    ("hp laserjet", "HP printer"),
+    ("hp http server", "HP printer"),
+    ("epson", "Epson printer"),
+    ("canon", "Canon printer"),
+    ("brother", "Brother printer"),
+    ("ricoh", "Ricoh printer"),
+    ("kyocera", "Kyocera printer"),
+    ("mikrotik", "MikroTik router"),
+    ("routeros", "MikroTik router"),
+    ("tp-link", "TP-Link device"),
+    ("ubiquiti", "Ubiquiti device"),
+    ("unifi", "Ubiquiti device"),
+];
+
+fn extract_title(html: &str) -> Option<String> {
+    let lower = html.to_lowercase();
+    let start = lower.find("<title")?;
+    let open_end = start + lower[start..].find('>')? + 1;
+    let close = open_end + lower[open_end..].find("</title")?;
+    let title = html.get(open_end..close)?.split_whitespace().collect::<Vec<_>>().join(" ");
+    if title.is_empty() { None } else { Some(title) }
+}
+
+fn realm(header: &str) -> Option<String> {
+    let lower = header.to_lowercase();
+    let start = lower.find("realm=")? + "realm=".len();
+    let value = header.get(start..)?.trim_start_matches('"');
+    Some(value.split('"').next().unwrap_or(value).to_string())
+}
+
+/// Fetch "/" and return a one-line banner: "title | Server header | realm"
+async fn fetch_banner(client: &reqwest::Client, ip: &str, port: u16) -> Option<String> {
+    let scheme = if port == 443 || port == 8443 { "https" } else { "http" };
+    let host = if ip.contains(':') { format!("[{}]", ip) } else { ip.to_string() };
+    let url = format!("{}://{}:{}/", scheme, host, port);
+
+    let response = match client.get(&url).send().await {
+        Ok(r) => r,
+        Err(e) => {
+            debug!("Banner fetch {} failed: {}", url, e);
+            return None;
+        }
+    };
+
+    let header = |name: &str| response.headers().get(name).and_then(|v| v.to_str().ok()).map(str::to_string);
+    let server = header("server");
+    let auth_realm = header("www-authenticate").and_then(|h| realm(&h));
+    let body = response.text().await.unwrap_or_default();
+    let title = extract_title(&body);
+
+    let parts: Vec<String> = [title, server, auth_realm].into_iter().flatten().collect();
+    if parts.is_empty() { None } else { Some(parts.join(" | ")) }
+}
+
+/// Guess the model family from a banner string
+pub fn guess_model(banner: &str) -> Option<String> {
+    let lower = banner.to_lowercase();
+    KNOWN_BANNERS.iter()
+        .find(|(needle, _)| lower.contains(needle))
+        .map(|(_, model)| model.to_string())
+}
+
+/// Grab banners from whichever web ports are open; returns (banner, model_guess)
+pub async fn fingerprint(ip: &str, open_ports: &[u16]) -> (Option<String>, Option<String>) {
+    let client = match reqwest::Client::builder()
+        .timeout(BANNER_TIMEOUT)
+        .danger_accept_invalid_certs(true) // Device UIs use self-signed certificates
+        .build()
+    {
+        Ok(c) => c,
+        Err(_) => return (None, None),
+    };
+
+    let mut first_banner = None;
+    for port in open_ports.iter().filter(|p| WEB_PORTS.contains(p)) {
+        if let Some(banner) = fetch_banner(&client, ip, *port).await {
+            if let Some(model) = guess_model(&banner) {
+                return (Some(banner), Some(model));
+            }
+            first_banner.get_or_insert(banner);
+        }
+    }
+    (first_banner, None)
+}
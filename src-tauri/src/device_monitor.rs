@@ -0,0 +1,160 @@
+//! Device Monitor - periodic reachability checks of saved device profiles
+//! Emits "device://offline" / "device://online" on state changes and keeps an uptime history.
+//! The first check of a device only records its state; events start with the first change.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::net::TcpStream;
+use log::{info, warn};
+use tauri::{AppHandle, Emitter};
+use crate::app_storage::{load_json, save_json};
+use crate::device_profiles::{load_profiles, DeviceProfile};
+use crate::zkteco_client::socket_addr;
+
+const UPTIME_FILE: &str = "device_uptime.json";
+const EVENT_OFFLINE: &str = "device://offline";
+const EVENT_ONLINE: &str = "device://online";
+
+const CHECK_TIMEOUT: Duration = Duration::from_secs(3);
+const MAX_SAMPLES_PER_DEVICE: usize = 2000;  // ~1 week at 5-minute intervals
+
+/// Running monitor task (at most one)
+static MONITOR: Mutex<Option<tauri::async_runtime::JoinHandle<()>>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UptimeSample {
+    pub at: String,   // RFC 3339
+    pub online: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceStatusEvent {
+    pub ip: String,
+    pub port: u16,
+    pub name: String,
+    pub online: bool,
+    pub at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceUptime {
+    pub ip: String,
+    pub port: u16,
+    pub online: Option<bool>,          // Latest check (None = never checked, or a serial line)
+    pub last_seen: Option<String>,     // Last successful check
+    pub uptime_percent: Option<f64>,   // Over the stored history (None for serial lines)
+    pub history: Vec<UptimeSample>,
+}
+
+/// "ip:port" -> samples, oldest first
+type UptimeHistory = HashMap<String, Vec<UptimeSample>>;
+
+fn device_key(ip: &str, port: u16) -> String {
+    format!("{}:{}", ip, port)
+}
+
+/// Serial terminals can't be probed without taking the line, so they are not monitored
+fn is_monitored(profile: &DeviceProfile) -> bool {
+    !profile.ip.starts_with("serial:")
+}
+
+async fn is_reachable(profile: &DeviceProfile) -> bool {
+    let Ok(addr) = socket_addr(&profile.ip, profile.port) else {
+        return false;
+    };
+    matches!(tokio::time::timeout(CHECK_TIMEOUT, TcpStream::connect(&addr)).await, Ok(Ok(_)))
+}
+
+/// Check every profile once, record samples and emit events for state changes (a device
+/// without history yet is recorded silently)
+async fn check_all(app: &AppHandle) -> Result<(), String> {
+    let profiles = load_profiles()?;
+    let mut history: UptimeHistory = load_json(UPTIME_FILE)?;
+    let now = chrono::Local::now().to_rfc3339();
+
+    for profile in profiles.iter().filter(|p| is_monitored(p)) {
+        let online = is_reachable(profile).await;
+        let samples = history.entry(device_key(&profile.ip, profile.port)).or_default();
+        let was_online = samples.last().map(|s| s.online);
+
+        if was_online.is_some_and(|was| was != online) {
+            let event = if online { EVENT_ONLINE } else { EVENT_OFFLINE };
+            if online { info!("🟢 {} is online", profile.name) } else { warn!("🔴 {} is offline", profile.name) }
+            let _ = app.emit(event, DeviceStatusEvent {
+                ip: profile.ip.clone(),
+                port: profile.port,
+                name: profile.name.clone(),
+                online,
+                at: now.clone(),
+            });
+        }
+
+        samples.push(UptimeSample { at: now.clone(), online });
+        if samples.len() > MAX_SAMPLES_PER_DEVICE {
+            let excess = samples.len() - MAX_SAMPLES_PER_DEVICE;
+            samples.drain(..excess);
+        }
+    }
+
+    save_json(UPTIME_FILE, &history)
+}
+
+/// Start (or restart) the background monitor
+pub fn start_monitor(app: AppHandle, interval_minutes: u64) -> Result<(), String> {
+    let interval = Duration::from_secs(interval_minutes.max(1) * 60);
+    stop_monitor()?;
+
+    let handle = tauri::async_runtime::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            if let Err(e) = check_all(&app).await {
+                warn!("Device monitor check failed: {}", e);
+            }
+        }
+    });
+
+    *MONITOR.lock().map_err(|_| "Monitor state poisoned".to_string())? = Some(handle);
+    info!("📡 Device monitor started (every {} min)", interval_minutes.max(1));
+    Ok(())
+}
+
+pub fn stop_monitor() -> Result<(), String> {
+    let mut monitor = MONITOR.lock().map_err(|_| "Monitor state poisoned".to_string())?;
+    if let Some(handle) = monitor.take() {
+        handle.abort();
+        info!("📡 Device monitor stopped");
+    }
+    Ok(())
+}
+
+pub fn is_running() -> bool {
+    MONITOR.lock().map(|m| m.is_some()).unwrap_or(false)
+}
+
+/// Uptime history for every saved profile
+pub fn get_uptime() -> Result<Vec<DeviceUptime>, String> {
+    let history: UptimeHistory = load_json(UPTIME_FILE)?;
+
+    Ok(load_profiles()?
+        .into_iter()
+        .map(|profile| {
+            let samples = if is_monitored(&profile) {
+                history.get(&device_key(&profile.ip, profile.port)).cloned().unwrap_or_default()
+            } else {
+                Vec::new()
+            };
+            let up = samples.iter().filter(|s| s.online).count();
+            DeviceUptime {
+                online: samples.last().map(|s| s.online),
+                last_seen: samples.iter().rev().find(|s| s.online).map(|s| s.at.clone()),
+                uptime_percent: if samples.is_empty() { None } else { Some(up as f64 * 100.0 / samples.len() as f64) },
+                ip: profile.ip,
+                port: profile.port,
+                history: samples,
+            }
+        })
+        .collect())
+}
@@ -0,0 +1,47 @@
+//! Device Profiles - saved terminals and per-device permissions (door control, ...)
+
+use serde::{Deserialize, Serialize};
+use crate::admin_auth::require_admin;
+use crate::app_storage::{load_json, save_json};
+
+const PROFILES_FILE: &str = "device_profiles.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceProfile {
+    pub ip: String,                  // IP address or serial target
+    pub port: u16,
+    pub name: String,
+    #[serde(default)]
+    pub allow_door_control: bool,    // Off by default - turning it on needs the admin password
+}
+
+pub fn load_profiles() -> Result<Vec<DeviceProfile>, String> {
+    load_json(PROFILES_FILE)
+}
+
+/// Find the saved profile for a device
+pub fn find_profile(ip: &str, port: u16) -> Result<Option<DeviceProfile>, String> {
+    Ok(load_profiles()?.into_iter().find(|p| p.ip == ip && p.port == port))
+}
+
+/// Add or update a profile (matched by ip + port); enabling door control needs the admin password
+pub fn save_profile(profile: DeviceProfile, admin_password: Option<String>) -> Result<Vec<DeviceProfile>, String> {
+    let mut profiles = load_profiles()?;
+    let existing = profiles.iter().find(|p| p.ip == profile.ip && p.port == profile.port);
+    if profile.allow_door_control && !existing.is_some_and(|p| p.allow_door_control) {
+        require_admin(admin_password.as_deref())?;
+    }
+    match profiles.iter_mut().find(|p| p.ip == profile.ip && p.port == profile.port) {
+        Some(existing) => *existing = profile,
+        None => profiles.push(profile),
+    }
+    save_json(PROFILES_FILE, &profiles)?;
+    Ok(profiles)
+}
+
+pub fn remove_profile(ip: String, port: u16) -> Result<Vec<DeviceProfile>, String> {
+    let mut profiles = load_profiles()?;
+    profiles.retain(|p| !(p.ip == ip && p.port == port));
+    save_json(PROFILES_FILE, &profiles)?;
+    Ok(profiles)
+}
@@ -4,8 +4,15 @@ use std::time::Duration;
 use tokio::net::TcpStream;
 use tokio::sync::Semaphore;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use log::{info, warn};
-use crate::zkteco_client::get_device_info_quick;
+use tauri::{AppHandle, Emitter};
+use crate::zkteco_client::{get_device_info_quick, socket_addr};
+use crate::ip_ranges::expand_ranges;
+use crate::mac_lookup::resolve_mac;
+use crate::host_lookup::resolve_hostname;
+use crate::device_banner::{fingerprint, guess_model};
+use crate::service_discovery::discover_services;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BiometricDevice {
@@ -15,8 +22,39 @@ pub struct BiometricDevice {
     pub device_name: Option<String>,
     pub firmware_version: Option<String>,
     pub serial_number: Option<String>,
+    #[serde(default)]
+    pub discovery: Vec<String>,             // "port_scan", "mdns", "ssdp"
+    #[serde(default)]
+    pub advertised_name: Option<String>,    // mDNS instance / SSDP SERVER header
+    #[serde(default)]
+    pub hostname: Option<String>,           // Reverse DNS / NetBIOS name
+    #[serde(default)]
+    pub http_banner: Option<String>,        // Web UI title | Server header | auth realm
+    #[serde(default)]
+    pub model_guess: Option<String>,        // Model family inferred from the banner
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkInterfaceInfo {
+    pub name: String,
+    pub mac: Option<String>,
+    pub ipv4: Vec<String>,   // CIDR notation
+    pub ipv6: Vec<String>,   // CIDR notation (link-local included)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanProgress {
+    pub checked: usize,
+    pub total: usize,
+    pub found: usize,
+}
+
+// Events streamed to the UI while a scan runs
+const EVENT_PROGRESS: &str = "scanner://progress";
+const EVENT_DEVICE_FOUND: &str = "scanner://device_found";
+// Emit progress every N hosts (a /16 would otherwise flood the webview)
+const PROGRESS_EVERY: usize = 16;
+
 // Common ports for biometric/time-attendance devices
 // ZKTeco protocol ports
 const ZKTECO_PORTS: &[u16] = &[4370, 4360, 5005, 5010, 89];
@@ -25,6 +63,30 @@ const OTHER_PORTS: &[u16] = &[80, 8080, 443, 8443];
 
 // Max concurrent connections for scanning
 const MAX_CONCURRENT: usize = 100;
+const DEFAULT_TIMEOUT_MS: u64 = 500;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScanOptions {
+    pub ports: Vec<u16>,          // ZK protocol ports that identify a device
+    pub service_ports: Vec<u16>,  // Extra ports reported for found devices (web UI, ...)
+    pub timeout_ms: u64,          // Connect timeout per port
+    pub max_concurrent: usize,    // Sockets open at once
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        ScanOptions {
+            ports: ZKTECO_PORTS.to_vec(),
+            service_ports: OTHER_PORTS.to_vec(),
+            timeout_ms: DEFAULT_TIMEOUT_MS,
+            max_concurrent: MAX_CONCURRENT,
+        }
+    }
+}
+
+// How long to listen for mDNS/SSDP replies (runs alongside the port sweep)
+const DISCOVERY_LISTEN: Duration = Duration::from_secs(3);
 
 fn get_local_ip() -> Result<Ipv4Addr, String> {
     let socket = std::net::UdpSocket::bind("0.0.0.0:0")
@@ -40,12 +102,29 @@ fn get_local_ip() -> Result<Ipv4Addr, String> {
     
     match local_addr.ip() {
         IpAddr::V4(ip) => Ok(ip),
-        IpAddr::V6(_) => Err("IPv6 not supported".to_string()),
+        IpAddr::V6(_) => Err("No IPv4 default route (use scan_range for IPv6 networks)".to_string()),
     }
 }
 
+/// Enumerate local interfaces with their IPv4/IPv6 networks (loopback skipped)
+pub fn list_interfaces() -> Vec<NetworkInterfaceInfo> {
+    pnet::datalink::interfaces()
+        .into_iter()
+        .filter(|iface| !iface.is_loopback() && !iface.ips.is_empty())
+        .map(|iface| NetworkInterfaceInfo {
+            mac: iface.mac.map(|m| m.to_string().to_uppercase()),
+            ipv4: iface.ips.iter().filter(|n| n.is_ipv4()).map(|n| n.to_string()).collect(),
+            ipv6: iface.ips.iter().filter(|n| n.is_ipv6()).map(|n| n.to_string()).collect(),
+            name: iface.name,
+        })
+        .collect()
+}
+
 async fn check_port(ip: &str, port: u16, timeout_ms: u64) -> bool {
-    let addr = format!("{}:{}", ip, port);
+    let addr = match socket_addr(ip, port) {
+        Ok(addr) => addr,
+        Err(_) => return false,
+    };
     match tokio::time::timeout(
         Duration::from_millis(timeout_ms),
         TcpStream::connect(&addr),
@@ -58,7 +137,9 @@ async fn check_port(ip: &str, port: u16, timeout_ms: u64) -> bool {
 }
 
 /// Check if IP has biometric port open (fast check)
-async fn check_biometric_ip(ip: String, semaphore: Arc<Semaphore>) -> Option<BiometricDevice> {
+async fn check_biometric_ip(ip: String, options: Arc<ScanOptions>, semaphore: Arc<Semaphore>) -> Option<BiometricDevice> {
+    // Host is known to be up once a ZK port answers, so follow-up probes can be shorter
+    let follow_up_ms = (options.timeout_ms * 3 / 5).max(50);
     // Only hold semaphore during port checking
     let main_port: Option<u16>;
     let mut open_ports: Vec<u16>;
@@ -69,8 +150,8 @@ async fn check_biometric_ip(ip: String, semaphore: Arc<Semaphore>) -> Option<Bio
         // Check all ZKTeco ports to find the main one
         main_port = {
             let mut found = None;
-            for port in ZKTECO_PORTS {
-                if check_port(&ip, *port, 500).await {
+            for port in options.ports.iter() {
+                if check_port(&ip, *port, options.timeout_ms).await {
                     found = Some(*port);
                     break;
                 }
@@ -86,15 +167,15 @@ async fn check_biometric_ip(ip: String, semaphore: Arc<Semaphore>) -> Option<Bio
         open_ports = vec![port];
         
         // Check all other ZKTeco ports
-        for p in ZKTECO_PORTS {
-            if *p != port && check_port(&ip, *p, 300).await {
+        for p in options.ports.iter() {
+            if *p != port && check_port(&ip, *p, follow_up_ms).await {
                 open_ports.push(*p);
             }
         }
         
         // Check web/service ports
-        for p in OTHER_PORTS {
-            if check_port(&ip, *p, 300).await {
+        for p in options.service_ports.iter() {
+            if check_port(&ip, *p, follow_up_ms).await {
                 open_ports.push(*p);
             }
         }
@@ -109,14 +190,23 @@ async fn check_biometric_ip(ip: String, semaphore: Arc<Semaphore>) -> Option<Bio
     let port = main_port.unwrap();
     info!("🔍 Device found at {}, fetching info on port {}...", ip, port);
     let device_info = get_device_info_quick(&ip, port).await;
+    let reported_mac = device_info.as_ref().map(|d| d.mac_address.as_str());
+    let mac = resolve_mac(&ip, reported_mac).await.unwrap_or_else(|| "Unknown".to_string());
+    let hostname = resolve_hostname(&ip).await;
+    let (http_banner, model_guess) = fingerprint(&ip, &open_ports).await;
     
     Some(BiometricDevice {
         ip,
-        mac: "Unknown".to_string(),
+        mac,
         open_ports,
         device_name: device_info.as_ref().map(|d| d.device_name.clone()).filter(|s| !s.is_empty()),
         firmware_version: device_info.as_ref().map(|d| d.firmware_version.clone()).filter(|s| !s.is_empty()),
         serial_number: device_info.as_ref().map(|d| d.serial_number.clone()).filter(|s| !s.is_empty()),
+        discovery: vec!["port_scan".to_string()],
+        advertised_name: None,
+        hostname,
+        http_banner,
+        model_guess,
     })
 }
 
@@ -130,14 +220,18 @@ const COMMON_SUBNETS: &[(u8, u8, u8)] = &[
     (172, 16, 0),
 ];
 
-pub async fn scan_network() -> Result<Vec<BiometricDevice>, String> {
-    let local_ip = get_local_ip()?;
-    let local_parts: Vec<u8> = local_ip.octets().to_vec();
-    
-    // Build list of subnets to scan
-    let mut subnets_to_scan: Vec<(u8, u8, u8)> = vec![
-        (local_parts[0], local_parts[1], local_parts[2])
-    ];
+pub async fn scan_network(options: ScanOptions, app: Option<AppHandle>) -> Result<Vec<BiometricDevice>, String> {
+    // Build list of subnets to scan (IPv6-only hosts fall back to the common subnets)
+    let mut subnets_to_scan: Vec<(u8, u8, u8)> = match get_local_ip() {
+        Ok(local_ip) => {
+            let o = local_ip.octets();
+            vec![(o[0], o[1], o[2])]
+        }
+        Err(e) => {
+            warn!("Local subnet unavailable: {}", e);
+            Vec::new()
+        }
+    };
     
     // Add common subnets if not already included
     for subnet in COMMON_SUBNETS {
@@ -148,22 +242,113 @@ pub async fn scan_network() -> Result<Vec<BiometricDevice>, String> {
     
     info!("🔍 Scanning {} subnets: local + common", subnets_to_scan.len());
     
+    let mut ips = Vec::new();
+    for (a, b, c) in &subnets_to_scan {
+        for i in 1..255u8 {
+            ips.push(IpAddr::V4(Ipv4Addr::new(*a, *b, *c, i)));
+        }
+    }
+    
+    // Passive discovery runs in parallel with the sweep and is merged afterwards
+    let (swept, services) = tokio::join!(
+        scan_ips(ips, options, app),
+        discover_services(DISCOVERY_LISTEN),
+    );
+    let mut devices = swept?;
+    
+    for service in services {
+        match devices.iter_mut().find(|d| d.ip == service.ip) {
+            Some(device) => {
+                if !device.discovery.contains(&service.method) {
+                    device.discovery.push(service.method);
+                }
+                if device.advertised_name.is_none() {
+                    device.advertised_name = service.name;
+                }
+            }
+            None => devices.push(BiometricDevice {
+                mac: resolve_mac(&service.ip, None).await.unwrap_or_else(|| "Unknown".to_string()),
+                hostname: resolve_hostname(&service.ip).await,
+                ip: service.ip,
+                open_ports: Vec::new(),
+                device_name: None,
+                firmware_version: None,
+                serial_number: None,
+                discovery: vec![service.method],
+                model_guess: service.name.as_deref().and_then(guess_model),
+                advertised_name: service.name,
+                http_banner: None,
+            }),
+        }
+    }
+    
+    Ok(devices)
+}
+
+/// Scan arbitrary CIDR blocks / start-end ranges (e.g. VLAN-separated device networks)
+pub async fn scan_range(
+    cidrs: Vec<String>,
+    ports: Option<Vec<u16>>,
+    mut options: ScanOptions,
+    app: Option<AppHandle>,
+) -> Result<Vec<BiometricDevice>, String> {
+    let ips = expand_ranges(&cidrs)?;
+    if let Some(ports) = ports.filter(|p| !p.is_empty()) {
+        options.ports = ports;
+    }
+    
+    info!("🔍 Scanning {} range(s) ({} hosts) on ports {:?}", cidrs.len(), ips.len(), options.ports);
+    scan_ips(ips, options, app).await
+}
+
+/// Probe every IP concurrently and collect the ones with a ZK port open.
+/// With an AppHandle, progress and each discovered device are streamed as events.
+async fn scan_ips(
+    ips: Vec<IpAddr>,
+    options: ScanOptions,
+    app: Option<AppHandle>,
+) -> Result<Vec<BiometricDevice>, String> {
+    if options.ports.is_empty() {
+        return Err("No ports to scan".to_string());
+    }
+    
     // Create semaphore for concurrent connections
-    let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT));
+    let semaphore = Arc::new(Semaphore::new(options.max_concurrent.clamp(1, 1000)));
+    let options = Arc::new(options);
+    let total = ips.len();
+    let checked = Arc::new(AtomicUsize::new(0));
+    let found = Arc::new(AtomicUsize::new(0));
     
-    // Spawn tasks for all IPs in all subnets
+    // Spawn tasks for all IPs
     let mut handles = Vec::new();
     
-    for (a, b, c) in &subnets_to_scan {
-        for i in 1..255u8 {
-            let ip = format!("{}.{}.{}.{}", a, b, c, i);
-            let sem = Arc::clone(&semaphore);
+    for ip in ips {
+        let sem = Arc::clone(&semaphore);
+        let options = Arc::clone(&options);
+        let checked = Arc::clone(&checked);
+        let found = Arc::clone(&found);
+        let app = app.clone();
+        
+        let handle = tokio::spawn(async move {
+            let device = check_biometric_ip(ip.to_string(), options, sem).await;
             
-            let handle = tokio::spawn(async move {
-                check_biometric_ip(ip, sem).await
-            });
-            handles.push(handle);
-        }
+            if let Some(app) = app {
+                if let Some(device) = &device {
+                    found.fetch_add(1, Ordering::Relaxed);
+                    let _ = app.emit(EVENT_DEVICE_FOUND, device);
+                }
+                let done = checked.fetch_add(1, Ordering::Relaxed) + 1;
+                if done.is_multiple_of(PROGRESS_EVERY) || done == total {
+                    let _ = app.emit(EVENT_PROGRESS, ScanProgress {
+                        checked: done,
+                        total,
+                        found: found.load(Ordering::Relaxed),
+                    });
+                }
+            }
+            device
+        });
+        handles.push(handle);
     }
     
     info!("🔍 Checking {} IPs...", handles.len());
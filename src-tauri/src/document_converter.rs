@@ -1,8 +1,16 @@
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use tokio::process::Command as TokioCommand;
 use log::info;
+use crate::ffmpeg_manager::ffmpeg_path;
+use crate::latex_pdf::{engine_path, LatexEngine};
+use crate::pdf_merge;
+use crate::tool_installer::{managed_path, ManagedTool};
+use crate::tool_registry::{custom_path, pdf_merge_order, PdfMergeEngine};
+use crate::tool_process::{run_tool, Tool, NOT_INSTALLED, TIMED_OUT};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DocumentInfo {
@@ -20,20 +28,39 @@ pub struct ConversionResult {
     pub output_size: Option<u64>,
 }
 
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PandocOptions {
+    pub reference_doc: Option<String>,  // .docx/.odt/.pptx whose styles the output takes (e.g. the institute template)
+    pub template: Option<String>,       // Pandoc template for HTML/LaTeX output
+    pub toc: Option<bool>,              // Table of contents
+    pub css: Option<String>,            // Stylesheet for HTML output and PDF through an HTML engine
+    pub pdf_engine: Option<String>,     // --pdf-engine; PDF with a stylesheet defaults to wkhtmltopdf
+    pub metadata: Option<BTreeMap<String, Vec<String>>>, // -M key=value: title, author, date, ...; several values make a list
+    pub variables: Option<BTreeMap<String, String>>, // -V key=value for the template
+    pub lua_filters: Option<Vec<String>>,            // Applied in order
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolStatus {
     pub name: String,
     pub available: bool,
     pub version: Option<String>,
     pub path: Option<String>,
+    #[serde(default)]
+    pub installable: bool,              // Can be downloaded with tool_installer on this platform
 }
 
 // ============================================================================
 // Cross-Platform Tool Paths
 // ============================================================================
 
-/// Get LibreOffice executable path for current platform
-fn get_soffice_path() -> &'static str {
+/// Get LibreOffice executable path: the user's setting (see tool_registry), else the usual place
+/// for the current platform
+fn get_soffice_path() -> String {
+    if let Some(path) = custom_path("soffice") {
+        return path.to_string_lossy().to_string();
+    }
     #[cfg(target_os = "windows")]
     {
         // Common Windows install paths
@@ -43,31 +70,35 @@ fn get_soffice_path() -> &'static str {
         ];
         for p in paths {
             if Path::new(p).exists() {
-                return p;
+                return p.to_string();
             }
         }
-        "soffice.exe" // Fall back to PATH
+        "soffice.exe".to_string() // Fall back to PATH
     }
     #[cfg(target_os = "macos")]
     {
         let app_path = "/Applications/LibreOffice.app/Contents/MacOS/soffice";
         if Path::new(app_path).exists() {
-            return app_path;
+            return app_path.to_string();
         }
-        "soffice" // Fall back to PATH (Homebrew)
+        "soffice".to_string() // Fall back to PATH (Homebrew)
     }
     #[cfg(target_os = "linux")]
     {
-        "soffice" // Usually in PATH on Linux
+        "soffice".to_string() // Usually in PATH on Linux
     }
     #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
     {
-        "soffice"
+        "soffice".to_string()
     }
 }
 
-/// Get Pandoc executable path for current platform
-fn get_pandoc_path() -> &'static str {
+/// Get Pandoc executable path: the user's setting, the managed download, else the usual place for
+/// the current platform
+fn get_pandoc_path() -> String {
+    if let Some(path) = custom_path("pandoc").or_else(|| managed_path(ManagedTool::Pandoc)) {
+        return path.to_string_lossy().to_string();
+    }
     #[cfg(target_os = "windows")]
     {
         let paths = [
@@ -76,19 +107,37 @@ fn get_pandoc_path() -> &'static str {
         ];
         for p in paths {
             if Path::new(p).exists() {
-                return p;
+                return p.to_string();
             }
         }
-        "pandoc.exe"
+        "pandoc.exe".to_string()
     }
     #[cfg(not(target_os = "windows"))]
     {
-        "pandoc"
+        "pandoc".to_string()
     }
 }
 
-/// Get wkhtmltopdf executable path
-fn get_wkhtmltopdf_path() -> &'static str {
+/// Get qpdf executable path: the user's setting, the managed download, else PATH
+fn get_qpdf_path() -> String {
+    custom_path("qpdf")
+        .or_else(|| managed_path(ManagedTool::Qpdf))
+        .map(|path| path.to_string_lossy().to_string())
+        .unwrap_or_else(|| "qpdf".to_string())
+}
+
+/// Get pdftk executable path: the user's setting, else PATH
+fn get_pdftk_path() -> String {
+    custom_path("pdftk")
+        .map(|path| path.to_string_lossy().to_string())
+        .unwrap_or_else(|| "pdftk".to_string())
+}
+
+/// Get wkhtmltopdf executable path: the user's setting, else the usual place for the current platform
+fn get_wkhtmltopdf_path() -> String {
+    if let Some(path) = custom_path("wkhtmltopdf") {
+        return path.to_string_lossy().to_string();
+    }
     #[cfg(target_os = "windows")]
     {
         let paths = [
@@ -97,14 +146,14 @@ fn get_wkhtmltopdf_path() -> &'static str {
         ];
         for p in paths {
             if Path::new(p).exists() {
-                return p;
+                return p.to_string();
             }
         }
-        "wkhtmltopdf.exe"
+        "wkhtmltopdf.exe".to_string()
     }
     #[cfg(not(target_os = "windows"))]
     {
-        "wkhtmltopdf"
+        "wkhtmltopdf".to_string()
     }
 }
 
@@ -114,41 +163,70 @@ pub fn check_tools() -> Vec<ToolStatus> {
     
     // Check LibreOffice (soffice)
     let soffice_path = get_soffice_path();
-    let soffice = check_command(soffice_path, &["--version"]);
+    let soffice = check_command(&soffice_path, &["--version"]);
     tools.push(ToolStatus {
         name: "LibreOffice".to_string(),
         available: soffice.is_some(),
         version: soffice,
-        path: if Path::new(soffice_path).exists() { Some(soffice_path.to_string()) } else { None },
+        path: if Path::new(&soffice_path).exists() { Some(soffice_path) } else { None },
+        installable: false,
     });
     
     // Check Pandoc
     let pandoc_path = get_pandoc_path();
-    let pandoc = check_command(pandoc_path, &["--version"]);
+    let pandoc = check_command(&pandoc_path, &["--version"]);
     tools.push(ToolStatus {
         name: "Pandoc".to_string(),
         available: pandoc.is_some(),
         version: pandoc.map(|v| v.lines().next().unwrap_or("").to_string()),
-        path: if Path::new(pandoc_path).exists() { Some(pandoc_path.to_string()) } else { None },
+        path: if Path::new(&pandoc_path).exists() { Some(pandoc_path) } else { None },
+        installable: ManagedTool::Pandoc.install_supported(),
     });
     
     // Check wkhtmltopdf
     let wk_path = get_wkhtmltopdf_path();
-    let wkhtmltopdf = check_command(wk_path, &["--version"]);
+    let wkhtmltopdf = check_command(&wk_path, &["--version"]);
     tools.push(ToolStatus {
         name: "wkhtmltopdf".to_string(),
         available: wkhtmltopdf.is_some(),
         version: wkhtmltopdf,
         path: None,
+        installable: false,
     });
+
+    // Check qpdf (managed download or PATH)
+    let qpdf_path = get_qpdf_path();
+    let qpdf = check_command(&qpdf_path, &["--version"]);
+    tools.push(ToolStatus {
+        name: "qpdf".to_string(),
+        available: qpdf.is_some(),
+        version: qpdf.map(|v| v.lines().next().unwrap_or("").to_string()),
+        path: if Path::new(&qpdf_path).is_absolute() { Some(qpdf_path) } else { None },
+        installable: ManagedTool::Qpdf.install_supported(),
+    });
+
+    // Check LaTeX engines (tectonic, xelatex, pdflatex)
+    for engine in LatexEngine::ALL {
+        let engine_path = engine_path(engine);
+        let version = check_command(&engine_path, &["--version"]);
+        tools.push(ToolStatus {
+            name: engine.binary().to_string(),
+            available: version.is_some(),
+            version: version.map(|v| v.lines().next().unwrap_or("").to_string()),
+            path: if Path::new(&engine_path).is_absolute() { Some(engine_path) } else { None },
+            installable: false,
+        });
+    }
     
-    // Check FFmpeg
-    let ffmpeg = check_command("ffmpeg", &["-version"]);
+    // Check FFmpeg (managed download or PATH)
+    let ffmpeg_path = ffmpeg_path();
+    let ffmpeg = check_command(&ffmpeg_path.to_string_lossy(), &["-version"]);
     tools.push(ToolStatus {
         name: "FFmpeg".to_string(),
         available: ffmpeg.is_some(),
         version: ffmpeg.map(|v| v.lines().next().unwrap_or("").to_string()),
-        path: None,
+        path: if ffmpeg_path.is_absolute() { Some(ffmpeg_path.to_string_lossy().to_string()) } else { None },
+        installable: false,             // Managed separately by ffmpeg_manager
     });
     
     tools
@@ -163,11 +241,46 @@ fn check_command(cmd: &str, args: &[&str]) -> Option<String> {
         .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
 }
 
+/// Private LibreOffice user profile in the temp folder, deleted when dropped. soffice will not
+/// start a second conversion while another instance (or the user's open LibreOffice) holds the
+/// default profile, so every conversion brings its own.
+pub struct LibreOfficeProfile(PathBuf);
+
+impl LibreOfficeProfile {
+    pub fn temporary() -> Self {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        let n = NEXT.fetch_add(1, Ordering::SeqCst);
+        LibreOfficeProfile(std::env::temp_dir().join(format!("alagappa-soffice-{}-{}", std::process::id(), n)))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for LibreOfficeProfile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
 // Convert document using LibreOffice
 pub async fn convert_with_libreoffice(
     input_path: String,
     output_format: String,
     output_dir: String,
+) -> Result<ConversionResult, String> {
+    let profile = LibreOfficeProfile::temporary();
+    convert_with_libreoffice_profile(input_path, output_format, output_dir, Some(profile.path())).await
+}
+
+/// Convert with LibreOffice using the given user profile folder (reusing one across files
+/// skips the profile setup soffice does on first start)
+pub async fn convert_with_libreoffice_profile(
+    input_path: String,
+    output_format: String,
+    output_dir: String,
+    profile_dir: Option<&Path>,
 ) -> Result<ConversionResult, String> {
     if !Path::new(&input_path).exists() {
         return Err(format!("File not found: {}", input_path));
@@ -178,24 +291,29 @@ pub async fn convert_with_libreoffice(
     let soffice_path = get_soffice_path();
     let mut cmd = TokioCommand::new(soffice_path);
     cmd.arg("--headless");
+    if let Some(profile_dir) = profile_dir {
+        // -env:UserInstallation takes a file URL with forward slashes, also on Windows
+        let path = profile_dir.to_string_lossy().replace('\\', "/");
+        cmd.arg(format!("-env:UserInstallation=file:///{}", path.trim_start_matches('/')));
+    }
     cmd.arg("--convert-to").arg(&output_format);
     cmd.arg("--outdir").arg(&output_dir);
     cmd.arg(&input_path);
 
-    let output = cmd.output().await
-        .map_err(|e| format!("Failed to run LibreOffice: {}. Is it installed?", e))?;
+    // Determine output filename ("pdf:writer_pdf_Export" still writes a .pdf)
+    let input_name = Path::new(&input_path)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("output");
+    let extension = output_format.split(':').next().unwrap_or(&output_format);
+
+    // Handle path separator for Windows vs Unix
+    let separator = if cfg!(windows) { "\\" } else { "/" };
+    let output_path = format!("{}{}{}.{}", output_dir, separator, input_name, extension);
+
+    let output = run_tool(cmd, Tool::LibreOffice, "LibreOffice (soffice)", &[Path::new(&output_path)]).await?;
 
     if output.status.success() {
-        // Determine output filename
-        let input_name = Path::new(&input_path)
-            .file_stem()
-            .and_then(|s| s.to_str())
-            .unwrap_or("output");
-        
-        // Handle path separator for Windows vs Unix
-        let separator = if cfg!(windows) { "\\" } else { "/" };
-        let output_path = format!("{}{}{}.{}", output_dir, separator, input_name, output_format);
-        
         let output_size = std::fs::metadata(&output_path).map(|m| m.len()).ok();
         
         info!("✅ Document converted: {}", output_path);
@@ -217,10 +335,28 @@ pub async fn convert_with_pandoc(
     output_path: String,
     from_format: Option<String>,
     to_format: Option<String>,
+    options: PandocOptions,
 ) -> Result<ConversionResult, String> {
     if !Path::new(&input_path).exists() {
         return Err(format!("File not found: {}", input_path));
     }
+    let local_file = |value: &Option<String>, what: &str| -> Result<Option<String>, String> {
+        match value.as_deref().map(str::trim).filter(|v| !v.is_empty()) {
+            Some(path) if !Path::new(path).is_file() => Err(format!("{} not found: {}", what, path)),
+            other => Ok(other.map(str::to_string)),
+        }
+    };
+    let reference_doc = local_file(&options.reference_doc, "Reference document")?;
+    let template = local_file(&options.template, "Template")?;
+    // A stylesheet may also be a URL
+    let css = match options.css.as_deref().map(str::trim).filter(|v| !v.is_empty()) {
+        Some(url) if url.starts_with("http://") || url.starts_with("https://") => Some(url.to_string()),
+        _ => local_file(&options.css, "Stylesheet")?,
+    };
+    let mut lua_filters = Vec::new();
+    for filter in options.lua_filters.iter().flatten() {
+        lua_filters.extend(local_file(&Some(filter.clone()), "Lua filter")?);
+    }
 
     info!("📄 Converting with Pandoc: {} -> {}", input_path, output_path);
 
@@ -232,15 +368,55 @@ pub async fn convert_with_pandoc(
     if let Some(from) = from_format {
         cmd.arg("-f").arg(from);
     }
-    if let Some(to) = to_format {
+    if let Some(to) = &to_format {
         cmd.arg("-t").arg(to);
     }
     
     // Enable smart quotes and other niceties
     cmd.arg("--standalone");
 
-    let output = cmd.output().await
-        .map_err(|e| format!("Failed to run Pandoc: {}. Is it installed?", e))?;
+    // Relative image paths are relative to the input, not to the app's working directory
+    if let Some(dir) = Path::new(&input_path).parent().filter(|d| !d.as_os_str().is_empty()) {
+        let separator = if cfg!(windows) { ";" } else { ":" };
+        cmd.arg(format!("--resource-path={}{}.", dir.to_string_lossy(), separator));
+    }
+
+    if let Some(reference_doc) = reference_doc {
+        cmd.arg(format!("--reference-doc={}", reference_doc));
+    }
+    if let Some(template) = template {
+        cmd.arg(format!("--template={}", template));
+    }
+    if options.toc.unwrap_or(false) {
+        cmd.arg("--toc");
+    }
+    // The default LaTeX engine ignores stylesheets, so styled PDFs go through wkhtmltopdf
+    let to_pdf = to_format.as_deref().map_or(
+        output_path.to_lowercase().ends_with(".pdf"),
+        |to| to.eq_ignore_ascii_case("pdf"),
+    );
+    let pdf_engine = options.pdf_engine.as_deref().map(str::trim).filter(|e| !e.is_empty()).map(str::to_string)
+        .or_else(|| (to_pdf && css.is_some()).then(get_wkhtmltopdf_path));
+    if let Some(pdf_engine) = pdf_engine {
+        cmd.arg(format!("--pdf-engine={}", pdf_engine));
+    }
+    if let Some(css) = css {
+        cmd.arg(format!("--css={}", css));
+    }
+    // Repeating a key gives Pandoc a list (several authors)
+    for (key, values) in options.metadata.iter().flatten().filter(|(k, _)| !k.trim().is_empty()) {
+        for value in values {
+            cmd.arg(format!("--metadata={}:{}", key, value));
+        }
+    }
+    for (key, value) in options.variables.iter().flatten().filter(|(k, _)| !k.trim().is_empty()) {
+        cmd.arg(format!("--variable={}:{}", key, value));
+    }
+    for filter in lua_filters {
+        cmd.arg(format!("--lua-filter={}", filter));
+    }
+
+    let output = run_tool(cmd, Tool::Pandoc, "Pandoc", &[Path::new(&output_path)]).await?;
 
     if output.status.success() {
         let output_size = std::fs::metadata(&output_path).map(|m| m.len()).ok();
@@ -259,7 +435,6 @@ pub async fn convert_with_pandoc(
 }
 
 // Convert HTML to PDF using wkhtmltopdf
-#[allow(dead_code)]
 pub async fn html_to_pdf(
     input_path: String,
     output_path: String,
@@ -273,11 +448,12 @@ pub async fn html_to_pdf(
     let wk_path = get_wkhtmltopdf_path();
     let mut cmd = TokioCommand::new(wk_path);
     cmd.arg("--quiet");
+    // Stylesheets and images next to the HTML file are blocked by default since 0.12.6
+    cmd.arg("--enable-local-file-access");
     cmd.arg(&input_path);
     cmd.arg(&output_path);
 
-    let output = cmd.output().await
-        .map_err(|e| format!("Failed to run wkhtmltopdf: {}. Is it installed?", e))?;
+    let output = run_tool(cmd, Tool::Wkhtmltopdf, "wkhtmltopdf", &[Path::new(&output_path)]).await?;
 
     if output.status.success() {
         let output_size = std::fs::metadata(&output_path).map(|m| m.len()).ok();
@@ -294,8 +470,26 @@ pub async fn html_to_pdf(
     }
 }
 
-// Merge multiple PDFs (requires pdftk or qpdf)
-#[allow(dead_code)]
+/// Run qpdf or pdftk to merge; errors carry NOT_INSTALLED / TIMED_OUT from run_tool
+async fn merge_with_tool(engine: PdfMergeEngine, input_paths: &[String], output_path: &str) -> Result<(), String> {
+    let cmd = if engine == PdfMergeEngine::Qpdf {
+        let mut cmd = TokioCommand::new(get_qpdf_path());
+        cmd.arg("--empty").arg("--pages").args(input_paths).arg("--").arg(output_path);
+        cmd
+    } else {
+        let mut cmd = TokioCommand::new(get_pdftk_path());
+        cmd.args(input_paths).arg("cat").arg("output").arg(output_path);
+        cmd
+    };
+    let output = run_tool(cmd, Tool::PdfMerge, engine.label(), &[Path::new(output_path)]).await?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!("PDF merge failed: {}", String::from_utf8_lossy(&output.stderr)))
+    }
+}
+
+// Merge multiple PDFs with the engines in the configured order (default qpdf, then pdftk)
 pub async fn merge_pdfs(
     input_paths: Vec<String>,
     output_path: String,
@@ -312,54 +506,44 @@ pub async fn merge_pdfs(
 
     info!("📄 Merging {} PDFs", input_paths.len());
 
-    // Try qpdf first (more commonly available on macOS)
-    let mut cmd = TokioCommand::new("qpdf");
-    cmd.arg("--empty");
-    cmd.arg("--pages");
-    for path in &input_paths {
-        cmd.arg(path);
-    }
-    cmd.arg("--");
-    cmd.arg(&output_path);
-
-    let output = cmd.output().await;
-    
-    match output {
-        Ok(o) if o.status.success() => {
-            let output_size = std::fs::metadata(&output_path).map(|m| m.len()).ok();
-            return Ok(ConversionResult {
-                success: true,
-                output_path,
-                message: format!("Merged {} PDFs successfully", input_paths.len()),
-                output_size,
-            });
-        }
-        _ => {
-            // Try pdftk as fallback
-            let mut cmd = TokioCommand::new("pdftk");
-            for path in &input_paths {
-                cmd.arg(path);
+    let order = pdf_merge_order();
+    let mut errors = Vec::new();
+    for engine in &order {
+        let attempt = match engine {
+            PdfMergeEngine::Bundled => {
+                let (inputs, output) = (input_paths.clone(), output_path.clone());
+                tokio::task::spawn_blocking(move || pdf_merge::merge_pdfs(inputs, output, None, false))
+                    .await
+                    .map_err(|e| format!("Task error: {}", e))
+                    .and_then(|result| result.map(|_| ()))
             }
-            cmd.arg("cat");
-            cmd.arg("output").arg(&output_path);
-
-            let output = cmd.output().await
-                .map_err(|e| format!("No PDF tools available (qpdf or pdftk): {}", e))?;
-
-            if output.status.success() {
+            tool => merge_with_tool(*tool, &input_paths, &output_path).await,
+        };
+        match attempt {
+            Ok(()) => {
                 let output_size = std::fs::metadata(&output_path).map(|m| m.len()).ok();
-                Ok(ConversionResult {
+                return Ok(ConversionResult {
                     success: true,
                     output_path,
-                    message: format!("Merged {} PDFs successfully", input_paths.len()),
+                    message: format!("Merged {} PDFs successfully with {}", input_paths.len(), engine.label()),
                     output_size,
-                })
-            } else {
-                let error = String::from_utf8_lossy(&output.stderr);
-                Err(format!("PDF merge failed: {}", error))
+                });
             }
+            // A hung tool is not retried with the next one
+            Err(e) if e.starts_with(TIMED_OUT) => return Err(e),
+            Err(e) => errors.push(e),
         }
     }
+
+    // When nothing ran, say what to install; otherwise the first real failure is the useful error
+    match errors.iter().find(|e| !e.starts_with(NOT_INSTALLED)) {
+        Some(error) => Err(error.clone()),
+        None => Err(format!(
+            "{}: none of {} was found. Install one of them to merge PDFs",
+            NOT_INSTALLED,
+            order.iter().map(PdfMergeEngine::label).collect::<Vec<_>>().join(", ")
+        )),
+    }
 }
 
 // Get document info
@@ -0,0 +1,295 @@
+//! DOCX Reader - the content of a Word document as a flat list of blocks
+//! Covers what simple documents use: paragraphs (heading level, list marker, bold, centered),
+//! tables (cell text) and inline pictures. Layout features it cannot represent are listed in
+//! `unsupported`, so callers can say the result is simplified.
+
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::io::Read;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use zip::ZipArchive;
+
+/// EMU (English Metric Units) per PDF point
+const EMU_PER_POINT: f32 = 12700.0;
+
+#[derive(Debug, Clone, Default)]
+pub struct DocxParagraph {
+    pub text: String,                   // Line breaks as '\n'
+    pub heading: Option<usize>,         // 1 = Title/Heading 1
+    pub list: Option<(usize, String)>,  // Nesting level and marker ("-", "3.", "b.")
+    pub bold: bool,                     // Every run is bold
+    pub centered: bool,
+}
+
+#[derive(Debug, Clone)]
+pub enum DocxBlock {
+    Paragraph(DocxParagraph),
+    Table(Vec<Vec<String>>),
+    Image { data: Vec<u8>, size: Option<(f32, f32)> }, // Displayed size in points
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DocxDocument {
+    pub blocks: Vec<DocxBlock>,
+    pub unsupported: BTreeSet<&'static str>,
+}
+
+fn attr(element: &BytesStart, name: &[u8]) -> Option<String> {
+    element.attributes().flatten()
+        .find(|a| a.key.as_ref() == name)
+        .and_then(|a| a.unescape_value().ok().map(|v| v.to_string()))
+}
+
+fn read_entry(archive: &mut ZipArchive<fs::File>, name: &str) -> Option<Vec<u8>> {
+    let mut entry = archive.by_name(name).ok()?;
+    let mut bytes = Vec::new();
+    entry.read_to_end(&mut bytes).ok()?;
+    Some(bytes)
+}
+
+/// Walk every start/empty element of an XML part
+fn for_each_element(xml: &[u8], mut visit: impl FnMut(&BytesStart)) {
+    let mut reader = Reader::from_reader(xml);
+    let mut buffer = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buffer) {
+            Ok(Event::Start(element)) | Ok(Event::Empty(element)) => visit(&element),
+            Ok(Event::Eof) | Err(_) => break,
+            _ => {}
+        }
+        buffer.clear();
+    }
+}
+
+/// Style id -> heading level, from the style names ("heading 2", "Title")
+fn heading_styles(xml: &[u8]) -> HashMap<String, usize> {
+    let mut styles = HashMap::new();
+    let mut current = None;
+    for_each_element(xml, |element| match element.name().as_ref() {
+        b"w:style" => current = attr(element, b"w:styleId"),
+        b"w:name" => {
+            let name = attr(element, b"w:val").unwrap_or_default().to_lowercase();
+            let level = if name == "title" { Some(1) } else { name.strip_prefix("heading ").and_then(|n| n.parse().ok()) };
+            if let (Some(id), Some(level)) = (current.clone(), level) {
+                styles.insert(id, level);
+            }
+        }
+        _ => {}
+    });
+    styles
+}
+
+/// numId -> number format per level ("bullet", "decimal", "lowerLetter", ...)
+fn number_formats(xml: &[u8]) -> HashMap<String, Vec<String>> {
+    let mut abstract_formats: HashMap<String, Vec<String>> = HashMap::new();
+    let mut nums: Vec<(String, String)> = Vec::new();
+    let (mut current_abstract, mut current_num) = (None, None);
+    for_each_element(xml, |element| match element.name().as_ref() {
+        b"w:abstractNum" => current_abstract = attr(element, b"w:abstractNumId"),
+        b"w:numFmt" => {
+            if let Some(id) = &current_abstract {
+                abstract_formats.entry(id.clone()).or_default().push(attr(element, b"w:val").unwrap_or_default());
+            }
+        }
+        b"w:num" => current_num = attr(element, b"w:numId"),
+        b"w:abstractNumId" => {
+            if let (Some(num), Some(id)) = (current_num.clone(), attr(element, b"w:val")) {
+                nums.push((num, id));
+            }
+        }
+        _ => {}
+    });
+    nums.into_iter()
+        .filter_map(|(num, id)| abstract_formats.get(&id).map(|formats| (num, formats.clone())))
+        .collect()
+}
+
+fn roman(mut n: u32) -> String {
+    let numerals = [(1000, "m"), (900, "cm"), (500, "d"), (400, "cd"), (100, "c"), (90, "xc"),
+        (50, "l"), (40, "xl"), (10, "x"), (9, "ix"), (5, "v"), (4, "iv"), (1, "i")];
+    let mut out = String::new();
+    for (value, numeral) in numerals {
+        while n >= value {
+            out.push_str(numeral);
+            n -= value;
+        }
+    }
+    out
+}
+
+fn list_marker(format: &str, count: u32) -> String {
+    let letter = |base: u8| ((base + ((count - 1) % 26) as u8) as char).to_string();
+    match format {
+        "decimal" => format!("{}.", count),
+        "lowerLetter" => format!("{}.", letter(b'a')),
+        "upperLetter" => format!("{}.", letter(b'A')),
+        "lowerRoman" => format!("{}.", roman(count)),
+        "upperRoman" => format!("{}.", roman(count).to_uppercase()),
+        _ => "-".to_string(),
+    }
+}
+
+/// Read a .docx file
+pub fn read_docx(path: &str) -> Result<DocxDocument, String> {
+    let file = fs::File::open(path).map_err(|e| format!("Failed to open document: {}", e))?;
+    let mut archive = ZipArchive::new(file).map_err(|e| format!("Not a valid DOCX file: {}", e))?;
+    let body = read_entry(&mut archive, "word/document.xml").ok_or("Not a valid DOCX file: word/document.xml is missing")?;
+    let styles = read_entry(&mut archive, "word/styles.xml").map(|x| heading_styles(&x)).unwrap_or_default();
+    let formats = read_entry(&mut archive, "word/numbering.xml").map(|x| number_formats(&x)).unwrap_or_default();
+
+    // Relationship id -> picture path inside the zip
+    let mut targets = HashMap::new();
+    if let Some(rels) = read_entry(&mut archive, "word/_rels/document.xml.rels") {
+        for_each_element(&rels, |element| {
+            if let (Some(id), Some(target)) = (attr(element, b"Id"), attr(element, b"Target")) {
+                if attr(element, b"TargetMode").as_deref() != Some("External") {
+                    let target = target.strip_prefix('/').map(str::to_string).unwrap_or_else(|| format!("word/{}", target));
+                    targets.insert(id, target);
+                }
+            }
+        });
+    }
+
+    let mut document = DocxDocument::default();
+    let mut reader = Reader::from_reader(body.as_slice());
+    let mut buffer = Vec::new();
+
+    let mut paragraph = DocxParagraph::default();
+    let (mut in_properties, mut in_text) = (false, false);
+    let (mut in_run, mut run_bold, mut bold_chars, mut plain_chars) = (false, false, 0, 0);
+    let mut numbering: Option<(String, usize)> = None;
+    let mut counters: HashMap<(String, usize), u32> = HashMap::new();
+    let mut pictures: Vec<(String, Option<(f32, f32)>)> = Vec::new();
+    let mut extent = None;
+    let (mut table_depth, mut rows, mut row, mut cell) = (0usize, Vec::new(), Vec::new(), String::new());
+
+    loop {
+        let event = reader.read_event_into(&mut buffer)
+            .map_err(|e| format!("Failed to read document.xml: {}", e))?;
+        match event {
+            Event::Start(ref element) | Event::Empty(ref element) => {
+                let is_start = matches!(event, Event::Start(_));
+                match element.name().as_ref() {
+                    // Text boxes are skipped (their paragraphs sit inside the anchoring one); mc:Fallback
+                    // repeats a drawing in legacy form
+                    b"w:txbxContent" | b"mc:Fallback" if is_start => {
+                        if element.name().as_ref() == b"w:txbxContent" {
+                            document.unsupported.insert("text boxes");
+                        }
+                        reader.read_to_end_into(element.to_end().name(), &mut Vec::new())
+                            .map_err(|e| format!("Failed to read document.xml: {}", e))?;
+                    }
+                    b"w:pPr" => in_properties = is_start,
+                    b"w:r" => (in_run, run_bold) = (true, false),
+                    b"w:t" => in_text = is_start,
+                    b"w:b" if in_run => run_bold = !matches!(attr(element, b"w:val").as_deref(), Some("0" | "false")),
+                    b"w:pStyle" => paragraph.heading = attr(element, b"w:val").and_then(|id| styles.get(&id).copied()),
+                    // Level 9 is body text
+                    b"w:outlineLvl" => {
+                        let level = attr(element, b"w:val").and_then(|v| v.parse::<usize>().ok()).filter(|l| *l < 9);
+                        paragraph.heading = paragraph.heading.or(level.map(|l| l + 1));
+                    }
+                    b"w:jc" if in_properties => {
+                        paragraph.centered = attr(element, b"w:val").as_deref() == Some("center");
+                    }
+                    b"w:ilvl" => numbering.get_or_insert((String::new(), 0)).1 = attr(element, b"w:val").and_then(|v| v.parse().ok()).unwrap_or(0),
+                    b"w:numId" => numbering.get_or_insert((String::new(), 0)).0 = attr(element, b"w:val").unwrap_or_default(),
+                    b"w:tab" if in_run => paragraph.text.push(' '),
+                    b"w:br" | b"w:cr" if in_run => paragraph.text.push('\n'),
+                    b"w:tbl" if is_start => {
+                        table_depth += 1;
+                        if table_depth > 1 {
+                            document.unsupported.insert("nested tables");
+                        }
+                    }
+                    b"w:tr" if table_depth == 1 => row = Vec::new(),
+                    b"w:tc" if table_depth == 1 => cell.clear(),
+                    b"wp:extent" => {
+                        let size = |name: &[u8]| attr(element, name).and_then(|v| v.parse::<f32>().ok()).map(|v| v / EMU_PER_POINT);
+                        extent = size(b"cx").zip(size(b"cy"));
+                    }
+                    b"a:blip" => {
+                        if let Some(id) = attr(element, b"r:embed") {
+                            pictures.push((id, extent.take()));
+                        }
+                    }
+                    b"wp:anchor" => { document.unsupported.insert("floating pictures and shapes"); }
+                    b"m:oMath" | b"m:oMathPara" => { document.unsupported.insert("equations"); }
+                    b"c:chart" => { document.unsupported.insert("charts"); }
+                    b"w:object" | b"v:shape" => { document.unsupported.insert("embedded objects"); }
+                    b"w:footnoteReference" | b"w:endnoteReference" => { document.unsupported.insert("footnotes"); }
+                    b"w:headerReference" | b"w:footerReference" => { document.unsupported.insert("headers and footers"); }
+                    b"w:vMerge" | b"w:gridSpan" => { document.unsupported.insert("merged table cells"); }
+                    b"w:ins" | b"w:del" => { document.unsupported.insert("tracked changes"); }
+                    b"w:cols" if attr(element, b"w:num").and_then(|n| n.parse::<u32>().ok()).unwrap_or(1) > 1 => {
+                        document.unsupported.insert("multiple columns");
+                    }
+                    _ => {}
+                }
+            }
+            // Only w:t holds document text (w:instrText holds field codes, w:delText deletions)
+            Event::Text(text) if in_text => {
+                let text = text.unescape().map_err(|e| format!("Invalid text: {}", e))?;
+                if run_bold { bold_chars += text.len() } else { plain_chars += text.len() }
+                paragraph.text.push_str(&text);
+            }
+            Event::End(element) => match element.name().as_ref() {
+                b"w:pPr" => in_properties = false,
+                b"w:r" => in_run = false,
+                b"w:t" => in_text = false,
+                b"w:p" => {
+                    let mut finished = std::mem::take(&mut paragraph);
+                    finished.bold = bold_chars > 0 && plain_chars == 0;
+                    (bold_chars, plain_chars) = (0, 0);
+                    if let Some((id, level)) = numbering.take().filter(|(id, _)| !id.is_empty() && id != "0") {
+                        let count = counters.entry((id.clone(), level)).or_insert(0);
+                        *count += 1;
+                        let count = *count;
+                        // A new item restarts the numbering of deeper levels
+                        counters.retain(|(other, deeper), _| *other != id || *deeper <= level);
+                        let format = formats.get(&id).and_then(|f| f.get(level)).map(String::as_str).unwrap_or("bullet");
+                        finished.list = Some((level, list_marker(format, count)));
+                    }
+                    if table_depth > 0 {
+                        if !cell.is_empty() {
+                            cell.push(' ');
+                        }
+                        cell.push_str(finished.text.trim());
+                    } else {
+                        document.blocks.push(DocxBlock::Paragraph(finished));
+                    }
+                    for (id, size) in pictures.drain(..) {
+                        if table_depth > 0 {
+                            document.unsupported.insert("pictures inside tables");
+                        } else if let Some(data) = targets.get(&id).and_then(|t| read_entry(&mut archive, t)) {
+                            document.blocks.push(DocxBlock::Image { data, size });
+                        }
+                    }
+                }
+                b"w:tc" if table_depth == 1 => row.push(std::mem::take(&mut cell)),
+                b"w:tr" if table_depth == 1 => rows.push(std::mem::take(&mut row)),
+                b"w:tbl" => {
+                    table_depth = table_depth.saturating_sub(1);
+                    if table_depth == 0 && !rows.is_empty() {
+                        document.blocks.push(DocxBlock::Table(std::mem::take(&mut rows)));
+                    }
+                }
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+        buffer.clear();
+    }
+
+    let has_non_latin = document.blocks.iter().any(|block| match block {
+        DocxBlock::Paragraph(p) => p.text.chars().any(|c| c as u32 > 255),
+        DocxBlock::Table(rows) => rows.iter().flatten().any(|t| t.chars().any(|c| c as u32 > 255)),
+        DocxBlock::Image { .. } => false,
+    });
+    if has_non_latin {
+        document.unsupported.insert("non-Latin text (e.g. Tamil)");
+    }
+    Ok(document)
+}
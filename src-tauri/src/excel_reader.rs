@@ -0,0 +1,197 @@
+//! Excel Reader - read one sheet of an xlsx/xls/ods workbook with explicit formula handling
+//! calamine does not calculate formulas: "values" exports the results Excel saved with the file,
+//! "text" exports the formulas themselves (=SUM(B2:B9)). Formula cells are reported either way.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::BufReader;
+use log::info;
+use calamine::{open_workbook, Ods, Reader, Xls, Xlsx};
+use crate::bundled_converter::ConversionResult;
+use crate::csv_dialect::{read_csv, CsvDialect};
+use crate::file_sniff::{sniff, workbook_extension};
+
+/// Formula addresses listed in a result; the message still gives the full count
+const MAX_LISTED_FORMULAS: usize = 500;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SheetExportResult {
+    #[serde(flatten)]
+    pub result: ConversionResult,
+    pub formula_cells: Vec<String>,     // A1-style addresses of cells holding formulas
+}
+
+pub struct SheetData {
+    pub rows: Vec<Vec<String>>,
+    pub formula_cells: Vec<String>,
+    pub uncalculated: usize,            // Formula cells without a saved result (exported empty as values)
+}
+
+impl SheetData {
+    /// Wrap a conversion result, noting formula cells in the message
+    pub fn report(mut self, mut result: ConversionResult, formulas_as_text: bool) -> SheetExportResult {
+        if !self.formula_cells.is_empty() {
+            let mode = if formulas_as_text { "exported as formula text" } else { "exported as saved results" };
+            result.message = format!("{} ({} formula cells {})", result.message, self.formula_cells.len(), mode);
+            if self.uncalculated > 0 && !formulas_as_text {
+                result.message.push_str(&format!(
+                    "; {} have no saved result - open and save the file in Excel to calculate them",
+                    self.uncalculated
+                ));
+            }
+        }
+        self.formula_cells.truncate(MAX_LISTED_FORMULAS);
+        SheetExportResult { result, formula_cells: self.formula_cells }
+    }
+}
+
+/// Column letters for a 0-based index (0 -> A, 26 -> AA)
+fn column_name(mut col: u32) -> String {
+    let mut name = Vec::new();
+    loop {
+        name.push(b'A' + (col % 26) as u8);
+        if col < 26 {
+            break;
+        }
+        col = col / 26 - 1;
+    }
+    name.reverse();
+    String::from_utf8(name).unwrap_or_default()
+}
+
+/// `formulas` is "values" (default) or "text"
+pub fn parse_formula_mode(formulas: Option<&str>) -> Result<bool, String> {
+    match formulas.map(str::to_lowercase).as_deref() {
+        None | Some("") | Some("values") => Ok(false),
+        Some("text") => Ok(true),
+        Some(other) => Err(format!("Unknown formula mode: {} (use values or text)", other)),
+    }
+}
+
+fn extract_sheet_data<R: Reader<BufReader<fs::File>>>(
+    workbook: &mut R,
+    sheet_index: Option<usize>,
+    formulas_as_text: bool,
+) -> Result<SheetData, String> {
+    let sheets = workbook.sheet_names().to_owned();
+    if sheets.is_empty() {
+        return Err("No sheets found in workbook".to_string());
+    }
+
+    let sheet_name = sheets.get(sheet_index.unwrap_or(0))
+        .ok_or("Sheet not found")?
+        .clone();
+
+    let range = workbook.worksheet_range(&sheet_name)
+        .map_err(|e| format!("Failed to read sheet: {:?}", e))?;
+
+    let mut data: Vec<Vec<String>> = range.rows()
+        .map(|row| row.iter().map(|cell| cell.to_string()).collect())
+        .collect();
+
+    // Formulas live in their own range, which may start elsewhere than the values
+    let (start_row, start_col) = range.start().unwrap_or((0, 0));
+    let mut formula_cells = Vec::new();
+    let mut uncalculated = 0;
+    if let Ok(formulas) = workbook.worksheet_formula(&sheet_name) {
+        let (formula_row, formula_col) = formulas.start().unwrap_or((0, 0));
+        for (row, col, formula) in formulas.used_cells() {
+            if formula.is_empty() {
+                continue;
+            }
+            let (row, col) = (formula_row + row as u32, formula_col + col as u32);
+            formula_cells.push(format!("{}{}", column_name(col), row + 1));
+            if row < start_row || col < start_col {
+                continue;
+            }
+            let (r, c) = ((row - start_row) as usize, (col - start_col) as usize);
+            if !matches!(data.get(r).and_then(|cells| cells.get(c)), Some(value) if !value.is_empty()) {
+                uncalculated += 1;
+            }
+            if formulas_as_text {
+                if data.len() <= r {
+                    data.resize(r + 1, Vec::new());
+                }
+                if data[r].len() <= c {
+                    data[r].resize(c + 1, String::new());
+                }
+                data[r][c] = format!("={}", formula);
+            }
+        }
+    }
+
+    Ok(SheetData { rows: data, formula_cells, uncalculated })
+}
+
+/// Read one sheet (first by default) of an xlsx, xls or ods workbook; the type is detected
+/// from the content, so an xlsx saved as .xls still opens
+pub fn read_sheet(input_path: &str, sheet_index: Option<usize>, formulas_as_text: bool) -> Result<SheetData, String> {
+    match workbook_extension(input_path)? {
+        "xlsx" => {
+            let mut workbook: Xlsx<_> = open_workbook(input_path)
+                .map_err(|e| format!("Failed to open Excel file: {}", e))?;
+            extract_sheet_data(&mut workbook, sheet_index, formulas_as_text)
+        }
+        "xls" => {
+            let mut workbook: Xls<_> = open_workbook(input_path)
+                .map_err(|e| format!("Failed to open Excel file: {}", e))?;
+            extract_sheet_data(&mut workbook, sheet_index, formulas_as_text)
+        }
+        "ods" => {
+            let mut workbook: Ods<_> = open_workbook(input_path)
+                .map_err(|e| format!("Failed to open ODS file: {}", e))?;
+            extract_sheet_data(&mut workbook, sheet_index, formulas_as_text)
+        }
+        other => Err(format!("Unsupported format: {}", other)),
+    }
+}
+
+/// Headers and rows of a CSV file or of one sheet (first by default) of a workbook
+pub fn read_table(path: &str, sheet_index: Option<usize>) -> Result<(Vec<String>, Vec<Vec<String>>), String> {
+    if sniff(path)?.workbook_extension().is_some() {
+        let mut rows = read_sheet(path, sheet_index, false)?.rows.into_iter();
+        let headers = rows.next().map(|h| h.into_iter().map(|c| c.trim().to_string()).collect()).unwrap_or_default();
+        Ok((headers, rows.collect()))
+    } else {
+        let table = read_csv(path, &CsvDialect::default())?;
+        Ok((table.headers, table.rows))
+    }
+}
+
+/// Convert a sheet to a JSON array of records keyed by the first row
+pub fn excel_to_json(
+    input_path: String,
+    output_path: String,
+    sheet_index: Option<usize>,
+    formulas: Option<String>,
+) -> Result<SheetExportResult, String> {
+    info!("📊 Converting Excel to JSON (bundled)");
+
+    let formulas_as_text = parse_formula_mode(formulas.as_deref())?;
+    let sheet = read_sheet(&input_path, sheet_index, formulas_as_text)?;
+
+    let mut rows = sheet.rows.iter();
+    let headers: Vec<String> = rows.next().map(|h| h.iter().map(|c| c.trim().to_string()).collect()).unwrap_or_default();
+    let records: Vec<serde_json::Map<String, serde_json::Value>> = rows
+        .map(|row| headers.iter().enumerate()
+            .filter(|(_, header)| !header.is_empty())
+            .map(|(i, header)| (header.clone(), serde_json::Value::String(row.get(i).cloned().unwrap_or_default())))
+            .collect())
+        .collect();
+
+    let json = serde_json::to_string_pretty(&records)
+        .map_err(|e| format!("Failed to serialize JSON: {}", e))?;
+    fs::write(&output_path, json)
+        .map_err(|e| format!("Failed to write JSON: {}", e))?;
+
+    let output_size = fs::metadata(&output_path).map(|m| m.len()).ok();
+
+    info!("✅ Excel converted to JSON: {}", output_path);
+    let result = ConversionResult {
+        success: true,
+        output_path,
+        message: format!("Converted {} records to JSON", records.len()),
+        output_size,
+    };
+    Ok(sheet.report(result, formulas_as_text))
+}
@@ -0,0 +1,175 @@
+//! FFmpeg Encoders - detect usable hardware H.264 encoders (VideoToolbox, NVENC, QSV, AMF, VAAPI)
+//! and which software encoders (x265, SVT-AV1, ...) the installed ffmpeg was built with.
+//! An encoder being compiled into ffmpeg doesn't mean the GPU/driver is present, so each
+//! candidate is verified with a tiny test encode. The result is cached for the app's lifetime.
+
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+use std::sync::OnceLock;
+use log::info;
+use crate::ffmpeg_manager::ffmpeg_path;
+
+const VAAPI_DEVICE: &str = "/dev/dri/renderD128";
+
+static DETECTED: OnceLock<HardwareEncoders> = OnceLock::new();
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HardwareEncoder {
+    pub name: String,    // ffmpeg encoder, e.g. "h264_nvenc"
+    pub vendor: String,  // Apple, NVIDIA, Intel, AMD, VAAPI
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HardwareEncoders {
+    pub compiled: Vec<String>,            // Hardware encoders ffmpeg was built with
+    pub usable: Vec<HardwareEncoder>,     // Passed a test encode on this machine
+    pub preferred: Option<HardwareEncoder>,
+}
+
+/// Platform candidates in order of preference
+fn candidates() -> Vec<(&'static str, &'static str)> {
+    if cfg!(target_os = "macos") {
+        vec![("h264_videotoolbox", "Apple")]
+    } else if cfg!(target_os = "windows") {
+        vec![("h264_nvenc", "NVIDIA"), ("h264_qsv", "Intel"), ("h264_amf", "AMD")]
+    } else {
+        vec![("h264_nvenc", "NVIDIA"), ("h264_qsv", "Intel"), ("h264_vaapi", "VAAPI")]
+    }
+}
+
+impl HardwareEncoder {
+    /// Options that must come before `-i` (device setup)
+    pub fn input_args(&self) -> Vec<String> {
+        if self.name.ends_with("_vaapi") {
+            vec!["-vaapi_device".to_string(), VAAPI_DEVICE.to_string()]
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Filter appended to the -vf chain (frames must be uploaded to the GPU for VAAPI)
+    pub fn filter_suffix(&self) -> Option<&'static str> {
+        if self.name.ends_with("_vaapi") { Some("format=nv12,hwupload") } else { None }
+    }
+
+    /// Quality options equivalent to CRF 18/23/28 for high/medium/low
+    pub fn quality_args(&self, quality: &str) -> Vec<String> {
+        let level = match quality.to_lowercase().as_str() {
+            "high" => 0,
+            "low" => 2,
+            _ => 1,
+        };
+        let (flag, values) = match self.name.as_str() {
+            "h264_videotoolbox" => ("-q:v", ["75", "60", "45"]),  // 1-100, higher is better
+            "h264_nvenc" => ("-cq", ["19", "24", "29"]),
+            "h264_qsv" => ("-global_quality", ["19", "24", "29"]),
+            "h264_amf" => ("-qp_i", ["19", "24", "29"]),
+            _ => ("-qp", ["19", "24", "29"]),
+        };
+        vec![flag.to_string(), values[level].to_string()]
+    }
+}
+
+fn compiled_encoders() -> Vec<String> {
+    candidates().iter()
+        .filter(|(name, _)| encoder_available(name))
+        .map(|(name, _)| name.to_string())
+        .collect()
+}
+
+/// Encode 0.1s of black frames to null; fails fast when the GPU/driver is missing
+fn test_encode(encoder: &HardwareEncoder) -> bool {
+    let mut cmd = Command::new(ffmpeg_path());
+    cmd.arg("-hide_banner").arg("-loglevel").arg("error");
+    cmd.args(encoder.input_args());
+    cmd.arg("-f").arg("lavfi").arg("-i").arg("color=c=black:s=256x256:d=0.1");
+    if let Some(filter) = encoder.filter_suffix() {
+        cmd.arg("-vf").arg(filter);
+    }
+    cmd.arg("-c:v").arg(&encoder.name);
+    cmd.arg("-f").arg("null").arg("-");
+    cmd.output().map(|o| o.status.success()).unwrap_or(false)
+}
+
+fn detect() -> HardwareEncoders {
+    let compiled = compiled_encoders();
+    let usable: Vec<HardwareEncoder> = candidates()
+        .into_iter()
+        .filter(|(name, _)| compiled.iter().any(|c| c == name))
+        .map(|(name, vendor)| HardwareEncoder { name: name.to_string(), vendor: vendor.to_string() })
+        .filter(test_encode)
+        .collect();
+
+    info!("🎛️ Hardware encoders: compiled {:?}, usable {:?}",
+        compiled, usable.iter().map(|e| e.name.as_str()).collect::<Vec<_>>());
+
+    HardwareEncoders {
+        preferred: usable.first().cloned(),
+        compiled,
+        usable,
+    }
+}
+
+/// Cached capability probe (first call runs the test encodes)
+pub fn hardware_encoders() -> HardwareEncoders {
+    DETECTED.get_or_init(detect).clone()
+}
+
+pub fn preferred_h264_encoder() -> Option<HardwareEncoder> {
+    hardware_encoders().preferred
+}
+
+/// Software encoders the converter can target
+const SOFTWARE_ENCODERS: &[(&str, &str)] = &[
+    ("libx264", "H.264"),
+    ("libx265", "HEVC"),
+    ("libsvtav1", "AV1"),
+    ("libaom-av1", "AV1"),
+    ("libvpx-vp9", "VP9"),
+    ("libmp3lame", "MP3"),
+    ("libopus", "Opus"),
+    ("libvorbis", "Vorbis"),
+];
+
+static ENCODER_LIST: OnceLock<Vec<String>> = OnceLock::new();
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EncoderSupport {
+    pub name: String,      // ffmpeg encoder, e.g. "libsvtav1"
+    pub codec: String,     // H.264, HEVC, AV1, ...
+    pub available: bool,   // Compiled into the installed ffmpeg
+}
+
+/// Every encoder name reported by `ffmpeg -encoders` (cached once ffmpeg has run successfully)
+fn encoder_list() -> Vec<String> {
+    if let Some(list) = ENCODER_LIST.get() {
+        return list.clone();
+    }
+    let output = match Command::new(ffmpeg_path()).arg("-hide_banner").arg("-encoders").output() {
+        Ok(o) if o.status.success() => o,
+        _ => return Vec::new(),
+    };
+    let list: Vec<String> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .skip_while(|line| !line.trim_start().starts_with("------"))
+        .skip(1)
+        .filter_map(|line| line.split_whitespace().nth(1))
+        .map(|name| name.to_string())
+        .collect();
+    let _ = ENCODER_LIST.set(list.clone());
+    list
+}
+
+pub fn encoder_available(name: &str) -> bool {
+    encoder_list().iter().any(|e| e == name)
+}
+
+pub fn software_encoders() -> Vec<EncoderSupport> {
+    SOFTWARE_ENCODERS.iter()
+        .map(|(name, codec)| EncoderSupport {
+            name: name.to_string(),
+            codec: codec.to_string(),
+            available: encoder_available(name),
+        })
+        .collect()
+}
@@ -0,0 +1,318 @@
+//! FFmpeg Manager - download a static ffmpeg/ffprobe build into app data so staff never install FFmpeg
+//! Builds come from BtbN/FFmpeg-Builds (Windows x64, Linux x64/arm64), checked against the release's
+//! published checksums.sha256, and from eugeneware/ffmpeg-static (macOS arm64/x64), checked against
+//! the SHA-256 digest GitHub publishes per asset. Nothing is extracted before it is verified. Media
+//! code resolves the binaries through ffmpeg_path()/ffprobe_path(), which prefer the managed copy over PATH.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+use log::info;
+use tauri::{AppHandle, Emitter};
+use crate::app_storage::app_data_dir;
+use crate::tool_process::WorkDir;
+use crate::tool_registry::custom_path;
+
+pub const EVENT_DOWNLOAD: &str = "ffmpeg://download_progress";
+
+const RELEASE_URL: &str = "https://github.com/BtbN/FFmpeg-Builds/releases/download/latest";
+const CHECKSUMS_FILE: &str = "checksums.sha256";
+const MACOS_RELEASE_API: &str = "https://api.github.com/repos/eugeneware/ffmpeg-static/releases/latest";
+const MANAGED_DIR: &str = "ffmpeg";
+
+/// Emit at most one progress event per this many bytes
+const PROGRESS_STEP: u64 = 512 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FfmpegStatus {
+    pub available: bool,
+    pub managed: bool,              // Using the copy downloaded into app data
+    pub path: String,
+    pub version: Option<String>,
+    pub download_supported: bool,   // A managed build exists for this platform
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FfmpegDownloadProgress {
+    pub stage: String,              // downloading, verifying, extracting, done
+    pub downloaded: u64,
+    pub total: Option<u64>,
+    pub percentage: Option<f32>,
+}
+
+pub fn exe_name(tool: &str) -> String {
+    if cfg!(windows) { format!("{}.exe", tool) } else { tool.to_string() }
+}
+
+fn managed_dir() -> Option<PathBuf> {
+    app_data_dir().ok().map(|dir| dir.join(MANAGED_DIR))
+}
+
+/// The user's configured binary (see tool_registry), the managed one if installed, otherwise the
+/// bare name (resolved through PATH)
+fn tool_path(tool: &str) -> PathBuf {
+    custom_path(tool)
+        .or_else(|| managed_dir().map(|dir| dir.join(exe_name(tool))).filter(|path| path.is_file()))
+        .unwrap_or_else(|| PathBuf::from(tool))
+}
+
+pub fn ffmpeg_path() -> PathBuf {
+    tool_path("ffmpeg")
+}
+
+pub fn ffprobe_path() -> PathBuf {
+    tool_path("ffprobe")
+}
+
+/// Where this platform's build comes from
+#[derive(Clone, Copy)]
+enum PlatformBuild {
+    Archive(&'static str),      // BtbN archive holding ffmpeg and ffprobe
+    Binaries(&'static str),     // ffmpeg-static "<tool>-<platform>.gz" binaries, e.g. "darwin-arm64"
+}
+
+/// Build for this platform (None where no static build is published)
+fn platform_asset() -> Option<PlatformBuild> {
+    if cfg!(all(target_os = "windows", target_arch = "x86_64")) {
+        Some(PlatformBuild::Archive("ffmpeg-master-latest-win64-gpl.zip"))
+    } else if cfg!(all(target_os = "linux", target_arch = "x86_64")) {
+        Some(PlatformBuild::Archive("ffmpeg-master-latest-linux64-gpl.tar.xz"))
+    } else if cfg!(all(target_os = "linux", target_arch = "aarch64")) {
+        Some(PlatformBuild::Archive("ffmpeg-master-latest-linuxarm64-gpl.tar.xz"))
+    } else if cfg!(all(target_os = "macos", target_arch = "aarch64")) {
+        Some(PlatformBuild::Binaries("darwin-arm64"))
+    } else if cfg!(all(target_os = "macos", target_arch = "x86_64")) {
+        Some(PlatformBuild::Binaries("darwin-x64"))
+    } else {
+        None
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ReleaseAsset {
+    name: String,
+    browser_download_url: String,
+    digest: Option<String>,         // "sha256:<hex>"
+}
+
+#[derive(Debug, Deserialize)]
+struct Release {
+    assets: Vec<ReleaseAsset>,
+}
+
+/// One file to fetch: (file name, URL, expected SHA-256)
+type Download = (String, String, String);
+
+pub fn status() -> FfmpegStatus {
+    let path = ffmpeg_path();
+    let version = Command::new(&path)
+        .arg("-version")
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .and_then(|o| String::from_utf8_lossy(&o.stdout).lines().next().map(str::to_string));
+    FfmpegStatus {
+        available: version.is_some(),
+        managed: managed_dir().is_some_and(|dir| path.starts_with(dir)),
+        path: path.to_string_lossy().to_string(),
+        version,
+        download_supported: platform_asset().is_some(),
+    }
+}
+
+fn emit(app: &AppHandle, stage: &str, downloaded: u64, total: Option<u64>) {
+    let percentage = total.filter(|t| *t > 0).map(|t| (downloaded as f64 / t as f64 * 100.0) as f32);
+    let _ = app.emit(EVENT_DOWNLOAD, FfmpegDownloadProgress {
+        stage: stage.to_string(),
+        downloaded,
+        total,
+        percentage,
+    });
+}
+
+/// Expected SHA-256 for `asset` from a "<hex>  <file name>" checksum listing
+fn expected_checksum(listing: &str, asset: &str) -> Option<String> {
+    listing.lines()
+        .filter_map(|line| line.split_once(char::is_whitespace))
+        .find(|(_, name)| name.trim().trim_start_matches('*') == asset)
+        .map(|(hash, _)| hash.to_lowercase())
+}
+
+/// Find a file by name anywhere under `dir` (archives nest binaries in <build>/bin/)
+pub fn find_file(dir: &Path, name: &str) -> Option<PathBuf> {
+    for entry in std::fs::read_dir(dir).ok()?.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            if let Some(found) = find_file(&path, name) {
+                return Some(found);
+            }
+        } else if path.file_name().and_then(|n| n.to_str()) == Some(name) {
+            return Some(path);
+        }
+    }
+    None
+}
+
+/// Downloads for this platform with their expected checksums
+async fn plan_downloads(client: &reqwest::Client, build: PlatformBuild) -> Result<Vec<Download>, String> {
+    match build {
+        PlatformBuild::Archive(asset) => {
+            let listing = client.get(format!("{}/{}", RELEASE_URL, CHECKSUMS_FILE))
+                .send().await
+                .and_then(|r| r.error_for_status())
+                .map_err(|e| format!("Failed to fetch checksums: {}", e))?
+                .text().await
+                .map_err(|e| format!("Failed to read checksums: {}", e))?;
+            let expected = expected_checksum(&listing, asset)
+                .ok_or_else(|| format!("No checksum published for {}", asset))?;
+            Ok(vec![(asset.to_string(), format!("{}/{}", RELEASE_URL, asset), expected)])
+        }
+        PlatformBuild::Binaries(platform) => {
+            let release: Release = client.get(MACOS_RELEASE_API)
+                .send().await
+                .and_then(|r| r.error_for_status())
+                .map_err(|e| format!("Failed to look up the latest FFmpeg build: {}", e))?
+                .json().await
+                .map_err(|e| format!("Failed to read FFmpeg release: {}", e))?;
+            ["ffmpeg", "ffprobe"].iter()
+                .map(|tool| {
+                    let name = format!("{}-{}.gz", tool, platform);
+                    let asset = release.assets.iter()
+                        .find(|a| a.name == name)
+                        .ok_or_else(|| format!("The latest FFmpeg release has no {} download", name))?;
+                    let expected = asset.digest.as_deref()
+                        .and_then(|d| d.strip_prefix("sha256:"))
+                        .map(str::to_lowercase)
+                        .ok_or_else(|| format!("No checksum published for {}", name))?;
+                    Ok((name, asset.browser_download_url.clone(), expected))
+                })
+                .collect()
+        }
+    }
+}
+
+/// Unpack the verified downloads in `staging` so ffmpeg and ffprobe can be found under it
+async fn unpack(build: PlatformBuild, staging: &Path, downloads: &[Download]) -> Result<(), String> {
+    for (name, _, _) in downloads {
+        // bsdtar (Windows 10+, macOS) reads zip; GNU tar reads tar.xz; single binaries are gzipped
+        let mut cmd = match build {
+            PlatformBuild::Archive(_) => {
+                let mut cmd = tokio::process::Command::new("tar");
+                cmd.arg("-xf").arg(staging.join(name)).arg("-C").arg(staging);
+                cmd
+            }
+            PlatformBuild::Binaries(_) => {
+                let mut cmd = tokio::process::Command::new("gzip");
+                cmd.arg("-d").arg(staging.join(name));
+                cmd
+            }
+        };
+        let output = cmd.output().await
+            .map_err(|e| format!("Failed to unpack {}: {}", name, e))?;
+        if !output.status.success() {
+            return Err(format!("Failed to extract FFmpeg: {}", String::from_utf8_lossy(&output.stderr)));
+        }
+        // "ffmpeg-darwin-arm64.gz" unpacks to "ffmpeg-darwin-arm64"; give it the tool's name
+        if let PlatformBuild::Binaries(platform) = build {
+            let unpacked = staging.join(name.trim_end_matches(".gz"));
+            let tool = name.trim_end_matches(".gz").trim_end_matches(platform).trim_end_matches('-');
+            std::fs::rename(&unpacked, staging.join(exe_name(tool)))
+                .map_err(|e| format!("Failed to unpack {}: {}", name, e))?;
+        }
+    }
+    Ok(())
+}
+
+/// Download, verify and install ffmpeg + ffprobe into app data
+pub async fn install(app: AppHandle) -> Result<FfmpegStatus, String> {
+    let build = platform_asset().ok_or(
+        "No managed FFmpeg build for this platform - install FFmpeg with your package manager and restart"
+    )?;
+    let target_dir = managed_dir().ok_or("Could not find app data directory")?;
+
+    let client = reqwest::Client::builder()
+        .connect_timeout(Duration::from_secs(15))
+        .user_agent("alagappa-tools")
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let downloads = plan_downloads(&client, build).await?;
+
+    // Own folder per install, so two installs running at once don't share files
+    let staging = WorkDir::create("ffmpeg-download")?;
+    let mut downloaded = 0;
+    let mut last_emit = 0;
+    let mut total = None;
+    for (name, url, expected) in &downloads {
+        info!("⬇️ Downloading FFmpeg: {}", name);
+        let mut response = client.get(url)
+            .send().await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| format!("Failed to download FFmpeg: {}", e))?;
+        total = response.content_length().map(|length| downloaded + length);
+
+        let mut file = std::fs::File::create(staging.path().join(name))
+            .map_err(|e| format!("Failed to save download: {}", e))?;
+        let mut hasher = Sha256::new();
+        emit(&app, "downloading", downloaded, total);
+        while let Some(chunk) = response.chunk().await.map_err(|e| format!("Download interrupted: {}", e))? {
+            hasher.update(&chunk);
+            file.write_all(&chunk)
+                .map_err(|e| format!("Failed to save download: {}", e))?;
+            downloaded += chunk.len() as u64;
+            if downloaded - last_emit >= PROGRESS_STEP {
+                last_emit = downloaded;
+                emit(&app, "downloading", downloaded, total);
+            }
+        }
+        drop(file);
+
+        emit(&app, "verifying", downloaded, total);
+        let actual: String = hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect();
+        if actual != *expected {
+            return Err(format!("Checksum mismatch for {} - download discarded", name));
+        }
+    }
+
+    emit(&app, "extracting", downloaded, total);
+    unpack(build, staging.path(), &downloads).await?;
+
+    std::fs::create_dir_all(&target_dir)
+        .map_err(|e| format!("Failed to create FFmpeg folder: {}", e))?;
+    for tool in ["ffmpeg", "ffprobe"] {
+        let name = exe_name(tool);
+        let source = find_file(staging.path(), &name)
+            .ok_or_else(|| format!("{} not found in the downloaded archive", name))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&source, std::fs::Permissions::from_mode(0o755))
+                .map_err(|e| format!("Failed to make {} executable: {}", name, e))?;
+        }
+        // Copy next to the destination, then rename over it, so a running ffmpeg is never half-written
+        let destination = target_dir.join(&name);
+        let staging_name = staging.path().file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        let partial = target_dir.join(format!("{}.{}.partial", name, staging_name));
+        std::fs::copy(&source, &partial)
+            .and_then(|_| std::fs::rename(&partial, &destination))
+            .map_err(|e| format!("Failed to install {}: {}", name, e))?;
+    }
+    drop(staging);
+    emit(&app, "done", downloaded, total);
+
+    let status = status();
+    info!("✅ Managed FFmpeg installed: {}", status.version.as_deref().unwrap_or("unknown version"));
+    Ok(status)
+}
+
+/// Delete the managed copy (falls back to FFmpeg on PATH)
+pub fn uninstall() -> Result<FfmpegStatus, String> {
+    if let Some(dir) = managed_dir().filter(|d| d.exists()) {
+        std::fs::remove_dir_all(&dir)
+            .map_err(|e| format!("Failed to remove managed FFmpeg: {}", e))?;
+        info!("🗑️ Managed FFmpeg removed");
+    }
+    Ok(status())
+}
@@ -0,0 +1,307 @@
+//! FFmpeg Runner - runs ffmpeg with `-progress pipe:1` and streams progress as "media://progress" events
+//! Running jobs are registered by id so they can be cancelled (child killed, partial output removed).
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, BufReader};
+use tokio::process::Command as TokioCommand;
+use tokio::sync::watch;
+use log::{info, warn};
+use tauri::{AppHandle, Emitter};
+use crate::ffmpeg_manager::{ffmpeg_path, ffprobe_path};
+
+pub const EVENT_PROGRESS: &str = "media://progress";
+
+/// Keep only the end of stderr for error messages (ffmpeg prints the real error last)
+const STDERR_TAIL: usize = 4000;
+
+pub const CANCELLED: &str = "Cancelled";
+
+static JOB_COUNTER: AtomicU64 = AtomicU64::new(1);
+
+/// job id -> (registration number, cancel flag) for every job in flight; the number makes sure a
+/// job only ever unregisters its own entry
+static RUNNING_JOBS: Mutex<BTreeMap<String, (u64, watch::Sender<bool>)>> = Mutex::new(BTreeMap::new());
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MediaProgress {
+    pub job_id: String,
+    pub percentage: Option<f32>,   // None when the input duration is unknown
+    pub out_time_secs: f64,
+    pub total_secs: Option<f64>,
+    pub fps: Option<f32>,
+    pub bitrate: Option<String>,   // e.g. "1834.2kbits/s"
+    pub speed: Option<f32>,        // Multiple of real time
+    pub eta_secs: Option<f64>,
+    pub done: bool,
+}
+
+/// Identifies one ffmpeg run for progress events
+#[derive(Clone)]
+pub struct MediaJob {
+    pub id: String,
+    pub app: Option<AppHandle>,
+    pub total_secs: Option<f64>,
+    pub outputs: Vec<PathBuf>,     // Files or folders removed if the job is cancelled
+    registration: Option<Arc<JobRegistration>>,
+}
+
+/// A job's entry in RUNNING_JOBS; dropping it unregisters the job
+struct JobRegistration {
+    id: String,
+    number: u64,
+    cancel: watch::Receiver<bool>,
+}
+
+impl JobRegistration {
+    fn register(id: &str) -> Result<Self, String> {
+        let (cancel_tx, cancel) = watch::channel(false);
+        let number = JOB_COUNTER.fetch_add(1, Ordering::Relaxed);
+        let mut jobs = RUNNING_JOBS.lock().map_err(|_| "Job registry poisoned".to_string())?;
+        // A second run under the same id would take over the first one's cancel signal
+        if jobs.contains_key(id) {
+            return Err(format!("A job with id {} is already running", id));
+        }
+        jobs.insert(id.to_string(), (number, cancel_tx));
+        Ok(JobRegistration { id: id.to_string(), number, cancel })
+    }
+}
+
+impl Drop for JobRegistration {
+    fn drop(&mut self) {
+        if let Ok(mut jobs) = RUNNING_JOBS.lock() {
+            if jobs.get(&self.id).is_some_and(|(number, _)| *number == self.number) {
+                jobs.remove(&self.id);
+            }
+        }
+    }
+}
+
+impl MediaJob {
+    /// Use the caller's job id (so the UI can match events) or generate one
+    pub fn new(app: Option<AppHandle>, job_id: Option<String>) -> Self {
+        let id = job_id.filter(|id| !id.is_empty()).unwrap_or_else(|| {
+            format!("job-{}-{}", chrono::Local::now().timestamp_millis(), JOB_COUNTER.fetch_add(1, Ordering::Relaxed))
+        });
+        MediaJob { id, app, total_secs: None, outputs: Vec::new(), registration: None }
+    }
+
+    /// Keep the job registered for cancel_job until this job and all its clones are dropped - for
+    /// work made of several ffmpeg passes, so a cancel between passes stops the next one
+    pub fn registered(mut self) -> Result<Self, String> {
+        if self.registration.is_none() {
+            self.registration = Some(Arc::new(JobRegistration::register(&self.id)?));
+        }
+        Ok(self)
+    }
+
+    /// Register an output file (or a folder the job created) to delete if the job is cancelled
+    pub fn with_output(mut self, path: &str) -> Self {
+        self.outputs.push(PathBuf::from(path));
+        self
+    }
+
+    /// Probe the input so progress can be reported as a percentage
+    pub async fn with_duration_of(mut self, input_path: &str) -> Self {
+        self.total_secs = probe_duration(input_path).await;
+        self
+    }
+
+    fn remove_outputs(&self) {
+        for output in &self.outputs {
+            let removed = if output.is_dir() {
+                std::fs::remove_dir_all(output)
+            } else if output.is_file() {
+                std::fs::remove_file(output)
+            } else {
+                continue;
+            };
+            if let Err(e) = removed {
+                warn!("Could not remove partial output {}: {}", output.display(), e);
+            }
+        }
+    }
+
+    fn emit(&self, progress: MediaProgress) {
+        if let Some(app) = &self.app {
+            let _ = app.emit(EVENT_PROGRESS, progress);
+        }
+    }
+}
+
+/// Duration in seconds via ffprobe
+pub async fn probe_duration(input_path: &str) -> Option<f64> {
+    let output = TokioCommand::new(ffprobe_path())
+        .arg("-v").arg("error")
+        .arg("-show_entries").arg("format=duration")
+        .arg("-of").arg("default=noprint_wrappers=1:nokey=1")
+        .arg(input_path)
+        .output()
+        .await
+        .ok()?;
+    String::from_utf8_lossy(&output.stdout).trim().parse().ok()
+}
+
+/// ffmpeg with progress reporting enabled - global options must precede inputs/outputs
+pub fn ffmpeg_command() -> TokioCommand {
+    let mut cmd = TokioCommand::new(ffmpeg_path());
+    cmd.arg("-hide_banner");
+    cmd.arg("-nostats");
+    cmd.arg("-progress").arg("pipe:1");
+    cmd
+}
+
+/// Parse "HH:MM:SS.micro", "MM:SS" or plain seconds ("90.5")
+pub fn parse_clock(value: &str) -> Option<f64> {
+    let mut parts = value.split(':').rev();
+    let seconds: f64 = parts.next()?.parse().ok()?;
+    let minutes: f64 = parts.next().unwrap_or("0").parse().ok()?;
+    let hours: f64 = parts.next().unwrap_or("0").parse().ok()?;
+    Some(hours * 3600.0 + minutes * 60.0 + seconds)
+}
+
+/// Apply one "key=value" line of ffmpeg's progress output; emits on each "progress=" line
+fn apply_progress_line(progress: &mut MediaProgress, job: &MediaJob, key: &str, value: &str) {
+    match key {
+        "out_time_us" | "out_time_ms" => {
+            // Both are microseconds (out_time_ms is a historical misnomer)
+            if let Ok(us) = value.parse::<i64>() {
+                progress.out_time_secs = us.max(0) as f64 / 1_000_000.0;
+            }
+        }
+        "out_time" => {
+            if let Some(secs) = parse_clock(value) {
+                progress.out_time_secs = secs;
+            }
+        }
+        "fps" => progress.fps = value.parse().ok(),
+        "bitrate" => progress.bitrate = Some(value.to_string()).filter(|v| v != "N/A"),
+        "speed" => progress.speed = value.trim_end_matches('x').parse().ok(),
+        "progress" => {
+            progress.done = value == "end";
+            if let Some(total) = job.total_secs.filter(|t| *t > 0.0) {
+                let pct = if progress.done { 100.0 } else { progress.out_time_secs / total * 100.0 };
+                progress.percentage = Some(pct.clamp(0.0, 100.0) as f32);
+                progress.eta_secs = progress.speed
+                    .filter(|s| *s > 0.0)
+                    .map(|s| ((total - progress.out_time_secs) / s as f64).max(0.0));
+            }
+            job.emit(progress.clone());
+        }
+        _ => {}
+    }
+}
+
+fn tail(text: &str) -> String {
+    let start = text.len().saturating_sub(STDERR_TAIL);
+    let start = (start..text.len()).find(|i| text.is_char_boundary(*i)).unwrap_or(text.len());
+    text[start..].to_string()
+}
+
+/// Run a command built with ffmpeg_command(), emitting progress until it exits or is cancelled.
+/// Returns Err(CANCELLED) after a cancel, otherwise Err(stderr tail) on failure.
+pub async fn run_ffmpeg(cmd: TokioCommand, job: &MediaJob) -> Result<(), String> {
+    run_ffmpeg_with_log(cmd, job).await.map(|_| ())
+}
+
+/// Like run_ffmpeg, but returns ffmpeg's full log (stderr) on success - for analysis filters
+/// (ebur128, silencedetect, psnr, ...) that report their results there.
+pub async fn run_ffmpeg_with_log(mut cmd: TokioCommand, job: &MediaJob) -> Result<String, String> {
+    // A job registered for several passes keeps its entry; a single run registers just for itself
+    let own_registration;
+    let registration = match &job.registration {
+        Some(registration) => registration.as_ref(),
+        None => {
+            own_registration = JobRegistration::register(&job.id)?;
+            &own_registration
+        }
+    };
+    let mut cancel_rx = registration.cancel.clone();
+    if *cancel_rx.borrow_and_update() {
+        job.remove_outputs();
+        info!("⏹️ Media job {} cancelled", job.id);
+        return Err(CANCELLED.to_string());
+    }
+
+    cmd.stdout(Stdio::piped()).stderr(Stdio::piped()).stdin(Stdio::null());
+    cmd.kill_on_drop(true);
+    let mut child = cmd.spawn()
+        .map_err(|e| format!("FFmpeg execution failed: {}", e))?;
+
+    let (Some(stdout), Some(mut stderr)) = (child.stdout.take(), child.stderr.take()) else {
+        return Err("Failed to capture ffmpeg output".to_string());
+    };
+
+    // Drain stderr concurrently so ffmpeg never blocks on a full pipe
+    let stderr_task = tokio::spawn(async move {
+        let mut text = String::new();
+        let _ = stderr.read_to_string(&mut text).await;
+        text
+    });
+
+    let mut lines = BufReader::new(stdout).lines();
+    let mut progress = MediaProgress {
+        job_id: job.id.clone(),
+        percentage: job.total_secs.map(|_| 0.0),
+        out_time_secs: 0.0,
+        total_secs: job.total_secs,
+        fps: None,
+        bitrate: None,
+        speed: None,
+        eta_secs: None,
+        done: false,
+    };
+    let mut cancelled = false;
+    // Once the sender is gone changed() resolves immediately; stop polling it
+    let mut cancel_closed = false;
+
+    loop {
+        tokio::select! {
+            line = lines.next_line() => match line {
+                Ok(Some(line)) => {
+                    if let Some((key, value)) = line.split_once('=') {
+                        apply_progress_line(&mut progress, job, key, value.trim());
+                    }
+                }
+                _ => break,
+            },
+            changed = cancel_rx.changed(), if !cancel_closed => {
+                if *cancel_rx.borrow_and_update() {
+                    cancelled = true;
+                    let _ = child.kill().await;
+                    break;
+                }
+                cancel_closed = changed.is_err();
+            }
+        }
+    }
+
+    let status = child.wait().await
+        .map_err(|e| format!("FFmpeg execution failed: {}", e))?;
+    let stderr_text = stderr_task.await.unwrap_or_default();
+
+    if cancelled {
+        job.remove_outputs();
+        info!("⏹️ Media job {} cancelled", job.id);
+        return Err(CANCELLED.to_string());
+    }
+
+    if status.success() {
+        Ok(stderr_text)
+    } else {
+        Err(tail(&stderr_text))
+    }
+}
+
+/// Kill a running ffmpeg job; its partial output is removed by run_ffmpeg
+pub fn cancel_job(job_id: &str) -> Result<(), String> {
+    let (_, sender) = RUNNING_JOBS.lock()
+        .map_err(|_| "Job registry poisoned".to_string())?
+        .remove(job_id)
+        .ok_or_else(|| format!("No running job with id {}", job_id))?;
+    sender.send(true).map_err(|_| format!("Job {} already finished", job_id))
+}
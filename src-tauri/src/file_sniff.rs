@@ -0,0 +1,172 @@
+//! File Sniff - identify a file by its content (magic bytes) rather than its extension
+//! Misnamed downloads (a PNG saved as .jpg, an .xls that is really xlsx, a PDF renamed .docx)
+//! are then either opened as what they are or rejected with a "file is actually ..." error.
+//! Zip-based office formats are told apart by the entry names in the zip's central directory.
+
+use std::fs;
+use std::io::{Read, Seek, SeekFrom};
+use image::ImageFormat;
+use crate::image_decode::is_heif;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FileKind {
+    Pdf,
+    Image(ImageFormat),
+    Heif,
+    Xlsx,
+    Docx,
+    Pptx,
+    Ods,
+    Odt,
+    Odp,
+    Zip,
+    Ole,        // Office 97-2003 (xls, doc, ppt share the compound file format)
+    Text,
+    Unknown,
+}
+
+impl FileKind {
+    pub fn label(&self) -> String {
+        match self {
+            FileKind::Pdf => "a PDF document".to_string(),
+            FileKind::Image(format) => format!("a {:?} image", format),
+            FileKind::Heif => "a HEIC/AVIF image".to_string(),
+            FileKind::Xlsx => "an Excel workbook (xlsx)".to_string(),
+            FileKind::Docx => "a Word document (docx)".to_string(),
+            FileKind::Pptx => "a PowerPoint presentation (pptx)".to_string(),
+            FileKind::Ods => "an OpenDocument spreadsheet (ods)".to_string(),
+            FileKind::Odt => "an OpenDocument text document (odt)".to_string(),
+            FileKind::Odp => "an OpenDocument presentation (odp)".to_string(),
+            FileKind::Zip => "a zip archive".to_string(),
+            FileKind::Ole => "an Office 97-2003 file (xls/doc/ppt)".to_string(),
+            FileKind::Text => "a text file".to_string(),
+            FileKind::Unknown => "an unrecognized binary file".to_string(),
+        }
+    }
+
+    /// Extension calamine should open this as, for workbook kinds
+    pub fn workbook_extension(&self) -> Option<&'static str> {
+        match self {
+            FileKind::Xlsx => Some("xlsx"),
+            FileKind::Ole => Some("xls"),
+            FileKind::Ods => Some("ods"),
+            _ => None,
+        }
+    }
+
+    pub fn is_image(&self) -> bool {
+        matches!(self, FileKind::Image(_) | FileKind::Heif)
+    }
+}
+
+/// Entry names from a zip's central directory (at most the first 200)
+fn zip_entries(file: &mut fs::File) -> Option<Vec<String>> {
+    // End of central directory record: 22 bytes plus a comment of up to 64 KB
+    let len = file.seek(SeekFrom::End(0)).ok()?;
+    let tail_len = len.min(22 + 65_535);
+    file.seek(SeekFrom::Start(len - tail_len)).ok()?;
+    let mut tail = Vec::with_capacity(tail_len as usize);
+    file.read_to_end(&mut tail).ok()?;
+    let eocd = tail.windows(4).rposition(|w| w == b"PK\x05\x06")?;
+    let record = tail.get(eocd..eocd + 22)?;
+    let count = u16::from_le_bytes([record[10], record[11]]) as usize;
+    let offset = u32::from_le_bytes([record[16], record[17], record[18], record[19]]) as u64;
+    let size = u32::from_le_bytes([record[12], record[13], record[14], record[15]]) as usize;
+
+    let mut directory = vec![0u8; size.min(4 << 20)];
+    file.seek(SeekFrom::Start(offset)).ok()?;
+    file.read_exact(&mut directory).ok()?;
+
+    let mut names = Vec::new();
+    let mut pos = 0;
+    while names.len() < count.min(200) && pos + 46 <= directory.len() && directory[pos..].starts_with(b"PK\x01\x02") {
+        let field = |at: usize| u16::from_le_bytes([directory[pos + at], directory[pos + at + 1]]) as usize;
+        let (name_len, extra_len, comment_len) = (field(28), field(30), field(32));
+        let name = directory.get(pos + 46..pos + 46 + name_len)?;
+        names.push(String::from_utf8_lossy(name).to_string());
+        pos += 46 + name_len + extra_len + comment_len;
+    }
+    Some(names)
+}
+
+/// Tell xlsx/docx/pptx/odf apart from a plain zip
+fn zip_kind(file: &mut fs::File) -> FileKind {
+    // ODF stores an uncompressed "mimetype" entry first; its content follows the 38-byte header
+    let mut head = [0u8; 120];
+    let read = file.seek(SeekFrom::Start(0)).and_then(|_| file.read(&mut head)).unwrap_or(0);
+    let head = String::from_utf8_lossy(&head[..read]);
+    if head.contains("mimetype") {
+        if head.contains("opendocument.spreadsheet") {
+            return FileKind::Ods;
+        }
+        if head.contains("opendocument.text") {
+            return FileKind::Odt;
+        }
+        if head.contains("opendocument.presentation") {
+            return FileKind::Odp;
+        }
+    }
+
+    let Some(entries) = zip_entries(file) else {
+        return FileKind::Zip;
+    };
+    let has = |prefix: &str| entries.iter().any(|name| name.starts_with(prefix));
+    if has("xl/") {
+        FileKind::Xlsx
+    } else if has("word/") {
+        FileKind::Docx
+    } else if has("ppt/") {
+        FileKind::Pptx
+    } else {
+        FileKind::Zip
+    }
+}
+
+/// Identify a file from its first bytes
+pub fn sniff(path: &str) -> Result<FileKind, String> {
+    let mut file = fs::File::open(path).map_err(|e| format!("Failed to open file: {}", e))?;
+    let mut header = [0u8; 512];
+    let read = file.read(&mut header).map_err(|e| format!("Failed to read file: {}", e))?;
+    let header = &header[..read];
+
+    // PDF allows up to 1 KB of junk before the header; in practice it is at or near the start
+    if header.windows(5).take(64).any(|w| w == b"%PDF-") {
+        return Ok(FileKind::Pdf);
+    }
+    if header.starts_with(b"PK\x03\x04") {
+        return Ok(zip_kind(&mut file));
+    }
+    if header.starts_with(&[0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1]) {
+        return Ok(FileKind::Ole);
+    }
+    if is_heif(path) {
+        return Ok(FileKind::Heif);
+    }
+    // PNM's "P1".."P7" signature also starts ordinary text, so it is not trusted here; other
+    // signatures made of letters ("BM", "DDS ", "qoif") only count in binary-looking data, as a
+    // CSV headed "BMI,Name" starts with the same bytes
+    let binary = header.iter().any(|&b| b < 0x20 && !matches!(b, b'\t' | b'\n' | b'\r' | 0x0C));
+    if let Some(format) = image::guess_format(header).ok().filter(|f| *f != ImageFormat::Pnm && binary) {
+        return Ok(FileKind::Image(format));
+    }
+    // Text: no NUL bytes in the sample (UTF-16 with a BOM counts as text too)
+    if header.starts_with(&[0xFF, 0xFE]) || header.starts_with(&[0xFE, 0xFF]) || !header.contains(&0) {
+        return Ok(FileKind::Text);
+    }
+    Ok(FileKind::Unknown)
+}
+
+/// Extension to open a workbook with, judged by content; a precise error for anything else
+pub fn workbook_extension(path: &str) -> Result<&'static str, String> {
+    let kind = sniff(path)?;
+    kind.workbook_extension()
+        .ok_or_else(|| format!("Not a spreadsheet: file is actually {}", kind.label()))
+}
+
+/// Reject binary documents and images passed where a text format (CSV, JSON) is expected
+pub fn ensure_text(path: &str, expected: &str) -> Result<(), String> {
+    match sniff(path)? {
+        FileKind::Text | FileKind::Unknown => Ok(()),
+        kind => Err(format!("Not a {} file: file is actually {}", expected, kind.label())),
+    }
+}
@@ -0,0 +1,202 @@
+//! Holiday Calendar - institution holidays and weekly offs used by attendance summaries
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use log::info;
+use crate::app_storage::{load_json, save_json};
+
+const HOLIDAYS_FILE: &str = "holidays.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Holiday {
+    pub date: String,  // YYYY-MM-DD
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HolidayCalendar {
+    pub holidays: Vec<Holiday>,
+    pub weekly_offs: Vec<String>,  // Weekday names: "Sun", "Sat", ...
+}
+
+impl Default for HolidayCalendar {
+    fn default() -> Self {
+        HolidayCalendar {
+            holidays: Vec::new(),
+            weekly_offs: vec!["Sun".to_string()],
+        }
+    }
+}
+
+impl HolidayCalendar {
+    pub fn holiday_on(&self, date: NaiveDate) -> Option<&Holiday> {
+        let key = date.format("%Y-%m-%d").to_string();
+        self.holidays.iter().find(|h| h.date == key)
+    }
+
+    pub fn is_weekly_off(&self, date: NaiveDate) -> bool {
+        let weekday = date.weekday();
+        self.weekly_offs.iter().any(|w| w.parse::<Weekday>().map(|d| d == weekday).unwrap_or(false))
+    }
+}
+
+// ============================================================================
+// Store (CRUD)
+// ============================================================================
+
+pub fn load_calendar() -> Result<HolidayCalendar, String> {
+    load_json(HOLIDAYS_FILE)
+}
+
+fn save_calendar(calendar: &mut HolidayCalendar) -> Result<(), String> {
+    calendar.holidays.sort_by(|a, b| a.date.cmp(&b.date));
+    save_json(HOLIDAYS_FILE, calendar)
+}
+
+/// Add or rename a holiday
+pub fn add_holiday(date: String, name: String) -> Result<HolidayCalendar, String> {
+    let date = normalize_date(&date).ok_or_else(|| format!("Invalid date: {}", date))?;
+    let mut calendar = load_calendar()?;
+
+    match calendar.holidays.iter_mut().find(|h| h.date == date) {
+        Some(existing) => existing.name = name,
+        None => calendar.holidays.push(Holiday { date, name }),
+    }
+
+    save_calendar(&mut calendar)?;
+    Ok(calendar)
+}
+
+pub fn remove_holiday(date: String) -> Result<HolidayCalendar, String> {
+    let date = normalize_date(&date).ok_or_else(|| format!("Invalid date: {}", date))?;
+    let mut calendar = load_calendar()?;
+    calendar.holidays.retain(|h| h.date != date);
+    save_calendar(&mut calendar)?;
+    Ok(calendar)
+}
+
+pub fn set_weekly_offs(weekdays: Vec<String>) -> Result<HolidayCalendar, String> {
+    for day in &weekdays {
+        day.parse::<Weekday>().map_err(|_| format!("Invalid weekday: {}", day))?;
+    }
+
+    let mut calendar = load_calendar()?;
+    calendar.weekly_offs = weekdays;
+    save_calendar(&mut calendar)?;
+    Ok(calendar)
+}
+
+/// Import holidays from a CSV (date,name) or ICS calendar file, merging into the store
+pub fn import_holidays(file_path: String) -> Result<HolidayCalendar, String> {
+    let content = fs::read_to_string(&file_path)
+        .map_err(|e| format!("Failed to read {}: {}", file_path, e))?;
+
+    let imported = if file_path.to_lowercase().ends_with(".ics") {
+        parse_ics(&content)
+    } else {
+        parse_csv(&content)?
+    };
+
+    info!("📅 Importing {} holidays from {}", imported.len(), file_path);
+
+    let mut calendar = load_calendar()?;
+    for holiday in imported {
+        match calendar.holidays.iter_mut().find(|h| h.date == holiday.date) {
+            Some(existing) => existing.name = holiday.name,
+            None => calendar.holidays.push(holiday),
+        }
+    }
+
+    save_calendar(&mut calendar)?;
+    Ok(calendar)
+}
+
+// ============================================================================
+// Parsers
+// ============================================================================
+
+/// Accept YYYY-MM-DD, DD-MM-YYYY, DD/MM/YYYY and YYYYMMDD
+fn normalize_date(value: &str) -> Option<String> {
+    let value = value.trim();
+    ["%Y-%m-%d", "%d-%m-%Y", "%d/%m/%Y", "%Y%m%d"]
+        .iter()
+        .find_map(|fmt| NaiveDate::parse_from_str(value, fmt).ok())
+        .map(|d| d.format("%Y-%m-%d").to_string())
+}
+
+fn parse_csv(content: &str) -> Result<Vec<Holiday>, String> {
+    let mut rdr = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(content.as_bytes());
+
+    let mut holidays = Vec::new();
+    for result in rdr.records() {
+        let record = result.map_err(|e| format!("Failed to read CSV: {}", e))?;
+        // Rows whose first column isn't a date (e.g. the header) are skipped
+        let date = match record.get(0).and_then(normalize_date) {
+            Some(d) => d,
+            None => continue,
+        };
+        let name = record.get(1).unwrap_or("Holiday").trim().to_string();
+        holidays.push(Holiday { date, name });
+    }
+    Ok(holidays)
+}
+
+fn parse_ics(content: &str) -> Vec<Holiday> {
+    // Unfold continuation lines (RFC 5545: lines starting with space/tab continue the previous one)
+    let mut lines: Vec<String> = Vec::new();
+    for raw in content.lines() {
+        if (raw.starts_with(' ') || raw.starts_with('\t')) && !lines.is_empty() {
+            if let Some(last) = lines.last_mut() {
+                last.push_str(&raw[1..]);
+            }
+        } else {
+            lines.push(raw.trim_end().to_string());
+        }
+    }
+
+    let mut holidays = Vec::new();
+    let mut start: Option<NaiveDate> = None;
+    let mut end: Option<NaiveDate> = None;
+    let mut summary = String::new();
+
+    for line in lines {
+        let (key, value) = match line.split_once(':') {
+            Some(kv) => kv,
+            None => continue,
+        };
+        let key = key.split(';').next().unwrap_or("").to_uppercase();
+        let ics_date = || value.get(0..8).and_then(|d| NaiveDate::parse_from_str(d, "%Y%m%d").ok());
+
+        match key.as_str() {
+            "BEGIN" if value == "VEVENT" => {
+                start = None;
+                end = None;
+                summary.clear();
+            }
+            "DTSTART" => start = ics_date(),
+            "DTEND" => end = ics_date(),
+            "SUMMARY" => summary = value.replace("\\,", ",").trim().to_string(),
+            "END" if value == "VEVENT" => {
+                if let Some(first) = start {
+                    // All-day DTEND is exclusive; single-day events have no DTEND or DTEND = start + 1
+                    let last = end.map(|e| e - Duration::days(1)).filter(|e| *e > first).unwrap_or(first);
+                    let mut day = first;
+                    while day <= last {
+                        holidays.push(Holiday {
+                            date: day.format("%Y-%m-%d").to_string(),
+                            name: if summary.is_empty() { "Holiday".to_string() } else { summary.clone() },
+                        });
+                        day += Duration::days(1);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    holidays
+}
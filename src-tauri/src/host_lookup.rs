@@ -0,0 +1,41 @@
+//! Host Lookup - reverse DNS (and NetBIOS on Windows) names for scan results
+
+use std::net::IpAddr;
+use std::time::Duration;
+
+/// Resolvers on misconfigured campus networks can hang for a long time
+const LOOKUP_TIMEOUT: Duration = Duration::from_secs(2);
+
+async fn reverse_dns(ip: IpAddr) -> Option<String> {
+    let lookup = tokio::task::spawn_blocking(move || dns_lookup::lookup_addr(&ip).ok());
+    let name = tokio::time::timeout(LOOKUP_TIMEOUT, lookup).await.ok()?.ok()??;
+    let name = name.trim_end_matches('.').to_string();
+    // Some resolvers echo the address back instead of failing
+    if name.is_empty() || name == ip.to_string() { None } else { Some(name) }
+}
+
+/// Windows: "nbtstat -A <ip>" lists "NAME  <00>  UNIQUE  Registered" for the workstation name
+#[cfg(target_os = "windows")]
+async fn netbios_name(ip: IpAddr) -> Option<String> {
+    let command = tokio::process::Command::new("nbtstat").arg("-A").arg(ip.to_string()).output();
+    let output = tokio::time::timeout(LOOKUP_TIMEOUT, command).await.ok()?.ok()?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    text.lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>())
+        .find(|cols| cols.len() >= 3 && cols[1] == "<00>" && cols[2].eq_ignore_ascii_case("UNIQUE"))
+        .map(|cols| cols[0].to_string())
+}
+
+#[cfg(not(target_os = "windows"))]
+async fn netbios_name(_ip: IpAddr) -> Option<String> {
+    None
+}
+
+/// Best-effort hostname for a device IP
+pub async fn resolve_hostname(ip: &str) -> Option<String> {
+    let ip: IpAddr = ip.trim_matches(|c| c == '[' || c == ']').parse().ok()?;
+    match reverse_dns(ip).await {
+        Some(name) => Some(name),
+        None => netbios_name(ip).await,
+    }
+}
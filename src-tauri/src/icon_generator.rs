@@ -0,0 +1,171 @@
+//! Icon Generator - multi-size .ico, favicons and app icon sets from one source image
+//! Non-square sources are centered on a transparent square rather than stretched; a source
+//! of at least 1024 px gives sharp results for every size.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use log::{info, warn};
+use image::codecs::ico::{IcoEncoder, IcoFrame};
+use image::imageops::{self, FilterType};
+use image::{DynamicImage, ExtendedColorType, RgbaImage};
+use crate::image_decode::open_image;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IconTarget {
+    Ico,        // icon.ico with 16-256 px
+    Png,        // icon-16.png ... icon-512.png
+    Favicon,    // favicon.ico, favicon PNGs, apple-touch-icon, android-chrome and site.webmanifest
+    Windows,    // windows/ tiles and taskbar sizes
+    Macos,      // icon.iconset/ for `iconutil -c icns`
+    Ios,        // ios/AppIcon-*.png
+    Android,    // android/mipmap-*/ic_launcher.png and the 512 px store icon
+}
+
+const ICO_SIZES: &[u32] = &[16, 24, 32, 48, 64, 128, 256];
+const PNG_SIZES: &[u32] = &[16, 32, 48, 64, 128, 256, 512];
+const WINDOWS_TILES: &[(&str, u32)] = &[
+    ("Square44x44Logo", 44),
+    ("Square71x71Logo", 71),
+    ("Square150x150Logo", 150),
+    ("Square310x310Logo", 310),
+    ("StoreLogo", 50),
+];
+const IOS_SIZES: &[u32] = &[20, 29, 40, 58, 60, 76, 80, 87, 120, 152, 167, 180, 1024];
+
+const WEB_MANIFEST: &str = r#"{
+  "icons": [
+    { "src": "/android-chrome-192x192.png", "sizes": "192x192", "type": "image/png" },
+    { "src": "/android-chrome-512x512.png", "sizes": "512x512", "type": "image/png" }
+  ],
+  "display": "standalone"
+}
+"#;
+
+/// Center the image on a transparent square
+fn square(img: &DynamicImage) -> RgbaImage {
+    let rgba = img.to_rgba8();
+    if rgba.width() == rgba.height() {
+        return rgba;
+    }
+    let side = rgba.width().max(rgba.height());
+    let mut canvas = RgbaImage::new(side, side);
+    let x = (side - rgba.width()) / 2;
+    let y = (side - rgba.height()) / 2;
+    imageops::overlay(&mut canvas, &rgba, x as i64, y as i64);
+    canvas
+}
+
+struct IconWriter<'a> {
+    source: RgbaImage,
+    output_dir: &'a Path,
+    files: Vec<String>,
+}
+
+impl IconWriter<'_> {
+    fn resized(&self, size: u32) -> RgbaImage {
+        imageops::resize(&self.source, size, size, FilterType::Lanczos3)
+    }
+
+    fn png(&mut self, relative: &str, size: u32) -> Result<(), String> {
+        let path = self.output_dir.join(relative);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("Failed to create output folder: {}", e))?;
+        }
+        self.resized(size).save(&path)
+            .map_err(|e| format!("Failed to write {}: {}", relative, e))?;
+        self.files.push(path.to_string_lossy().to_string());
+        Ok(())
+    }
+
+    fn ico(&mut self, relative: &str, sizes: &[u32]) -> Result<(), String> {
+        let images: Vec<RgbaImage> = sizes.iter().map(|&size| self.resized(size)).collect();
+        let frames = images.iter()
+            .map(|img| IcoFrame::as_png(img.as_raw(), img.width(), img.height(), ExtendedColorType::Rgba8))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Failed to encode icon: {}", e))?;
+
+        let path = self.output_dir.join(relative);
+        let file = fs::File::create(&path).map_err(|e| format!("Failed to create {}: {}", relative, e))?;
+        IcoEncoder::new(file).encode_images(&frames)
+            .map_err(|e| format!("Failed to write {}: {}", relative, e))?;
+        self.files.push(path.to_string_lossy().to_string());
+        Ok(())
+    }
+
+    fn text(&mut self, relative: &str, content: &str) -> Result<(), String> {
+        let path = self.output_dir.join(relative);
+        fs::write(&path, content).map_err(|e| format!("Failed to write {}: {}", relative, e))?;
+        self.files.push(path.to_string_lossy().to_string());
+        Ok(())
+    }
+
+    fn target(&mut self, target: IconTarget) -> Result<(), String> {
+        match target {
+            IconTarget::Ico => self.ico("icon.ico", ICO_SIZES)?,
+            IconTarget::Png => {
+                for &size in PNG_SIZES {
+                    self.png(&format!("icon-{}.png", size), size)?;
+                }
+            }
+            IconTarget::Favicon => {
+                self.ico("favicon.ico", &[16, 32, 48])?;
+                self.png("favicon-16x16.png", 16)?;
+                self.png("favicon-32x32.png", 32)?;
+                self.png("apple-touch-icon.png", 180)?;
+                self.png("android-chrome-192x192.png", 192)?;
+                self.png("android-chrome-512x512.png", 512)?;
+                self.text("site.webmanifest", WEB_MANIFEST)?;
+            }
+            IconTarget::Windows => {
+                for (name, size) in WINDOWS_TILES {
+                    self.png(&format!("windows/{}.png", name), *size)?;
+                }
+            }
+            IconTarget::Macos => {
+                for size in [16, 32, 128, 256, 512] {
+                    self.png(&format!("icon.iconset/icon_{0}x{0}.png", size), size)?;
+                    self.png(&format!("icon.iconset/icon_{0}x{0}@2x.png", size), size * 2)?;
+                }
+            }
+            IconTarget::Ios => {
+                for &size in IOS_SIZES {
+                    self.png(&format!("ios/AppIcon-{}.png", size), size)?;
+                }
+            }
+            IconTarget::Android => {
+                for (density, size) in [("mdpi", 48), ("hdpi", 72), ("xhdpi", 96), ("xxhdpi", 144), ("xxxhdpi", 192)] {
+                    self.png(&format!("android/mipmap-{}/ic_launcher.png", density), size)?;
+                }
+                self.png("android/playstore-icon.png", 512)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Write icons for each target into `output_dir`; no targets means ico + png + favicon
+pub fn generate_icons(input_path: String, output_dir: String, targets: Vec<IconTarget>) -> Result<Vec<String>, String> {
+    let targets = if targets.is_empty() {
+        vec![IconTarget::Ico, IconTarget::Png, IconTarget::Favicon]
+    } else {
+        targets
+    };
+
+    info!("🖼️ Generating icons {:?} -> {}", targets, output_dir);
+
+    let source = square(&open_image(&input_path)?);
+    if source.width() < 512 {
+        warn!("Icon source is only {} px; larger icons will be upscaled", source.width());
+    }
+    fs::create_dir_all(&output_dir).map_err(|e| format!("Failed to create output folder: {}", e))?;
+
+    let mut writer = IconWriter { source, output_dir: Path::new(&output_dir), files: Vec::new() };
+    for target in targets {
+        writer.target(target)?;
+    }
+
+    info!("✅ {} icon files written", writer.files.len());
+    Ok(writer.files)
+}
@@ -0,0 +1,121 @@
+//! ID Photo - passport/admission-portal photos in one step
+//! Crop to the required shape (centered), resize to the exact pixel size, flatten transparency
+//! onto white, stamp the DPI and keep the file under the portal's KB limit. Output is JPEG or
+//! WebP by extension; the DPI is written to the JPEG's JFIF header.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use log::info;
+use image::imageops::FilterType;
+use image::{DynamicImage, RgbImage};
+use crate::bundled_converter::ConversionResult;
+use crate::image_edit::{crop_to_aspect, open_oriented, parse_ratio};
+use crate::image_size_limit::{best_quality, encode, LossyFormat};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IdPhotoSpec {
+    pub aspect: Option<String>,         // "35:45"; by default the shape of the mm or pixel size
+    pub width_mm: Option<f64>,          // Printed size, e.g. 35 x 45 mm
+    pub height_mm: Option<f64>,
+    pub width_px: Option<u32>,          // Exact pixel size; overrides mm x DPI
+    pub height_px: Option<u32>,
+    pub dpi: Option<u16>,               // Default 300
+    pub max_kb: Option<u32>,
+    pub white_background: Option<bool>, // Flatten transparent areas onto white (default true; always for JPEG)
+}
+
+/// Width / height the photo must have
+fn target_aspect(spec: &IdPhotoSpec) -> Result<Option<f64>, String> {
+    if let Some(aspect) = spec.aspect.as_deref().filter(|a| !a.trim().is_empty()) {
+        return parse_ratio(aspect).map(Some);
+    }
+    Ok(match (spec.width_px, spec.height_px, spec.width_mm, spec.height_mm) {
+        (Some(w), Some(h), _, _) if w > 0 && h > 0 => Some(w as f64 / h as f64),
+        (_, _, Some(w), Some(h)) if w > 0.0 && h > 0.0 => Some(w / h),
+        _ => None,
+    })
+}
+
+/// Pixel size from the spec: explicit pixels, else mm at the DPI; a missing side follows the aspect
+fn target_size(spec: &IdPhotoSpec, aspect: f64, dpi: u16) -> Option<(u32, u32)> {
+    let from_mm = |mm: Option<f64>| mm.filter(|v| *v > 0.0).map(|v| (v / 25.4 * dpi as f64).round() as u32);
+    let width = spec.width_px.filter(|v| *v > 0).or_else(|| from_mm(spec.width_mm));
+    let height = spec.height_px.filter(|v| *v > 0).or_else(|| from_mm(spec.height_mm));
+    match (width, height) {
+        (Some(w), Some(h)) => Some((w, h)),
+        (Some(w), None) => Some((w, (w as f64 / aspect).round() as u32)),
+        (None, Some(h)) => Some(((h as f64 * aspect).round() as u32, h)),
+        (None, None) => None,
+    }
+    .map(|(w, h)| (w.max(1), h.max(1)))
+}
+
+fn flatten_on_white(img: &DynamicImage) -> DynamicImage {
+    let rgba = img.to_rgba8();
+    let rgb = RgbImage::from_fn(rgba.width(), rgba.height(), |x, y| {
+        let [r, g, b, a] = rgba.get_pixel(x, y).0;
+        let alpha = a as f32 / 255.0;
+        let blend = |c: u8| (c as f32 * alpha + 255.0 * (1.0 - alpha)).round() as u8;
+        image::Rgb([blend(r), blend(g), blend(b)])
+    });
+    DynamicImage::ImageRgb8(rgb)
+}
+
+/// Set the density in a JFIF APP0 header (the image crate writes one at the start)
+fn set_jpeg_dpi(bytes: &mut [u8], dpi: u16) -> bool {
+    if bytes.len() < 18 || bytes[2..4] != [0xFF, 0xE0] || &bytes[6..11] != b"JFIF\0" {
+        return false;
+    }
+    bytes[13] = 1; // Dots per inch
+    bytes[14..16].copy_from_slice(&dpi.to_be_bytes());
+    bytes[16..18].copy_from_slice(&dpi.to_be_bytes());
+    true
+}
+
+/// Prepare an ID photo following `spec`
+pub fn prepare_id_photo(input_path: String, output_path: String, spec: IdPhotoSpec) -> Result<ConversionResult, String> {
+    info!("🖼️ Preparing ID photo (bundled)");
+
+    let format = LossyFormat::from_path(&output_path)?;
+    let dpi = spec.dpi.unwrap_or(300).max(1);
+    let mut img = open_oriented(&input_path)?;
+
+    // JPEG has no alpha channel, so transparent areas would otherwise come out black
+    if format == LossyFormat::Jpeg || spec.white_background.unwrap_or(true) {
+        img = flatten_on_white(&img);
+    }
+    let aspect = target_aspect(&spec)?;
+    if let Some(aspect) = aspect {
+        img = crop_to_aspect(&img, aspect);
+    }
+    let aspect = aspect.unwrap_or(img.width() as f64 / img.height() as f64);
+    if let Some((width, height)) = target_size(&spec, aspect, dpi) {
+        img = img.resize_exact(width, height, FilterType::Lanczos3);
+    }
+
+    // The pixel size is part of the rules, so only quality may give way to the KB limit
+    let (mut bytes, quality) = match spec.max_kb {
+        Some(max_kb) if max_kb > 0 => best_quality(&img, format, max_kb as usize * 1024, 20)?
+            .ok_or_else(|| format!("Cannot reach {} KB at {}x{}; raise max_kb", max_kb, img.width(), img.height()))?,
+        _ => (encode(&img, format, 90)?, 90),
+    };
+    let dpi_set = format == LossyFormat::Jpeg && set_jpeg_dpi(&mut bytes, dpi);
+
+    fs::write(&output_path, &bytes).map_err(|e| format!("Failed to write image: {}", e))?;
+
+    info!("✅ ID photo saved: {}", output_path);
+    Ok(ConversionResult {
+        success: true,
+        output_path,
+        message: format!(
+            "{}x{} px{}, {:.1} KB at quality {}",
+            img.width(),
+            img.height(),
+            if dpi_set { format!(" at {} DPI", dpi) } else { String::new() },
+            bytes.len() as f64 / 1024.0,
+            quality
+        ),
+        output_size: Some(bytes.len() as u64),
+    })
+}
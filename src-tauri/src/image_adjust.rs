@@ -0,0 +1,88 @@
+//! Image Adjust - brightness, contrast, saturation, grayscale and sharpening
+//! For fixing dim scans and phone photos before they go into documents. Adjustments are
+//! applied in that order on the upright image; the output format follows its extension.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use log::info;
+use image::DynamicImage;
+use crate::bundled_converter::{save_image, ConversionResult};
+use crate::image_edit::open_oriented;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ImageAdjustments {
+    pub brightness: Option<i32>,        // -100 (black) to 100 (white), 0 = unchanged
+    pub contrast: Option<f32>,          // -100 to 100, 0 = unchanged
+    pub saturation: Option<f32>,        // -100 (gray) to 100 (twice as vivid), 0 = unchanged
+    pub grayscale: Option<bool>,
+    pub sharpen: Option<f32>,           // Unsharp mask radius in pixels, e.g. 1.0-3.0
+    pub quality: Option<u8>,            // JPEG quality (default 90)
+}
+
+/// Move each pixel towards (negative) or away from (positive) its own gray value
+fn saturate(img: &DynamicImage, amount: f32) -> DynamicImage {
+    let factor = 1.0 + amount / 100.0;
+    let mut rgba = img.to_rgba8();
+    for pixel in rgba.pixels_mut() {
+        let [r, g, b, _] = pixel.0.map(f32::from);
+        let luma = 0.299 * r + 0.587 * g + 0.114 * b;
+        for channel in 0..3 {
+            let value = luma + (f32::from(pixel[channel]) - luma) * factor;
+            pixel[channel] = value.round().clamp(0.0, 255.0) as u8;
+        }
+    }
+    DynamicImage::ImageRgba8(rgba)
+}
+
+/// Apply the adjustments that are set; returns the names of those applied
+pub fn apply_adjustments(mut img: DynamicImage, adjustments: &ImageAdjustments) -> (DynamicImage, Vec<&'static str>) {
+    let mut applied = Vec::new();
+
+    if let Some(brightness) = adjustments.brightness.filter(|b| *b != 0) {
+        img = img.brighten(brightness.clamp(-100, 100) * 255 / 100);
+        applied.push("brightness");
+    }
+    if let Some(contrast) = adjustments.contrast.filter(|c| *c != 0.0) {
+        img = img.adjust_contrast(contrast.clamp(-100.0, 100.0));
+        applied.push("contrast");
+    }
+    if let Some(saturation) = adjustments.saturation.filter(|s| *s != 0.0) {
+        img = saturate(&img, saturation.clamp(-100.0, 100.0));
+        applied.push("saturation");
+    }
+    if adjustments.grayscale.unwrap_or(false) {
+        img = img.grayscale();
+        applied.push("grayscale");
+    }
+    if let Some(radius) = adjustments.sharpen.filter(|r| *r > 0.0) {
+        img = img.unsharpen(radius.min(10.0), 1);
+        applied.push("sharpen");
+    }
+    (img, applied)
+}
+
+/// Adjust an image and save it to `output_path`
+pub fn adjust_image(input_path: String, output_path: String, adjustments: ImageAdjustments) -> Result<ConversionResult, String> {
+    info!("🖼️ Adjusting image (bundled)");
+
+    let img = open_oriented(&input_path)?;
+    let (img, applied) = apply_adjustments(img, &adjustments);
+
+    save_image(&img, &output_path, adjustments.quality)?;
+
+    let output_size = fs::metadata(&output_path).map(|m| m.len()).ok();
+
+    info!("✅ Image adjusted: {}", output_path);
+    let message = if applied.is_empty() {
+        "No adjustments set; image saved unchanged".to_string()
+    } else {
+        format!("Applied {}", applied.join(", "))
+    };
+    Ok(ConversionResult {
+        success: true,
+        output_path,
+        message,
+        output_size,
+    })
+}
@@ -0,0 +1,241 @@
+//! Image Animation - animated GIF, WebP and APNG: frame info, GIF <-> WebP, first frame
+//! Frame delays are carried over as-is. Frame counts and durations come from the frame headers,
+//! so inspecting a file does not decode its pixels. The image crate cannot write animated WebP, so
+//! that direction goes through libwebp's animation encoder (webp crate).
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::BufReader;
+use std::path::Path;
+use std::time::Duration;
+use log::info;
+use image::codecs::gif::{GifDecoder, GifEncoder, Repeat};
+use image::codecs::png::PngDecoder;
+use image::codecs::webp::WebPDecoder;
+use image::{AnimationDecoder, Frame, ImageFormat, ImageReader};
+use crate::bundled_converter::{save_image, ConversionResult};
+use crate::image_decode::{is_heif, open_image};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImageInfo {
+    pub width: u32,
+    pub height: u32,
+    pub format: Option<String>,
+    pub frame_count: usize,             // 1 for still images
+    pub duration_ms: u64,               // One loop of the animation; 0 for still images
+    pub animated: bool,
+}
+
+fn guess_format(path: &str) -> Result<Option<ImageFormat>, String> {
+    ImageReader::open(path)
+        .and_then(|reader| reader.with_guessed_format())
+        .map(|reader| reader.format())
+        .map_err(|e| format!("Failed to open image: {}", e))
+}
+
+fn reader(path: &str) -> Result<BufReader<fs::File>, String> {
+    fs::File::open(path)
+        .map(BufReader::new)
+        .map_err(|e| format!("Failed to open image: {}", e))
+}
+
+/// All frames of an animated GIF/WebP/APNG; None for still images
+fn decode_animation(path: &str) -> Result<Option<Vec<Frame>>, String> {
+    let decode_err = |e: image::ImageError| format!("Failed to decode animation: {}", e);
+    let frames = match guess_format(path)? {
+        Some(ImageFormat::Gif) => GifDecoder::new(reader(path)?).map_err(decode_err)?
+            .into_frames()
+            .collect_frames(),
+        Some(ImageFormat::WebP) => {
+            let decoder = WebPDecoder::new(reader(path)?).map_err(decode_err)?;
+            if !decoder.has_animation() {
+                return Ok(None);
+            }
+            decoder.into_frames().collect_frames()
+        }
+        Some(ImageFormat::Png) => {
+            let decoder = PngDecoder::new(reader(path)?).map_err(decode_err)?;
+            if !decoder.is_apng().map_err(decode_err)? {
+                return Ok(None);
+            }
+            decoder.apng().map_err(decode_err)?.into_frames().collect_frames()
+        }
+        _ => return Ok(None),
+    };
+    let frames = frames.map_err(decode_err)?;
+    Ok(if frames.len() > 1 { Some(frames) } else { None })
+}
+
+fn frame_ms(frame: &Frame) -> u64 {
+    Duration::from(frame.delay()).as_millis() as u64
+}
+
+struct AnimationInfo {
+    width: u32,
+    height: u32,
+    frame_count: usize,
+    duration_ms: u64,
+}
+
+/// Size, frame count and loop length of an animated GIF/WebP/APNG read from the frame headers
+/// only; None for still images
+fn animation_info(path: &str) -> Result<Option<AnimationInfo>, String> {
+    let info = match guess_format(path)? {
+        Some(ImageFormat::Gif) => {
+            let decode_err = |e: gif::DecodingError| format!("Failed to read GIF: {}", e);
+            let mut options = gif::DecodeOptions::new();
+            options.skip_frame_decoding(true);
+            let mut decoder = options.read_info(reader(path)?).map_err(decode_err)?;
+            let (width, height) = (decoder.width() as u32, decoder.height() as u32);
+            let (mut frame_count, mut duration_ms) = (0, 0);
+            while let Some(frame) = decoder.next_frame_info().map_err(decode_err)? {
+                frame_count += 1;
+                duration_ms += frame.delay as u64 * 10;
+            }
+            AnimationInfo { width, height, frame_count, duration_ms }
+        }
+        Some(ImageFormat::WebP) => {
+            let decoder = image_webp::WebPDecoder::new(reader(path)?)
+                .map_err(|e| format!("Failed to read WebP: {}", e))?;
+            let (width, height) = decoder.dimensions();
+            AnimationInfo { width, height, frame_count: decoder.num_frames() as usize, duration_ms: decoder.loop_duration() }
+        }
+        Some(ImageFormat::Png) => {
+            let decode_err = |e: png::DecodingError| format!("Failed to read PNG: {}", e);
+            let mut decoder = png::Decoder::new(reader(path)?).read_info().map_err(decode_err)?;
+            let info = decoder.info();
+            let (width, height) = (info.width, info.height);
+            let Some(frame_count) = info.animation_control.map(|control| control.num_frames as usize) else {
+                return Ok(None);
+            };
+            let delay_ms = |control: &png::FrameControl| {
+                let denominator = if control.delay_den == 0 { 100 } else { control.delay_den as u64 };
+                control.delay_num as u64 * 1000 / denominator
+            };
+            // The first frame control comes before the default image when that image is part of the animation
+            let mut duration_ms = info.frame_control.as_ref().map_or(0, delay_ms);
+            let remaining = if info.frame_control.is_some() { frame_count.saturating_sub(1) } else { frame_count };
+            for _ in 0..remaining {
+                duration_ms += delay_ms(decoder.next_frame_info().map_err(decode_err)?);
+            }
+            AnimationInfo { width, height, frame_count, duration_ms }
+        }
+        _ => return Ok(None),
+    };
+    Ok((info.frame_count > 1).then_some(info))
+}
+
+/// Size, format and frame count of an image
+pub fn get_image_info(path: &str) -> Result<ImageInfo, String> {
+    if is_heif(path) {
+        let img = open_image(path)?;
+        return Ok(ImageInfo {
+            width: img.width(),
+            height: img.height(),
+            format: Some("Heif".to_string()),
+            frame_count: 1,
+            duration_ms: 0,
+            animated: false,
+        });
+    }
+    let format = guess_format(path)?.map(|f| format!("{:?}", f));
+    if let Some(info) = animation_info(path)? {
+        return Ok(ImageInfo {
+            width: info.width,
+            height: info.height,
+            format,
+            frame_count: info.frame_count,
+            duration_ms: info.duration_ms,
+            animated: true,
+        });
+    }
+    let (width, height) = ImageReader::open(path)
+        .and_then(|r| r.with_guessed_format())
+        .map_err(|e| format!("Failed to open image: {}", e))?
+        .into_dimensions()
+        .map_err(|e| format!("Failed to read image: {}", e))?;
+    Ok(ImageInfo { width, height, format, frame_count: 1, duration_ms: 0, animated: false })
+}
+
+/// Animated input about to be written as GIF or WebP (the formats that can keep the frames)
+pub fn is_animation_conversion(input_path: &str, output_path: &str) -> bool {
+    let ext = Path::new(output_path).extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
+    matches!(ext.as_str(), "gif" | "webp") && !is_heif(input_path) && matches!(animation_info(input_path), Ok(Some(_)))
+}
+
+fn write_gif(frames: Vec<Frame>, output_path: &str) -> Result<(), String> {
+    let file = fs::File::create(output_path).map_err(|e| format!("Failed to create output: {}", e))?;
+    let mut encoder = GifEncoder::new_with_speed(file, 10);
+    encoder.set_repeat(Repeat::Infinite)
+        .map_err(|e| format!("Failed to encode GIF: {}", e))?;
+    encoder.encode_frames(frames)
+        .map_err(|e| format!("Failed to encode GIF: {}", e))
+}
+
+/// `quality` None writes lossless WebP
+fn write_webp(frames: &[Frame], output_path: &str, quality: Option<u8>) -> Result<(), String> {
+    let mut config = webp::WebPConfig::new().map_err(|_| "Failed to set up WebP encoder".to_string())?;
+    match quality {
+        Some(q) => config.quality = q.min(100) as f32,
+        None => config.lossless = 1,
+    }
+
+    let (width, height) = frames[0].buffer().dimensions();
+    let mut encoder = webp::AnimEncoder::new(width, height, &config);
+    encoder.set_loop_count(0);
+    let mut timestamp = 0;
+    for frame in frames {
+        let buffer = frame.buffer();
+        encoder.add_frame(webp::AnimFrame::from_rgba(buffer.as_raw(), buffer.width(), buffer.height(), timestamp as i32));
+        timestamp += frame_ms(frame);
+    }
+    let data = encoder.try_encode()
+        .map_err(|e| format!("Failed to encode WebP: {:?}", e))?;
+    fs::write(output_path, &*data).map_err(|e| format!("Failed to write WebP: {}", e))
+}
+
+/// Convert an animated GIF/WebP/APNG to animated GIF or WebP, keeping each frame's delay.
+/// WebP `quality` is lossy 0-100 (lossless when None); GIF is always palette-quantized.
+pub fn convert_animation(input_path: String, output_path: String, quality: Option<u8>) -> Result<ConversionResult, String> {
+    info!("🖼️ Converting animation (bundled)");
+
+    let frames = decode_animation(&input_path)?
+        .ok_or_else(|| "Input is not an animated image".to_string())?;
+    let frame_count = frames.len();
+
+    let ext = Path::new(&output_path).extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
+    match ext.as_str() {
+        "gif" => write_gif(frames, &output_path)?,
+        "webp" => write_webp(&frames, &output_path, quality)?,
+        _ => return Err(format!("Animations can be saved as gif or webp, not {}", ext)),
+    }
+
+    let output_size = fs::metadata(&output_path).map(|m| m.len()).ok();
+
+    info!("✅ Animation converted: {}", output_path);
+    Ok(ConversionResult {
+        success: true,
+        output_path,
+        message: format!("Converted animation with {} frames", frame_count),
+        output_size,
+    })
+}
+
+/// Save the first frame of an animation (or the image itself) as a still image
+pub fn extract_first_frame(input_path: String, output_path: String, quality: Option<u8>) -> Result<ConversionResult, String> {
+    info!("🖼️ Extracting first frame (bundled)");
+
+    // Still decoders return the first frame of an animation
+    let img = open_image(&input_path)?;
+    save_image(&img, &output_path, quality)?;
+
+    let output_size = fs::metadata(&output_path).map(|m| m.len()).ok();
+
+    info!("✅ First frame saved: {}", output_path);
+    Ok(ConversionResult {
+        success: true,
+        output_path,
+        message: format!("First frame saved at {}x{}", img.width(), img.height()),
+        output_size,
+    })
+}
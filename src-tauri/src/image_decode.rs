@@ -0,0 +1,94 @@
+//! Image Decode - open any supported image, including AVIF and iPhone HEIC photos
+//! The image crate cannot read HEIF containers. HEIC/HEIF go through libheif (`heif` feature) and
+//! AVIF through the image crate's dav1d decoder (`avif` feature, see avif_decode); both apply the
+//! stored rotation and mirroring so results are upright. The app (tauri dev/build) enables both
+//! features - they need libheif and dav1d installed, e.g. via vcpkg on Windows. A plain cargo
+//! build without them reports those formats as unsupported.
+
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+use image::{DynamicImage, ImageReader};
+#[cfg(feature = "avif")]
+use crate::avif_decode::decode_avif;
+use crate::file_sniff::sniff;
+
+/// ISO-BMFF brands used by HEIC, HEIF and AVIF files (bytes 8..12 after "ftyp")
+const HEIF_BRANDS: [&[u8; 4]; 8] = [b"heic", b"heix", b"hevc", b"hevx", b"mif1", b"msf1", b"avif", b"avis"];
+
+/// HEIF-family file, judged by its ftyp box since phones do not always name files right
+/// (an iPhone "IMG.HEIC" may be a JPEG); the extension decides only for unreadable files
+pub fn is_heif(path: &str) -> bool {
+    let mut header = [0u8; 12];
+    match fs::File::open(path).and_then(|mut file| file.read_exact(&mut header)) {
+        Ok(()) => &header[4..8] == b"ftyp" && HEIF_BRANDS.iter().any(|brand| header[8..12] == brand[..]),
+        Err(_) => {
+            let ext = Path::new(path).extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
+            ["heic", "heif", "avif"].contains(&ext.as_str())
+        }
+    }
+}
+
+/// AVIF rather than HEIC/HEIF, judged by the ftyp major brand
+fn is_avif(path: &str) -> bool {
+    let mut header = [0u8; 12];
+    match fs::File::open(path).and_then(|mut file| file.read_exact(&mut header)) {
+        Ok(()) => &header[4..8] == b"ftyp" && matches!(&header[8..12], b"avif" | b"avis"),
+        Err(_) => Path::new(path).extension().is_some_and(|ext| ext.eq_ignore_ascii_case("avif")),
+    }
+}
+
+#[cfg(feature = "heif")]
+fn decode_heif(path: &str) -> Result<DynamicImage, String> {
+    use libheif_rs::{ColorSpace, HeifContext, LibHeif, RgbChroma};
+
+    let lib_heif = LibHeif::new();
+    let context = HeifContext::read_from_file(path)
+        .map_err(|e| format!("Failed to open HEIF image: {}", e))?;
+    let handle = context.primary_image_handle()
+        .map_err(|e| format!("Failed to read HEIF image: {}", e))?;
+    let decoded = lib_heif.decode(&handle, ColorSpace::Rgb(RgbChroma::Rgba), None)
+        .map_err(|e| format!("Failed to decode HEIF image: {}", e))?;
+
+    let (width, height) = (decoded.width(), decoded.height());
+    let planes = decoded.planes();
+    let plane = planes.interleaved.ok_or("HEIF image has no RGBA data")?;
+    let row_bytes = width as usize * 4;
+    let mut pixels = Vec::with_capacity(row_bytes * height as usize);
+    for row in plane.data.chunks(plane.stride).take(height as usize) {
+        pixels.extend_from_slice(&row[..row_bytes]);
+    }
+    image::RgbaImage::from_raw(width, height, pixels)
+        .map(DynamicImage::ImageRgba8)
+        .ok_or_else(|| "HEIF image data is incomplete".to_string())
+}
+
+#[cfg(not(feature = "heif"))]
+fn decode_heif(_path: &str) -> Result<DynamicImage, String> {
+    Err("HEIC photos are not supported in this build - convert them to JPEG first \
+         (on an iPhone: Settings > Camera > Formats > Most Compatible)".to_string())
+}
+
+#[cfg(not(feature = "avif"))]
+fn decode_avif(_bytes: &[u8]) -> Result<DynamicImage, String> {
+    Err("AVIF images are not supported in this build - convert them to JPEG or PNG first".to_string())
+}
+
+/// Open an image with the bundled decoders; AVIF via avif_decode, HEIC/HEIF via libheif. The
+/// format is taken from the file content, so misnamed files still open; non-images get a precise error.
+pub fn open_image(path: &str) -> Result<DynamicImage, String> {
+    if is_avif(path) {
+        let bytes = fs::read(path).map_err(|e| format!("Failed to open image: {}", e))?;
+        return decode_avif(&bytes);
+    }
+    if is_heif(path) {
+        return decode_heif(path);
+    }
+    let reader = ImageReader::open(path)
+        .and_then(|reader| reader.with_guessed_format())
+        .map_err(|e| format!("Failed to open image: {}", e))?;
+    reader.decode().map_err(|e| match sniff(path) {
+        Ok(kind) if !kind.is_image() => format!("Not an image: file is actually {}", kind.label()),
+        _ => format!("Failed to open image: {}", e),
+    })
+}
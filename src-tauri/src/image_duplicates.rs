@@ -0,0 +1,251 @@
+//! Image Duplicates - find duplicate photos in a folder tree
+//! "exact" compares file contents (SHA-256, only for files of equal size); "dhash" and "phash"
+//! compare 64-bit perceptual hashes, so resized or re-saved copies match too. Two images are
+//! similar when their hashes differ in at most `threshold` bits.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use log::{info, warn};
+use image::imageops::FilterType;
+use image::DynamicImage;
+use sha2::{Digest, Sha256};
+use crate::image_edit::open_oriented;
+
+const IMAGE_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "webp", "bmp", "gif", "tif", "tiff", "heic", "heif", "avif"];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DuplicateMethod {
+    #[default]
+    Exact,      // Byte-identical files
+    Dhash,      // Difference hash: fast, robust to resizing and recompression
+    Phash,      // DCT hash: slower, also tolerates small edits and colour changes
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateFile {
+    pub path: String,
+    pub size: u64,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateGroup {
+    pub files: Vec<DuplicateFile>,      // Largest (most pixels, then bytes) first - the copy to keep
+    pub max_distance: u32,              // Largest hash difference in the group (0 = identical)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateReport {
+    pub files_scanned: usize,
+    pub skipped: usize,                 // Files that could not be read or decoded
+    pub groups: Vec<DuplicateGroup>,
+    pub reclaimable_bytes: u64,         // Size of every file except the first of each group
+}
+
+fn collect_images(dir: &Path, images: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        warn!("Skipping unreadable folder {}", dir.display());
+        return;
+    };
+    for path in entries.flatten().map(|e| e.path()) {
+        if path.is_dir() {
+            collect_images(&path, images);
+        } else if path.extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| IMAGE_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+        {
+            images.push(path);
+        }
+    }
+}
+
+fn sha256_file(path: &Path) -> Option<[u8; 32]> {
+    let mut file = fs::File::open(path).ok()?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 1 << 16];
+    loop {
+        let read = file.read(&mut buffer).ok()?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Some(hasher.finalize().into())
+}
+
+/// 9x8 grayscale; each bit says whether a pixel is brighter than its right neighbour
+fn dhash(img: &DynamicImage) -> u64 {
+    let small = img.resize_exact(9, 8, FilterType::Triangle).to_luma8();
+    let mut hash = 0u64;
+    for y in 0..8 {
+        for x in 0..8 {
+            hash = (hash << 1) | (small.get_pixel(x, y)[0] > small.get_pixel(x + 1, y)[0]) as u64;
+        }
+    }
+    hash
+}
+
+/// 32x32 grayscale DCT; each bit says whether one of the 8x8 lowest frequencies is above their median
+fn phash(img: &DynamicImage) -> u64 {
+    let small = img.resize_exact(32, 32, FilterType::Triangle).to_luma8();
+    let cosines: Vec<[f64; 32]> = (0..8)
+        .map(|u| std::array::from_fn(|x| ((2 * x + 1) as f64 * u as f64 * std::f64::consts::PI / 64.0).cos()))
+        .collect();
+
+    let mut coefficients = Vec::with_capacity(64);
+    for cos_v in &cosines {
+        for cos_u in &cosines {
+            let mut sum = 0.0;
+            for (y, row) in small.rows().enumerate() {
+                for (x, pixel) in row.enumerate() {
+                    sum += pixel[0] as f64 * cos_u[x] * cos_v[y];
+                }
+            }
+            coefficients.push(sum);
+        }
+    }
+    // The DC term only reflects overall brightness
+    let mut sorted = coefficients[1..].to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let median = sorted[sorted.len() / 2];
+    coefficients.iter().fold(0u64, |hash, c| (hash << 1) | (*c > median) as u64)
+}
+
+/// Decode and hash images on every core; None for files that fail to decode
+fn perceptual_hashes(paths: &[PathBuf], method: DuplicateMethod) -> Vec<Option<(u64, u32, u32)>> {
+    let threads = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(4);
+    let chunk_size = paths.len().div_ceil(threads).max(1);
+    std::thread::scope(|scope| {
+        let workers: Vec<_> = paths.chunks(chunk_size)
+            .map(|chunk| (chunk.len(), scope.spawn(move || {
+                chunk.iter()
+                    .map(|path| match open_oriented(&path.to_string_lossy()) {
+                        Ok(img) => {
+                            let hash = if method == DuplicateMethod::Phash { phash(&img) } else { dhash(&img) };
+                            Some((hash, img.width(), img.height()))
+                        }
+                        Err(e) => {
+                            warn!("Skipping {}: {}", path.display(), e);
+                            None
+                        }
+                    })
+                    .collect::<Vec<_>>()
+            })))
+            .collect();
+        workers.into_iter()
+            .flat_map(|(len, worker)| worker.join().unwrap_or_else(|_| vec![None; len]))
+            .collect()
+    })
+}
+
+fn find(parents: &mut [usize], i: usize) -> usize {
+    let mut root = i;
+    while parents[root] != root {
+        root = parents[root];
+    }
+    parents[i] = root;
+    root
+}
+
+/// Find groups of duplicate images under `dir` (recursively). `threshold` is the number of
+/// differing hash bits still counted as a match (default 5 of 64; ignored for exact).
+pub fn find_duplicate_images(dir: String, method: DuplicateMethod, threshold: Option<u32>) -> Result<DuplicateReport, String> {
+    if !Path::new(&dir).is_dir() {
+        return Err(format!("Folder not found: {}", dir));
+    }
+    let mut paths = Vec::new();
+    collect_images(Path::new(&dir), &mut paths);
+    paths.sort();
+
+    info!("🖼️ Looking for duplicates among {} images ({:?})", paths.len(), method);
+
+    let sizes: Vec<u64> = paths.iter().map(|p| fs::metadata(p).map(|m| m.len()).unwrap_or(0)).collect();
+    let mut skipped = 0;
+    // Each entry: (indexes into paths, max distance)
+    let mut clusters: Vec<(Vec<usize>, u32)> = Vec::new();
+    let mut dimensions: HashMap<usize, (u32, u32)> = HashMap::new();
+
+    if method == DuplicateMethod::Exact {
+        let mut by_size: HashMap<u64, Vec<usize>> = HashMap::new();
+        for (i, size) in sizes.iter().enumerate() {
+            by_size.entry(*size).or_default().push(i);
+        }
+        let mut by_hash: HashMap<[u8; 32], Vec<usize>> = HashMap::new();
+        for candidates in by_size.into_values().filter(|c| c.len() > 1) {
+            for i in candidates {
+                match sha256_file(&paths[i]) {
+                    Some(hash) => by_hash.entry(hash).or_default().push(i),
+                    None => skipped += 1,
+                }
+            }
+        }
+        clusters.extend(by_hash.into_values().filter(|g| g.len() > 1).map(|g| (g, 0)));
+    } else {
+        let threshold = threshold.unwrap_or(5).min(32);
+        let hashes = perceptual_hashes(&paths, method);
+        let hashed: Vec<(usize, u64)> = hashes.iter().enumerate()
+            .filter_map(|(i, h)| h.map(|(hash, width, height)| {
+                dimensions.insert(i, (width, height));
+                (i, hash)
+            }))
+            .collect();
+        skipped = paths.len() - hashed.len();
+
+        let mut parents: Vec<usize> = (0..hashed.len()).collect();
+        for (a, (_, hash_a)) in hashed.iter().enumerate() {
+            for (b, (_, hash_b)) in hashed.iter().enumerate().skip(a + 1) {
+                if (hash_a ^ hash_b).count_ones() <= threshold {
+                    let (root_a, root_b) = (find(&mut parents, a), find(&mut parents, b));
+                    parents[root_b] = root_a;
+                }
+            }
+        }
+        let mut groups: HashMap<usize, Vec<usize>> = HashMap::new();
+        for a in 0..hashed.len() {
+            let root = find(&mut parents, a);
+            groups.entry(root).or_default().push(a);
+        }
+        for members in groups.into_values().filter(|g| g.len() > 1) {
+            let max_distance = members.iter()
+                .flat_map(|&a| members.iter().map(move |&b| (a, b)))
+                .map(|(a, b)| (hashed[a].1 ^ hashed[b].1).count_ones())
+                .max()
+                .unwrap_or(0);
+            clusters.push((members.iter().map(|&a| hashed[a].0).collect(), max_distance));
+        }
+    }
+
+    let mut groups: Vec<DuplicateGroup> = clusters.into_iter()
+        .map(|(members, max_distance)| {
+            let mut files: Vec<DuplicateFile> = members.into_iter()
+                .map(|i| DuplicateFile {
+                    path: paths[i].to_string_lossy().to_string(),
+                    size: sizes[i],
+                    width: dimensions.get(&i).map(|d| d.0),
+                    height: dimensions.get(&i).map(|d| d.1),
+                })
+                .collect();
+            files.sort_by_key(|f| std::cmp::Reverse((f.width.unwrap_or(0) as u64 * f.height.unwrap_or(0) as u64, f.size)));
+            DuplicateGroup { files, max_distance }
+        })
+        .collect();
+    groups.sort_by(|a, b| a.files[0].path.cmp(&b.files[0].path));
+
+    let reclaimable_bytes = groups.iter()
+        .flat_map(|g| g.files.iter().skip(1))
+        .map(|f| f.size)
+        .sum();
+
+    info!("✅ Found {} duplicate groups", groups.len());
+    Ok(DuplicateReport {
+        files_scanned: paths.len(),
+        skipped,
+        groups,
+        reclaimable_bytes,
+    })
+}
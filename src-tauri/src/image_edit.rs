@@ -0,0 +1,149 @@
+//! Image Edit - crop, rotate and flip with the bundled image crate
+//! Output format follows the output file's extension, as in bundled_convert_image.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use log::info;
+use image::metadata::Orientation;
+use image::{DynamicImage, ImageDecoder, ImageReader};
+use crate::bundled_converter::{save_image, ConversionResult};
+use crate::image_decode::{is_heif, open_image};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum CropMode {
+    Region { x: u32, y: u32, width: u32, height: u32 },   // Pixel rectangle from the top-left corner
+    Aspect { ratio: String },                              // Largest centered area with this shape: "4:3", "16:9", "1:1", "35:45"
+}
+
+/// Open an image turned upright per its EXIF orientation; phones save photos sideways and
+/// only record how they should be shown
+pub fn open_oriented(path: &str) -> Result<DynamicImage, String> {
+    // The HEIF and AVIF decoders already apply the rotation stored in the file
+    if is_heif(path) {
+        return open_image(path);
+    }
+    let mut decoder = ImageReader::open(path)
+        .and_then(|reader| reader.with_guessed_format())
+        .map_err(|e| format!("Failed to open image: {}", e))?
+        .into_decoder()
+        .map_err(|e| format!("Failed to open image: {}", e))?;
+    let orientation = decoder.orientation().unwrap_or(Orientation::NoTransforms);
+    let mut img = DynamicImage::from_decoder(decoder)
+        .map_err(|e| format!("Failed to decode image: {}", e))?;
+    img.apply_orientation(orientation);
+    Ok(img)
+}
+
+/// Parse "4:3", "4x3", "4/3" or "1.333" into width / height
+pub fn parse_ratio(ratio: &str) -> Result<f64, String> {
+    let parts: Vec<&str> = ratio.split([':', 'x', 'X', '/']).map(str::trim).collect();
+    let value = match parts.as_slice() {
+        [single] => single.parse::<f64>().ok(),
+        [w, h] => match (w.parse::<f64>(), h.parse::<f64>()) {
+            (Ok(w), Ok(h)) if h > 0.0 => Some(w / h),
+            _ => None,
+        },
+        _ => None,
+    };
+    value
+        .filter(|v| v.is_finite() && *v > 0.0)
+        .ok_or_else(|| format!("Invalid aspect ratio: {} (use e.g. 4:3)", ratio))
+}
+
+/// Center-crop to width / height = `ratio`, keeping as much of the image as possible
+pub fn crop_to_aspect(img: &DynamicImage, ratio: f64) -> DynamicImage {
+    let (width, height) = (img.width(), img.height());
+    if (width as f64) / (height as f64) > ratio {
+        let new_width = ((height as f64 * ratio).round() as u32).clamp(1, width);
+        img.crop_imm((width - new_width) / 2, 0, new_width, height)
+    } else {
+        let new_height = ((width as f64 / ratio).round() as u32).clamp(1, height);
+        img.crop_imm(0, (height - new_height) / 2, width, new_height)
+    }
+}
+
+/// Crop an image to a pixel region or a centered aspect ratio
+pub fn crop_image(
+    input_path: String,
+    output_path: String,
+    mode: CropMode,
+    quality: Option<u8>,
+) -> Result<ConversionResult, String> {
+    info!("🖼️ Cropping image (bundled)");
+
+    let img = open_image(&input_path)?;
+
+    let cropped = match mode {
+        CropMode::Region { x, y, width, height } => {
+            if width == 0 || height == 0 {
+                return Err("Crop width and height must be greater than 0".to_string());
+            }
+            if x.saturating_add(width) > img.width() || y.saturating_add(height) > img.height() {
+                return Err(format!(
+                    "Crop area {}x{} at ({}, {}) is outside the {}x{} image",
+                    width, height, x, y, img.width(), img.height()
+                ));
+            }
+            img.crop_imm(x, y, width, height)
+        }
+        CropMode::Aspect { ratio } => crop_to_aspect(&img, parse_ratio(&ratio)?),
+    };
+
+    save_image(&cropped, &output_path, quality)?;
+
+    let output_size = fs::metadata(&output_path).map(|m| m.len()).ok();
+
+    info!("✅ Image cropped: {}", output_path);
+    Ok(ConversionResult {
+        success: true,
+        output_path,
+        message: format!("Image cropped to {}x{}", cropped.width(), cropped.height()),
+        output_size,
+    })
+}
+
+/// Rotate by quarter turns (clockwise, negative for counter-clockwise) and flip.
+/// With `auto_orient` the EXIF orientation is applied first, so edits start from the upright photo.
+pub fn transform_image(
+    input_path: String,
+    output_path: String,
+    rotate90s: i32,
+    flip_horizontal: bool,
+    flip_vertical: bool,
+    auto_orient: bool,
+    quality: Option<u8>,
+) -> Result<ConversionResult, String> {
+    info!("🖼️ Transforming image (bundled)");
+
+    let img = if auto_orient {
+        open_oriented(&input_path)?
+    } else {
+        open_image(&input_path)?
+    };
+
+    let mut img = match rotate90s.rem_euclid(4) {
+        1 => img.rotate90(),
+        2 => img.rotate180(),
+        3 => img.rotate270(),
+        _ => img,
+    };
+    if flip_horizontal {
+        img = img.fliph();
+    }
+    if flip_vertical {
+        img = img.flipv();
+    }
+
+    save_image(&img, &output_path, quality)?;
+
+    let output_size = fs::metadata(&output_path).map(|m| m.len()).ok();
+
+    info!("✅ Image transformed: {}", output_path);
+    Ok(ConversionResult {
+        success: true,
+        output_path,
+        message: format!("Image saved at {}x{}", img.width(), img.height()),
+        output_size,
+    })
+}
@@ -0,0 +1,241 @@
+//! Image Metadata - read EXIF (camera, date, GPS) from photos and strip it before publishing
+//! JPEG and PNG are stripped losslessly by dropping metadata segments/chunks; other formats
+//! are re-encoded, which writes no metadata at all.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::BufReader;
+use std::path::Path;
+use log::info;
+use exif::{In, Tag, Value};
+use image::ImageReader;
+use crate::bundled_converter::{save_image, ConversionResult};
+use crate::image_decode::{is_heif, open_image};
+use crate::image_edit::open_oriented;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GpsInfo {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub altitude: Option<f64>,          // Metres above sea level
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExifField {
+    pub tag: String,
+    pub ifd: String,                    // primary or thumbnail
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImageMetadata {
+    pub width: u32,
+    pub height: u32,
+    pub format: Option<String>,
+    pub camera_make: Option<String>,
+    pub camera_model: Option<String>,
+    pub lens: Option<String>,
+    pub taken_at: Option<String>,       // DateTimeOriginal as written by the camera
+    pub orientation: Option<u32>,       // EXIF orientation 1-8 (1 = upright)
+    pub exposure_time: Option<String>,
+    pub f_number: Option<String>,
+    pub iso: Option<u32>,
+    pub focal_length: Option<String>,
+    pub software: Option<String>,
+    pub gps: Option<GpsInfo>,
+    pub fields: Vec<ExifField>,         // Every EXIF field, for the details view
+}
+
+fn text_field(exif: &exif::Exif, tag: Tag) -> Option<String> {
+    exif.get_field(tag, In::PRIMARY)
+        .map(|f| f.display_value().to_string().trim_matches('"').trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// Degrees from an EXIF degrees/minutes/seconds triple; S and W are negative
+fn coordinate(exif: &exif::Exif, tag: Tag, reference: Tag) -> Option<f64> {
+    let Value::Rational(parts) = &exif.get_field(tag, In::PRIMARY)?.value else {
+        return None;
+    };
+    let degrees = parts.iter().zip([1.0, 60.0, 3600.0]).map(|(p, div)| p.to_f64() / div).sum::<f64>();
+    let negative = text_field(exif, reference).is_some_and(|r| r.starts_with('S') || r.starts_with('W'));
+    Some(if negative { -degrees } else { degrees })
+}
+
+/// Read dimensions and EXIF metadata of an image; images without EXIF return empty fields
+pub fn get_image_metadata(input_path: &str) -> Result<ImageMetadata, String> {
+    let (width, height, format) = if is_heif(input_path) {
+        let img = open_image(input_path)?;
+        (img.width(), img.height(), Some("Heif".to_string()))
+    } else {
+        let reader = ImageReader::open(input_path)
+            .and_then(|r| r.with_guessed_format())
+            .map_err(|e| format!("Failed to open image: {}", e))?;
+        let format = reader.format().map(|f| format!("{:?}", f));
+        let (width, height) = reader.into_dimensions()
+            .map_err(|e| format!("Failed to read image: {}", e))?;
+        (width, height, format)
+    };
+
+    let mut metadata = ImageMetadata { width, height, format, ..Default::default() };
+
+    let file = fs::File::open(input_path).map_err(|e| format!("Failed to open image: {}", e))?;
+    let Ok(exif) = exif::Reader::new().read_from_container(&mut BufReader::new(file)) else {
+        return Ok(metadata);
+    };
+
+    metadata.camera_make = text_field(&exif, Tag::Make);
+    metadata.camera_model = text_field(&exif, Tag::Model);
+    metadata.lens = text_field(&exif, Tag::LensModel);
+    metadata.taken_at = text_field(&exif, Tag::DateTimeOriginal).or_else(|| text_field(&exif, Tag::DateTime));
+    metadata.orientation = exif.get_field(Tag::Orientation, In::PRIMARY).and_then(|f| f.value.get_uint(0));
+    metadata.exposure_time = text_field(&exif, Tag::ExposureTime);
+    metadata.f_number = text_field(&exif, Tag::FNumber);
+    metadata.iso = exif.get_field(Tag::PhotographicSensitivity, In::PRIMARY).and_then(|f| f.value.get_uint(0));
+    metadata.focal_length = text_field(&exif, Tag::FocalLength);
+    metadata.software = text_field(&exif, Tag::Software);
+
+    if let (Some(latitude), Some(longitude)) = (
+        coordinate(&exif, Tag::GPSLatitude, Tag::GPSLatitudeRef),
+        coordinate(&exif, Tag::GPSLongitude, Tag::GPSLongitudeRef),
+    ) {
+        let altitude = match exif.get_field(Tag::GPSAltitude, In::PRIMARY).map(|f| &f.value) {
+            Some(Value::Rational(parts)) if !parts.is_empty() => Some(parts[0].to_f64()),
+            _ => None,
+        };
+        metadata.gps = Some(GpsInfo { latitude, longitude, altitude });
+    }
+
+    metadata.fields = exif.fields()
+        .map(|field| ExifField {
+            tag: field.tag.to_string(),
+            ifd: if field.ifd_num == In::PRIMARY { "primary" } else { "thumbnail" }.to_string(),
+            value: field.display_value().with_unit(&exif).to_string(),
+        })
+        .collect();
+    Ok(metadata)
+}
+
+/// Copy JPEG segments except APP1 (EXIF, XMP), APP2 multi-picture index, APP13 (IPTC) and comments;
+/// the ICC profile stays. Copying stops at the end-of-image marker, so the preview and depth images
+/// phones append after it (indexed by the MPF segment) are dropped too.
+fn strip_jpeg(bytes: &[u8]) -> Option<Vec<u8>> {
+    if !bytes.starts_with(&[0xFF, 0xD8]) {
+        return None;
+    }
+    let mut output = vec![0xFF, 0xD8];
+    let mut pos = 2;
+    while pos + 2 <= bytes.len() {
+        if bytes[pos] != 0xFF {
+            return None;
+        }
+        let marker = bytes[pos + 1];
+        if marker == 0xFF {
+            pos += 1;
+            continue;
+        }
+        if marker == 0xD9 {
+            output.extend_from_slice(&[0xFF, 0xD9]);
+            return Some(output);
+        }
+        if marker == 0x01 || (0xD0..=0xD7).contains(&marker) {
+            output.extend_from_slice(&bytes[pos..pos + 2]);
+            pos += 2;
+            continue;
+        }
+        if pos + 4 > bytes.len() {
+            return None;
+        }
+        let length = u16::from_be_bytes([bytes[pos + 2], bytes[pos + 3]]) as usize;
+        let mut end = pos + 2 + length;
+        if end > bytes.len() {
+            return None;
+        }
+        let multi_picture = marker == 0xE2 && bytes[pos + 4..end].starts_with(b"MPF\0");
+        if !multi_picture && !matches!(marker, 0xE1 | 0xED | 0xFE) {
+            // Start of scan: the compressed data runs to the next marker that is not a stuffed 0xFF
+            // byte or a restart marker (progressive files have several scans)
+            if marker == 0xDA {
+                while end + 1 < bytes.len() && !(bytes[end] == 0xFF && bytes[end + 1] != 0 && !(0xD0..=0xD7).contains(&bytes[end + 1])) {
+                    end += 1;
+                }
+                if end + 1 >= bytes.len() {
+                    return None;
+                }
+            }
+            output.extend_from_slice(&bytes[pos..end]);
+        }
+        pos = end;
+    }
+    None
+}
+
+/// Copy PNG chunks except eXIf, text chunks and the modification time
+fn strip_png(bytes: &[u8]) -> Option<Vec<u8>> {
+    const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+    if !bytes.starts_with(&SIGNATURE) {
+        return None;
+    }
+    let mut output = SIGNATURE.to_vec();
+    let mut pos = 8;
+    while pos + 12 <= bytes.len() {
+        let length = u32::from_be_bytes([bytes[pos], bytes[pos + 1], bytes[pos + 2], bytes[pos + 3]]) as usize;
+        let end = pos + 12 + length;
+        if end > bytes.len() {
+            return None;
+        }
+        let kind = &bytes[pos + 4..pos + 8];
+        if !matches!(kind, b"eXIf" | b"tEXt" | b"zTXt" | b"iTXt" | b"tIME") {
+            output.extend_from_slice(&bytes[pos..end]);
+        }
+        if kind == b"IEND" {
+            return Some(output);
+        }
+        pos = end;
+    }
+    None
+}
+
+/// Remove EXIF/GPS and other metadata. A rotated JPEG is re-encoded upright, since dropping
+/// its orientation tag would otherwise leave it sideways.
+pub fn strip_image_metadata(input_path: String, output_path: String) -> Result<ConversionResult, String> {
+    info!("🖼️ Stripping image metadata (bundled)");
+
+    let bytes = fs::read(&input_path).map_err(|e| format!("Failed to read image: {}", e))?;
+    let rotated = get_image_metadata(&input_path)?.orientation.is_some_and(|o| o > 1);
+    let same_format = |ext: &[&str]| {
+        let output_ext = Path::new(&output_path).extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
+        ext.contains(&output_ext.as_str())
+    };
+
+    let stripped = if rotated {
+        None
+    } else if same_format(&["jpg", "jpeg"]) {
+        strip_jpeg(&bytes)
+    } else if same_format(&["png"]) {
+        strip_png(&bytes)
+    } else {
+        None
+    };
+
+    let method = match stripped {
+        Some(output) => {
+            fs::write(&output_path, output).map_err(|e| format!("Failed to write image: {}", e))?;
+            "losslessly"
+        }
+        None => {
+            save_image(&open_oriented(&input_path)?, &output_path, Some(95))?;
+            "by re-encoding"
+        }
+    };
+
+    let output_size = fs::metadata(&output_path).map(|m| m.len()).ok();
+
+    info!("✅ Metadata stripped: {}", output_path);
+    Ok(ConversionResult {
+        success: true,
+        output_path,
+        message: format!("Metadata removed {}", method),
+        output_size,
+    })
+}
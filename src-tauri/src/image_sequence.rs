@@ -0,0 +1,155 @@
+//! Image Sequence - split a video into numbered frames and assemble frames back into a video
+
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use log::info;
+use crate::ffmpeg_runner::{ffmpeg_command, run_ffmpeg, MediaJob};
+use crate::media_converter::ConversionResult;
+
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "bmp", "tif", "tiff", "webp"];
+
+/// Sorted image files in a folder (names like frame_0001.png sort correctly)
+fn list_images(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let mut images: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read folder: {}", e))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| IMAGE_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+        })
+        .collect();
+    images.sort();
+    Ok(images)
+}
+
+/// Write every frame of `input_path` (resampled to `fps` if given) as dir/frame_000001.<format>
+pub async fn video_to_images(
+    input_path: String,
+    output_dir: String,
+    fps: Option<f64>,
+    format: String,
+    job: MediaJob,
+) -> Result<ConversionResult, String> {
+    if !Path::new(&input_path).exists() {
+        return Err(format!("Input file not found: {}", input_path));
+    }
+    let format = format.to_lowercase();
+    if !IMAGE_EXTENSIONS.contains(&format.as_str()) {
+        return Err(format!("Unsupported image format: {}", format));
+    }
+    std::fs::create_dir_all(&output_dir)
+        .map_err(|e| format!("Failed to create output folder: {}", e))?;
+
+    info!("🎞️ Exporting frames: {} -> {} ({})", input_path, output_dir, format);
+
+    let pattern = Path::new(&output_dir).join(format!("frame_%06d.{}", format));
+    let job = job.with_duration_of(&input_path).await;
+    let mut cmd = ffmpeg_command();
+    cmd.arg("-y");
+    cmd.arg("-i").arg(&input_path);
+    if let Some(fps) = fps.filter(|f| *f > 0.0) {
+        cmd.arg("-vf").arg(format!("fps={}", fps));
+    }
+    if matches!(format.as_str(), "jpg" | "jpeg") {
+        cmd.arg("-q:v").arg("2");
+    }
+    cmd.arg(&pattern);
+
+    run_ffmpeg(cmd, &job).await
+        .map_err(|error| format!("Frame export failed: {}", error))?;
+
+    let count = list_images(Path::new(&output_dir))?.len();
+    info!("✅ Exported {} frames to {}", count, output_dir);
+    Ok(ConversionResult {
+        success: true,
+        output_path: output_dir,
+        message: format!("{} frames exported", count),
+        output_size: None,
+    })
+}
+
+/// Assemble frames into a video. `source` is either an ffmpeg pattern ("shot_%04d.png")
+/// or a folder, in which case every image in it is used in name order.
+pub async fn images_to_video(
+    source: String,
+    output_path: String,
+    fps: f64,
+    codec: String,
+    mut job: MediaJob,
+) -> Result<ConversionResult, String> {
+    if fps <= 0.0 {
+        return Err("Frame rate must be greater than zero".to_string());
+    }
+
+    let (encoder, pix_fmt) = match codec.to_lowercase().as_str() {
+        "h264" | "libx264" => ("libx264", "yuv420p"),
+        "h265" | "hevc" | "libx265" => ("libx265", "yuv420p"),
+        "vp9" | "libvpx-vp9" => ("libvpx-vp9", "yuv420p"),
+        "prores" => ("prores_ks", "yuv422p10le"),
+        other => return Err(format!("Unsupported codec: {}", other)),
+    };
+
+    let mut cmd = ffmpeg_command();
+    cmd.arg("-y");
+
+    let source_dir = Path::new(&source);
+    let list_file = if source_dir.is_dir() {
+        let images = list_images(source_dir)?;
+        if images.is_empty() {
+            return Err(format!("No images found in {}", source));
+        }
+        job.total_secs = Some(images.len() as f64 / fps);
+
+        // Concat list with a fixed duration per image: works on every platform, unlike glob patterns
+        let mut list = String::new();
+        let quoted = |image: &PathBuf| image.to_string_lossy().replace('\'', "'\\''");
+        for image in &images {
+            let _ = writeln!(list, "file '{}'\nduration {:.6}", quoted(image), 1.0 / fps);
+        }
+        // The demuxer ignores the last duration unless the last file is listed once more
+        if let Some(last) = images.last() {
+            let _ = writeln!(list, "file '{}'", quoted(last));
+        }
+        let list_path = std::env::temp_dir().join(format!("{}-frames.txt", job.id));
+        std::fs::write(&list_path, list)
+            .map_err(|e| format!("Failed to write frame list: {}", e))?;
+
+        info!("🎞️ Assembling {} images from {} -> {}", images.len(), source, output_path);
+        cmd.arg("-f").arg("concat");
+        cmd.arg("-safe").arg("0");
+        cmd.arg("-i").arg(&list_path);
+        cmd.arg("-r").arg(fps.to_string());
+        Some(list_path)
+    } else {
+        info!("🎞️ Assembling images {} -> {}", source, output_path);
+        cmd.arg("-framerate").arg(fps.to_string());
+        cmd.arg("-i").arg(&source);
+        None
+    };
+
+    let job = job.with_output(&output_path);
+    cmd.arg("-c:v").arg(encoder);
+    cmd.arg("-pix_fmt").arg(pix_fmt);
+    // 4:2:0 encoders need even dimensions
+    cmd.arg("-vf").arg("scale=trunc(iw/2)*2:trunc(ih/2)*2");
+    if encoder == "libx264" {
+        cmd.arg("-crf").arg("18");
+    }
+    cmd.arg(&output_path);
+
+    let result = run_ffmpeg(cmd, &job).await;
+    if let Some(list_path) = list_file {
+        let _ = std::fs::remove_file(list_path);
+    }
+    result.map_err(|error| format!("Video assembly failed: {}", error))?;
+
+    let output_size = std::fs::metadata(&output_path).map(|m| m.len()).ok();
+    info!("✅ Video assembled: {}", output_path);
+    Ok(ConversionResult {
+        success: true,
+        output_path,
+        message: "Video created from images successfully".to_string(),
+        output_size,
+    })
+}
@@ -0,0 +1,148 @@
+//! Image Size Limit - fit a JPEG or WebP under a file size limit ("photo under 50 KB")
+//! Finds the highest quality that fits by binary search; if even the lowest allowed quality is
+//! too big and resizing is allowed, the image is scaled down step by step and searched again.
+
+use std::fs;
+use std::path::Path;
+use log::info;
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::DynamicImage;
+use crate::bundled_converter::ConversionResult;
+use crate::image_decode::open_image;
+
+/// Never scale below this many pixels on the longer side
+const MIN_DIMENSION: u32 = 64;
+const MAX_QUALITY: u8 = 95;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LossyFormat {
+    Jpeg,
+    WebP,
+}
+
+impl LossyFormat {
+    pub fn from_path(path: &str) -> Result<Self, String> {
+        let ext = Path::new(path).extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
+        match ext.as_str() {
+            "jpg" | "jpeg" => Ok(LossyFormat::Jpeg),
+            "webp" => Ok(LossyFormat::WebP),
+            _ => Err(format!("Size targets need a JPEG or WebP output, not .{}", ext)),
+        }
+    }
+}
+
+pub struct SizedImage {
+    pub bytes: Vec<u8>,
+    pub quality: u8,
+    pub width: u32,
+    pub height: u32,
+}
+
+pub fn encode(img: &DynamicImage, format: LossyFormat, quality: u8) -> Result<Vec<u8>, String> {
+    match format {
+        LossyFormat::Jpeg => {
+            let mut bytes = Vec::new();
+            JpegEncoder::new_with_quality(&mut bytes, quality)
+                .encode_image(&DynamicImage::ImageRgb8(img.to_rgb8()))
+                .map_err(|e| format!("Failed to encode JPEG: {}", e))?;
+            Ok(bytes)
+        }
+        LossyFormat::WebP => {
+            let rgba = DynamicImage::ImageRgba8(img.to_rgba8());
+            let encoder = webp::Encoder::from_image(&rgba)
+                .map_err(|e| format!("Failed to encode WebP: {}", e))?;
+            Ok(encoder.encode(quality as f32).to_vec())
+        }
+    }
+}
+
+/// Highest quality in `min_quality..=95` whose output fits `max_bytes`, if any
+pub fn best_quality(img: &DynamicImage, format: LossyFormat, max_bytes: usize, min_quality: u8) -> Result<Option<(Vec<u8>, u8)>, String> {
+    let smallest = encode(img, format, min_quality)?;
+    if smallest.len() > max_bytes {
+        return Ok(None);
+    }
+    let (mut low, mut high) = (min_quality, MAX_QUALITY);
+    let mut best = (smallest, min_quality);
+    while low < high {
+        let mid = low + (high - low).div_ceil(2);
+        let bytes = encode(img, format, mid)?;
+        if bytes.len() <= max_bytes {
+            best = (bytes, mid);
+            low = mid;
+        } else {
+            high = mid - 1;
+        }
+    }
+    Ok(Some(best))
+}
+
+/// Encode `img` under `max_bytes`, lowering quality first and then (if allowed) dimensions
+pub fn encode_to_size(
+    img: &DynamicImage,
+    format: LossyFormat,
+    max_bytes: usize,
+    allow_resize: bool,
+    min_quality: u8,
+) -> Result<SizedImage, String> {
+    let mut current = img.clone();
+    loop {
+        if let Some((bytes, quality)) = best_quality(&current, format, max_bytes, min_quality)? {
+            return Ok(SizedImage { bytes, quality, width: current.width(), height: current.height() });
+        }
+        let longest = current.width().max(current.height());
+        if !allow_resize || longest <= MIN_DIMENSION {
+            return Err(format!(
+                "Cannot get under {} KB at {}x{} even at quality {}{}",
+                max_bytes / 1024,
+                current.width(),
+                current.height(),
+                min_quality,
+                if allow_resize { "" } else { " - allow resizing to go smaller" }
+            ));
+        }
+        // File size grows roughly with pixel count
+        let size = encode(&current, format, min_quality)?.len();
+        let scale = ((max_bytes as f64 / size as f64).sqrt() * 0.95).clamp(0.5, 0.9);
+        let width = ((current.width() as f64 * scale) as u32).max(1);
+        let height = ((current.height() as f64 * scale) as u32).max(1);
+        current = img.resize_exact(width, height, FilterType::Lanczos3);
+    }
+}
+
+/// Compress an image to at most `max_kb` kilobytes as JPEG or WebP (by output extension)
+pub fn compress_image_to_size(
+    input_path: String,
+    output_path: String,
+    max_kb: u32,
+    allow_resize: bool,
+    min_quality: Option<u8>,
+) -> Result<ConversionResult, String> {
+    info!("🖼️ Compressing image under {} KB (bundled)", max_kb);
+
+    if max_kb == 0 {
+        return Err("Size limit must be at least 1 KB".to_string());
+    }
+    let format = LossyFormat::from_path(&output_path)?;
+    let img = open_image(&input_path)?;
+
+    let min_quality = min_quality.unwrap_or(30).clamp(1, MAX_QUALITY);
+    let sized = encode_to_size(&img, format, max_kb as usize * 1024, allow_resize, min_quality)?;
+    fs::write(&output_path, &sized.bytes)
+        .map_err(|e| format!("Failed to write image: {}", e))?;
+
+    info!("✅ Image compressed: {}", output_path);
+    Ok(ConversionResult {
+        success: true,
+        output_path,
+        message: format!(
+            "Saved {:.1} KB at quality {}, {}x{}",
+            sized.bytes.len() as f64 / 1024.0,
+            sized.quality,
+            sized.width,
+            sized.height
+        ),
+        output_size: Some(sized.bytes.len() as u64),
+    })
+}
@@ -0,0 +1,100 @@
+//! IP Ranges - expand CIDR blocks and start-end ranges into scan targets (IPv4 and IPv6)
+
+use std::collections::BTreeSet;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use ipnetwork::IpNetwork;
+
+/// Refuse scans larger than a /16 (or an IPv6 /112) - a typo like "10.0.0.0/8" would take hours
+pub const MAX_SCAN_HOSTS: usize = 65_536;
+
+/// Expand one range spec:
+///   "10.20.30.0/23"           CIDR (network and broadcast addresses skipped)
+///   "10.0.0.10-10.0.0.50"     start-end
+///   "10.0.0.10-50"            start-end, last octet only
+///   "10.0.0.7"                single host
+///   "fd00:10::/120"           IPv6 CIDR (must be small - a /64 can't be swept)
+///   "fd00:10::5-fd00:10::40"  IPv6 start-end
+pub fn expand_range(spec: &str) -> Result<Vec<IpAddr>, String> {
+    let spec = spec.trim();
+
+    if spec.contains('/') {
+        let network: IpNetwork = spec.parse()
+            .map_err(|e| format!("Invalid CIDR '{}': {}", spec, e))?;
+        let host_bits = match network {
+            IpNetwork::V4(_) => 32 - network.prefix() as u32,
+            IpNetwork::V6(_) => 128 - network.prefix() as u32,
+        };
+        if host_bits > 16 {
+            return Err(format!("Range '{}' is too large (max {} hosts)", spec, MAX_SCAN_HOSTS));
+        }
+        let hosts = match network {
+            IpNetwork::V4(v4) => v4.iter()
+                .filter(|ip| v4.prefix() >= 31 || (*ip != v4.network() && *ip != v4.broadcast()))
+                .map(IpAddr::V4)
+                .collect(),
+            // IPv6 has no broadcast; only the all-zeros subnet-router anycast address is skipped
+            IpNetwork::V6(v6) => v6.iter()
+                .filter(|ip| v6.prefix() >= 127 || *ip != v6.network())
+                .map(IpAddr::V6)
+                .collect(),
+        };
+        return Ok(hosts);
+    }
+
+    if let Some((start, end)) = spec.split_once('-') {
+        let start: IpAddr = start.trim().parse()
+            .map_err(|_| format!("Invalid start address in '{}'", spec))?;
+        let end = end.trim();
+        return match start {
+            IpAddr::V4(start) => {
+                let end: Ipv4Addr = match end.parse::<u8>() {
+                    Ok(last_octet) => {
+                        let o = start.octets();
+                        Ipv4Addr::new(o[0], o[1], o[2], last_octet)
+                    }
+                    Err(_) => end.parse()
+                        .map_err(|_| format!("Invalid end address in '{}'", spec))?,
+                };
+                let (start, end) = (u32::from(start) as u128, u32::from(end) as u128);
+                check_span(spec, start, end)?;
+                Ok((start..=end).map(|n| IpAddr::V4(Ipv4Addr::from(n as u32))).collect())
+            }
+            IpAddr::V6(start) => {
+                let end: Ipv6Addr = end.parse()
+                    .map_err(|_| format!("Invalid end address in '{}'", spec))?;
+                let (start, end) = (u128::from(start), u128::from(end));
+                check_span(spec, start, end)?;
+                Ok((start..=end).map(|n| IpAddr::V6(Ipv6Addr::from(n))).collect())
+            }
+        };
+    }
+
+    let ip: IpAddr = spec.trim_matches(|c| c == '[' || c == ']').parse()
+        .map_err(|_| format!("Invalid address or range '{}'", spec))?;
+    Ok(vec![ip])
+}
+
+fn check_span(spec: &str, start: u128, end: u128) -> Result<(), String> {
+    if end < start {
+        return Err(format!("Range '{}' ends before it starts", spec));
+    }
+    if end - start >= MAX_SCAN_HOSTS as u128 {
+        return Err(format!("Range '{}' is too large (max {} hosts)", spec, MAX_SCAN_HOSTS));
+    }
+    Ok(())
+}
+
+/// Expand and de-duplicate several range specs (sorted)
+pub fn expand_ranges(specs: &[String]) -> Result<Vec<IpAddr>, String> {
+    let mut hosts = BTreeSet::new();
+    for spec in specs.iter().filter(|s| !s.trim().is_empty()) {
+        hosts.extend(expand_range(spec)?);
+        if hosts.len() > MAX_SCAN_HOSTS {
+            return Err(format!("Combined ranges exceed {} hosts", MAX_SCAN_HOSTS));
+        }
+    }
+    if hosts.is_empty() {
+        return Err("No addresses to scan".to_string());
+    }
+    Ok(hosts.into_iter().collect())
+}
@@ -0,0 +1,157 @@
+//! JSON to Excel - a JSON array of records becomes a typed, formatted workbook
+//! Nested objects flatten into dot-named columns (address.city). Arrays of objects move to an
+//! extra sheet named after the key, whose parent_row column is the record number it belongs to;
+//! arrays of plain values are joined into one cell.
+
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use log::info;
+use rust_xlsxwriter::Workbook;
+use crate::bundled_converter::ConversionResult;
+use crate::csv_excel::EXCEL_MAX_ROWS;
+use crate::json_flatten::parse_json_records;
+use crate::sheet_writer::{detect, sheet_name, write_table, xlsx_err, CellValue};
+
+const PARENT_COLUMN: &str = "parent_row";
+
+struct Table {
+    name: String,
+    columns: Vec<String>,
+    rows: Vec<HashMap<String, CellValue>>,
+}
+
+fn table_index(tables: &mut Vec<Table>, name: &str) -> usize {
+    if let Some(index) = tables.iter().position(|t| t.name == name) {
+        return index;
+    }
+    tables.push(Table { name: name.to_string(), columns: Vec::new(), rows: Vec::new() });
+    tables.len() - 1
+}
+
+/// JSON already carries number/bool types; strings are only checked for dates
+fn scalar(value: &Value) -> CellValue {
+    match value {
+        Value::Null => CellValue::Empty,
+        Value::Bool(flag) => CellValue::Bool(*flag),
+        // Integers beyond 2^53 would lose digits as f64
+        Value::Number(number) => match number.as_f64() {
+            Some(float) if float.abs() < 9_007_199_254_740_992.0 => CellValue::Number(float),
+            _ => CellValue::Text(number.to_string()),
+        },
+        Value::String(text) => match detect(text) {
+            date @ (CellValue::Date(_) | CellValue::DateTime(_)) => date,
+            CellValue::Empty => CellValue::Empty,
+            _ => CellValue::Text(text.clone()),
+        },
+        other => CellValue::Text(other.to_string()),
+    }
+}
+
+fn set(table: &mut Table, row: &mut HashMap<String, CellValue>, column: &str, value: CellValue) {
+    if !table.columns.iter().any(|c| c == column) {
+        table.columns.push(column.to_string());
+    }
+    row.insert(column.to_string(), value);
+}
+
+fn add_row(tables: &mut Vec<Table>, table: usize, mut row: HashMap<String, CellValue>, item: &Value, parent: Option<usize>) {
+    if let Some(parent) = parent {
+        set(&mut tables[table], &mut row, PARENT_COLUMN, CellValue::Number(parent as f64));
+    }
+    let row_number = tables[table].rows.len() + 1;
+    match item {
+        Value::Object(object) => flatten(tables, table, &mut row, "", object, row_number),
+        other => set(&mut tables[table], &mut row, "value", scalar(other)),
+    }
+    tables[table].rows.push(row);
+}
+
+fn flatten(
+    tables: &mut Vec<Table>,
+    table: usize,
+    row: &mut HashMap<String, CellValue>,
+    prefix: &str,
+    object: &Map<String, Value>,
+    row_number: usize,
+) {
+    for (key, value) in object {
+        let column = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+        match value {
+            Value::Object(nested) => flatten(tables, table, row, &column, nested, row_number),
+            Value::Array(items) if items.iter().any(Value::is_object) => {
+                let child_name = if table == 0 { column } else { format!("{}.{}", tables[table].name, column) };
+                let child = table_index(tables, &child_name);
+                for item in items {
+                    add_row(tables, child, HashMap::new(), item, Some(row_number));
+                }
+            }
+            Value::Array(items) => {
+                let joined = items.iter()
+                    .map(|item| match item {
+                        Value::String(text) => text.clone(),
+                        other => other.to_string(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                set(&mut tables[table], row, &column, if joined.is_empty() { CellValue::Empty } else { CellValue::Text(joined) });
+            }
+            scalar_value => set(&mut tables[table], row, &column, scalar(scalar_value)),
+        }
+    }
+}
+
+/// Convert a JSON array of objects to an Excel workbook (first sheet for the records,
+/// one more sheet per nested array of objects)
+pub fn json_to_excel(input_path: String, output_path: String) -> Result<ConversionResult, String> {
+    info!("📊 Converting JSON to Excel (bundled)");
+
+    let content = fs::read_to_string(&input_path)
+        .map_err(|e| format!("Failed to read JSON: {}", e))?;
+    let records = parse_json_records(&content)?;
+    if records.is_empty() {
+        return Err("JSON array is empty".to_string());
+    }
+
+    let main_name = Path::new(&input_path).file_stem().and_then(|s| s.to_str()).unwrap_or("Data");
+    let mut tables = vec![Table { name: main_name.to_string(), columns: Vec::new(), rows: Vec::new() }];
+    for record in &records {
+        add_row(&mut tables, 0, HashMap::new(), record, None);
+    }
+
+    let mut workbook = Workbook::new();
+    let mut used_names: Vec<String> = Vec::new();
+    for table in &tables {
+        if table.rows.len() + 1 > EXCEL_MAX_ROWS {
+            return Err(format!("Sheet {} has {} rows; an Excel sheet holds at most {}", table.name, table.rows.len(), EXCEL_MAX_ROWS - 1));
+        }
+        // Truncation to 31 characters can make names collide; Excel compares them case-insensitively
+        let mut name = sheet_name(&table.name);
+        let mut counter = 2;
+        while used_names.contains(&name.to_lowercase()) {
+            let suffix = format!("~{}", counter);
+            name = format!("{}{}", sheet_name(&table.name).chars().take(31 - suffix.len()).collect::<String>(), suffix);
+            counter += 1;
+        }
+        used_names.push(name.to_lowercase());
+
+        let rows: Vec<Vec<CellValue>> = table.rows.iter()
+            .map(|row| table.columns.iter().map(|c| row.get(c).cloned().unwrap_or(CellValue::Empty)).collect())
+            .collect();
+        let worksheet = workbook.add_worksheet();
+        worksheet.set_name(&name).map_err(xlsx_err)?;
+        write_table(worksheet, &table.columns, &rows, true).map_err(xlsx_err)?;
+    }
+    workbook.save(&output_path).map_err(xlsx_err)?;
+
+    let output_size = fs::metadata(&output_path).map(|m| m.len()).ok();
+
+    info!("✅ JSON converted to Excel: {} ({} sheets)", output_path, tables.len());
+    Ok(ConversionResult {
+        success: true,
+        output_path,
+        message: format!("Converted {} records to Excel ({} sheets)", records.len(), tables.len()),
+        output_size,
+    })
+}
@@ -0,0 +1,126 @@
+//! JSON Flatten - turn nested JSON records into flat CSV rows
+//! Nested objects become dot-named columns (address.city). Arrays are joined into one cell,
+//! exploded into one row per element (several arrays in a record multiply), or kept as JSON text.
+//! Input may be an array of records, a single record or JSON Lines (one record per line).
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JsonToCsvOptions {
+    pub flatten: Option<bool>,          // Nested objects become dot-named columns (default true)
+    pub arrays: Option<String>,         // join (default), explode (one row per element) or json
+    pub separator: Option<String>,      // Between joined array values, default "; "
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum ArrayMode {
+    Join,
+    Explode,
+    Json,
+}
+
+struct Flattener<'a> {
+    nested: bool,
+    arrays: ArrayMode,
+    separator: &'a str,
+}
+
+type Row = Vec<(String, String)>;
+
+fn column(prefix: &str, key: &str) -> String {
+    if prefix.is_empty() { key.to_string() } else { format!("{}.{}", prefix, key) }
+}
+
+fn text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Every combination of one row from `left` and one from `right`
+fn combine(left: Vec<Row>, right: Vec<Row>) -> Vec<Row> {
+    left.iter()
+        .flat_map(|l| right.iter().map(move |r| l.iter().chain(r).cloned().collect()))
+        .collect()
+}
+
+impl Flattener<'_> {
+    fn rows(&self, prefix: &str, value: &Value) -> Vec<Row> {
+        let name = if prefix.is_empty() { "value" } else { prefix };
+        match value {
+            Value::Object(object) if self.nested || prefix.is_empty() => {
+                object.iter().fold(vec![Vec::new()], |rows, (key, nested)| {
+                    combine(rows, self.rows(&column(prefix, key), nested))
+                })
+            }
+            Value::Array(items) if self.arrays == ArrayMode::Explode => {
+                if items.is_empty() {
+                    return vec![vec![(name.to_string(), String::new())]];
+                }
+                items.iter().flat_map(|item| self.rows(prefix, item)).collect()
+            }
+            Value::Array(items) if self.arrays == ArrayMode::Join && !items.iter().any(|i| i.is_object() || i.is_array()) => {
+                let joined = items.iter().map(text).collect::<Vec<_>>().join(self.separator);
+                vec![vec![(name.to_string(), joined)]]
+            }
+            other => vec![vec![(name.to_string(), text(other))]],
+        }
+    }
+}
+
+/// Records from a JSON array, a single object or NDJSON / JSON Lines text
+pub fn parse_json_records(content: &str) -> Result<Vec<Value>, String> {
+    let mut values = serde_json::Deserializer::from_str(content)
+        .into_iter::<Value>()
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to parse JSON: {}", e))?;
+    if values.len() != 1 {
+        return Ok(values);
+    }
+    match values.remove(0) {
+        Value::Array(records) => Ok(records),
+        record @ Value::Object(_) => Ok(vec![record]),
+        _ => Err("Expected a JSON array of records".to_string()),
+    }
+}
+
+/// Flatten records into a header (union of every record's columns, in first-seen order) and rows
+pub fn flatten_records(records: &[Value], options: &JsonToCsvOptions) -> Result<(Vec<String>, Vec<Vec<String>>), String> {
+    let flattener = Flattener {
+        nested: options.flatten.unwrap_or(true),
+        arrays: match options.arrays.as_deref().map(str::to_lowercase).as_deref() {
+            None | Some("") | Some("join") => ArrayMode::Join,
+            Some("explode") => ArrayMode::Explode,
+            Some("json") => ArrayMode::Json,
+            Some(other) => return Err(format!("Unknown array mode: {} (use join, explode or json)", other)),
+        },
+        separator: options.separator.as_deref().unwrap_or("; "),
+    };
+
+    let flat: Vec<Row> = records.iter().flat_map(|record| flattener.rows("", record)).collect();
+    let mut headers: Vec<String> = Vec::new();
+    let mut positions: HashMap<String, usize> = HashMap::new();
+    for row in &flat {
+        for (name, _) in row {
+            positions.entry(name.clone()).or_insert_with(|| {
+                headers.push(name.clone());
+                headers.len() - 1
+            });
+        }
+    }
+    let rows = flat.into_iter()
+        .map(|row| {
+            let mut cells = vec![String::new(); headers.len()];
+            for (name, value) in row {
+                cells[positions[&name]] = value;
+            }
+            cells
+        })
+        .collect();
+    Ok((headers, rows))
+}
@@ -0,0 +1,46 @@
+//! JSON Lines - NDJSON (one JSON record per line) to and from CSV
+//! Log pipelines and many APIs stream records this way instead of as one JSON array.
+
+use serde_json::{Map, Value};
+use std::fs;
+use log::info;
+use crate::bundled_converter::{json_to_csv, ConversionResult};
+use crate::csv_dialect::{read_csv, CsvDialect};
+use crate::json_flatten::JsonToCsvOptions;
+
+/// Convert NDJSON to CSV; nested values are flattened as in json_to_csv
+pub fn ndjson_to_csv(input_path: String, output_path: String, options: JsonToCsvOptions) -> Result<ConversionResult, String> {
+    info!("📊 Converting NDJSON to CSV (bundled)");
+    json_to_csv(input_path, output_path, options)
+}
+
+/// Convert CSV to NDJSON, one object of text values per row
+pub fn csv_to_ndjson(input_path: String, output_path: String, dialect: CsvDialect) -> Result<ConversionResult, String> {
+    info!("📊 Converting CSV to NDJSON (bundled)");
+
+    let table = read_csv(&input_path, &dialect)?;
+
+    let mut output = String::new();
+    for row in &table.rows {
+        let record: Map<String, Value> = table.headers.iter().cloned()
+            .zip(row.iter().cloned().map(Value::String))
+            .collect();
+        let line = serde_json::to_string(&record)
+            .map_err(|e| format!("Failed to serialize JSON: {}", e))?;
+        output.push_str(&line);
+        output.push('\n');
+    }
+
+    fs::write(&output_path, output)
+        .map_err(|e| format!("Failed to write NDJSON: {}", e))?;
+
+    let output_size = fs::metadata(&output_path).map(|m| m.len()).ok();
+
+    info!("✅ CSV converted to NDJSON: {}", output_path);
+    Ok(ConversionResult {
+        success: true,
+        output_path,
+        message: format!("Converted {} records to NDJSON ({})", table.rows.len(), table.encoding),
+        output_size,
+    })
+}
@@ -0,0 +1,277 @@
+//! LaTeX to PDF - compile .tex files with tectonic, xelatex or pdflatex
+//! The engine runs in the document's folder (so \input and \includegraphics resolve) with its
+//! auxiliary files in a temporary folder that mirrors the document's subfolders (\include{chapters/x}
+//! writes chapters/x.aux there). xelatex/pdflatex run bibtex or biber after the first pass when the
+//! document has a bibliography, then run again while the log asks for a rerun; tectonic does both by
+//! itself. When compilation fails, the errors (file, line, message and the offending source line)
+//! are extracted from the log.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use log::{info, warn};
+use regex::Regex;
+use tokio::process::Command as TokioCommand;
+use crate::tool_process::{run_tool, Tool, WorkDir};
+use crate::tool_registry::custom_path;
+
+/// Passes at most for xelatex/pdflatex (cross-references and the table of contents need two or three)
+const MAX_PASSES: usize = 3;
+
+/// Errors reported at most
+const MAX_ERRORS: usize = 20;
+
+/// Subfolder levels mirrored into the auxiliary folder
+const MAX_FOLDER_DEPTH: usize = 8;
+
+/// "file.tex:12: message" from -file-line-error
+static FILE_LINE: OnceLock<Regex> = OnceLock::new();
+
+/// "l.12 \foo", the source line TeX stopped at
+static CONTEXT_LINE: OnceLock<Regex> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LatexEngine {
+    Tectonic,
+    Xelatex,
+    Pdflatex,
+}
+
+impl LatexEngine {
+    /// Order tried when no engine is chosen
+    pub const ALL: [LatexEngine; 3] = [LatexEngine::Tectonic, LatexEngine::Xelatex, LatexEngine::Pdflatex];
+
+    pub fn binary(&self) -> &'static str {
+        match self {
+            LatexEngine::Tectonic => "tectonic",
+            LatexEngine::Xelatex => "xelatex",
+            LatexEngine::Pdflatex => "pdflatex",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LatexError {
+    pub file: Option<String>,
+    pub line: Option<u32>,
+    pub message: String,
+    pub context: Option<String>,        // The source line TeX stopped at ("l.12 \foo")
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LatexResult {
+    pub success: bool,
+    pub output_path: String,
+    pub message: String,
+    pub output_size: Option<u64>,
+    pub engine: LatexEngine,
+    pub errors: Vec<LatexError>,        // Filled when compilation failed
+}
+
+/// The user's configured binary (see tool_registry), else PATH
+pub fn engine_path(engine: LatexEngine) -> String {
+    custom_path(engine.binary())
+        .map(|path| path.to_string_lossy().to_string())
+        .unwrap_or_else(|| engine.binary().to_string())
+}
+
+fn is_available(engine: LatexEngine) -> bool {
+    std::process::Command::new(engine_path(engine))
+        .arg("--version")
+        .output()
+        .is_ok_and(|o| o.status.success())
+}
+
+/// TeX errors from a log: "! message" blocks and -file-line-error "file.tex:12: message" lines
+pub fn extract_errors(log: &str) -> Vec<LatexError> {
+    let file_line = FILE_LINE.get_or_init(|| Regex::new(r"^(.+\.(?:tex|sty|cls|bib|bbl)):(\d+): (.+)$").expect("valid regex"));
+    let context_line = CONTEXT_LINE.get_or_init(|| Regex::new(r"^l\.(\d+) (.*)$").expect("valid regex"));
+    let lines: Vec<&str> = log.lines().collect();
+
+    let mut errors: Vec<LatexError> = Vec::new();
+    for (index, line) in lines.iter().enumerate() {
+        let (file, line_number, message) = if let Some(captures) = file_line.captures(line) {
+            (Some(captures[1].to_string()), captures[2].parse().ok(), captures[3].to_string())
+        } else if let Some(message) = line.strip_prefix("! ") {
+            (None, None, message.to_string())
+        } else {
+            continue;
+        };
+        // TeX shows where it stopped a few lines further down
+        let context = lines.iter().skip(index + 1).take(12).find_map(|l| context_line.captures(l));
+        let line_number = line_number.or_else(|| context.as_ref().and_then(|c| c[1].parse().ok()));
+        let error = LatexError {
+            file,
+            line: line_number,
+            message: message.trim().to_string(),
+            context: context.map(|c| c[0].trim().to_string()),
+        };
+        if !errors.iter().any(|e| e.message == error.message && e.line == error.line) {
+            errors.push(error);
+        }
+        if errors.len() >= MAX_ERRORS {
+            break;
+        }
+    }
+    errors
+}
+
+/// Create `source`'s subfolders (hidden ones skipped) under `target`
+fn mirror_folders(source: &Path, target: &Path, depth: usize) {
+    if depth >= MAX_FOLDER_DEPTH {
+        return;
+    }
+    let Ok(entries) = fs::read_dir(source) else {
+        return;
+    };
+    for path in entries.filter_map(|entry| entry.ok().map(|e| e.path())).filter(|p| p.is_dir()) {
+        let Some(name) = path.file_name().filter(|n| !n.to_string_lossy().starts_with('.')) else {
+            continue;
+        };
+        let mirrored = target.join(name);
+        if path != target && fs::create_dir_all(&mirrored).is_ok() {
+            mirror_folders(&path, &mirrored, depth + 1);
+        }
+    }
+}
+
+/// bibtex or biber next to a configured engine, else from PATH
+fn companion_path(engine: LatexEngine, binary: &str) -> String {
+    custom_path(engine.binary())
+        .and_then(|path| path.parent().map(|dir| dir.join(format!("{}{}", binary, std::env::consts::EXE_SUFFIX))))
+        .filter(|path| path.is_file())
+        .map(|path| path.to_string_lossy().to_string())
+        .unwrap_or_else(|| binary.to_string())
+}
+
+/// Run biber (biblatex writes a .bcf) or bibtex (the .aux names a \bibdata) after the first pass;
+/// false when the document has no bibliography
+async fn run_bibliography(engine: LatexEngine, folder: &Path, work: &Path, stem: &str) -> Result<bool, String> {
+    let aux = fs::read_to_string(work.join(format!("{}.aux", stem))).unwrap_or_default();
+    // The tools run in the auxiliary folder, so a relative document folder would point elsewhere
+    let folder = std::path::absolute(folder).unwrap_or_else(|_| folder.to_path_buf());
+    let (binary, mut cmd) = if work.join(format!("{}.bcf", stem)).is_file() {
+        let mut cmd = TokioCommand::new(companion_path(engine, "biber"));
+        cmd.arg("--input-directory").arg(&folder).arg("--output-directory").arg(work);
+        ("biber", cmd)
+    } else if aux.contains("\\bibdata") {
+        // bibtex runs in the auxiliary folder and finds .bib/.bst files in the document's folder
+        // through BIBINPUTS/BSTINPUTS; the trailing separator keeps the default search path
+        let separator = if cfg!(windows) { ";" } else { ":" };
+        let mut search_path = folder.as_os_str().to_os_string();
+        search_path.push(separator);
+        let mut cmd = TokioCommand::new(companion_path(engine, "bibtex"));
+        cmd.env("BIBINPUTS", &search_path).env("BSTINPUTS", &search_path);
+        ("bibtex", cmd)
+    } else {
+        return Ok(false);
+    };
+    cmd.current_dir(work).arg(stem);
+
+    info!("📄 Running {} for {}", binary, stem);
+    let result = run_tool(cmd, Tool::Latex, binary, &[]).await?;
+    if !result.status.success() {
+        // Missing entries and other warnings also end with a non-zero status; LaTeX still shows "?"
+        let output = String::from_utf8_lossy(&result.stdout);
+        let tail: Vec<&str> = output.lines().rev().take(5).collect();
+        warn!("{} reported problems: {}", binary, tail.into_iter().rev().collect::<Vec<_>>().join(" | "));
+    }
+    Ok(true)
+}
+
+/// Compile `input_path` to `output_path`; `engine` defaults to the first installed of tectonic,
+/// xelatex and pdflatex
+pub async fn latex_to_pdf(input_path: String, output_path: String, engine: Option<LatexEngine>) -> Result<LatexResult, String> {
+    let input = Path::new(&input_path);
+    if !input.is_file() {
+        return Err(format!("File not found: {}", input_path));
+    }
+    let engine = match engine {
+        Some(engine) => engine,
+        None => tokio::task::spawn_blocking(|| LatexEngine::ALL.into_iter().find(|e| is_available(*e)))
+            .await
+            .map_err(|e| format!("Task error: {}", e))?
+            .ok_or("No LaTeX engine found - install tectonic, TeX Live or MiKTeX, or set its path in the tool settings")?,
+    };
+    let stem = input.file_stem().and_then(|s| s.to_str()).unwrap_or("document").to_string();
+    let file_name = input.file_name().map(|n| n.to_os_string()).unwrap_or_default();
+    let folder = input.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let work = WorkDir::create("latex")?;
+    let (source_folder, work_folder): (PathBuf, PathBuf) = (folder.to_path_buf(), work.path().to_path_buf());
+    tokio::task::spawn_blocking(move || mirror_folders(&source_folder, &work_folder, 0))
+        .await
+        .map_err(|e| format!("Task error: {}", e))?;
+
+    info!("📄 Compiling LaTeX with {}: {}", engine.binary(), input_path);
+
+    let passes = if engine == LatexEngine::Tectonic { 1 } else { MAX_PASSES };
+    let mut output = None;
+    for pass in 0..passes {
+        let mut cmd = TokioCommand::new(engine_path(engine));
+        cmd.current_dir(folder);
+        if engine == LatexEngine::Tectonic {
+            cmd.arg("--keep-logs").arg("--outdir").arg(work.path());
+        } else {
+            cmd.args(["-interaction=nonstopmode", "-halt-on-error", "-file-line-error"]);
+            cmd.arg(format!("-output-directory={}", work.path().to_string_lossy()));
+        }
+        cmd.arg(&file_name);
+        let result = run_tool(cmd, Tool::Latex, engine.binary(), &[]).await?;
+        let log = fs::read_to_string(work.path().join(format!("{}.log", stem))).unwrap_or_default();
+        let failed = !result.status.success();
+        // The citations only appear in the passes after the bibliography run
+        let bibliography = pass == 0 && !failed && engine != LatexEngine::Tectonic
+            && run_bibliography(engine, folder, work.path(), &stem).await?;
+        let rerun = bibliography
+            || log.contains("Rerun to get")
+            || log.contains("Label(s) may have changed")
+            || log.contains("Please rerun LaTeX");
+        output = Some((result, log));
+        if failed || !rerun {
+            break;
+        }
+    }
+    let (result, log) = output.ok_or("LaTeX did not run")?;
+
+    let pdf = work.path().join(format!("{}.pdf", stem));
+    if !result.status.success() || !pdf.is_file() {
+        // Tectonic reports errors on stderr as well; the log is missing when it stops early
+        let mut errors = extract_errors(&log);
+        if errors.is_empty() {
+            let stderr = String::from_utf8_lossy(&result.stderr);
+            errors = extract_errors(&stderr.replace("error: ", ""));
+            if errors.is_empty() && !stderr.trim().is_empty() {
+                errors.push(LatexError { file: None, line: None, message: stderr.trim().to_string(), context: None });
+            }
+        }
+        let first = errors.first()
+            .map(|e| match e.line {
+                Some(line) => format!(": line {}: {}", line, e.message),
+                None => format!(": {}", e.message),
+            })
+            .unwrap_or_default();
+        return Ok(LatexResult {
+            success: false,
+            output_path,
+            message: format!("LaTeX compilation failed with {} error(s){}", errors.len(), first),
+            output_size: None,
+            engine,
+            errors,
+        });
+    }
+
+    fs::copy(&pdf, &output_path).map_err(|e| format!("Failed to write PDF: {}", e))?;
+    let output_size = fs::metadata(&output_path).map(|m| m.len()).ok();
+
+    info!("✅ LaTeX compiled: {}", output_path);
+    Ok(LatexResult {
+        success: true,
+        output_path,
+        message: format!("Compiled with {}", engine.binary()),
+        output_size,
+        engine,
+        errors: Vec::new(),
+    })
+}
@@ -1,18 +1,132 @@
 mod device_scanner;
+mod ip_ranges;
+mod mac_lookup;
+mod host_lookup;
+mod device_banner;
+mod service_discovery;
+mod scan_cache;
 mod zkteco_client;
+mod zkteco_admin;
+mod zkteco_bells;
 mod video_converter;
 mod media_converter;
+mod ffmpeg_runner;
+mod ffmpeg_encoders;
+mod ffmpeg_manager;
+mod video_edit;
+mod media_tracks;
+mod media_chapters;
+mod video_scenes;
+mod video_quality;
+mod video_compose;
+mod media_metadata;
+mod conversion_presets;
+mod conversion_estimate;
+mod video_frames;
+mod video_gif;
+mod video_filters;
+mod video_hls;
+mod image_sequence;
+mod image_decode;
+#[cfg(feature = "avif")]
+mod avif_decode;
+mod image_duplicates;
+mod image_adjust;
+mod image_animation;
+mod image_edit;
+mod image_metadata;
+mod image_size_limit;
+mod id_photo;
+mod contact_sheet;
+mod scan_enhance;
+mod icon_generator;
+mod screen_recorder;
+mod audio_tools;
 mod document_converter;
+mod office_batch;
+mod markdown_pdf;
+mod presentation;
+mod latex_pdf;
+mod tool_process;
+mod tool_installer;
+mod tool_registry;
 mod bundled_converter;
+mod docx_reader;
+mod csv_concat;
+mod csv_dialect;
+mod csv_excel;
+mod csv_filter;
+mod csv_transform;
+mod csv_validate;
+mod excel_reader;
+mod file_sniff;
+mod json_excel;
+mod json_flatten;
+mod json_lines;
+mod markdown_table;
+mod sheet_writer;
+mod spreadsheet_diff;
+mod sqlite_import;
+mod structured_convert;
+mod xml_convert;
 mod ai_assistant;
 mod erp_sync;
+mod app_storage;
+mod attendance_summary;
+mod attendance_register;
+mod holidays;
+mod pdf_attachments;
+mod pdf_compress;
+mod pdf_flatten;
+mod pdf_forms;
+mod pdf_icc;
+mod pdf_merge;
+mod pdf_outline;
+mod pdf_page_numbers;
+mod pdf_pages;
+mod pdf_pdfa;
+mod pdf_render;
+mod pdf_watermark;
+mod pdf_metadata;
+mod pdf_security;
+mod pdf_signatures;
+mod pdf_writer;
+mod report_generator;
+mod attendance_merge;
+mod admin_auth;
+mod device_profiles;
+mod device_monitor;
 
-use device_scanner::{scan_network, BiometricDevice};
-use zkteco_client::{connect_and_fetch_attendance, AttendanceResponse};
+use std::collections::HashMap;
+use device_scanner::{BiometricDevice, NetworkInterfaceInfo, ScanOptions};
+use scan_cache::ScanDiff;
+use zkteco_client::{connect_and_fetch_attendance, AttendanceRecord, AttendanceResponse};
+use attendance_summary::SummaryOptions;
+use holidays::HolidayCalendar;
+use report_generator::ReportFilters;
+use attendance_merge::MergeResult;
+use admin_auth::AdminStatus;
+use device_profiles::DeviceProfile;
+use device_monitor::DeviceUptime;
+use zkteco_admin::DoorState;
+use zkteco_bells::BellSchedule;
 use media_converter::{
     VideoConvertOptions, ImageConvertOptions, ConversionResult, MediaInfo,
 };
-use document_converter::ToolStatus;
+use ffmpeg_runner::MediaJob;
+use ffmpeg_encoders::{EncoderSupport, HardwareEncoders};
+use ffmpeg_manager::FfmpegStatus;
+use audio_tools::{AudioAnalysis, AudioConvertOptions};
+use video_frames::FrameMode;
+use video_gif::GifOptions;
+use video_edit::CropRect;
+use video_hls::HlsRendition;
+use screen_recorder::{RecordingOptions, RecordingStatus};
+use conversion_presets::ConversionPreset;
+use conversion_estimate::ConversionEstimate;
+use video_quality::QualityScores;
+use document_converter::{PandocOptions, ToolStatus};
+use tool_process::ToolTimeouts;
 use ai_assistant::{AIProvider, ChatRequest, ChatResponse, BitNetSetupStatus};
 use erp_sync::{ErpConfig, AttendanceSyncRequest, SyncResult, ApiKeyInfo};
 
@@ -21,8 +135,31 @@ use erp_sync::{ErpConfig, AttendanceSyncRequest, SyncResult, ApiKeyInfo};
 // ============================================================================
 
 #[tauri::command]
-async fn scan_for_devices() -> Result<Vec<BiometricDevice>, String> {
-    scan_network().await
+async fn scan_for_devices(
+    app: tauri::AppHandle,
+    options: Option<ScanOptions>,
+) -> Result<Vec<BiometricDevice>, String> {
+    scan_cache::scan_and_remember(options.unwrap_or_default(), Some(app)).await
+}
+
+#[tauri::command]
+fn list_network_interfaces() -> Vec<NetworkInterfaceInfo> {
+    device_scanner::list_interfaces()
+}
+
+#[tauri::command]
+async fn rescan_and_diff(app: tauri::AppHandle, options: Option<ScanOptions>) -> Result<ScanDiff, String> {
+    scan_cache::rescan_and_diff(options.unwrap_or_default(), Some(app)).await
+}
+
+#[tauri::command]
+async fn scan_range(
+    app: tauri::AppHandle,
+    cidrs: Vec<String>,
+    ports: Option<Vec<u16>>,
+    options: Option<ScanOptions>,
+) -> Result<Vec<BiometricDevice>, String> {
+    device_scanner::scan_range(cidrs, ports, options.unwrap_or_default(), Some(app)).await
 }
 
 #[tauri::command]
@@ -30,6 +167,142 @@ async fn fetch_attendance(ip: String, port: u16) -> Result<AttendanceResponse, S
     connect_and_fetch_attendance(&ip, port).await
 }
 
+#[tauri::command]
+async fn delete_device_user(ip: String, port: u16, uid: u32) -> Result<String, String> {
+    zkteco_admin::delete_device_user(&ip, port, uid).await
+}
+
+#[tauri::command]
+async fn clear_all_data(ip: String, port: u16, confirm_serial: String, admin_password: String) -> Result<String, String> {
+    zkteco_admin::clear_all_data(&ip, port, confirm_serial, admin_password).await
+}
+
+#[tauri::command]
+async fn unlock_door(ip: String, port: u16, seconds: u32, admin_password: String) -> Result<String, String> {
+    zkteco_admin::unlock_door(&ip, port, seconds, admin_password).await
+}
+
+#[tauri::command]
+fn admin_status() -> Result<AdminStatus, String> {
+    admin_auth::admin_status()
+}
+
+#[tauri::command]
+async fn admin_set_password(current_password: Option<String>, new_password: String) -> Result<AdminStatus, String> {
+    tokio::task::spawn_blocking(move || admin_auth::set_admin_password(current_password, new_password))
+        .await
+        .map_err(|e| format!("Task error: {}", e))?
+}
+
+#[tauri::command]
+async fn get_door_state(ip: String, port: u16) -> Result<DoorState, String> {
+    zkteco_admin::get_door_state(&ip, port).await
+}
+
+#[tauri::command]
+async fn get_bell_schedule(ip: String, port: u16) -> Result<BellSchedule, String> {
+    zkteco_bells::get_bell_schedule(&ip, port).await
+}
+
+#[tauri::command]
+async fn set_bell_schedule(ip: String, port: u16, schedule: BellSchedule) -> Result<BellSchedule, String> {
+    zkteco_bells::set_bell_schedule(&ip, port, schedule).await
+}
+
+#[tauri::command]
+fn export_attendance_register_xlsx(
+    records: Vec<AttendanceRecord>,
+    options: SummaryOptions,
+    output_path: String,
+) -> Result<bundled_converter::ConversionResult, String> {
+    attendance_register::export_register_xlsx(records, options, output_path)
+}
+
+#[tauri::command]
+fn merge_attendance_records(records: Vec<AttendanceRecord>, tolerance_seconds: Option<i64>) -> MergeResult {
+    attendance_merge::merge_device_records(records, tolerance_seconds)
+}
+
+#[tauri::command]
+fn generate_attendance_report_pdf(
+    month: String,
+    records: Vec<AttendanceRecord>,
+    filters: Option<ReportFilters>,
+    output_path: String,
+) -> Result<bundled_converter::ConversionResult, String> {
+    report_generator::generate_attendance_report_pdf(month, records, filters.unwrap_or_default(), output_path)
+}
+
+// ============================================================================
+// Device Profile & Monitor Commands
+// ============================================================================
+
+#[tauri::command]
+fn device_profiles_list() -> Result<Vec<DeviceProfile>, String> {
+    device_profiles::load_profiles()
+}
+
+#[tauri::command]
+async fn device_profiles_save(profile: DeviceProfile, admin_password: Option<String>) -> Result<Vec<DeviceProfile>, String> {
+    tokio::task::spawn_blocking(move || device_profiles::save_profile(profile, admin_password))
+        .await
+        .map_err(|e| format!("Task error: {}", e))?
+}
+
+#[tauri::command]
+fn device_profiles_remove(ip: String, port: u16) -> Result<Vec<DeviceProfile>, String> {
+    device_profiles::remove_profile(ip, port)
+}
+
+#[tauri::command]
+fn device_monitor_start(app: tauri::AppHandle, interval_minutes: Option<u64>) -> Result<(), String> {
+    device_monitor::start_monitor(app, interval_minutes.unwrap_or(5))
+}
+
+#[tauri::command]
+fn device_monitor_stop() -> Result<(), String> {
+    device_monitor::stop_monitor()
+}
+
+#[tauri::command]
+fn device_monitor_status() -> bool {
+    device_monitor::is_running()
+}
+
+#[tauri::command]
+fn device_uptime() -> Result<Vec<DeviceUptime>, String> {
+    device_monitor::get_uptime()
+}
+
+// ============================================================================
+// Holiday Calendar Commands
+// ============================================================================
+
+#[tauri::command]
+fn holidays_list() -> Result<HolidayCalendar, String> {
+    holidays::load_calendar()
+}
+
+#[tauri::command]
+fn holidays_add(date: String, name: String) -> Result<HolidayCalendar, String> {
+    holidays::add_holiday(date, name)
+}
+
+#[tauri::command]
+fn holidays_remove(date: String) -> Result<HolidayCalendar, String> {
+    holidays::remove_holiday(date)
+}
+
+#[tauri::command]
+fn holidays_set_weekly_offs(weekdays: Vec<String>) -> Result<HolidayCalendar, String> {
+    holidays::set_weekly_offs(weekdays)
+}
+
+#[tauri::command]
+fn holidays_import(file_path: String) -> Result<HolidayCalendar, String> {
+    holidays::import_holidays(file_path)
+}
+
 // ============================================================================
 // Media Commands - FFmpeg
 // ============================================================================
@@ -39,36 +312,365 @@ fn check_ffmpeg_status() -> Result<String, String> {
     media_converter::check_ffmpeg()
 }
 
+#[tauri::command]
+async fn ffmpeg_managed_status() -> Result<FfmpegStatus, String> {
+    tokio::task::spawn_blocking(ffmpeg_manager::status)
+        .await
+        .map_err(|e| format!("Task error: {}", e))
+}
+
+#[tauri::command]
+async fn ffmpeg_install_managed(app: tauri::AppHandle) -> Result<FfmpegStatus, String> {
+    ffmpeg_manager::install(app).await
+}
+
+#[tauri::command]
+fn ffmpeg_remove_managed() -> Result<FfmpegStatus, String> {
+    ffmpeg_manager::uninstall()
+}
+
 #[tauri::command]
 async fn get_media_information(file_path: String) -> Result<MediaInfo, String> {
     media_converter::get_media_info(&file_path).await
 }
 
+#[tauri::command]
+async fn media_set_metadata(
+    app: tauri::AppHandle,
+    input: String,
+    output: String,
+    tags: HashMap<String, String>,
+    cover_art: Option<String>,
+    job_id: Option<String>,
+) -> Result<ConversionResult, String> {
+    let job = MediaJob::new(Some(app), job_id);
+    media_metadata::set_metadata(input, output, tags, cover_art, job).await
+}
+
+#[tauri::command]
+fn conversion_presets_list() -> Result<Vec<ConversionPreset>, String> {
+    conversion_presets::load_presets()
+}
+
+#[tauri::command]
+fn conversion_presets_save(preset: ConversionPreset) -> Result<Vec<ConversionPreset>, String> {
+    conversion_presets::save_preset(preset)
+}
+
+#[tauri::command]
+fn conversion_presets_remove(name: String, kind: String) -> Result<Vec<ConversionPreset>, String> {
+    conversion_presets::remove_preset(name, kind)
+}
+
+#[tauri::command]
+fn cancel_media_job(job_id: String) -> Result<(), String> {
+    ffmpeg_runner::cancel_job(&job_id)
+}
+
+#[tauri::command]
+async fn check_hardware_encoders() -> Result<HardwareEncoders, String> {
+    tokio::task::spawn_blocking(ffmpeg_encoders::hardware_encoders)
+        .await
+        .map_err(|e| format!("Task error: {}", e))
+}
+
+#[tauri::command]
+async fn check_video_encoders() -> Result<Vec<EncoderSupport>, String> {
+    tokio::task::spawn_blocking(ffmpeg_encoders::software_encoders)
+        .await
+        .map_err(|e| format!("Task error: {}", e))
+}
+
 // ============================================================================
 // Video Commands
 // ============================================================================
 
 #[tauri::command]
-async fn video_convert(options: VideoConvertOptions) -> Result<ConversionResult, String> {
-    media_converter::convert_video(options).await
+async fn video_convert(
+    app: tauri::AppHandle,
+    options: VideoConvertOptions,
+    job_id: Option<String>,
+) -> Result<ConversionResult, String> {
+    media_converter::convert_video(options, MediaJob::new(Some(app), job_id)).await
+}
+
+#[tauri::command]
+async fn estimate_conversion(options: VideoConvertOptions) -> Result<ConversionEstimate, String> {
+    conversion_estimate::estimate_conversion(options).await
 }
 
 #[tauri::command]
 async fn video_compress(
+    app: tauri::AppHandle,
     input_path: String,
     output_path: String,
     target_bitrate: Option<String>,
+    job_id: Option<String>,
 ) -> Result<ConversionResult, String> {
-    media_converter::compress_video(input_path, output_path, target_bitrate).await
+    let job = MediaJob::new(Some(app), job_id);
+    media_converter::compress_video(input_path, output_path, target_bitrate, job).await
 }
 
 #[tauri::command]
 async fn video_extract_audio(
+    app: tauri::AppHandle,
     input_path: String,
     output_path: String,
     format: String,
+    audio_track_index: Option<u32>,
+    job_id: Option<String>,
+) -> Result<ConversionResult, String> {
+    let job = MediaJob::new(Some(app), job_id);
+    media_converter::extract_audio(input_path, output_path, format, audio_track_index, job).await
+}
+
+#[tauri::command]
+async fn video_trim(
+    app: tauri::AppHandle,
+    input: String,
+    output: String,
+    start: String,
+    end: String,
+    reencode: bool,
+    job_id: Option<String>,
+) -> Result<ConversionResult, String> {
+    let job = MediaJob::new(Some(app), job_id);
+    video_edit::trim_video(input, output, start, end, reencode, job).await
+}
+
+#[tauri::command]
+async fn video_detect_crop(input: String) -> Result<CropRect, String> {
+    video_edit::detect_crop(&input).await
+}
+
+#[tauri::command]
+async fn video_crop(
+    app: tauri::AppHandle,
+    input: String,
+    output: String,
+    rect: Option<CropRect>,
+    job_id: Option<String>,
+) -> Result<ConversionResult, String> {
+    let job = MediaJob::new(Some(app), job_id);
+    video_edit::crop_video(input, output, rect, job).await
+}
+
+#[tauri::command]
+async fn video_change_speed(
+    app: tauri::AppHandle,
+    input: String,
+    output: String,
+    factor: f64,
+    keep_pitch: bool,
+    job_id: Option<String>,
+) -> Result<ConversionResult, String> {
+    let job = MediaJob::new(Some(app), job_id);
+    video_edit::change_speed(input, output, factor, keep_pitch, job).await
+}
+
+#[tauri::command]
+async fn video_to_hls(
+    app: tauri::AppHandle,
+    input: String,
+    output_dir: String,
+    renditions: Option<Vec<HlsRendition>>,
+    job_id: Option<String>,
+) -> Result<ConversionResult, String> {
+    let job = MediaJob::new(Some(app), job_id);
+    video_hls::video_to_hls(input, output_dir, renditions, job).await
+}
+
+#[tauri::command]
+fn video_hls_presets() -> Vec<HlsRendition> {
+    video_hls::default_renditions()
+}
+
+#[tauri::command]
+async fn video_to_images(
+    app: tauri::AppHandle,
+    input: String,
+    dir: String,
+    fps: Option<f64>,
+    format: String,
+    job_id: Option<String>,
+) -> Result<ConversionResult, String> {
+    let job = MediaJob::new(Some(app), job_id);
+    image_sequence::video_to_images(input, dir, fps, format, job).await
+}
+
+#[tauri::command]
+async fn images_to_video(
+    app: tauri::AppHandle,
+    pattern_or_dir: String,
+    output: String,
+    fps: f64,
+    codec: String,
+    job_id: Option<String>,
+) -> Result<ConversionResult, String> {
+    let job = MediaJob::new(Some(app), job_id);
+    image_sequence::images_to_video(pattern_or_dir, output, fps, codec, job).await
+}
+
+#[tauri::command]
+async fn video_mute(
+    app: tauri::AppHandle,
+    input: String,
+    output: String,
+    job_id: Option<String>,
+) -> Result<ConversionResult, String> {
+    let job = MediaJob::new(Some(app), job_id);
+    media_tracks::mute_video(input, output, job).await
+}
+
+#[tauri::command]
+async fn video_replace_audio(
+    app: tauri::AppHandle,
+    video: String,
+    audio: String,
+    output: String,
+    offset: Option<f64>,
+    pad_audio: bool,
+    job_id: Option<String>,
+) -> Result<ConversionResult, String> {
+    let job = MediaJob::new(Some(app), job_id);
+    media_tracks::replace_audio(video, audio, output, offset.unwrap_or(0.0), pad_audio, job).await
+}
+
+#[tauri::command]
+async fn video_split_by_chapters(
+    app: tauri::AppHandle,
+    input: String,
+    output_dir: String,
+    job_id: Option<String>,
+) -> Result<Vec<String>, String> {
+    let job = MediaJob::new(Some(app), job_id);
+    media_chapters::split_by_chapters(input, output_dir, job).await
+}
+
+#[tauri::command]
+async fn video_detect_scenes(
+    app: tauri::AppHandle,
+    input: String,
+    threshold: Option<f64>,
+    job_id: Option<String>,
+) -> Result<Vec<f64>, String> {
+    let job = MediaJob::new(Some(app), job_id);
+    video_scenes::detect_scenes(input, threshold, job).await
+}
+
+#[tauri::command]
+async fn video_split_at(
+    app: tauri::AppHandle,
+    input: String,
+    timestamps: Vec<f64>,
+    output_dir: String,
+    job_id: Option<String>,
+) -> Result<Vec<String>, String> {
+    let job = MediaJob::new(Some(app), job_id);
+    video_scenes::split_at(input, timestamps, output_dir, job).await
+}
+
+#[tauri::command]
+async fn video_compare_quality(
+    app: tauri::AppHandle,
+    original: String,
+    encoded: String,
+    metrics: Option<Vec<String>>,
+    job_id: Option<String>,
+) -> Result<QualityScores, String> {
+    let job = MediaJob::new(Some(app), job_id);
+    video_quality::compare_quality(original, encoded, metrics.unwrap_or_default(), job).await
+}
+
+#[tauri::command]
+async fn video_compose(
+    app: tauri::AppHandle,
+    layout: String,
+    inputs: Vec<String>,
+    output: String,
+    job_id: Option<String>,
+) -> Result<ConversionResult, String> {
+    let job = MediaJob::new(Some(app), job_id);
+    video_compose::compose(layout, inputs, output, job).await
+}
+
+#[tauri::command]
+async fn video_extract_subtitles(
+    app: tauri::AppHandle,
+    input: String,
+    output: String,
+    track_index: u32,
+    job_id: Option<String>,
+) -> Result<ConversionResult, String> {
+    let job = MediaJob::new(Some(app), job_id);
+    media_tracks::extract_subtitles(input, output, track_index, job).await
+}
+
+#[tauri::command]
+async fn video_extract_frames(
+    app: tauri::AppHandle,
+    input: String,
+    output_pattern: String,
+    mode: FrameMode,
+    job_id: Option<String>,
+) -> Result<Vec<String>, String> {
+    let job = MediaJob::new(Some(app), job_id);
+    video_frames::extract_frames(input, output_pattern, mode, job).await
+}
+
+#[tauri::command]
+async fn video_preview_frame(input: String, timestamp: String) -> Result<String, String> {
+    video_frames::preview_frame(input, timestamp).await
+}
+
+#[tauri::command]
+async fn video_to_gif(
+    app: tauri::AppHandle,
+    options: GifOptions,
+    job_id: Option<String>,
+) -> Result<ConversionResult, String> {
+    video_gif::video_to_gif(options, MediaJob::new(Some(app), job_id)).await
+}
+
+// ============================================================================
+// Screen Recording Commands
+// ============================================================================
+
+#[tauri::command]
+async fn start_recording(options: RecordingOptions) -> Result<RecordingStatus, String> {
+    screen_recorder::start_recording(options).await
+}
+
+#[tauri::command]
+async fn stop_recording() -> Result<ConversionResult, String> {
+    screen_recorder::stop_recording().await
+}
+
+#[tauri::command]
+fn recording_status() -> RecordingStatus {
+    screen_recorder::recording_status()
+}
+
+// ============================================================================
+// Audio Commands
+// ============================================================================
+
+#[tauri::command]
+async fn audio_convert(
+    app: tauri::AppHandle,
+    options: AudioConvertOptions,
+    job_id: Option<String>,
 ) -> Result<ConversionResult, String> {
-    media_converter::extract_audio(input_path, output_path, format).await
+    audio_tools::convert_audio(options, MediaJob::new(Some(app), job_id)).await
+}
+
+#[tauri::command]
+async fn audio_analyze(
+    app: tauri::AppHandle,
+    input: String,
+    job_id: Option<String>,
+) -> Result<AudioAnalysis, String> {
+    audio_tools::analyze_audio(input, MediaJob::new(Some(app), job_id)).await
 }
 
 // ============================================================================
@@ -118,76 +720,744 @@ async fn document_convert_office(
     document_converter::convert_with_libreoffice(input_path, output_format, output_dir).await
 }
 
+#[tauri::command]
+async fn document_convert_office_batch(
+    app: tauri::AppHandle,
+    inputs: Vec<String>,
+    output_format: String,
+    output_dir: String,
+    concurrency: Option<usize>,
+    batch_id: Option<String>,
+) -> Result<office_batch::OfficeBatchResult, String> {
+    office_batch::convert_office_batch(Some(app), inputs, output_format, output_dir, concurrency, batch_id).await
+}
+
 #[tauri::command]
 async fn document_convert_pandoc(
     input_path: String,
     output_path: String,
     from_format: Option<String>,
     to_format: Option<String>,
+    options: Option<PandocOptions>,
 ) -> Result<document_converter::ConversionResult, String> {
-    document_converter::convert_with_pandoc(input_path, output_path, from_format, to_format).await
+    document_converter::convert_with_pandoc(input_path, output_path, from_format, to_format, options.unwrap_or_default()).await
 }
 
-// ============================================================================
-// Bundled Document Commands (No external dependencies!)
-// ============================================================================
-
 #[tauri::command]
-fn bundled_get_doc_info(file_path: String) -> Result<bundled_converter::DocumentInfo, String> {
-    bundled_converter::get_document_info(&file_path)
+async fn document_html_to_pdf(
+    input_path: String,
+    output_path: String,
+) -> Result<document_converter::ConversionResult, String> {
+    document_converter::html_to_pdf(input_path, output_path).await
 }
 
 #[tauri::command]
-fn bundled_merge_pdfs(
+async fn document_merge_pdfs(
     input_paths: Vec<String>,
     output_path: String,
-) -> Result<bundled_converter::ConversionResult, String> {
-    bundled_converter::merge_pdfs(input_paths, output_path)
+) -> Result<document_converter::ConversionResult, String> {
+    document_converter::merge_pdfs(input_paths, output_path).await
 }
 
 #[tauri::command]
-fn bundled_excel_to_csv(
+async fn document_convert_presentation(
+    app: tauri::AppHandle,
     input_path: String,
-    output_path: String,
-    sheet_index: Option<usize>,
-) -> Result<bundled_converter::ConversionResult, String> {
-    bundled_converter::excel_to_csv(input_path, output_path, sheet_index)
+    mode: Option<presentation::PresentationMode>,
+    output_dir: String,
+    dpi: Option<u32>,
+) -> Result<presentation::PresentationResult, String> {
+    presentation::convert_presentation(app, input_path, mode.unwrap_or_default(), output_dir, dpi).await
 }
 
 #[tauri::command]
-fn bundled_csv_to_json(
+async fn markdown_to_pdf(
     input_path: String,
     output_path: String,
-) -> Result<bundled_converter::ConversionResult, String> {
-    bundled_converter::csv_to_json(input_path, output_path)
+    style: Option<PandocOptions>,
+) -> Result<document_converter::ConversionResult, String> {
+    markdown_pdf::markdown_to_pdf(input_path, output_path, style.unwrap_or_default()).await
 }
 
 #[tauri::command]
-fn bundled_json_to_csv(
+async fn latex_to_pdf(
     input_path: String,
     output_path: String,
-) -> Result<bundled_converter::ConversionResult, String> {
-    bundled_converter::json_to_csv(input_path, output_path)
+    engine: Option<latex_pdf::LatexEngine>,
+) -> Result<latex_pdf::LatexResult, String> {
+    latex_pdf::latex_to_pdf(input_path, output_path, engine).await
 }
 
 #[tauri::command]
-fn bundled_convert_image(
-    input_path: String,
-    output_path: String,
-    quality: Option<u8>,
-) -> Result<bundled_converter::ConversionResult, String> {
-    bundled_converter::convert_image_format(input_path, output_path, quality)
+async fn document_install_tool(
+    app: tauri::AppHandle,
+    tool: tool_installer::ManagedTool,
+) -> Result<Vec<ToolStatus>, String> {
+    tool_installer::install(app, tool).await
 }
 
 #[tauri::command]
-fn bundled_resize_image(
-    input_path: String,
-    output_path: String,
-    width: u32,
-    height: u32,
-    maintain_aspect: bool,
-) -> Result<bundled_converter::ConversionResult, String> {
-    bundled_converter::resize_image(input_path, output_path, width, height, maintain_aspect)
+async fn document_remove_tool(tool: tool_installer::ManagedTool) -> Result<Vec<ToolStatus>, String> {
+    tokio::task::spawn_blocking(move || tool_installer::uninstall(tool))
+        .await
+        .map_err(|e| format!("Task error: {}", e))?
+}
+
+#[tauri::command]
+fn document_get_tool_registry() -> Result<tool_registry::ToolRegistry, String> {
+    tool_registry::load_registry()
+}
+
+#[tauri::command]
+fn document_set_tool_registry(registry: tool_registry::ToolRegistry) -> Result<tool_registry::ToolRegistry, String> {
+    tool_registry::save_registry(registry)
+}
+
+#[tauri::command]
+fn document_get_tool_timeouts() -> Result<ToolTimeouts, String> {
+    tool_process::load_timeouts()
+}
+
+#[tauri::command]
+fn document_set_tool_timeouts(timeouts: ToolTimeouts) -> Result<ToolTimeouts, String> {
+    tool_process::save_timeouts(timeouts)
+}
+
+// ============================================================================
+// Bundled Document Commands (No external dependencies!)
+// ============================================================================
+
+#[tauri::command]
+fn bundled_get_doc_info(
+    file_path: String,
+    password: Option<String>,
+) -> Result<bundled_converter::DocumentInfo, String> {
+    bundled_converter::get_document_info(&file_path, password.as_deref())
+}
+
+#[tauri::command]
+fn bundled_merge_pdfs(
+    input_paths: Vec<String>,
+    output_path: String,
+    password: Option<String>,
+    bookmark_per_file: Option<bool>,
+) -> Result<bundled_converter::ConversionResult, String> {
+    pdf_merge::merge_pdfs(input_paths, output_path, password, bookmark_per_file.unwrap_or(false))
+}
+
+#[tauri::command]
+async fn bundled_docx_to_pdf(
+    input_path: String,
+    output_path: String,
+) -> Result<bundled_converter::ConversionResult, String> {
+    tokio::task::spawn_blocking(move || bundled_converter::docx_to_pdf(input_path, output_path))
+        .await
+        .map_err(|e| format!("Task error: {}", e))?
+}
+
+#[tauri::command]
+fn bundled_excel_to_csv(
+    input_path: String,
+    output_path: String,
+    sheet_index: Option<usize>,
+    formulas: Option<String>,
+    dialect: Option<csv_dialect::CsvDialect>,
+) -> Result<excel_reader::SheetExportResult, String> {
+    bundled_converter::excel_to_csv(input_path, output_path, sheet_index, formulas, dialect.unwrap_or_default())
+}
+
+#[tauri::command]
+fn bundled_excel_to_json(
+    input_path: String,
+    output_path: String,
+    sheet_index: Option<usize>,
+    formulas: Option<String>,
+) -> Result<excel_reader::SheetExportResult, String> {
+    excel_reader::excel_to_json(input_path, output_path, sheet_index, formulas)
+}
+
+#[tauri::command]
+fn bundled_csv_to_json(
+    input_path: String,
+    output_path: String,
+    dialect: Option<csv_dialect::CsvDialect>,
+) -> Result<bundled_converter::ConversionResult, String> {
+    bundled_converter::csv_to_json(input_path, output_path, dialect.unwrap_or_default())
+}
+
+#[tauri::command]
+fn bundled_json_to_csv(
+    input_path: String,
+    output_path: String,
+    options: Option<json_flatten::JsonToCsvOptions>,
+) -> Result<bundled_converter::ConversionResult, String> {
+    bundled_converter::json_to_csv(input_path, output_path, options.unwrap_or_default())
+}
+
+#[tauri::command]
+fn bundled_ndjson_to_csv(
+    input_path: String,
+    output_path: String,
+    options: Option<json_flatten::JsonToCsvOptions>,
+) -> Result<bundled_converter::ConversionResult, String> {
+    json_lines::ndjson_to_csv(input_path, output_path, options.unwrap_or_default())
+}
+
+#[tauri::command]
+fn bundled_csv_to_ndjson(
+    input_path: String,
+    output_path: String,
+    dialect: Option<csv_dialect::CsvDialect>,
+) -> Result<bundled_converter::ConversionResult, String> {
+    json_lines::csv_to_ndjson(input_path, output_path, dialect.unwrap_or_default())
+}
+
+#[tauri::command]
+fn bundled_xml_to_json(
+    input_path: String,
+    output_path: String,
+    options: Option<xml_convert::XmlOptions>,
+) -> Result<bundled_converter::ConversionResult, String> {
+    xml_convert::xml_to_json(input_path, output_path, options.unwrap_or_default())
+}
+
+#[tauri::command]
+fn bundled_json_to_xml(
+    input_path: String,
+    output_path: String,
+    options: Option<xml_convert::XmlOptions>,
+) -> Result<bundled_converter::ConversionResult, String> {
+    xml_convert::json_to_xml(input_path, output_path, options.unwrap_or_default())
+}
+
+#[tauri::command]
+async fn bundled_yaml_to_json(
+    input_path: String,
+    output_path: String,
+) -> Result<bundled_converter::ConversionResult, String> {
+    tokio::task::spawn_blocking(move || {
+        structured_convert::convert_structured(
+            input_path,
+            output_path,
+            Some(structured_convert::DataFormat::Yaml),
+            Some(structured_convert::DataFormat::Json),
+            structured_convert::StructuredOptions::default(),
+        )
+    })
+    .await
+    .map_err(|e| format!("Task error: {}", e))?
+}
+
+#[tauri::command]
+fn bundled_json_to_yaml(
+    input_path: String,
+    output_path: String,
+) -> Result<bundled_converter::ConversionResult, String> {
+    structured_convert::convert_structured(
+        input_path,
+        output_path,
+        Some(structured_convert::DataFormat::Json),
+        Some(structured_convert::DataFormat::Yaml),
+        structured_convert::StructuredOptions::default(),
+    )
+}
+
+#[tauri::command]
+fn bundled_toml_to_json(
+    input_path: String,
+    output_path: String,
+) -> Result<bundled_converter::ConversionResult, String> {
+    structured_convert::convert_structured(
+        input_path,
+        output_path,
+        Some(structured_convert::DataFormat::Toml),
+        Some(structured_convert::DataFormat::Json),
+        structured_convert::StructuredOptions::default(),
+    )
+}
+
+#[tauri::command]
+fn bundled_convert_structured(
+    input_path: String,
+    output_path: String,
+    from: Option<structured_convert::DataFormat>,
+    to: Option<structured_convert::DataFormat>,
+    options: Option<structured_convert::StructuredOptions>,
+) -> Result<bundled_converter::ConversionResult, String> {
+    structured_convert::convert_structured(input_path, output_path, from, to, options.unwrap_or_default())
+}
+
+#[tauri::command]
+fn bundled_convert_image(
+    input_path: String,
+    output_path: String,
+    quality: Option<u8>,
+) -> Result<bundled_converter::ConversionResult, String> {
+    bundled_converter::convert_image_format(input_path, output_path, quality)
+}
+
+#[tauri::command]
+fn bundled_resize_image(
+    input_path: String,
+    output_path: String,
+    width: u32,
+    height: u32,
+    maintain_aspect: bool,
+) -> Result<bundled_converter::ConversionResult, String> {
+    bundled_converter::resize_image(input_path, output_path, width, height, maintain_aspect)
+}
+
+#[tauri::command]
+async fn bundled_crop_image(
+    input_path: String,
+    output_path: String,
+    mode: image_edit::CropMode,
+    quality: Option<u8>,
+) -> Result<bundled_converter::ConversionResult, String> {
+    tokio::task::spawn_blocking(move || image_edit::crop_image(input_path, output_path, mode, quality))
+        .await
+        .map_err(|e| format!("Task error: {}", e))?
+}
+
+#[tauri::command]
+fn bundled_transform_image(
+    input_path: String,
+    output_path: String,
+    rotate90s: Option<i32>,
+    flip_h: Option<bool>,
+    flip_v: Option<bool>,
+    auto_orient: Option<bool>,
+    quality: Option<u8>,
+) -> Result<bundled_converter::ConversionResult, String> {
+    image_edit::transform_image(
+        input_path,
+        output_path,
+        rotate90s.unwrap_or(0),
+        flip_h.unwrap_or(false),
+        flip_v.unwrap_or(false),
+        auto_orient.unwrap_or(true),
+        quality,
+    )
+}
+
+#[tauri::command]
+fn bundled_get_image_metadata(input_path: String) -> Result<image_metadata::ImageMetadata, String> {
+    image_metadata::get_image_metadata(&input_path)
+}
+
+#[tauri::command]
+fn bundled_strip_image_metadata(
+    input_path: String,
+    output_path: String,
+) -> Result<bundled_converter::ConversionResult, String> {
+    image_metadata::strip_image_metadata(input_path, output_path)
+}
+
+#[tauri::command]
+async fn bundled_compress_image_to_size(
+    input_path: String,
+    output_path: String,
+    max_kb: u32,
+    allow_resize: Option<bool>,
+    min_quality: Option<u8>,
+) -> Result<bundled_converter::ConversionResult, String> {
+    tokio::task::spawn_blocking(move || {
+        image_size_limit::compress_image_to_size(input_path, output_path, max_kb, allow_resize.unwrap_or(true), min_quality)
+    })
+    .await
+    .map_err(|e| format!("Task error: {}", e))?
+}
+
+#[tauri::command]
+async fn bundled_convert_animation(
+    input_path: String,
+    output_path: String,
+    quality: Option<u8>,
+) -> Result<bundled_converter::ConversionResult, String> {
+    tokio::task::spawn_blocking(move || image_animation::convert_animation(input_path, output_path, quality))
+        .await
+        .map_err(|e| format!("Task error: {}", e))?
+}
+
+#[tauri::command]
+fn bundled_extract_first_frame(
+    input_path: String,
+    output_path: String,
+    quality: Option<u8>,
+) -> Result<bundled_converter::ConversionResult, String> {
+    image_animation::extract_first_frame(input_path, output_path, quality)
+}
+
+#[tauri::command]
+async fn bundled_find_duplicate_images(
+    dir: String,
+    method: Option<image_duplicates::DuplicateMethod>,
+    threshold: Option<u32>,
+) -> Result<image_duplicates::DuplicateReport, String> {
+    tokio::task::spawn_blocking(move || {
+        image_duplicates::find_duplicate_images(dir, method.unwrap_or_default(), threshold)
+    })
+    .await
+    .map_err(|e| format!("Task error: {}", e))?
+}
+
+#[tauri::command]
+async fn bundled_enhance_scan(
+    input_path: String,
+    output_path: String,
+    options: Option<scan_enhance::ScanOptions>,
+) -> Result<bundled_converter::ConversionResult, String> {
+    tokio::task::spawn_blocking(move || {
+        scan_enhance::enhance_scan(input_path, output_path, options.unwrap_or_default())
+    })
+    .await
+    .map_err(|e| format!("Task error: {}", e))?
+}
+
+#[tauri::command]
+async fn bundled_adjust_image(
+    input_path: String,
+    output_path: String,
+    adjustments: image_adjust::ImageAdjustments,
+) -> Result<bundled_converter::ConversionResult, String> {
+    tokio::task::spawn_blocking(move || image_adjust::adjust_image(input_path, output_path, adjustments))
+        .await
+        .map_err(|e| format!("Task error: {}", e))?
+}
+
+#[tauri::command]
+async fn bundled_generate_icons(
+    input_path: String,
+    output_dir: String,
+    targets: Option<Vec<icon_generator::IconTarget>>,
+) -> Result<Vec<String>, String> {
+    tokio::task::spawn_blocking(move || icon_generator::generate_icons(input_path, output_dir, targets.unwrap_or_default()))
+        .await
+        .map_err(|e| format!("Task error: {}", e))?
+}
+
+#[tauri::command]
+async fn bundled_prepare_id_photo(
+    input_path: String,
+    output_path: String,
+    spec: id_photo::IdPhotoSpec,
+) -> Result<bundled_converter::ConversionResult, String> {
+    tokio::task::spawn_blocking(move || id_photo::prepare_id_photo(input_path, output_path, spec))
+        .await
+        .map_err(|e| format!("Task error: {}", e))?
+}
+
+#[tauri::command]
+async fn bundled_make_contact_sheet(
+    inputs: Vec<String>,
+    output_path: String,
+    columns: Option<u32>,
+    thumb_size: Option<u32>,
+    labels: Option<bool>,
+) -> Result<bundled_converter::ConversionResult, String> {
+    tokio::task::spawn_blocking(move || {
+        contact_sheet::make_contact_sheet(
+            inputs,
+            output_path,
+            columns.unwrap_or(5),
+            thumb_size.unwrap_or(240),
+            labels.unwrap_or(true),
+        )
+    })
+    .await
+    .map_err(|e| format!("Task error: {}", e))?
+}
+
+// ============================================================================
+// PDF Tools Commands
+// ============================================================================
+
+#[tauri::command]
+async fn pdf_encrypt(
+    input_path: String,
+    output_path: String,
+    user_password: String,
+    owner_password: Option<String>,
+    permissions: Option<Vec<String>>,
+    key_length: Option<u32>,
+) -> Result<bundled_converter::ConversionResult, String> {
+    tokio::task::spawn_blocking(move || {
+        pdf_security::encrypt_pdf(input_path, output_path, user_password, owner_password, permissions, key_length)
+    })
+    .await
+    .map_err(|e| format!("Task error: {}", e))?
+}
+
+#[tauri::command]
+async fn pdf_compress(
+    input_path: String,
+    output_path: String,
+    dpi: Option<u32>,
+    jpeg_quality: Option<u8>,
+    password: Option<String>,
+) -> Result<bundled_converter::ConversionResult, String> {
+    tokio::task::spawn_blocking(move || {
+        pdf_compress::compress_pdf(input_path, output_path, dpi, jpeg_quality, password)
+    })
+    .await
+    .map_err(|e| format!("Task error: {}", e))?
+}
+
+#[tauri::command]
+async fn pdf_set_metadata(
+    input_path: String,
+    output_path: String,
+    fields: pdf_metadata::PdfMetadata,
+    password: Option<String>,
+) -> Result<bundled_converter::ConversionResult, String> {
+    tokio::task::spawn_blocking(move || {
+        pdf_metadata::set_pdf_metadata(input_path, output_path, fields, password)
+    })
+    .await
+    .map_err(|e| format!("Task error: {}", e))?
+}
+
+#[tauri::command]
+fn pdf_get_outline(
+    input_path: String,
+    password: Option<String>,
+) -> Result<Vec<pdf_outline::OutlineItem>, String> {
+    pdf_outline::get_pdf_outline(input_path, password)
+}
+
+#[tauri::command]
+async fn pdf_set_outline(
+    input_path: String,
+    output_path: String,
+    items: Vec<pdf_outline::OutlineItem>,
+    password: Option<String>,
+) -> Result<bundled_converter::ConversionResult, String> {
+    tokio::task::spawn_blocking(move || {
+        pdf_outline::set_pdf_outline(input_path, output_path, items, password)
+    })
+    .await
+    .map_err(|e| format!("Task error: {}", e))?
+}
+
+#[tauri::command]
+fn pdf_check_pdfa(input_path: String, password: Option<String>) -> Result<pdf_pdfa::PdfaReport, String> {
+    pdf_pdfa::check_pdfa(input_path, password)
+}
+
+#[tauri::command]
+async fn pdf_convert_to_pdfa(
+    input_path: String,
+    output_path: String,
+    password: Option<String>,
+) -> Result<bundled_converter::ConversionResult, String> {
+    tokio::task::spawn_blocking(move || pdf_pdfa::convert_to_pdfa(input_path, output_path, password))
+        .await
+        .map_err(|e| format!("Task error: {}", e))?
+}
+
+#[tauri::command]
+async fn pdf_attach_files(
+    input_path: String,
+    output_path: String,
+    attachments: Vec<String>,
+    password: Option<String>,
+) -> Result<bundled_converter::ConversionResult, String> {
+    tokio::task::spawn_blocking(move || {
+        pdf_attachments::attach_files(input_path, output_path, attachments, password)
+    })
+    .await
+    .map_err(|e| format!("Task error: {}", e))?
+}
+
+#[tauri::command]
+async fn pdf_extract_attachments(
+    input_path: String,
+    output_dir: String,
+    password: Option<String>,
+) -> Result<Vec<String>, String> {
+    tokio::task::spawn_blocking(move || {
+        pdf_attachments::extract_attachments(input_path, output_dir, password)
+    })
+    .await
+    .map_err(|e| format!("Task error: {}", e))?
+}
+
+#[tauri::command]
+fn pdf_signature_info(
+    input_path: String,
+    password: Option<String>,
+) -> Result<Vec<pdf_signatures::SignatureInfo>, String> {
+    pdf_signatures::get_signature_info(input_path, password)
+}
+
+#[tauri::command]
+async fn pdf_watermark(options: pdf_watermark::WatermarkOptions) -> Result<bundled_converter::ConversionResult, String> {
+    tokio::task::spawn_blocking(move || pdf_watermark::watermark_pdf(options))
+        .await
+        .map_err(|e| format!("Task error: {}", e))?
+}
+
+#[tauri::command]
+async fn pdf_page_numbers(
+    options: pdf_page_numbers::PageNumberOptions,
+) -> Result<bundled_converter::ConversionResult, String> {
+    tokio::task::spawn_blocking(move || pdf_page_numbers::stamp_page_numbers(options))
+        .await
+        .map_err(|e| format!("Task error: {}", e))?
+}
+
+#[tauri::command]
+async fn pdf_to_images(
+    app: tauri::AppHandle,
+    input_path: String,
+    output_dir: String,
+    dpi: Option<u32>,
+    format: Option<String>,
+    pages: Option<String>,
+    password: Option<String>,
+) -> Result<Vec<String>, String> {
+    tokio::task::spawn_blocking(move || {
+        pdf_render::pdf_to_images(app, input_path, output_dir, dpi, format, pages, password)
+    })
+    .await
+    .map_err(|e| format!("Task error: {}", e))?
+}
+
+#[tauri::command]
+fn pdf_form_fields(
+    input_path: String,
+    password: Option<String>,
+) -> Result<Vec<pdf_forms::FormField>, String> {
+    pdf_forms::get_pdf_form_fields(input_path, password)
+}
+
+#[tauri::command]
+fn pdf_fill_form(
+    input_path: String,
+    output_path: String,
+    values: HashMap<String, String>,
+    flatten: bool,
+    password: Option<String>,
+) -> Result<bundled_converter::ConversionResult, String> {
+    pdf_forms::fill_pdf_form(input_path, output_path, values, flatten, password)
+}
+
+#[tauri::command]
+async fn pdf_fill_forms_from_csv(
+    input_path: String,
+    csv_path: String,
+    output_dir: String,
+    name_column: Option<String>,
+    flatten: bool,
+    password: Option<String>,
+) -> Result<Vec<String>, String> {
+    tokio::task::spawn_blocking(move || {
+        pdf_forms::fill_pdf_forms_from_csv(input_path, csv_path, output_dir, name_column, flatten, password)
+    })
+    .await
+    .map_err(|e| format!("Task error: {}", e))?
+}
+
+// ============================================================================
+// Data Tools Commands
+// ============================================================================
+
+#[tauri::command]
+async fn data_csv_to_excel(
+    input_path: String,
+    output_path: String,
+    options: csv_excel::CsvToExcelOptions,
+) -> Result<bundled_converter::ConversionResult, String> {
+    tokio::task::spawn_blocking(move || csv_excel::csv_to_excel(input_path, output_path, options))
+        .await
+        .map_err(|e| format!("Task error: {}", e))?
+}
+
+#[tauri::command]
+async fn data_json_to_excel(
+    input_path: String,
+    output_path: String,
+) -> Result<bundled_converter::ConversionResult, String> {
+    tokio::task::spawn_blocking(move || json_excel::json_to_excel(input_path, output_path))
+        .await
+        .map_err(|e| format!("Task error: {}", e))?
+}
+
+#[tauri::command]
+async fn data_csv_concat(
+    input_paths: Vec<String>,
+    output_path: String,
+    options: csv_concat::CsvConcatOptions,
+) -> Result<bundled_converter::ConversionResult, String> {
+    tokio::task::spawn_blocking(move || csv_concat::csv_concat(input_paths, output_path, options))
+        .await
+        .map_err(|e| format!("Task error: {}", e))?
+}
+
+#[tauri::command]
+async fn data_csv_transform(
+    input_path: String,
+    output_path: String,
+    operations: Vec<csv_transform::CsvOperation>,
+    dialect: Option<csv_dialect::CsvDialect>,
+) -> Result<bundled_converter::ConversionResult, String> {
+    tokio::task::spawn_blocking(move || {
+        csv_transform::csv_transform(input_path, output_path, operations, dialect.unwrap_or_default())
+    })
+    .await
+    .map_err(|e| format!("Task error: {}", e))?
+}
+
+#[tauri::command]
+async fn data_table_to_markdown(
+    input_path: String,
+    output_path: String,
+    sheet_index: Option<usize>,
+) -> Result<bundled_converter::ConversionResult, String> {
+    tokio::task::spawn_blocking(move || markdown_table::table_to_markdown(input_path, output_path, sheet_index))
+        .await
+        .map_err(|e| format!("Task error: {}", e))?
+}
+
+#[tauri::command]
+async fn data_validate_csv(
+    input_path: String,
+    schema: csv_validate::CsvSchema,
+) -> Result<csv_validate::ValidationReport, String> {
+    tokio::task::spawn_blocking(move || csv_validate::validate_csv(input_path, schema))
+        .await
+        .map_err(|e| format!("Task error: {}", e))?
+}
+
+#[tauri::command]
+async fn data_diff_spreadsheets(
+    old_path: String,
+    new_path: String,
+    options: spreadsheet_diff::DiffOptions,
+) -> Result<spreadsheet_diff::DiffReport, String> {
+    tokio::task::spawn_blocking(move || spreadsheet_diff::diff_spreadsheets(old_path, new_path, options))
+        .await
+        .map_err(|e| format!("Task error: {}", e))?
+}
+
+#[tauri::command]
+async fn data_import_to_sqlite(
+    inputs: Vec<String>,
+    output_db: String,
+    table_names: Option<Vec<String>>,
+) -> Result<bundled_converter::ConversionResult, String> {
+    tokio::task::spawn_blocking(move || {
+        sqlite_import::import_to_sqlite(inputs, output_db, table_names.unwrap_or_default())
+    })
+    .await
+    .map_err(|e| format!("Task error: {}", e))?
+}
+
+#[tauri::command]
+async fn data_query_sqlite(db_path: String, sql: String) -> Result<sqlite_import::QueryResult, String> {
+    tokio::task::spawn_blocking(move || sqlite_import::query_sqlite(db_path, sql))
+        .await
+        .map_err(|e| format!("Task error: {}", e))?
 }
 
 // ============================================================================
@@ -288,14 +1558,79 @@ pub fn run() {
         .invoke_handler(tauri::generate_handler![
             // Attendance
             scan_for_devices,
+            scan_range,
+            rescan_and_diff,
+            list_network_interfaces,
             fetch_attendance,
+            delete_device_user,
+            clear_all_data,
+            unlock_door,
+            get_door_state,
+            admin_status,
+            admin_set_password,
+            get_bell_schedule,
+            set_bell_schedule,
+            merge_attendance_records,
+            export_attendance_register_xlsx,
+            generate_attendance_report_pdf,
+            // Device Profiles & Monitor
+            device_profiles_list,
+            device_profiles_save,
+            device_profiles_remove,
+            device_monitor_start,
+            device_monitor_stop,
+            device_monitor_status,
+            device_uptime,
+            // Holiday Calendar
+            holidays_list,
+            holidays_add,
+            holidays_remove,
+            holidays_set_weekly_offs,
+            holidays_import,
             // Media (FFmpeg)
             check_ffmpeg_status,
+            ffmpeg_managed_status,
+            ffmpeg_install_managed,
+            ffmpeg_remove_managed,
             get_media_information,
+            media_set_metadata,
+            conversion_presets_list,
+            conversion_presets_save,
+            conversion_presets_remove,
+            cancel_media_job,
+            check_hardware_encoders,
+            check_video_encoders,
             // Video (FFmpeg)
             video_convert,
+            estimate_conversion,
             video_compress,
             video_extract_audio,
+            video_trim,
+            video_detect_crop,
+            video_crop,
+            video_change_speed,
+            video_to_hls,
+            video_hls_presets,
+            video_to_images,
+            images_to_video,
+            video_mute,
+            video_replace_audio,
+            video_split_by_chapters,
+            video_detect_scenes,
+            video_split_at,
+            video_compare_quality,
+            video_compose,
+            video_extract_subtitles,
+            video_extract_frames,
+            video_preview_frame,
+            video_to_gif,
+            // Screen recording
+            start_recording,
+            stop_recording,
+            recording_status,
+            // Audio (FFmpeg)
+            audio_convert,
+            audio_analyze,
             // Image (FFmpeg)
             image_convert,
             image_compress,
@@ -303,15 +1638,77 @@ pub fn run() {
             // Document (external tools - optional)
             check_document_tools,
             document_convert_office,
+            document_convert_office_batch,
             document_convert_pandoc,
+            document_html_to_pdf,
+            document_merge_pdfs,
+            document_convert_presentation,
+            markdown_to_pdf,
+            latex_to_pdf,
+            document_install_tool,
+            document_remove_tool,
+            document_get_tool_registry,
+            document_set_tool_registry,
+            document_get_tool_timeouts,
+            document_set_tool_timeouts,
             // Bundled (no external deps!)
             bundled_get_doc_info,
             bundled_merge_pdfs,
+            bundled_docx_to_pdf,
             bundled_excel_to_csv,
+            bundled_excel_to_json,
             bundled_csv_to_json,
             bundled_json_to_csv,
+            bundled_ndjson_to_csv,
+            bundled_csv_to_ndjson,
+            bundled_xml_to_json,
+            bundled_json_to_xml,
+            bundled_yaml_to_json,
+            bundled_json_to_yaml,
+            bundled_toml_to_json,
+            bundled_convert_structured,
             bundled_convert_image,
             bundled_resize_image,
+            bundled_crop_image,
+            bundled_transform_image,
+            bundled_get_image_metadata,
+            bundled_strip_image_metadata,
+            bundled_compress_image_to_size,
+            bundled_prepare_id_photo,
+            bundled_make_contact_sheet,
+            bundled_adjust_image,
+            bundled_generate_icons,
+            bundled_convert_animation,
+            bundled_extract_first_frame,
+            bundled_find_duplicate_images,
+            bundled_enhance_scan,
+            // PDF Tools
+            pdf_encrypt,
+            pdf_compress,
+            pdf_set_metadata,
+            pdf_get_outline,
+            pdf_set_outline,
+            pdf_check_pdfa,
+            pdf_convert_to_pdfa,
+            pdf_attach_files,
+            pdf_extract_attachments,
+            pdf_signature_info,
+            pdf_watermark,
+            pdf_page_numbers,
+            pdf_to_images,
+            pdf_form_fields,
+            pdf_fill_form,
+            pdf_fill_forms_from_csv,
+            // Data Tools
+            data_csv_to_excel,
+            data_json_to_excel,
+            data_csv_concat,
+            data_csv_transform,
+            data_validate_csv,
+            data_import_to_sqlite,
+            data_query_sqlite,
+            data_table_to_markdown,
+            data_diff_spreadsheets,
             // AI Assistant
             ai_get_providers,
             ai_chat,
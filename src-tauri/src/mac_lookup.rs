@@ -0,0 +1,65 @@
+//! MAC Lookup - resolve a responding device's MAC address from the OS ARP cache
+//! Only local-segment hosts are in the cache; routed subnets fall back to the MAC the device reports
+
+/// Normalize "0:11:2:aa:bb:cc" / "00-11-02-AA-BB-CC" to "00:11:02:AA:BB:CC"
+fn normalize_mac(raw: &str) -> Option<String> {
+    let parts: Vec<&str> = raw.split([':', '-']).collect();
+    if parts.len() != 6 {
+        return None;
+    }
+    let mut octets = Vec::with_capacity(6);
+    for part in parts {
+        let value = u8::from_str_radix(part, 16).ok()?;
+        octets.push(format!("{:02X}", value));
+    }
+    let mac = octets.join(":");
+    // Incomplete ARP entries show up as all zeros
+    if mac == "00:00:00:00:00:00" { None } else { Some(mac) }
+}
+
+/// Linux: /proc/net/arp ("IP address  HW type  Flags  HW address  Mask  Device")
+#[cfg(target_os = "linux")]
+async fn arp_cache_entry(ip: &str) -> Option<String> {
+    let table = tokio::fs::read_to_string("/proc/net/arp").await.ok()?;
+    table.lines()
+        .skip(1)
+        .map(|line| line.split_whitespace().collect::<Vec<_>>())
+        .find(|cols| cols.len() >= 4 && cols[0] == ip)
+        .and_then(|cols| normalize_mac(cols[3]))
+}
+
+/// macOS: "? (192.168.1.5) at 0:11:22:33:44:55 on en0 ifscope [ethernet]"
+#[cfg(target_os = "macos")]
+async fn arp_cache_entry(ip: &str) -> Option<String> {
+    let output = tokio::process::Command::new("arp").arg("-n").arg(ip).output().await.ok()?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    text.split_whitespace()
+        .skip_while(|word| *word != "at")
+        .nth(1)
+        .and_then(normalize_mac)
+}
+
+/// Windows: "  192.168.1.5     00-11-22-33-44-55     dynamic"
+#[cfg(target_os = "windows")]
+async fn arp_cache_entry(ip: &str) -> Option<String> {
+    let output = tokio::process::Command::new("arp").arg("-a").arg(ip).output().await.ok()?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    text.lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>())
+        .find(|cols| cols.len() >= 2 && cols[0] == ip)
+        .and_then(|cols| normalize_mac(cols[1]))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+async fn arp_cache_entry(_ip: &str) -> Option<String> {
+    None
+}
+
+/// Look up the MAC for an IP that has just answered a TCP connect (so the ARP cache is warm).
+/// Falls back to the MAC the device reports about itself (ZK "MAC" option) for routed subnets.
+pub async fn resolve_mac(ip: &str, reported: Option<&str>) -> Option<String> {
+    match arp_cache_entry(ip).await {
+        Some(mac) => Some(mac),
+        None => reported.and_then(normalize_mac),
+    }
+}
@@ -0,0 +1,261 @@
+//! Markdown to PDF - one command that works with whatever is installed
+//! Tries Pandoc + wkhtmltopdf (HTML with the stylesheet), then Pandoc + LibreOffice (DOCX with the
+//! reference document), then the bundled renderer, which lays out headings, paragraphs, lists,
+//! quotes, code blocks and tables with the built-in PDF writer (no styling, Latin-1 text).
+
+use std::fs;
+use std::path::Path;
+use log::{info, warn};
+use crate::document_converter::{
+    check_tools, convert_with_libreoffice, convert_with_pandoc, html_to_pdf, ConversionResult, PandocOptions,
+};
+use crate::pdf_writer::{text_width, PdfWriter, A4_PORTRAIT};
+use crate::tool_process::WorkDir;
+
+const BODY_SIZE: f32 = 10.5;
+const CODE_SIZE: f32 = 9.0;
+
+/// Strip inline Markdown: emphasis markers, code ticks, links and images ([text](url) -> text)
+fn plain_inline(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('[') {
+        let label_end = rest[start..].find("](").map(|i| start + i);
+        let Some((label_end, url_end)) = label_end.and_then(|end| rest[end..].find(')').map(|i| (end, end + i))) else {
+            break;
+        };
+        out.push_str(rest[..start].strip_suffix('!').unwrap_or(&rest[..start]));
+        out.push_str(&rest[start + 1..label_end]);
+        rest = &rest[url_end + 1..];
+    }
+    out.push_str(rest);
+    out.replace("**", "").replace("__", "").replace("~~", "").replace(['*', '`'], "")
+}
+
+/// "- item", "* item", "+ item" or "1. item" -> (marker to print, item text)
+fn list_item(line: &str) -> Option<(String, &str)> {
+    if let Some(text) = ["- ", "* ", "+ "].iter().find_map(|m| line.strip_prefix(m)) {
+        return Some(("-".to_string(), text));
+    }
+    let digits = line.chars().take_while(char::is_ascii_digit).count();
+    let text = line[digits..].strip_prefix(". ").or_else(|| line[digits..].strip_prefix(") "))?;
+    (digits > 0).then(|| (format!("{}.", &line[..digits]), text))
+}
+
+fn is_rule(line: &str) -> bool {
+    let compact: String = line.chars().filter(|c| !c.is_whitespace()).collect();
+    compact.len() >= 3 && ['-', '*', '_'].iter().any(|c| compact.chars().all(|x| x == *c))
+}
+
+/// "## Title" -> (2, "Title"); "#hashtag" is not a heading
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    let text = &line[level..];
+    ((1..=6).contains(&level) && (text.is_empty() || text.starts_with(' '))).then(|| (level, text.trim()))
+}
+
+fn table_cells(line: &str) -> Vec<String> {
+    line.trim().trim_matches('|').split('|').map(|cell| plain_inline(cell.trim())).collect()
+}
+
+/// The |---|:---:| line under a table header
+fn is_table_separator(cells: &[String]) -> bool {
+    cells.iter().all(|c| !c.is_empty() && c.chars().all(|x| matches!(x, '-' | ':' | ' ')))
+}
+
+struct Renderer {
+    writer: PdfWriter,
+    paragraph: Vec<String>,
+    table: Vec<Vec<String>>,
+}
+
+impl Renderer {
+    fn flush(&mut self) {
+        if !self.paragraph.is_empty() {
+            let text = plain_inline(&self.paragraph.join(" "));
+            self.paragraph.clear();
+            self.writer.paragraph(&text, BODY_SIZE, false);
+            self.writer.move_down(BODY_SIZE * 0.5);
+        }
+        if !self.table.is_empty() {
+            let rows = std::mem::take(&mut self.table);
+            let headers: Vec<&str> = rows[0].iter().map(String::as_str).collect();
+            let widths: Vec<f32> = (0..headers.len())
+                .map(|c| rows.iter().map(|r| r.get(c).map_or(0, |v| v.chars().count())).max().unwrap_or(0).clamp(4, 30) as f32)
+                .collect();
+            self.writer.table(&headers, &rows[1..], &widths, false);
+            self.writer.move_down(BODY_SIZE * 0.5);
+        }
+    }
+
+    /// Monospaced look is not available, so code keeps its line breaks on a grey band
+    fn code(&mut self, lines: &[String]) {
+        let line_height = CODE_SIZE * 1.4;
+        let (left, width) = (self.writer.left(), self.writer.content_width());
+        for line in lines {
+            self.writer.ensure_space(line_height);
+            let y = self.writer.cursor();
+            self.writer.fill_rect(left, y - line_height, width, line_height, (0.94, 0.94, 0.94));
+            let mut text = line.replace('\t', "    ");
+            while text.len() > 1 && text_width(&text, CODE_SIZE) > width - 12.0 {
+                text.pop();
+            }
+            self.writer.text_at(left + 6.0, y - CODE_SIZE - 1.0, CODE_SIZE, false, &text);
+            self.writer.move_down(line_height);
+        }
+        self.writer.move_down(BODY_SIZE * 0.5);
+    }
+}
+
+/// Lay out Markdown with the built-in PDF writer
+fn render_bundled(markdown: &str, output_path: &str, title: Option<&str>) -> Result<(), String> {
+    let mut renderer = Renderer { writer: PdfWriter::new(A4_PORTRAIT), paragraph: Vec::new(), table: Vec::new() };
+    if let Some(title) = title {
+        renderer.writer.centered(title, 18.0, true);
+        renderer.writer.move_down(12.0);
+    }
+
+    let mut code: Option<Vec<String>> = None;
+    for line in markdown.lines() {
+        if let Some(block) = code.as_mut() {
+            if line.trim_start().starts_with("```") || line.trim_start().starts_with("~~~") {
+                renderer.code(block);
+                code = None;
+            } else {
+                block.push(line.to_string());
+            }
+            continue;
+        }
+        let trimmed = line.trim();
+        if trimmed.starts_with('|') {
+            if !renderer.paragraph.is_empty() {
+                renderer.flush();
+            }
+            let cells = table_cells(trimmed);
+            if !is_table_separator(&cells) {
+                renderer.table.push(cells);
+            }
+            continue;
+        }
+        if !renderer.table.is_empty() || trimmed.is_empty() {
+            renderer.flush();
+        }
+        if trimmed.is_empty() {
+            continue;
+        }
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            renderer.flush();
+            code = Some(Vec::new());
+        } else if let Some((level, text)) = heading(trimmed) {
+            renderer.flush();
+            let size = match level {
+                1 => 18.0,
+                2 => 15.0,
+                3 => 13.0,
+                _ => 11.5,
+            };
+            renderer.writer.move_down(size * 0.4);
+            renderer.writer.indented(0.0, "", &plain_inline(text.trim_end_matches('#').trim()), size, true);
+            renderer.writer.move_down(size * 0.2);
+        } else if is_rule(trimmed) {
+            renderer.flush();
+            renderer.writer.ensure_space(12.0);
+            let (left, y) = (renderer.writer.left(), renderer.writer.cursor() - 6.0);
+            let right = left + renderer.writer.content_width();
+            renderer.writer.hline(left, right, y, 0.5);
+            renderer.writer.move_down(12.0);
+        } else if let Some((marker, text)) = list_item(trimmed) {
+            renderer.flush();
+            let nesting = (line.len() - line.trim_start().len()) / 2;
+            renderer.writer.indented(16.0 + 16.0 * nesting as f32, &marker, &plain_inline(text), BODY_SIZE, false);
+        } else if let Some(quote) = trimmed.strip_prefix('>') {
+            renderer.flush();
+            renderer.writer.indented(20.0, "|", &plain_inline(quote.trim()), BODY_SIZE, false);
+        } else {
+            renderer.paragraph.push(trimmed.to_string());
+        }
+    }
+    if let Some(block) = code {
+        renderer.code(&block);
+    }
+    renderer.flush();
+    renderer.writer.save(output_path)
+}
+
+/// Pandoc -> HTML next to the input (so relative images still resolve) -> wkhtmltopdf
+async fn via_html(input_path: &str, output_path: &str, style: &PandocOptions) -> Result<(), String> {
+    let input = Path::new(input_path);
+    let stem = input.file_stem().and_then(|s| s.to_str()).unwrap_or("document");
+    let html = input.with_file_name(format!(".{}.pdf-render.html", stem)).to_string_lossy().to_string();
+
+    let result = async {
+        convert_with_pandoc(input_path.to_string(), html.clone(), None, Some("html".to_string()), style.clone()).await?;
+        html_to_pdf(html.clone(), output_path.to_string()).await
+    }
+    .await;
+    let _ = fs::remove_file(&html);
+    result.map(|_| ())
+}
+
+/// Pandoc -> DOCX (with the reference document) -> LibreOffice PDF
+async fn via_office(input_path: &str, output_path: &str, style: &PandocOptions) -> Result<(), String> {
+    let work = WorkDir::create("markdown")?;
+    let docx = work.path().join("document.docx").to_string_lossy().to_string();
+    convert_with_pandoc(input_path.to_string(), docx.clone(), None, None, style.clone()).await?;
+    let pdf = convert_with_libreoffice(docx, "pdf".to_string(), work.path().to_string_lossy().to_string()).await?;
+    fs::copy(&pdf.output_path, output_path).map_err(|e| format!("Failed to write PDF: {}", e))?;
+    Ok(())
+}
+
+/// Convert a Markdown file to PDF with the best pipeline the installed tools allow
+pub async fn markdown_to_pdf(input_path: String, output_path: String, style: PandocOptions) -> Result<ConversionResult, String> {
+    if !Path::new(&input_path).is_file() {
+        return Err(format!("File not found: {}", input_path));
+    }
+    info!("📄 Converting Markdown to PDF: {}", input_path);
+
+    let tools = tokio::task::spawn_blocking(check_tools).await
+        .map_err(|e| format!("Task error: {}", e))?;
+    let available = |name: &str| tools.iter().any(|t| t.name == name && t.available);
+
+    let mut failures = Vec::new();
+    let mut method = None;
+    if available("Pandoc") && available("wkhtmltopdf") {
+        match via_html(&input_path, &output_path, &style).await {
+            Ok(()) => method = Some("Pandoc + wkhtmltopdf"),
+            Err(e) => failures.push(format!("Pandoc + wkhtmltopdf: {}", e)),
+        }
+    }
+    if method.is_none() && available("Pandoc") && available("LibreOffice") {
+        match via_office(&input_path, &output_path, &style).await {
+            Ok(()) => method = Some("Pandoc + LibreOffice"),
+            Err(e) => failures.push(format!("Pandoc + LibreOffice: {}", e)),
+        }
+    }
+    for failure in &failures {
+        warn!("Markdown to PDF fallback after {}", failure);
+    }
+    if method.is_none() {
+        let markdown = fs::read_to_string(&input_path)
+            .map_err(|e| format!("Failed to read Markdown: {}", e))?;
+        let title = style.metadata.as_ref().and_then(|m| m.get("title")).and_then(|v| v.first()).cloned();
+        let output = output_path.clone();
+        tokio::task::spawn_blocking(move || render_bundled(&markdown, &output, title.as_deref())).await
+            .map_err(|e| format!("Task error: {}", e))??;
+        method = Some("built-in renderer");
+    }
+
+    let output_size = fs::metadata(&output_path).map(|m| m.len()).ok();
+    let method = method.unwrap_or_default();
+    info!("✅ Markdown converted with {}: {}", method, output_path);
+    Ok(ConversionResult {
+        success: true,
+        output_path,
+        message: if failures.is_empty() {
+            format!("PDF created with {}", method)
+        } else {
+            format!("PDF created with {} ({} failed)", method, failures.join("; "))
+        },
+        output_size,
+    })
+}
@@ -0,0 +1,94 @@
+//! Markdown Table - CSV or a workbook sheet as a GitHub-flavored Markdown table
+//! Number columns are right-aligned, everything else left-aligned; columns are padded so the
+//! table also reads well as plain text (documentation, LMS posts).
+
+use std::fs;
+use log::info;
+use crate::bundled_converter::ConversionResult;
+use crate::excel_reader::read_table;
+use crate::sheet_writer::{detect, CellValue};
+
+/// Cell text safe inside a table row: pipes escaped, line breaks as <br>
+fn markdown_cell(value: &str) -> String {
+    value.trim()
+        .replace('|', "\\|")
+        .replace("\r\n", "<br>")
+        .replace(['\n', '\r'], "<br>")
+}
+
+/// A column is numeric when every non-empty cell is a number
+fn is_numeric(rows: &[Vec<String>], column: usize) -> bool {
+    let mut cells = rows.iter()
+        .filter_map(|row| row.get(column))
+        .map(|value| detect(value))
+        .filter(|value| *value != CellValue::Empty)
+        .peekable();
+    cells.peek().is_some() && cells.all(|v| matches!(v, CellValue::Number(_)))
+}
+
+/// Render headers and rows as a Markdown table
+pub fn render_markdown(headers: &[String], rows: &[Vec<String>]) -> String {
+    let columns = headers.len().max(rows.iter().map(Vec::len).max().unwrap_or(0));
+    let header: Vec<String> = (0..columns)
+        .map(|c| markdown_cell(headers.get(c).map(String::as_str).unwrap_or("")))
+        .collect();
+    let body: Vec<Vec<String>> = rows.iter()
+        .map(|row| (0..columns).map(|c| markdown_cell(row.get(c).map(String::as_str).unwrap_or(""))).collect())
+        .collect();
+    let numeric: Vec<bool> = (0..columns).map(|c| is_numeric(rows, c)).collect();
+    let widths: Vec<usize> = (0..columns)
+        .map(|c| body.iter().map(|row| row[c].chars().count()).chain([header[c].chars().count(), 3]).max().unwrap_or(3))
+        .collect();
+
+    let line = |cells: &[String]| -> String {
+        let padded: Vec<String> = cells.iter().enumerate()
+            .map(|(c, cell)| if numeric[c] {
+                format!("{:>width$}", cell, width = widths[c])
+            } else {
+                format!("{:<width$}", cell, width = widths[c])
+            })
+            .collect();
+        format!("| {} |\n", padded.join(" | "))
+    };
+
+    let mut markdown = line(&header);
+    let separator: Vec<String> = (0..columns)
+        .map(|c| if numeric[c] {
+            format!("{}:", "-".repeat(widths[c] - 1))
+        } else {
+            format!(":{}", "-".repeat(widths[c] - 1))
+        })
+        .collect();
+    markdown.push_str(&format!("| {} |\n", separator.join(" | ")));
+    for row in &body {
+        markdown.push_str(&line(row));
+    }
+    markdown
+}
+
+/// Convert a CSV file or workbook sheet (first by default) to a Markdown table
+pub fn table_to_markdown(
+    input_path: String,
+    output_path: String,
+    sheet_index: Option<usize>,
+) -> Result<ConversionResult, String> {
+    info!("📊 Converting table to Markdown (bundled)");
+
+    let (headers, rows) = read_table(&input_path, sheet_index)?;
+    if headers.is_empty() {
+        return Err("No data found".to_string());
+    }
+
+    fs::write(&output_path, render_markdown(&headers, &rows))
+        .map_err(|e| format!("Failed to write Markdown: {}", e))?;
+
+    let output_size = fs::metadata(&output_path).map(|m| m.len()).ok();
+
+    info!("✅ Table converted to Markdown: {}", output_path);
+    Ok(ConversionResult {
+        success: true,
+        output_path,
+        message: format!("Converted {} rows to a Markdown table", rows.len()),
+        output_size,
+    })
+}
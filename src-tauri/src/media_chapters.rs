@@ -0,0 +1,71 @@
+//! Media Chapters - split a recording into one file per chapter mark (stream copy, no re-encode)
+
+use std::path::Path;
+use log::info;
+use crate::ffmpeg_runner::{ffmpeg_command, run_ffmpeg, MediaJob};
+use crate::media_converter::get_media_info;
+
+/// Characters that aren't allowed in file names on Windows/macOS
+fn safe_file_name(title: &str) -> String {
+    let cleaned: String = title.chars()
+        .map(|c| if matches!(c, '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*') || c.is_control() { '_' } else { c })
+        .collect();
+    cleaned.trim().trim_end_matches('.').chars().take(80).collect()
+}
+
+/// Write each chapter to output_dir as "NN - <title>.<ext>" (same container as the input).
+/// Cuts are stream copies, so they snap to the keyframe at or before each chapter start.
+pub async fn split_by_chapters(
+    input_path: String,
+    output_dir: String,
+    job: MediaJob,
+) -> Result<Vec<String>, String> {
+    let info = get_media_info(&input_path).await?;
+    if info.chapters.is_empty() {
+        return Err(format!("{} has no chapters", info.file_name));
+    }
+    std::fs::create_dir_all(&output_dir)
+        .map_err(|e| format!("Failed to create output folder: {}", e))?;
+
+    let extension = Path::new(&input_path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("mkv")
+        .to_string();
+
+    info!("📑 Splitting {} into {} chapters -> {}", info.file_name, info.chapters.len(), output_dir);
+
+    let mut outputs = Vec::new();
+    for chapter in &info.chapters {
+        let title = chapter.title.as_deref()
+            .map(safe_file_name)
+            .filter(|t| !t.is_empty())
+            .unwrap_or_else(|| format!("Chapter {}", chapter.index + 1));
+        let output_path = Path::new(&output_dir)
+            .join(format!("{:02} - {}.{}", chapter.index + 1, title, extension))
+            .to_string_lossy()
+            .to_string();
+
+        let mut chapter_job = job.clone().with_output(&output_path);
+        chapter_job.total_secs = Some(chapter.end_secs - chapter.start_secs);
+
+        let mut cmd = ffmpeg_command();
+        cmd.arg("-y");
+        cmd.arg("-ss").arg(format!("{:.3}", chapter.start_secs));
+        cmd.arg("-to").arg(format!("{:.3}", chapter.end_secs));
+        cmd.arg("-i").arg(&input_path);
+        cmd.arg("-map").arg("0");
+        cmd.arg("-map_chapters").arg("-1"); // The piece is a single chapter
+        cmd.arg("-metadata").arg(format!("title={}", title));
+        cmd.arg("-c").arg("copy");
+        cmd.arg("-avoid_negative_ts").arg("make_zero");
+        cmd.arg(&output_path);
+
+        run_ffmpeg(cmd, &chapter_job).await
+            .map_err(|error| format!("Splitting chapter {} failed: {}", chapter.index + 1, error))?;
+        outputs.push(output_path);
+    }
+
+    info!("✅ Split into {} files", outputs.len());
+    Ok(outputs)
+}
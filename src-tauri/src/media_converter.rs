@@ -1,8 +1,15 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::Path;
 use std::process::Command;
 use tokio::process::Command as TokioCommand;
 use log::info;
+use crate::ffmpeg_encoders::{encoder_available, preferred_h264_encoder};
+use crate::ffmpeg_runner::{ffmpeg_command, run_ffmpeg, MediaJob};
+use crate::video_gif::{video_to_gif, GifOptions};
+use crate::video_filters::{denoise_filter, sharpen_filter};
+use crate::conversion_presets::{apply_image_preset, apply_video_preset};
+use crate::ffmpeg_manager::{ffmpeg_path, ffprobe_path};
 
 // ============================================================================
 // Common Types
@@ -19,6 +26,49 @@ pub struct MediaInfo {
     pub height: Option<u32>,
     pub bitrate: Option<u64>,
     pub codec: Option<String>,
+    #[serde(default)]
+    pub tracks: Vec<MediaTrack>,     // Every stream (video, audio, subtitle, ...)
+    #[serde(default)]
+    pub tags: BTreeMap<String, String>, // Container metadata (title, artist, creation_time, ...)
+    #[serde(default)]
+    pub chapters: Vec<MediaChapter>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MediaChapter {
+    pub index: u32,
+    pub start_secs: f64,
+    pub end_secs: f64,
+    pub title: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MediaTrack {
+    pub index: u32,                  // Absolute stream index in the file
+    pub type_index: u32,             // Index among streams of the same type (for -map 0:s:N)
+    pub codec_type: String,          // video, audio, subtitle, data, attachment
+    pub codec: Option<String>,
+    pub language: Option<String>,    // ISO 639 tag, e.g. "eng"
+    pub title: Option<String>,
+    pub is_default: bool,
+    pub channels: Option<u32>,       // Audio only
+    pub channel_layout: Option<String>, // Audio only, e.g. "stereo", "5.1"
+    #[serde(default)]
+    pub bitrate: Option<u64>,        // bits/s, when the container reports it per stream
+    #[serde(default)]
+    pub sample_rate: Option<u32>,    // Audio only, Hz
+    #[serde(default)]
+    pub width: Option<u32>,          // Video only
+    #[serde(default)]
+    pub height: Option<u32>,
+    #[serde(default)]
+    pub frame_rate: Option<f64>,     // Video only, from avg_frame_rate (e.g. 29.97)
+    #[serde(default)]
+    pub pixel_format: Option<String>, // Video only, e.g. "yuv420p"
+    #[serde(default)]
+    pub color_space: Option<String>, // Video only, e.g. "bt709"
+    #[serde(default)]
+    pub tags: BTreeMap<String, String>, // Stream metadata (language, title, handler_name, ...)
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -34,10 +84,10 @@ pub struct ConversionResult {
 // ============================================================================
 
 pub fn check_ffmpeg() -> Result<String, String> {
-    let output = Command::new("ffmpeg")
+    let output = Command::new(ffmpeg_path())
         .arg("-version")
         .output()
-        .map_err(|e| format!("FFmpeg not found: {}. Please install FFmpeg.", e))?;
+        .map_err(|e| format!("FFmpeg not found: {}. Install FFmpeg or download the managed build.", e))?;
 
     if output.status.success() {
         let version = String::from_utf8_lossy(&output.stdout);
@@ -56,84 +106,190 @@ pub fn check_ffmpeg() -> Result<String, String> {
 pub struct VideoConvertOptions {
     pub input_path: String,
     pub output_path: String,
-    pub format: String,        // mp4, webm, avi, mov, mkv
+    pub format: String,        // mp4, webm, avi, mov, mkv, gif, or hevc/av1 (container from output extension)
     pub quality: String,       // high, medium, low
     pub resolution: Option<String>,  // 1080p, 720p, 480p, or custom
     pub fps: Option<u32>,
+    #[serde(default)]
+    pub use_hardware: bool,    // Use a GPU H.264 encoder when one is available
+    #[serde(default)]
+    pub audio_track_index: Option<u32>,  // Audio track to keep (among audio tracks); default picks one
+    #[serde(default)]
+    pub denoise: Option<String>,     // low, medium, high
+    #[serde(default)]
+    pub sharpen: Option<String>,     // low, medium, high
+    #[serde(default)]
+    pub crf: Option<u32>,            // Exact CRF, overrides quality (software encoders)
+    #[serde(default)]
+    pub audio_codec: Option<String>, // Overrides the format's default audio codec
+    #[serde(default)]
+    pub audio_bitrate: Option<String>, // e.g. "160k"
+    #[serde(default)]
+    pub preset: Option<String>,      // Saved conversion preset to apply
+}
+
+fn is_mp4_family(output_path: &str) -> bool {
+    Path::new(output_path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| matches!(e.to_lowercase().as_str(), "mp4" | "m4v" | "mov"))
 }
 
-pub async fn convert_video(options: VideoConvertOptions) -> Result<ConversionResult, String> {
+/// CRF for high/medium/low - each codec's scale sits differently (x265 ~ x264 + 4, AV1 higher still)
+fn crf_for(encoder: &str, quality: &str) -> &'static str {
+    let level = match quality.to_lowercase().as_str() {
+        "high" => 0,
+        "low" => 2,
+        _ => 1,
+    };
+    let scale = match encoder {
+        "libx265" => ["22", "27", "32"],
+        "libsvtav1" => ["25", "32", "40"],
+        _ => ["18", "23", "28"],
+    };
+    scale[level]
+}
+
+pub async fn convert_video(mut options: VideoConvertOptions, job: MediaJob) -> Result<ConversionResult, String> {
     if !Path::new(&options.input_path).exists() {
         return Err(format!("Input file not found: {}", options.input_path));
     }
+    apply_video_preset(&mut options)?;
+
+    let format = options.format.to_lowercase();
+    if format == "gif" {
+        let mut gif = GifOptions::for_paths(options.input_path, options.output_path);
+        if let Some(fps) = options.fps {
+            gif.fps = fps;
+        }
+        return video_to_gif(gif, job).await;
+    }
 
     info!("🎬 Converting video: {} -> {}", options.input_path, options.output_path);
 
-    let mut cmd = TokioCommand::new("ffmpeg");
+    let video_encoder = match format.as_str() {
+        "hevc" => "libx265",
+        "av1" => "libsvtav1",
+        "webm" => "libvpx-vp9",
+        _ => "libx264",
+    };
+    let available = video_encoder == "libx264"
+        || tokio::task::spawn_blocking(move || encoder_available(video_encoder)).await
+            .map_err(|e| format!("Task error: {}", e))?;
+    if !available {
+        return Err(format!("The installed FFmpeg has no {} encoder", video_encoder));
+    }
+
+    // Hardware H.264 encoder, only for the formats that would otherwise use libx264
+    let hardware = if options.use_hardware && video_encoder == "libx264" {
+        // The first call runs test encodes
+        let encoder = tokio::task::spawn_blocking(preferred_h264_encoder).await
+            .map_err(|e| format!("Task error: {}", e))?;
+        if encoder.is_none() {
+            info!("No usable hardware encoder, falling back to libx264");
+        }
+        encoder
+    } else {
+        None
+    };
+    let h264 = hardware.as_ref().map(|e| e.name.clone()).unwrap_or_else(|| video_encoder.to_string());
+
+    let job = job.with_duration_of(&options.input_path).await.with_output(&options.output_path);
+    let mut cmd = ffmpeg_command();
+    if let Some(encoder) = &hardware {
+        cmd.args(encoder.input_args());
+    }
     cmd.arg("-i").arg(&options.input_path);
     cmd.arg("-y"); // Overwrite
+    // Keep container tags and chapter marks
+    cmd.arg("-map_metadata").arg("0");
+    cmd.arg("-map_chapters").arg("0");
+    if let Some(track) = options.audio_track_index {
+        cmd.arg("-map").arg("0:v:0");
+        cmd.arg("-map").arg(format!("0:a:{}", track));
+    }
+
+    let mut filters: Vec<String> = Vec::new();
+    if let Some(level) = &options.denoise {
+        filters.push(denoise_filter(level)?.to_string());
+    }
+
+    let audio_codec = |default: &str| options.audio_codec.clone().unwrap_or_else(|| default.to_string());
 
     // Video codec based on format
-    match options.format.to_lowercase().as_str() {
+    match format.as_str() {
         "mp4" => {
-            cmd.arg("-c:v").arg("libx264");
-            cmd.arg("-c:a").arg("aac");
+            cmd.arg("-c:v").arg(&h264);
+            cmd.arg("-c:a").arg(audio_codec("aac"));
             cmd.arg("-movflags").arg("+faststart");
         }
         "webm" => {
             cmd.arg("-c:v").arg("libvpx-vp9");
-            cmd.arg("-c:a").arg("libopus");
+            cmd.arg("-c:a").arg(audio_codec("libopus"));
         }
         "avi" => {
-            cmd.arg("-c:v").arg("libx264");
-            cmd.arg("-c:a").arg("mp3");
-        }
-        "mov" => {
-            cmd.arg("-c:v").arg("libx264");
-            cmd.arg("-c:a").arg("aac");
+            cmd.arg("-c:v").arg(&h264);
+            cmd.arg("-c:a").arg(audio_codec("mp3"));
         }
-        "mkv" => {
-            cmd.arg("-c:v").arg("libx264");
-            cmd.arg("-c:a").arg("aac");
-        }
-        "gif" => {
-            cmd.arg("-vf").arg("fps=10,scale=480:-1:flags=lanczos");
+        "hevc" | "av1" => {
+            // Codec-named formats: the container comes from the output extension
+            cmd.arg("-c:v").arg(video_encoder);
+            cmd.arg("-c:a").arg(audio_codec("aac"));
+            if is_mp4_family(&options.output_path) {
+                if format == "hevc" {
+                    cmd.arg("-tag:v").arg("hvc1"); // Needed for QuickTime/Safari playback
+                }
+                cmd.arg("-movflags").arg("+faststart");
+            }
+            if format == "av1" {
+                cmd.arg("-preset").arg("8"); // SVT-AV1: 0 slowest .. 13 fastest
+            }
         }
         _ => {
-            cmd.arg("-c:v").arg("libx264");
-            cmd.arg("-c:a").arg("aac");
+            // mov, mkv and anything else
+            cmd.arg("-c:v").arg(&h264);
+            cmd.arg("-c:a").arg(audio_codec("aac"));
         }
     }
 
-    // Quality (CRF for x264)
-    if options.format != "gif" {
-        let crf = match options.quality.to_lowercase().as_str() {
-            "high" => "18",
-            "medium" => "23",
-            "low" => "28",
-            _ => "23",
-        };
-        cmd.arg("-crf").arg(crf);
+    // Quality (CRF scaled per codec, the encoder's own constant-quality option for hardware)
+    if let Some(encoder) = &hardware {
+        cmd.args(encoder.quality_args(&options.quality));
+    } else if let Some(crf) = options.crf {
+        cmd.arg("-crf").arg(crf.to_string());
+    } else {
+        cmd.arg("-crf").arg(crf_for(video_encoder, &options.quality));
+    }
+    if let Some(bitrate) = &options.audio_bitrate {
+        cmd.arg("-b:a").arg(bitrate);
     }
 
     // Resolution
     if let Some(res) = &options.resolution {
         let scale = match res.as_str() {
-            "1080p" => "scale=1920:1080",
-            "720p" => "scale=1280:720",
-            "480p" => "scale=854:480",
-            "360p" => "scale=640:360",
+            "1080p" => "scale=1920:1080".to_string(),
+            "720p" => "scale=1280:720".to_string(),
+            "480p" => "scale=854:480".to_string(),
+            "360p" => "scale=640:360".to_string(),
             _ => {
                 if res.contains('x') {
-                    &format!("scale={}", res.replace('x', ":"))
+                    format!("scale={}", res.replace('x', ":"))
                 } else {
-                    "scale=-1:-1"
+                    "scale=-1:-1".to_string()
                 }
             }
         };
-        if options.format != "gif" {
-            cmd.arg("-vf").arg(scale);
-        }
+        filters.push(scale);
+    }
+    if let Some(level) = &options.sharpen {
+        filters.push(sharpen_filter(level)?.to_string());
+    }
+
+    if let Some(suffix) = hardware.as_ref().and_then(|e| e.filter_suffix()) {
+        filters.push(suffix.to_string());
+    }
+    if !filters.is_empty() {
+        cmd.arg("-vf").arg(filters.join(","));
     }
 
     // Frame rate
@@ -143,31 +299,27 @@ pub async fn convert_video(options: VideoConvertOptions) -> Result<ConversionRes
 
     cmd.arg(&options.output_path);
 
-    let output = cmd.output().await
-        .map_err(|e| format!("FFmpeg execution failed: {}", e))?;
+    run_ffmpeg(cmd, &job).await
+        .map_err(|error| format!("Conversion failed: {}", error))?;
 
-    if output.status.success() {
-        let output_size = std::fs::metadata(&options.output_path)
-            .map(|m| m.len())
-            .ok();
-        
-        info!("✅ Video converted: {}", options.output_path);
-        Ok(ConversionResult {
-            success: true,
-            output_path: options.output_path,
-            message: "Video converted successfully".to_string(),
-            output_size,
-        })
-    } else {
-        let error = String::from_utf8_lossy(&output.stderr);
-        Err(format!("Conversion failed: {}", error))
-    }
+    let output_size = std::fs::metadata(&options.output_path)
+        .map(|m| m.len())
+        .ok();
+    
+    info!("✅ Video converted: {}", options.output_path);
+    Ok(ConversionResult {
+        success: true,
+        output_path: options.output_path,
+        message: "Video converted successfully".to_string(),
+        output_size,
+    })
 }
 
 pub async fn compress_video(
     input_path: String,
     output_path: String,
     target_bitrate: Option<String>,
+    job: MediaJob,
 ) -> Result<ConversionResult, String> {
     if !Path::new(&input_path).exists() {
         return Err(format!("Input file not found: {}", input_path));
@@ -175,7 +327,8 @@ pub async fn compress_video(
 
     info!("📦 Compressing video: {}", input_path);
 
-    let mut cmd = TokioCommand::new("ffmpeg");
+    let job = job.with_duration_of(&input_path).await.with_output(&output_path);
+    let mut cmd = ffmpeg_command();
     cmd.arg("-i").arg(&input_path);
     cmd.arg("-y");
     cmd.arg("-c:v").arg("libx264");
@@ -190,28 +343,25 @@ pub async fn compress_video(
     cmd.arg("-preset").arg("medium");
     cmd.arg(&output_path);
 
-    let output = cmd.output().await
-        .map_err(|e| format!("FFmpeg execution failed: {}", e))?;
+    run_ffmpeg(cmd, &job).await
+        .map_err(|error| format!("Compression failed: {}", error))?;
 
-    if output.status.success() {
-        let output_size = std::fs::metadata(&output_path).map(|m| m.len()).ok();
-        info!("✅ Video compressed: {}", output_path);
-        Ok(ConversionResult {
-            success: true,
-            output_path,
-            message: "Video compressed successfully".to_string(),
-            output_size,
-        })
-    } else {
-        let error = String::from_utf8_lossy(&output.stderr);
-        Err(format!("Compression failed: {}", error))
-    }
+    let output_size = std::fs::metadata(&output_path).map(|m| m.len()).ok();
+    info!("✅ Video compressed: {}", output_path);
+    Ok(ConversionResult {
+        success: true,
+        output_path,
+        message: "Video compressed successfully".to_string(),
+        output_size,
+    })
 }
 
 pub async fn extract_audio(
     input_path: String,
     output_path: String,
     format: String,
+    audio_track_index: Option<u32>,
+    job: MediaJob,
 ) -> Result<ConversionResult, String> {
     if !Path::new(&input_path).exists() {
         return Err(format!("Input file not found: {}", input_path));
@@ -219,9 +369,13 @@ pub async fn extract_audio(
 
     info!("🎵 Extracting audio: {} -> {}", input_path, output_path);
 
-    let mut cmd = TokioCommand::new("ffmpeg");
+    let job = job.with_duration_of(&input_path).await.with_output(&output_path);
+    let mut cmd = ffmpeg_command();
     cmd.arg("-i").arg(&input_path);
     cmd.arg("-vn"); // No video
+    if let Some(track) = audio_track_index {
+        cmd.arg("-map").arg(format!("0:a:{}", track));
+    }
     cmd.arg("-y");
 
     match format.to_lowercase().as_str() {
@@ -249,22 +403,17 @@ pub async fn extract_audio(
 
     cmd.arg(&output_path);
 
-    let output = cmd.output().await
-        .map_err(|e| format!("FFmpeg execution failed: {}", e))?;
+    run_ffmpeg(cmd, &job).await
+        .map_err(|error| format!("Audio extraction failed: {}", error))?;
 
-    if output.status.success() {
-        let output_size = std::fs::metadata(&output_path).map(|m| m.len()).ok();
-        info!("✅ Audio extracted: {}", output_path);
-        Ok(ConversionResult {
-            success: true,
-            output_path,
-            message: "Audio extracted successfully".to_string(),
-            output_size,
-        })
-    } else {
-        let error = String::from_utf8_lossy(&output.stderr);
-        Err(format!("Audio extraction failed: {}", error))
-    }
+    let output_size = std::fs::metadata(&output_path).map(|m| m.len()).ok();
+    info!("✅ Audio extracted: {}", output_path);
+    Ok(ConversionResult {
+        success: true,
+        output_path,
+        message: "Audio extracted successfully".to_string(),
+        output_size,
+    })
 }
 
 // ============================================================================
@@ -280,16 +429,19 @@ pub struct ImageConvertOptions {
     pub width: Option<u32>,
     pub height: Option<u32>,
     pub maintain_aspect: bool,
+    #[serde(default)]
+    pub preset: Option<String>,      // Saved conversion preset to apply
 }
 
-pub async fn convert_image(options: ImageConvertOptions) -> Result<ConversionResult, String> {
+pub async fn convert_image(mut options: ImageConvertOptions) -> Result<ConversionResult, String> {
     if !Path::new(&options.input_path).exists() {
         return Err(format!("Input file not found: {}", options.input_path));
     }
+    apply_image_preset(&mut options)?;
 
     info!("🖼️ Converting image: {} -> {}", options.input_path, options.output_path);
 
-    let mut cmd = TokioCommand::new("ffmpeg");
+    let mut cmd = TokioCommand::new(ffmpeg_path());
     cmd.arg("-i").arg(&options.input_path);
     cmd.arg("-y");
 
@@ -373,6 +525,7 @@ pub async fn compress_image(
         width: None,
         height: None,
         maintain_aspect: true,
+        preset: None,
     };
 
     convert_image(options).await
@@ -399,6 +552,7 @@ pub async fn resize_image(
         width: Some(width),
         height: Some(height),
         maintain_aspect,
+        preset: None,
     };
 
     convert_image(options).await
@@ -408,16 +562,33 @@ pub async fn resize_image(
 // Media Info
 // ============================================================================
 
+/// ffprobe rates are fractions like "30000/1001"; "0/0" means unknown
+fn parse_rate(value: &str) -> Option<f64> {
+    let (num, den) = value.split_once('/').unwrap_or((value, "1"));
+    let (num, den): (f64, f64) = (num.parse().ok()?, den.parse().ok()?);
+    if num > 0.0 && den > 0.0 { Some(num / den) } else { None }
+}
+
+/// ffprobe "tags" object as string pairs
+fn string_tags(tags: &serde_json::Value) -> BTreeMap<String, String> {
+    tags.as_object()
+        .map(|map| map.iter()
+            .filter_map(|(key, value)| value.as_str().map(|v| (key.clone(), v.to_string())))
+            .collect())
+        .unwrap_or_default()
+}
+
 pub async fn get_media_info(file_path: &str) -> Result<MediaInfo, String> {
     if !Path::new(file_path).exists() {
         return Err(format!("File not found: {}", file_path));
     }
 
-    let output = Command::new("ffprobe")
+    let output = Command::new(ffprobe_path())
         .arg("-v").arg("quiet")
         .arg("-print_format").arg("json")
         .arg("-show_format")
         .arg("-show_streams")
+        .arg("-show_chapters")
         .arg(file_path)
         .output()
         .map_err(|e| format!("ffprobe failed: {}", e))?;
@@ -469,6 +640,42 @@ pub async fn get_media_info(file_path: &str) -> Result<MediaInfo, String> {
         .as_str()
         .and_then(|b| b.parse::<u64>().ok());
 
+    let mut type_counts: std::collections::HashMap<String, u32> = std::collections::HashMap::new();
+    let tracks = streams.map(|s| s.iter().map(|stream| {
+        let codec_type = stream["codec_type"].as_str().unwrap_or("unknown").to_string();
+        let count = type_counts.entry(codec_type.clone()).or_insert(0);
+        let type_index = *count;
+        *count += 1;
+        MediaTrack {
+            index: stream["index"].as_u64().unwrap_or(0) as u32,
+            type_index,
+            codec_type,
+            codec: stream["codec_name"].as_str().map(|s| s.to_string()),
+            language: stream["tags"]["language"].as_str().map(|s| s.to_string()),
+            title: stream["tags"]["title"].as_str().map(|s| s.to_string()),
+            is_default: stream["disposition"]["default"].as_u64() == Some(1),
+            channels: stream["channels"].as_u64().map(|c| c as u32),
+            channel_layout: stream["channel_layout"].as_str().map(|s| s.to_string()),
+            bitrate: stream["bit_rate"].as_str().and_then(|b| b.parse().ok()),
+            sample_rate: stream["sample_rate"].as_str().and_then(|r| r.parse().ok()),
+            width: stream["width"].as_u64().map(|w| w as u32),
+            height: stream["height"].as_u64().map(|h| h as u32),
+            frame_rate: stream["avg_frame_rate"].as_str().and_then(parse_rate),
+            pixel_format: stream["pix_fmt"].as_str().map(|s| s.to_string()),
+            color_space: stream["color_space"].as_str().map(|s| s.to_string()),
+            tags: string_tags(&stream["tags"]),
+        }
+    }).collect()).unwrap_or_default();
+
+    let chapters = json["chapters"].as_array().map(|list| list.iter().enumerate().map(|(i, chapter)| {
+        MediaChapter {
+            index: i as u32,
+            start_secs: chapter["start_time"].as_str().and_then(|t| t.parse().ok()).unwrap_or(0.0),
+            end_secs: chapter["end_time"].as_str().and_then(|t| t.parse().ok()).unwrap_or(0.0),
+            title: chapter["tags"]["title"].as_str().map(|s| s.to_string()),
+        }
+    }).collect()).unwrap_or_default();
+
     Ok(MediaInfo {
         file_path: file_path.to_string(),
         file_name,
@@ -479,5 +686,8 @@ pub async fn get_media_info(file_path: &str) -> Result<MediaInfo, String> {
         height,
         bitrate,
         codec,
+        tracks,
+        tags: string_tags(&json["format"]["tags"]),
+        chapters,
     })
 }
@@ -0,0 +1,91 @@
+//! Media Metadata - write title/artist/album/comment tags and embed cover art (stream copy)
+
+use std::collections::HashMap;
+use std::path::Path;
+use log::info;
+use crate::ffmpeg_runner::{ffmpeg_command, run_ffmpeg, MediaJob};
+use crate::media_converter::{get_media_info, ConversionResult};
+
+/// Set tags (an empty value removes that tag) and optionally embed `cover_art` (jpg/png).
+/// Other existing tags are kept. `output_path` may equal `input_path` to edit in place.
+pub async fn set_metadata(
+    input_path: String,
+    output_path: String,
+    tags: HashMap<String, String>,
+    cover_art: Option<String>,
+    job: MediaJob,
+) -> Result<ConversionResult, String> {
+    let info = get_media_info(&input_path).await?;
+    if let Some(cover) = &cover_art {
+        if !Path::new(cover).exists() {
+            return Err(format!("Cover image not found: {}", cover));
+        }
+    }
+
+    // ffmpeg can't write over its input, so in-place edits go through a temp file
+    let in_place = Path::new(&input_path) == Path::new(&output_path);
+    let write_path = if in_place {
+        let extension = Path::new(&input_path).extension().and_then(|e| e.to_str()).unwrap_or("tmp");
+        format!("{}.tagging.{}", input_path, extension)
+    } else {
+        output_path.clone()
+    };
+
+    info!("🏷️ Writing {} tags{} -> {}", tags.len(),
+        if cover_art.is_some() { " + cover art" } else { "" }, output_path);
+
+    let job = job.with_output(&write_path);
+    let mut cmd = ffmpeg_command();
+    cmd.arg("-y");
+    cmd.arg("-i").arg(&input_path);
+    match &cover_art {
+        Some(cover) => {
+            cmd.arg("-i").arg(cover);
+            // Replace any existing cover: keep the input's non-picture streams, add the new image
+            let video_tracks = info.tracks.iter()
+                .filter(|t| t.codec_type == "video" && !is_cover(t.codec.as_deref()))
+                .count();
+            if video_tracks > 0 {
+                cmd.arg("-map").arg("0:v");
+            }
+            cmd.arg("-map").arg("0:a?");
+            cmd.arg("-map").arg("0:s?");
+            cmd.arg("-map").arg("1:0");
+            cmd.arg(format!("-disposition:v:{}", video_tracks)).arg("attached_pic");
+        }
+        None => {
+            cmd.arg("-map").arg("0");
+        }
+    }
+    cmd.arg("-map_metadata").arg("0");
+    for (key, value) in &tags {
+        cmd.arg("-metadata").arg(format!("{}={}", key, value));
+    }
+    cmd.arg("-c").arg("copy");
+    if write_path.to_lowercase().ends_with(".mp3") {
+        cmd.arg("-id3v2_version").arg("3"); // Most players read ID3v2.3 covers
+    }
+    cmd.arg(&write_path);
+
+    run_ffmpeg(cmd, &job).await
+        .map_err(|error| format!("Writing metadata failed: {}", error))?;
+
+    if in_place {
+        std::fs::rename(&write_path, &output_path)
+            .map_err(|e| format!("Failed to replace original file: {}", e))?;
+    }
+
+    let output_size = std::fs::metadata(&output_path).map(|m| m.len()).ok();
+    info!("✅ Metadata written: {}", output_path);
+    Ok(ConversionResult {
+        success: true,
+        output_path,
+        message: "Metadata updated successfully".to_string(),
+        output_size,
+    })
+}
+
+/// Still-image codecs that only ever appear as embedded cover art
+fn is_cover(codec: Option<&str>) -> bool {
+    matches!(codec, Some("mjpeg") | Some("png") | Some("bmp"))
+}
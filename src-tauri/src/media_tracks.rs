@@ -0,0 +1,150 @@
+//! Media Tracks - pull individual embedded tracks (subtitles) out of a container, remove or swap audio
+
+use std::path::Path;
+use log::info;
+use crate::ffmpeg_runner::{ffmpeg_command, run_ffmpeg, MediaJob};
+use crate::media_converter::{get_media_info, ConversionResult};
+
+/// Bitmap subtitle codecs can't be turned into text formats without OCR
+const IMAGE_SUBTITLE_CODECS: &[&str] = &["hdmv_pgs_subtitle", "dvd_subtitle", "dvb_subtitle", "xsub"];
+
+/// Extract one subtitle track (`track_index` counts subtitle tracks only, as listed by get_media_info).
+/// Output format follows the extension: .srt, .vtt, .ass; anything else keeps the original codec.
+pub async fn extract_subtitles(
+    input_path: String,
+    output_path: String,
+    track_index: u32,
+    job: MediaJob,
+) -> Result<ConversionResult, String> {
+    let info = get_media_info(&input_path).await?;
+    let track = info.tracks.iter()
+        .find(|t| t.codec_type == "subtitle" && t.type_index == track_index)
+        .ok_or_else(|| format!("No subtitle track {} in {}", track_index, info.file_name))?;
+
+    let extension = Path::new(&output_path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("srt")
+        .to_lowercase();
+    let codec = match extension.as_str() {
+        "srt" => "srt",
+        "vtt" => "webvtt",
+        "ass" | "ssa" => "ass",
+        _ => "copy",
+    };
+
+    let source_codec = track.codec.clone().unwrap_or_default();
+    if codec != "copy" && IMAGE_SUBTITLE_CODECS.contains(&source_codec.as_str()) {
+        return Err(format!(
+            "Subtitle track {} is image-based ({}) and can't be converted to .{} - save it as .mks/.sup instead",
+            track_index, source_codec, extension
+        ));
+    }
+
+    info!("💬 Extracting subtitle track {} ({}) -> {}", track_index,
+        track.language.as_deref().unwrap_or("und"), output_path);
+
+    let job = job.with_output(&output_path);
+    let mut cmd = ffmpeg_command();
+    cmd.arg("-y");
+    cmd.arg("-i").arg(&input_path);
+    cmd.arg("-map").arg(format!("0:s:{}", track_index));
+    cmd.arg("-c:s").arg(codec);
+    cmd.arg(&output_path);
+
+    run_ffmpeg(cmd, &job).await
+        .map_err(|error| format!("Subtitle extraction failed: {}", error))?;
+
+    let output_size = std::fs::metadata(&output_path).map(|m| m.len()).ok();
+    info!("✅ Subtitles extracted: {}", output_path);
+    Ok(ConversionResult {
+        success: true,
+        output_path,
+        message: "Subtitles extracted successfully".to_string(),
+        output_size,
+    })
+}
+
+/// Drop every audio track, copying the video untouched
+pub async fn mute_video(input_path: String, output_path: String, job: MediaJob) -> Result<ConversionResult, String> {
+    if !Path::new(&input_path).exists() {
+        return Err(format!("Input file not found: {}", input_path));
+    }
+
+    info!("🔇 Removing audio: {} -> {}", input_path, output_path);
+
+    let job = job.with_duration_of(&input_path).await.with_output(&output_path);
+    let mut cmd = ffmpeg_command();
+    cmd.arg("-y");
+    cmd.arg("-i").arg(&input_path);
+    cmd.arg("-map").arg("0");
+    cmd.arg("-map").arg("-0:a");
+    cmd.arg("-c").arg("copy");
+    cmd.arg(&output_path);
+
+    run_ffmpeg(cmd, &job).await
+        .map_err(|error| format!("Audio removal failed: {}", error))?;
+
+    let output_size = std::fs::metadata(&output_path).map(|m| m.len()).ok();
+    info!("✅ Audio removed: {}", output_path);
+    Ok(ConversionResult {
+        success: true,
+        output_path,
+        message: "Audio removed successfully".to_string(),
+        output_size,
+    })
+}
+
+/// Replace the soundtrack with `audio_path`. `offset_secs` > 0 delays the new audio, < 0 skips
+/// its beginning. With `pad_audio` a short narration is padded with silence to the full video
+/// length; otherwise the output ends with whichever stream is shorter.
+pub async fn replace_audio(
+    video_path: String,
+    audio_path: String,
+    output_path: String,
+    offset_secs: f64,
+    pad_audio: bool,
+    job: MediaJob,
+) -> Result<ConversionResult, String> {
+    for path in [&video_path, &audio_path] {
+        if !Path::new(path).exists() {
+            return Err(format!("Input file not found: {}", path));
+        }
+    }
+
+    info!("🎙️ Replacing audio: {} + {} -> {} (offset {}s)", video_path, audio_path, output_path, offset_secs);
+
+    let job = job.with_duration_of(&video_path).await.with_output(&output_path);
+    let mut cmd = ffmpeg_command();
+    cmd.arg("-y");
+    cmd.arg("-i").arg(&video_path);
+    if offset_secs > 0.0 {
+        cmd.arg("-itsoffset").arg(format!("{:.3}", offset_secs));
+    } else if offset_secs < 0.0 {
+        cmd.arg("-ss").arg(format!("{:.3}", -offset_secs));
+    }
+    cmd.arg("-i").arg(&audio_path);
+    cmd.arg("-map").arg("0:v:0");
+    cmd.arg("-map").arg("1:a:0");
+    cmd.arg("-c:v").arg("copy");
+    cmd.arg("-c:a").arg("aac");
+    cmd.arg("-b:a").arg("192k");
+    if pad_audio {
+        // Endless silence after the narration; -shortest then stops at the end of the video
+        cmd.arg("-af").arg("apad");
+    }
+    cmd.arg("-shortest");
+    cmd.arg(&output_path);
+
+    run_ffmpeg(cmd, &job).await
+        .map_err(|error| format!("Audio replacement failed: {}", error))?;
+
+    let output_size = std::fs::metadata(&output_path).map(|m| m.len()).ok();
+    info!("✅ Audio replaced: {}", output_path);
+    Ok(ConversionResult {
+        success: true,
+        output_path,
+        message: "Audio replaced successfully".to_string(),
+        output_size,
+    })
+}
@@ -0,0 +1,222 @@
+//! Office Batch - convert a folder of office documents through LibreOffice
+//! A few soffice processes run side by side, each worker reusing its own temporary profile.
+//! "document://office_batch_file" reports each file as it starts and finishes, so the UI can
+//! show per-file status. Documents that would land on the same output name (report.doc and
+//! report.docx, or two folders with a report.docx) are converted aside and saved as
+//! "report (docx).pdf" / "report (2).pdf" instead of overwriting each other.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use log::info;
+use tauri::{AppHandle, Emitter};
+use crate::document_converter::{convert_with_libreoffice_profile, ConversionResult, LibreOfficeProfile};
+use crate::tool_process::WorkDir;
+
+pub const EVENT_OFFICE_FILE: &str = "document://office_batch_file";
+
+const OFFICE_EXTENSIONS: &[&str] = &["doc", "docx", "odt", "rtf", "xls", "xlsx", "ods", "ppt", "pptx", "odp"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OfficeBatchEvent {
+    pub batch_id: Option<String>,
+    pub input_path: String,
+    pub index: usize,                   // 1-based
+    pub total: usize,
+    pub status: String,                 // converting, done or failed
+    pub output_path: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OfficeBatchFailure {
+    pub input_path: String,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OfficeBatchResult {
+    pub converted: Vec<ConversionResult>,
+    pub failed: Vec<OfficeBatchFailure>,
+}
+
+/// Expand folders into their office documents (sorted), skipping files already in the target format
+fn collect_documents(inputs: &[String], output_format: &str) -> Result<Vec<String>, String> {
+    let target = output_format.split(':').next().unwrap_or(output_format).to_lowercase();
+    let extension = |path: &Path| path.extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
+
+    let mut documents = Vec::new();
+    for input in inputs {
+        let path = Path::new(input);
+        if !path.is_dir() {
+            documents.push(input.clone());
+            continue;
+        }
+        let mut found: Vec<PathBuf> = std::fs::read_dir(path)
+            .map_err(|e| format!("Failed to read folder: {}", e))?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|p| p.is_file())
+            .filter(|p| {
+                let ext = extension(p);
+                OFFICE_EXTENSIONS.contains(&ext.as_str()) && ext != target
+            })
+            .collect();
+        found.sort();
+        documents.extend(found.into_iter().map(|p| p.to_string_lossy().to_string()));
+    }
+    Ok(documents)
+}
+
+/// Output stem for each document whose default name (its own stem) is shared with another
+/// document; None keeps the default. Shared stems get the source extension, then a number.
+fn unique_stems(documents: &[String]) -> Vec<Option<String>> {
+    let stem = |doc: &String| Path::new(doc).file_stem().and_then(|s| s.to_str()).unwrap_or("output").to_string();
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for doc in documents {
+        *counts.entry(stem(doc).to_lowercase()).or_default() += 1;
+    }
+    // Names kept as they are are taken first, so a renamed document never lands on one of them
+    let mut taken: HashSet<String> = counts.iter().filter(|(_, n)| **n == 1).map(|(s, _)| s.clone()).collect();
+    documents.iter()
+        .map(|doc| {
+            let stem = stem(doc);
+            if counts[&stem.to_lowercase()] == 1 {
+                return None;
+            }
+            let ext = Path::new(doc).extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
+            let base = if ext.is_empty() { stem } else { format!("{} ({})", stem, ext) };
+            let mut name = base.clone();
+            let mut number = 2;
+            while !taken.insert(name.to_lowercase()) {
+                name = format!("{} ({})", base, number);
+                number += 1;
+            }
+            Some(name)
+        })
+        .collect()
+}
+
+/// Convert into a temporary folder, then copy the result to `output_dir` under `stem`
+async fn convert_renamed(
+    input_path: String,
+    output_format: String,
+    output_dir: &str,
+    stem: &str,
+    profile: &Path,
+) -> Result<ConversionResult, String> {
+    let staging = WorkDir::create("office-batch")?;
+    let mut result = convert_with_libreoffice_profile(
+        input_path,
+        output_format.clone(),
+        staging.path().to_string_lossy().to_string(),
+        Some(profile),
+    ).await?;
+    let extension = output_format.split(':').next().unwrap_or(&output_format);
+    let output_path = Path::new(output_dir).join(format!("{}.{}", stem, extension));
+    if result.output_size.is_some() {
+        std::fs::copy(&result.output_path, &output_path).map_err(|e| format!("Failed to save output: {}", e))?;
+    }
+    result.output_path = output_path.to_string_lossy().to_string();
+    Ok(result)
+}
+
+/// Convert every document with up to `concurrency` soffice processes (default: half the cores, max 4)
+pub async fn convert_office_batch(
+    app: Option<AppHandle>,
+    inputs: Vec<String>,
+    output_format: String,
+    output_dir: String,
+    concurrency: Option<usize>,
+    batch_id: Option<String>,
+) -> Result<OfficeBatchResult, String> {
+    let documents = Arc::new(collect_documents(&inputs, &output_format)?);
+    if documents.is_empty() {
+        return Err("No office documents to convert".to_string());
+    }
+    let stems = Arc::new(unique_stems(&documents));
+    std::fs::create_dir_all(&output_dir)
+        .map_err(|e| format!("Failed to create output folder: {}", e))?;
+
+    let cores = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(2);
+    let workers = concurrency.unwrap_or((cores / 2).min(4)).clamp(1, 8).min(documents.len());
+    info!("📄 Converting {} documents to {} with {} LibreOffice workers", documents.len(), output_format, workers);
+
+    let next = Arc::new(AtomicUsize::new(0));
+    let mut tasks = tokio::task::JoinSet::new();
+    for _ in 0..workers {
+        let (documents, stems, next) = (documents.clone(), stems.clone(), next.clone());
+        let (app, batch_id) = (app.clone(), batch_id.clone());
+        let (output_format, output_dir) = (output_format.clone(), output_dir.clone());
+        tasks.spawn(async move {
+            let profile = LibreOfficeProfile::temporary();
+            let emit = |index: usize, status: &str, output_path: Option<String>, error: Option<String>| {
+                if let Some(app) = &app {
+                    let _ = app.emit(EVENT_OFFICE_FILE, OfficeBatchEvent {
+                        batch_id: batch_id.clone(),
+                        input_path: documents[index].clone(),
+                        index: index + 1,
+                        total: documents.len(),
+                        status: status.to_string(),
+                        output_path,
+                        error,
+                    });
+                }
+            };
+
+            let mut results = Vec::new();
+            loop {
+                let index = next.fetch_add(1, Ordering::SeqCst);
+                if index >= documents.len() {
+                    break;
+                }
+                emit(index, "converting", None, None);
+                // A stuck soffice (e.g. a dialog in a corrupt file) is stopped by the LibreOffice time limit
+                let conversion = match &stems[index] {
+                    Some(stem) => convert_renamed(
+                        documents[index].clone(),
+                        output_format.clone(),
+                        &output_dir,
+                        stem,
+                        profile.path(),
+                    ).await,
+                    None => convert_with_libreoffice_profile(
+                        documents[index].clone(),
+                        output_format.clone(),
+                        output_dir.clone(),
+                        Some(profile.path()),
+                    ).await,
+                };
+                let outcome = match conversion {
+                    Ok(result) if result.output_size.is_some() => Ok(result),
+                    Ok(_) => Err("LibreOffice produced no output".to_string()),
+                    Err(error) => Err(error),
+                };
+                match &outcome {
+                    Ok(result) => emit(index, "done", Some(result.output_path.clone()), None),
+                    Err(error) => emit(index, "failed", None, Some(error.clone())),
+                }
+                results.push((index, outcome));
+            }
+            results
+        });
+    }
+
+    let mut outcomes = Vec::with_capacity(documents.len());
+    while let Some(joined) = tasks.join_next().await {
+        outcomes.extend(joined.map_err(|e| format!("Task error: {}", e))?);
+    }
+    outcomes.sort_by_key(|(index, _)| *index);
+
+    let mut batch = OfficeBatchResult { converted: Vec::new(), failed: Vec::new() };
+    for (index, outcome) in outcomes {
+        match outcome {
+            Ok(result) => batch.converted.push(result),
+            Err(error) => batch.failed.push(OfficeBatchFailure { input_path: documents[index].clone(), error }),
+        }
+    }
+
+    info!("✅ Office batch: {} converted, {} failed", batch.converted.len(), batch.failed.len());
+    Ok(batch)
+}
@@ -0,0 +1,248 @@
+//! PDF Attachments - embed source files (spreadsheets, CSVs) in a PDF and extract them again
+//! Files go into the document's EmbeddedFiles name tree and are listed as associated files,
+//! which is how PDF/A-3 archives expect source data to travel with a report.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use log::info;
+use lopdf::{dictionary, Dictionary, Document, Object, ObjectId, Stream, StringFormat};
+use crate::bundled_converter::ConversionResult;
+use crate::pdf_merge::catalog_id;
+use crate::pdf_metadata::{decode_text, encode_text, pdf_date};
+use crate::pdf_pages::resolve;
+use crate::pdf_security::open_pdf;
+
+fn mime_type(path: &Path) -> &'static str {
+    match path.extension().and_then(|e| e.to_str()).map(str::to_lowercase).as_deref() {
+        Some("csv") => "text/csv",
+        Some("txt") => "text/plain",
+        Some("json") => "application/json",
+        Some("xml") => "application/xml",
+        Some("pdf") => "application/pdf",
+        Some("xlsx") => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        Some("xls") => "application/vnd.ms-excel",
+        Some("ods") => "application/vnd.oasis.opendocument.spreadsheet",
+        Some("docx") => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        _ => "application/octet-stream",
+    }
+}
+
+/// (key, value) pairs of a name tree, in order
+fn name_tree_entries(doc: &Document, node: &Dictionary, entries: &mut Vec<(Vec<u8>, Object)>, depth: usize) {
+    if let Ok(pairs) = node.get(b"Names").and_then(Object::as_array) {
+        for pair in pairs.chunks(2) {
+            if let [key, value] = pair {
+                if let Ok(key) = resolve(doc, key).as_str() {
+                    entries.push((key.to_vec(), value.clone()));
+                }
+            }
+        }
+    }
+    if depth > 16 {
+        return;
+    }
+    if let Ok(kids) = node.get(b"Kids").and_then(Object::as_array) {
+        for kid in kids {
+            if let Ok(kid) = resolve(doc, kid).as_dict() {
+                name_tree_entries(doc, kid, entries, depth + 1);
+            }
+        }
+    }
+}
+
+fn names_dict(doc: &Document) -> Option<&Dictionary> {
+    let catalog = doc.get_dictionary(catalog_id(doc)?).ok()?;
+    resolve(doc, catalog.get(b"Names").ok()?).as_dict().ok()
+}
+
+fn embedded_entries(doc: &Document) -> Vec<(Vec<u8>, Object)> {
+    let mut entries = Vec::new();
+    if let Some(tree) = names_dict(doc)
+        .and_then(|names| names.get(b"EmbeddedFiles").ok())
+        .and_then(|tree| resolve(doc, tree).as_dict().ok())
+    {
+        name_tree_entries(doc, tree, &mut entries, 0);
+    }
+    entries
+}
+
+/// Embed files as document attachments. A file named like an existing attachment replaces it.
+pub fn attach_files(
+    input_path: String,
+    output_path: String,
+    attachments: Vec<String>,
+    password: Option<String>,
+) -> Result<ConversionResult, String> {
+    if attachments.is_empty() {
+        return Err("No files to attach".to_string());
+    }
+
+    info!("📎 Attaching {} files to {}", attachments.len(), input_path);
+
+    let mut doc = open_pdf(&input_path, password.as_deref())?;
+    let mut entries = embedded_entries(&doc);
+    let mut replaced: HashSet<ObjectId> = HashSet::new();
+    let mut associated: Vec<ObjectId> = Vec::new();
+
+    for attachment in &attachments {
+        let path = Path::new(attachment);
+        let name = path.file_name().and_then(|n| n.to_str())
+            .ok_or_else(|| format!("Invalid file name: {}", attachment))?;
+        let data = fs::read(path)
+            .map_err(|e| format!("Failed to read {}: {}", attachment, e))?;
+        let modified = fs::metadata(path).and_then(|m| m.modified())
+            .map(chrono::DateTime::<chrono::Local>::from)
+            .unwrap_or_else(|_| chrono::Local::now());
+
+        let file_id = doc.add_object(Stream::new(dictionary! {
+            "Type" => "EmbeddedFile",
+            "Subtype" => mime_type(path),
+            "Params" => dictionary! {
+                "Size" => data.len() as i64,
+                "ModDate" => Object::string_literal(pdf_date(&modified)),
+            },
+        }, data));
+        let spec_id = doc.add_object(dictionary! {
+            "Type" => "Filespec",
+            "F" => encode_text(name),
+            "UF" => encode_text(name),
+            "EF" => dictionary! { "F" => file_id, "UF" => file_id },
+            "AFRelationship" => "Source",
+        });
+
+        let key = encode_text(name).as_str().map(<[u8]>::to_vec).unwrap_or_default();
+        entries.retain(|(existing, spec)| {
+            if *existing != key {
+                return true;
+            }
+            if let Object::Reference(id) = spec {
+                replaced.insert(*id);
+            }
+            false
+        });
+        entries.push((key, spec_id.into()));
+        associated.push(spec_id);
+    }
+
+    // Name tree keys must be sorted
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+    let names_array: Vec<Object> = entries.into_iter()
+        .flat_map(|(key, spec)| [Object::String(key, StringFormat::Literal), spec])
+        .collect();
+    let mut names = names_dict(&doc).cloned().unwrap_or_default();
+    names.set("EmbeddedFiles", dictionary! { "Names" => names_array });
+    let names_id = doc.add_object(names);
+
+    let catalog_id = catalog_id(&doc).ok_or("PDF has no catalog")?;
+    let mut files: Vec<Object> = doc.get_dictionary(catalog_id).ok()
+        .and_then(|catalog| catalog.get(b"AF").ok())
+        .and_then(|af| resolve(&doc, af).as_array().ok())
+        .cloned()
+        .unwrap_or_default();
+    files.retain(|spec| !matches!(spec, Object::Reference(id) if replaced.contains(id)));
+    files.extend(associated.into_iter().map(Object::Reference));
+
+    let catalog = doc.get_object_mut(catalog_id)
+        .and_then(Object::as_dict_mut)
+        .map_err(|e| format!("Invalid PDF catalog: {}", e))?;
+    catalog.set("Names", names_id);
+    catalog.set("AF", files);
+    if !catalog.has(b"PageMode") {
+        catalog.set("PageMode", "UseAttachments");
+    }
+
+    doc.prune_objects();
+    doc.compress();
+    doc.save(&output_path)
+        .map_err(|e| format!("Failed to save PDF: {}", e))?;
+
+    let output_size = fs::metadata(&output_path).map(|m| m.len()).ok();
+
+    info!("✅ Attached {} files: {}", attachments.len(), output_path);
+    Ok(ConversionResult {
+        success: true,
+        output_path,
+        message: format!("Attached {} files", attachments.len()),
+        output_size,
+    })
+}
+
+/// "report.csv" -> "report (2).csv" when the name is already taken
+fn unique_name(name: &str, used: &mut HashSet<String>) -> String {
+    let path = Path::new(name);
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("attachment");
+    let extension = path.extension().and_then(|e| e.to_str()).map(|e| format!(".{}", e)).unwrap_or_default();
+    let mut candidate = name.to_string();
+    let mut counter = 2;
+    while !used.insert(candidate.to_lowercase()) {
+        candidate = format!("{} ({}){}", stem, counter, extension);
+        counter += 1;
+    }
+    candidate
+}
+
+/// Save every embedded file (document attachments and file attachment annotations) into
+/// `output_dir`; returns the written paths, empty when the PDF has none
+pub fn extract_attachments(
+    input_path: String,
+    output_dir: String,
+    password: Option<String>,
+) -> Result<Vec<String>, String> {
+    info!("📎 Extracting attachments from {}", input_path);
+
+    let doc = open_pdf(&input_path, password.as_deref())?;
+    let mut specs: Vec<Object> = embedded_entries(&doc).into_iter().map(|(_, spec)| spec).collect();
+    for page_id in doc.get_pages().into_values() {
+        let annotations = doc.get_dictionary(page_id).ok()
+            .and_then(|page| page.get(b"Annots").ok())
+            .and_then(|annots| resolve(&doc, annots).as_array().ok());
+        for annotation in annotations.into_iter().flatten() {
+            let Ok(annotation) = resolve(&doc, annotation).as_dict() else { continue };
+            if annotation.get(b"Subtype").and_then(Object::as_name).ok() == Some(b"FileAttachment".as_slice()) {
+                if let Ok(spec) = annotation.get(b"FS") {
+                    specs.push(spec.clone());
+                }
+            }
+        }
+    }
+
+    fs::create_dir_all(&output_dir)
+        .map_err(|e| format!("Failed to create output folder: {}", e))?;
+
+    let mut seen: HashSet<ObjectId> = HashSet::new();
+    let mut used: HashSet<String> = HashSet::new();
+    let mut outputs = Vec::new();
+    for (index, spec) in specs.iter().enumerate() {
+        if let Object::Reference(id) = spec {
+            if !seen.insert(*id) {
+                continue; // Listed both as an attachment and as an annotation
+            }
+        }
+        let Ok(spec) = resolve(&doc, spec).as_dict() else { continue };
+        let stream = spec.get(b"EF").ok()
+            .and_then(|ef| resolve(&doc, ef).as_dict().ok())
+            .and_then(|ef| ef.get(b"UF").or_else(|_| ef.get(b"F")).ok())
+            .and_then(|file| resolve(&doc, file).as_stream().ok());
+        let Some(stream) = stream else { continue };
+
+        // Keep only the file name part, so a crafted name cannot write outside output_dir
+        let name = [b"UF".as_slice(), b"F".as_slice()].into_iter()
+            .find_map(|key| spec.get(key).ok())
+            .and_then(|name| resolve(&doc, name).as_str().ok())
+            .map(|name| decode_text(name).replace('\\', "/"))
+            .and_then(|name| Path::new(&name).file_name().map(|n| n.to_string_lossy().to_string()))
+            .unwrap_or_else(|| format!("attachment_{}", index + 1));
+        let data = stream.decompressed_content().unwrap_or_else(|_| stream.content.clone());
+
+        let path = Path::new(&output_dir).join(unique_name(&name, &mut used));
+        fs::write(&path, data)
+            .map_err(|e| format!("Failed to save {}: {}", path.display(), e))?;
+        outputs.push(path.to_string_lossy().to_string());
+    }
+
+    info!("✅ Extracted {} attachments to {}", outputs.len(), output_dir);
+    Ok(outputs)
+}
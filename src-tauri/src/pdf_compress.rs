@@ -0,0 +1,160 @@
+//! PDF Compress - shrink scanned PDFs by downsampling embedded images and dropping unused objects
+//! Only 8-bit RGB/gray images (JPEG or Flate) are re-encoded; masks, CMYK and indexed images are left alone.
+
+use std::fs;
+use log::info;
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, GrayImage, ImageFormat, RgbImage};
+use lopdf::{Dictionary, Document, Object, ObjectId, Stream};
+use crate::bundled_converter::ConversionResult;
+use crate::pdf_security::open_pdf;
+
+const DEFAULT_DPI: u32 = 150;
+const DEFAULT_JPEG_QUALITY: u8 = 75;
+
+/// Longest page edge in points (A4 height when no page declares a MediaBox)
+fn longest_page_edge(doc: &Document) -> f32 {
+    let edge = doc.get_pages().values()
+        .filter_map(|id| doc.get_dictionary(*id).ok())
+        .filter_map(|page| page.get(b"MediaBox").and_then(Object::as_array).ok())
+        .filter_map(|b| {
+            let n: Vec<f32> = b.iter().filter_map(|v| v.as_float().ok()).collect();
+            (n.len() == 4).then(|| (n[2] - n[0]).abs().max((n[3] - n[1]).abs()))
+        })
+        .fold(0.0, f32::max);
+    if edge > 0.0 { edge } else { 842.0 }
+}
+
+fn is_image(object: &Object) -> bool {
+    matches!(object, Object::Stream(stream)
+        if stream.dict.get(b"Subtype").and_then(Object::as_name).ok() == Some(b"Image".as_slice()))
+}
+
+/// Colour components of a colour space we can re-encode (gray or RGB)
+fn channels(doc: &Document, dict: &Dictionary) -> Option<u8> {
+    match dict.get(b"ColorSpace").ok()? {
+        Object::Name(name) => match name.as_slice() {
+            b"DeviceGray" => Some(1),
+            b"DeviceRGB" => Some(3),
+            _ => None,
+        },
+        Object::Array(space) if space.first().and_then(|o| o.as_name().ok()) == Some(b"ICCBased".as_slice()) => {
+            let profile = doc.get_object(space.get(1)?.as_reference().ok()?).ok()?.as_stream().ok()?;
+            match profile.dict.get(b"N").and_then(Object::as_i64).ok()? {
+                1 => Some(1),
+                3 => Some(3),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+fn filters(dict: &Dictionary) -> Vec<Vec<u8>> {
+    match dict.get(b"Filter") {
+        Ok(Object::Name(name)) => vec![name.clone()],
+        Ok(Object::Array(list)) => list.iter().filter_map(|o| o.as_name().ok().map(|n| n.to_vec())).collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn decode_image(stream: &Stream, channels: u8) -> Option<DynamicImage> {
+    let dict = &stream.dict;
+    if dict.get(b"ImageMask").and_then(Object::as_bool).unwrap_or(false) || dict.has(b"Decode") {
+        return None;
+    }
+    let filters = filters(dict);
+    if filters == [b"DCTDecode".to_vec()] {
+        return image::load_from_memory_with_format(&stream.content, ImageFormat::Jpeg).ok();
+    }
+    if !(filters.is_empty() || filters == [b"FlateDecode".to_vec()])
+        || dict.get(b"BitsPerComponent").and_then(Object::as_i64).ok() != Some(8) {
+        return None;
+    }
+    let width = dict.get(b"Width").and_then(Object::as_i64).ok()? as u32;
+    let height = dict.get(b"Height").and_then(Object::as_i64).ok()? as u32;
+    let data = if filters.is_empty() { stream.content.clone() } else { stream.decompressed_content().ok()? };
+    match channels {
+        1 => GrayImage::from_raw(width, height, data).map(DynamicImage::ImageLuma8),
+        _ => RgbImage::from_raw(width, height, data).map(DynamicImage::ImageRgb8),
+    }
+}
+
+/// Downsample to `max_edge` pixels and re-encode as JPEG; keeps the original if that is smaller
+fn recompress(stream: &mut Stream, channels: u8, max_edge: u32, quality: u8) -> bool {
+    let Some(image) = decode_image(stream, channels) else { return false };
+    let image = if image.width().max(image.height()) > max_edge {
+        image.resize(max_edge, max_edge, FilterType::CatmullRom)
+    } else {
+        image
+    };
+
+    let mut jpeg = Vec::new();
+    let mut encoder = JpegEncoder::new_with_quality(&mut jpeg, quality);
+    let encoded = match channels {
+        1 => encoder.encode_image(&image.to_luma8()),
+        _ => encoder.encode_image(&image.to_rgb8()),
+    };
+    if encoded.is_err() || jpeg.len() >= stream.content.len() {
+        return false;
+    }
+
+    stream.dict.set("Width", image.width() as i64);
+    stream.dict.set("Height", image.height() as i64);
+    stream.dict.set("BitsPerComponent", 8);
+    stream.dict.set("Filter", "DCTDecode");
+    stream.dict.remove(b"DecodeParms");
+    stream.set_content(jpeg);
+    stream.allows_compression = false;
+    true
+}
+
+/// Re-encode embedded images at `dpi` (relative to the largest page) and `jpeg_quality`,
+/// then drop unreferenced objects and compress the remaining streams
+pub fn compress_pdf(
+    input_path: String,
+    output_path: String,
+    dpi: Option<u32>,
+    jpeg_quality: Option<u8>,
+    password: Option<String>,
+) -> Result<ConversionResult, String> {
+    let dpi = dpi.unwrap_or(DEFAULT_DPI).clamp(36, 600);
+    let quality = jpeg_quality.unwrap_or(DEFAULT_JPEG_QUALITY).clamp(10, 100);
+    let input_size = fs::metadata(&input_path).map(|m| m.len()).unwrap_or(0);
+
+    info!("🗜️ Compressing {} ({} dpi, JPEG quality {})", input_path, dpi, quality);
+
+    let mut doc = open_pdf(&input_path, password.as_deref())?;
+    let max_edge = (longest_page_edge(&doc) / 72.0 * dpi as f32).round() as u32;
+
+    let images: Vec<(ObjectId, u8)> = doc.objects.iter()
+        .filter(|(_, object)| is_image(object))
+        .filter_map(|(id, object)| Some((*id, channels(&doc, &object.as_stream().ok()?.dict)?)))
+        .collect();
+    let mut recompressed = 0;
+    for (id, channels) in images {
+        if let Some(Object::Stream(stream)) = doc.objects.get_mut(&id) {
+            if recompress(stream, channels, max_edge, quality) {
+                recompressed += 1;
+            }
+        }
+    }
+
+    doc.prune_objects();
+    doc.delete_zero_length_streams();
+    doc.compress();
+    doc.save(&output_path)
+        .map_err(|e| format!("Failed to save compressed PDF: {}", e))?;
+
+    let output_size = fs::metadata(&output_path).map(|m| m.len()).ok();
+    let saved = input_size.saturating_sub(output_size.unwrap_or(0));
+
+    info!("✅ PDF compressed: {} ({} images re-encoded, {} KB saved)", output_path, recompressed, saved / 1024);
+    Ok(ConversionResult {
+        success: true,
+        output_path,
+        message: format!("Re-encoded {} images, saved {:.1} MB", recompressed, saved as f64 / 1_048_576.0),
+        output_size,
+    })
+}
@@ -0,0 +1,94 @@
+//! PDF Flatten - burn form field appearances into the page content so values can no longer be edited
+
+use lopdf::content::Operation;
+use lopdf::{Document, Object, ObjectId};
+use crate::pdf_merge::catalog_id;
+use crate::pdf_pages::{add_page_resource, overlay_page, resolve};
+
+/// Appearance to draw when flattening a widget; None if the annotation is not a widget,
+/// Some(None) for a hidden widget or one without an appearance
+fn widget_placement(doc: &Document, annot_id: ObjectId) -> Option<Option<(ObjectId, [f32; 6])>> {
+    let annot = doc.get_dictionary(annot_id).ok()?;
+    if annot.get(b"Subtype").and_then(Object::as_name).ok() != Some(b"Widget".as_slice()) {
+        return None;
+    }
+    if annot.get(b"F").and_then(Object::as_i64).unwrap_or(0) & 2 != 0 {
+        return Some(None);
+    }
+    let stream_id = match annot.get(b"AP").ok()
+        .and_then(|ap| resolve(doc, ap).as_dict().ok())
+        .and_then(|ap| ap.get(b"N").ok())
+    {
+        Some(Object::Reference(id)) => match doc.get_object(*id) {
+            Ok(Object::Stream(_)) => Some(*id),
+            Ok(Object::Dictionary(states)) => annot.get(b"AS").and_then(Object::as_name).ok()
+                .and_then(|state| states.get(state).and_then(Object::as_reference).ok()),
+            _ => None,
+        },
+        Some(Object::Dictionary(states)) => annot.get(b"AS").and_then(Object::as_name).ok()
+            .and_then(|state| states.get(state).and_then(Object::as_reference).ok()),
+        _ => None,
+    };
+
+    let numbers = |object: Option<&Object>| -> Option<[f32; 4]> {
+        let n: Vec<f32> = object?.as_array().ok()?.iter().filter_map(|v| v.as_float().ok()).collect();
+        (n.len() == 4).then(|| [n[0].min(n[2]), n[1].min(n[3]), n[0].max(n[2]), n[1].max(n[3])])
+    };
+    Some(stream_id.and_then(|id| {
+        let rect = numbers(annot.get(b"Rect").ok())?;
+        let bbox = numbers(doc.get_object(id).ok()?.as_stream().ok()?.dict.get(b"BBox").ok())
+            .unwrap_or([0.0, 0.0, rect[2] - rect[0], rect[3] - rect[1]]);
+        let sx = (rect[2] - rect[0]) / (bbox[2] - bbox[0]).max(0.01);
+        let sy = (rect[3] - rect[1]) / (bbox[3] - bbox[1]).max(0.01);
+        Some((id, [sx, 0.0, 0.0, sy, rect[0] - bbox[0] * sx, rect[1] - bbox[1] * sy]))
+    }))
+}
+
+/// Draw every widget's current appearance into the page content and remove the form
+pub fn flatten_fields(doc: &mut Document) -> Result<(), String> {
+    let pages: Vec<ObjectId> = doc.get_pages().into_values().collect();
+    for page_id in pages {
+        let annots: Vec<Object> = match doc.get_dictionary(page_id).ok()
+            .and_then(|page| page.get(b"Annots").ok())
+            .map(|annots| resolve(doc, annots))
+        {
+            Some(Object::Array(list)) => list.clone(),
+            _ => continue,
+        };
+
+        let mut keep = Vec::new();
+        let mut drawn = Vec::new();
+        for annot in annots {
+            match annot.as_reference().ok().and_then(|id| widget_placement(doc, id)) {
+                None => keep.push(annot),
+                Some(Some(placement)) => drawn.push(placement),
+                Some(None) => {}
+            }
+        }
+
+        let mut operations = Vec::new();
+        for (index, (appearance_id, matrix)) in drawn.into_iter().enumerate() {
+            let name = format!("AtFlat{}", index);
+            if let Ok(stream) = doc.get_object_mut(appearance_id).and_then(Object::as_stream_mut) {
+                stream.dict.set("Type", "XObject");
+                stream.dict.set("Subtype", "Form");
+            }
+            add_page_resource(doc, page_id, "XObject", &name, appearance_id.into())?;
+            operations.push(Operation::new("q", vec![]));
+            operations.push(Operation::new("cm", matrix.iter().map(|v| (*v).into()).collect()));
+            operations.push(Operation::new("Do", vec![Object::Name(name.into_bytes())]));
+            operations.push(Operation::new("Q", vec![]));
+        }
+        if !operations.is_empty() {
+            overlay_page(doc, page_id, operations)?;
+        }
+        if let Ok(page) = doc.get_object_mut(page_id).and_then(Object::as_dict_mut) {
+            page.set("Annots", Object::Array(keep));
+        }
+    }
+
+    if let Some(catalog) = catalog_id(doc).and_then(|id| doc.get_object_mut(id).and_then(Object::as_dict_mut).ok()) {
+        catalog.remove(b"AcroForm");
+    }
+    Ok(())
+}
@@ -0,0 +1,393 @@
+//! PDF Forms - read AcroForm fields, fill them (one file or a batch from CSV) and optionally flatten
+//! Text appearances are generated with the form's own fonts and WinAnsi text (Latin-1); when not
+//! flattening, NeedAppearances is also set so viewers can redraw values in other scripts.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use log::{info, warn};
+use lopdf::content::{Content, Operation};
+use lopdf::{dictionary, Dictionary, Document, Object, ObjectId, Stream};
+use crate::bundled_converter::ConversionResult;
+use crate::pdf_merge::catalog_id;
+use crate::pdf_metadata::{decode_text, encode_text};
+use crate::pdf_flatten::flatten_fields;
+use crate::pdf_pages::{resolve, standard_font};
+use crate::pdf_security::open_pdf;
+use crate::pdf_writer::{pdf_text, text_width};
+
+const FLAG_READ_ONLY: i64 = 1;
+const FLAG_REQUIRED: i64 = 1 << 1;
+const FLAG_RADIO: i64 = 1 << 15;
+const FLAG_PUSHBUTTON: i64 = 1 << 16;
+const DEFAULT_APPEARANCE: &str = "/Helv 0 Tf 0 g";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FormField {
+    pub name: String,               // Fully qualified, e.g. "student.name"
+    pub field_type: String,         // text, checkbox, radio, choice, signature, button
+    pub value: Option<String>,
+    pub options: Vec<String>,       // Choice options, or the on-states of a checkbox/radio
+    pub read_only: bool,
+    pub required: bool,
+}
+
+pub struct FieldNode {
+    pub id: ObjectId,
+    pub widgets: Vec<ObjectId>,
+    pub field: FormField,
+}
+
+/// Attributes a field inherits from its ancestors
+#[derive(Default, Clone)]
+struct Inherited {
+    field_type: Option<Vec<u8>>,
+    flags: i64,
+    value: Option<Object>,
+}
+
+fn acroform(doc: &Document) -> Option<&Dictionary> {
+    let catalog = doc.get_dictionary(catalog_id(doc)?).ok()?;
+    resolve(doc, catalog.get(b"AcroForm").ok()?).as_dict().ok()
+}
+
+fn value_text(doc: &Document, object: &Object) -> Option<String> {
+    match resolve(doc, object) {
+        Object::String(bytes, _) => Some(decode_text(bytes)),
+        Object::Name(name) => Some(String::from_utf8_lossy(name).to_string()),
+        Object::Array(items) => Some(items.iter().filter_map(|i| value_text(doc, i)).collect::<Vec<_>>().join(", ")),
+        _ => None,
+    }
+}
+
+/// Normal appearance of a widget: one stream, or a dictionary of states for checkboxes/radios
+fn normal_appearance<'a>(doc: &'a Document, widget: &'a Dictionary) -> Option<&'a Object> {
+    let appearance = resolve(doc, widget.get(b"AP").ok()?).as_dict().ok()?;
+    Some(resolve(doc, appearance.get(b"N").ok()?))
+}
+
+/// Appearance states a checkbox/radio widget can switch to, besides Off
+fn on_states(doc: &Document, widget_id: ObjectId) -> Vec<String> {
+    match doc.get_dictionary(widget_id).ok().and_then(|w| normal_appearance(doc, w)) {
+        Some(Object::Dictionary(states)) => states.iter()
+            .map(|(name, _)| String::from_utf8_lossy(name).to_string())
+            .filter(|name| name != "Off")
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn collect_fields(
+    doc: &Document,
+    kids: &[Object],
+    parent_name: &str,
+    inherited: &Inherited,
+    out: &mut Vec<FieldNode>,
+    depth: usize,
+) {
+    for kid in kids {
+        let Ok(id) = kid.as_reference() else { continue };
+        let Ok(dict) = doc.get_dictionary(id) else { continue };
+
+        let name = match dict.get(b"T").ok().and_then(|t| value_text(doc, t)) {
+            Some(partial) if parent_name.is_empty() => partial,
+            Some(partial) => format!("{}.{}", parent_name, partial),
+            None => parent_name.to_string(),
+        };
+        let state = Inherited {
+            field_type: dict.get(b"FT").and_then(Object::as_name).ok().map(<[u8]>::to_vec)
+                .or_else(|| inherited.field_type.clone()),
+            flags: dict.get(b"Ff").and_then(Object::as_i64).unwrap_or(inherited.flags),
+            value: dict.get(b"V").ok().cloned().or_else(|| inherited.value.clone()),
+        };
+        let children: Vec<Object> = dict.get(b"Kids").ok()
+            .and_then(|k| resolve(doc, k).as_array().ok())
+            .cloned()
+            .unwrap_or_default();
+
+        // Kids with a name are fields of their own; nameless kids are this field's widgets
+        let has_child_fields = children.iter()
+            .filter_map(|c| c.as_reference().ok())
+            .any(|c| doc.get_dictionary(c).is_ok_and(|c| c.has(b"T")));
+        if has_child_fields {
+            if depth < 32 {
+                collect_fields(doc, &children, &name, &state, out, depth + 1);
+            }
+            continue;
+        }
+        let widgets: Vec<ObjectId> = if children.is_empty() {
+            vec![id]
+        } else {
+            children.iter().filter_map(|c| c.as_reference().ok()).collect()
+        };
+
+        let field_type = match (state.field_type.as_deref(), state.flags) {
+            (Some(b"Tx"), _) => "text",
+            (Some(b"Btn"), flags) if flags & FLAG_PUSHBUTTON != 0 => "button",
+            (Some(b"Btn"), flags) if flags & FLAG_RADIO != 0 => "radio",
+            (Some(b"Btn"), _) => "checkbox",
+            (Some(b"Ch"), _) => "choice",
+            (Some(b"Sig"), _) => "signature",
+            _ => "unknown",
+        };
+        let mut options: Vec<String> = match field_type {
+            "choice" => dict.get(b"Opt").ok()
+                .and_then(|o| resolve(doc, o).as_array().ok())
+                .map(|items| items.iter().filter_map(|item| match resolve(doc, item) {
+                    Object::Array(pair) => pair.first().and_then(|export| value_text(doc, export)),
+                    other => value_text(doc, other),
+                }).collect())
+                .unwrap_or_default(),
+            "checkbox" | "radio" => widgets.iter().flat_map(|w| on_states(doc, *w)).collect(),
+            _ => Vec::new(),
+        };
+        options.dedup();
+
+        out.push(FieldNode {
+            id,
+            widgets,
+            field: FormField {
+                name,
+                field_type: field_type.to_string(),
+                value: state.value.as_ref().and_then(|v| value_text(doc, v)),
+                options,
+                read_only: state.flags & FLAG_READ_ONLY != 0,
+                required: state.flags & FLAG_REQUIRED != 0,
+            },
+        });
+    }
+}
+
+/// Terminal fields of the AcroForm with their object and widget ids
+pub fn form_fields(doc: &Document) -> Vec<FieldNode> {
+    let mut out = Vec::new();
+    if let Some(roots) = acroform(doc)
+        .and_then(|form| form.get(b"Fields").ok())
+        .and_then(|fields| resolve(doc, fields).as_array().ok())
+    {
+        collect_fields(doc, roots, "", &Inherited::default(), &mut out, 0);
+    }
+    out
+}
+
+/// List the fillable fields of a PDF form
+pub fn get_pdf_form_fields(input_path: String, password: Option<String>) -> Result<Vec<FormField>, String> {
+    let doc = open_pdf(&input_path, password.as_deref())?;
+    Ok(form_fields(&doc).into_iter().map(|node| node.field).collect())
+}
+
+fn is_checked(value: &str) -> bool {
+    matches!(value.trim().to_lowercase().as_str(), "yes" | "true" | "1" | "on" | "x" | "checked")
+}
+
+fn set_entry(doc: &mut Document, id: ObjectId, key: &str, value: Object) {
+    if let Ok(dict) = doc.get_object_mut(id).and_then(Object::as_dict_mut) {
+        dict.set(key, value);
+    }
+}
+
+/// Single-line text appearance built from the widget's default appearance (DA) string
+fn text_appearance(doc: &mut Document, widget_id: ObjectId, da: &str, fonts: &Dictionary, text: &str) -> Result<(), String> {
+    let rect: Vec<f32> = doc.get_dictionary(widget_id).ok()
+        .and_then(|w| w.get(b"Rect").and_then(Object::as_array).ok())
+        .map(|r| r.iter().filter_map(|v| v.as_float().ok()).collect())
+        .unwrap_or_default();
+    if rect.len() != 4 {
+        return Ok(());
+    }
+    let (width, height) = ((rect[2] - rect[0]).abs(), (rect[3] - rect[1]).abs());
+
+    let tokens: Vec<&str> = da.split_whitespace().collect();
+    let tf = tokens.iter().position(|t| *t == "Tf").filter(|i| *i >= 2);
+    let font = tf.map(|i| tokens[i - 2].trim_start_matches('/')).unwrap_or("Helv");
+    let mut size: f32 = tf.and_then(|i| tokens[i - 1].parse().ok()).unwrap_or(0.0);
+    if size <= 0.0 {
+        size = (height * 0.65).clamp(6.0, 12.0);
+    }
+    size = size.min((width - 4.0).max(1.0) / text_width(text, 1.0).max(0.01)).max(4.0);
+    let colour: Vec<&str> = match tf {
+        Some(i) => tokens.iter().enumerate().filter(|(j, _)| *j + 2 < i || *j > i).map(|(_, t)| *t).collect(),
+        None => vec!["0", "g"],
+    };
+
+    let mut content = format!(
+        "/Tx BMC\nq\nBT\n/{} {} Tf\n{}\n2 {:.2} Td\n",
+        font, size, colour.join(" "), (height - size * 0.7) / 2.0
+    ).into_bytes();
+    content.extend(Content { operations: vec![Operation::new("Tj", vec![pdf_text(text)])] }.encode()
+        .map_err(|e| format!("Failed to encode field appearance: {}", e))?);
+    content.extend_from_slice(b"ET\nQ\nEMC\n");
+
+    let font_resource = fonts.get(font.as_bytes()).ok().cloned()
+        .unwrap_or_else(|| Object::Dictionary(standard_font("Helvetica")));
+    let appearance_id = doc.add_object(Stream::new(dictionary! {
+        "Type" => "XObject",
+        "Subtype" => "Form",
+        "BBox" => vec![0.into(), 0.into(), width.into(), height.into()],
+        "Resources" => dictionary! { "Font" => dictionary! { font => font_resource } },
+    }, content));
+    set_entry(doc, widget_id, "AP", Object::Dictionary(dictionary! { "N" => appearance_id }));
+    Ok(())
+}
+
+/// Apply `values` (full field name -> value); returns the filled count and names with no matching field
+fn fill_document(doc: &mut Document, values: &HashMap<String, String>) -> Result<(usize, Vec<String>), String> {
+    let fields = form_fields(doc);
+    let form = acroform(doc).cloned().ok_or("This PDF has no form fields")?;
+    let fonts = form.get(b"DR").ok()
+        .and_then(|dr| resolve(doc, dr).as_dict().ok())
+        .and_then(|dr| dr.get(b"Font").ok())
+        .and_then(|f| resolve(doc, f).as_dict().ok())
+        .cloned()
+        .unwrap_or_default();
+    let form_da = form.get(b"DA").ok().and_then(|da| value_text(doc, da));
+
+    let mut filled = 0;
+    for node in &fields {
+        let Some(value) = values.get(&node.field.name) else { continue };
+        match node.field.field_type.as_str() {
+            "text" | "choice" => {
+                set_entry(doc, node.id, "V", encode_text(value));
+                for widget_id in &node.widgets {
+                    let da = [*widget_id, node.id].iter()
+                        .find_map(|id| doc.get_dictionary(*id).ok()?.get(b"DA").ok().and_then(|da| value_text(doc, da)))
+                        .or_else(|| form_da.clone())
+                        .unwrap_or_else(|| DEFAULT_APPEARANCE.to_string());
+                    text_appearance(doc, *widget_id, &da, &fonts, value)?;
+                }
+            }
+            "checkbox" | "radio" => {
+                let on_state = if node.field.field_type == "radio" {
+                    node.field.options.iter().find(|o| o.eq_ignore_ascii_case(value.trim())).cloned()
+                } else if is_checked(value) || node.field.options.iter().any(|o| o.eq_ignore_ascii_case(value.trim())) {
+                    node.field.options.first().cloned()
+                } else {
+                    None
+                };
+                let state = on_state.unwrap_or_else(|| "Off".to_string());
+                set_entry(doc, node.id, "V", Object::Name(state.clone().into_bytes()));
+                for widget_id in &node.widgets {
+                    let widget_state = if on_states(doc, *widget_id).contains(&state) { state.as_str() } else { "Off" };
+                    set_entry(doc, *widget_id, "AS", Object::Name(widget_state.as_bytes().to_vec()));
+                }
+            }
+            other => {
+                warn!("Skipping {} field {}", other, node.field.name);
+                continue;
+            }
+        }
+        filled += 1;
+    }
+
+    let unknown = values.keys()
+        .filter(|name| !fields.iter().any(|node| &node.field.name == *name))
+        .cloned()
+        .collect();
+    Ok((filled, unknown))
+}
+
+fn finish(mut doc: Document, flatten_form: bool, output_path: &str) -> Result<(), String> {
+    if flatten_form {
+        flatten_fields(&mut doc)?;
+    } else if let Some(catalog) = catalog_id(&doc) {
+        let form_id = doc.get_dictionary(catalog).ok()
+            .and_then(|c| c.get(b"AcroForm").and_then(Object::as_reference).ok());
+        let form = match form_id {
+            Some(id) => doc.get_object_mut(id),
+            None => doc.get_object_mut(catalog)
+                .and_then(Object::as_dict_mut)
+                .and_then(|c| c.get_mut(b"AcroForm")),
+        };
+        if let Ok(form) = form.and_then(Object::as_dict_mut) {
+            form.set("NeedAppearances", true);
+        }
+    }
+    doc.compress();
+    doc.save(output_path)
+        .map_err(|e| format!("Failed to save filled PDF: {}", e))?;
+    Ok(())
+}
+
+/// Fill a form from field name -> value. Checkboxes take yes/true/1/on/x; radios take an option name.
+pub fn fill_pdf_form(
+    input_path: String,
+    output_path: String,
+    values: HashMap<String, String>,
+    flatten: bool,
+    password: Option<String>,
+) -> Result<ConversionResult, String> {
+    info!("📝 Filling form {} ({} values)", input_path, values.len());
+
+    let mut doc = open_pdf(&input_path, password.as_deref())?;
+    let (filled, unknown) = fill_document(&mut doc, &values)?;
+    finish(doc, flatten, &output_path)?;
+
+    let mut message = format!("Filled {} fields", filled);
+    if !unknown.is_empty() {
+        message.push_str(&format!(" (no field named: {})", unknown.join(", ")));
+    }
+    let output_size = fs::metadata(&output_path).map(|m| m.len()).ok();
+
+    info!("✅ Form filled: {}", output_path);
+    Ok(ConversionResult {
+        success: true,
+        output_path,
+        message,
+        output_size,
+    })
+}
+
+/// One filled copy per CSV row; column headers are field names and empty cells are left untouched.
+/// Files are named after `name_column` when given (e.g. the register number), else the row number;
+/// a name already used gets the row number appended.
+pub fn fill_pdf_forms_from_csv(
+    input_path: String,
+    csv_path: String,
+    output_dir: String,
+    name_column: Option<String>,
+    flatten: bool,
+    password: Option<String>,
+) -> Result<Vec<String>, String> {
+    let template = open_pdf(&input_path, password.as_deref())?;
+    let mut reader = csv::Reader::from_path(&csv_path)
+        .map_err(|e| format!("Failed to open CSV: {}", e))?;
+    let headers = reader.headers()
+        .map_err(|e| format!("Failed to read CSV headers: {}", e))?
+        .clone();
+    fs::create_dir_all(&output_dir)
+        .map_err(|e| format!("Failed to create output folder: {}", e))?;
+    let stem = Path::new(&input_path).file_stem().and_then(|s| s.to_str()).unwrap_or("form");
+
+    info!("📝 Batch filling {} from {}", input_path, csv_path);
+
+    let mut outputs = Vec::new();
+    let mut used = HashSet::new();
+    for (row, record) in reader.records().enumerate() {
+        let record = record.map_err(|e| format!("Failed to read CSV row {}: {}", row + 2, e))?;
+        let values: HashMap<String, String> = headers.iter()
+            .zip(record.iter())
+            .filter(|(_, value)| !value.trim().is_empty())
+            .map(|(header, value)| (header.trim().to_string(), value.trim().to_string()))
+            .collect();
+
+        let mut label = name_column.as_deref()
+            .and_then(|column| values.get(column))
+            .map(|name| name.chars().map(|c| if c.is_alphanumeric() || c == '-' { c } else { '_' }).collect())
+            .unwrap_or_else(|| format!("{:03}", row + 1));
+        // Repeated names (or ones differing only in case) get the row number so no copy is overwritten
+        if !used.insert(label.to_lowercase()) {
+            label = format!("{}_{:03}", label, row + 1);
+            used.insert(label.to_lowercase());
+        }
+        let output_path = Path::new(&output_dir).join(format!("{}_{}.pdf", stem, label));
+
+        let mut doc = template.clone();
+        fill_document(&mut doc, &values)?;
+        finish(doc, flatten, &output_path.to_string_lossy())?;
+        outputs.push(output_path.to_string_lossy().to_string());
+    }
+
+    info!("✅ Filled {} forms into {}", outputs.len(), output_dir);
+    Ok(outputs)
+}
@@ -0,0 +1,116 @@
+//! PDF ICC - a small built-in sRGB ICC profile for PDF/A output intents
+//! ICC v2 display profile with the sRGB primaries (Bradford-adapted to D50) and the IEC 61966-2.1
+//! tone curve sampled like the reference profile, so no profile file has to ship with the app.
+
+use lopdf::{dictionary, Document, Object, ObjectId, Stream};
+
+pub const SRGB_NAME: &str = "sRGB IEC61966-2.1";
+
+const D50_WHITE: [f64; 3] = [0.9642, 1.0, 0.8249];
+const SRGB_RED: [f64; 3] = [0.4360747, 0.2225045, 0.0139322];
+const SRGB_GREEN: [f64; 3] = [0.3850649, 0.7168786, 0.0971045];
+const SRGB_BLUE: [f64; 3] = [0.1430804, 0.0606169, 0.7141733];
+
+/// Entries in the sampled tone curve (as in the reference sRGB profile)
+const CURVE_POINTS: usize = 1024;
+
+/// s15Fixed16Number
+fn fixed(value: f64) -> [u8; 4] {
+    ((value * 65536.0).round() as i32).to_be_bytes()
+}
+
+fn xyz_tag(value: [f64; 3]) -> Vec<u8> {
+    let mut tag = b"XYZ \0\0\0\0".to_vec();
+    for component in value {
+        tag.extend(fixed(component));
+    }
+    tag
+}
+
+fn desc_tag(name: &str) -> Vec<u8> {
+    let mut tag = b"desc\0\0\0\0".to_vec();
+    tag.extend((name.len() as u32 + 1).to_be_bytes());
+    tag.extend(name.as_bytes());
+    tag.push(0);
+    // Empty Unicode (language code + count) and ScriptCode (code + count + 67 byte buffer) parts
+    tag.extend([0u8; 4 + 4 + 2 + 1 + 67]);
+    tag
+}
+
+/// The sRGB transfer function (linear toe, then a 2.4 power) as a sampled curv tag
+fn srgb_curve_tag() -> Vec<u8> {
+    let mut tag = b"curv\0\0\0\0".to_vec();
+    tag.extend((CURVE_POINTS as u32).to_be_bytes());
+    for index in 0..CURVE_POINTS {
+        let encoded = index as f64 / (CURVE_POINTS - 1) as f64;
+        let linear = if encoded <= 0.04045 {
+            encoded / 12.92
+        } else {
+            ((encoded + 0.055) / 1.055).powf(2.4)
+        };
+        tag.extend(((linear * 65535.0).round() as u16).to_be_bytes());
+    }
+    tag
+}
+
+/// sRGB ICC profile bytes
+pub fn srgb_profile() -> Vec<u8> {
+    let mut copyright = b"text\0\0\0\0".to_vec();
+    copyright.extend(b"No copyright, use freely\0");
+    let curve = srgb_curve_tag();
+
+    let tags: [(&[u8; 4], Vec<u8>); 9] = [
+        (b"desc", desc_tag(SRGB_NAME)),
+        (b"cprt", copyright),
+        (b"wtpt", xyz_tag(D50_WHITE)),
+        (b"rXYZ", xyz_tag(SRGB_RED)),
+        (b"gXYZ", xyz_tag(SRGB_GREEN)),
+        (b"bXYZ", xyz_tag(SRGB_BLUE)),
+        (b"rTRC", curve.clone()),
+        (b"gTRC", curve.clone()),
+        (b"bTRC", curve),
+    ];
+
+    let mut table = (tags.len() as u32).to_be_bytes().to_vec();
+    let mut data = Vec::new();
+    let data_start = 128 + 4 + 12 * tags.len();
+    for (signature, tag) in &tags {
+        table.extend(*signature);
+        table.extend(((data_start + data.len()) as u32).to_be_bytes());
+        table.extend((tag.len() as u32).to_be_bytes());
+        data.extend(tag);
+        while data.len() % 4 != 0 {
+            data.push(0);
+        }
+    }
+
+    let size = 128 + table.len() + data.len();
+    let mut profile = Vec::with_capacity(size);
+    profile.extend((size as u32).to_be_bytes());
+    profile.extend([0u8; 4]);                       // Preferred CMM
+    profile.extend([0x02, 0x10, 0x00, 0x00]);       // Version 2.1
+    profile.extend(b"mntrRGB XYZ ");                // Display device, RGB data, XYZ connection space
+    profile.extend([0u8; 12]);                      // Creation date (unset)
+    profile.extend(b"acsp");
+    profile.extend([0u8; 24]);                      // Platform, flags, manufacturer, model, attributes
+    profile.extend([0u8; 4]);                       // Perceptual intent
+    for component in D50_WHITE {
+        profile.extend(fixed(component));
+    }
+    profile.resize(128, 0);                         // Creator and reserved bytes
+    profile.extend(table);
+    profile.extend(data);
+    profile
+}
+
+/// Add a PDF/A output intent carrying the sRGB profile; returns the intent dictionary's id
+pub fn add_srgb_output_intent(doc: &mut Document) -> ObjectId {
+    let profile_id = doc.add_object(Stream::new(dictionary! { "N" => 3 }, srgb_profile()));
+    doc.add_object(dictionary! {
+        "Type" => "OutputIntent",
+        "S" => "GTS_PDFA1",
+        "OutputConditionIdentifier" => Object::string_literal(SRGB_NAME),
+        "Info" => Object::string_literal(SRGB_NAME),
+        "DestOutputProfile" => profile_id,
+    })
+}
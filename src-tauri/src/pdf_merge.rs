@@ -0,0 +1,616 @@
+//! PDF Merge - combine whole documents (page trees, resources, bookmarks) into one file
+//! Each source is renumbered into its own id range so its fonts, images and links stay intact.
+//! Named destinations and the other catalog name trees (attachments, scripts) are merged too; a
+//! name an earlier file already uses gets the file's number appended ("_Toc12_2") and the links,
+//! bookmarks and actions of that file are pointed at the new name.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+use log::info;
+use lopdf::{dictionary, Dictionary, Document, Object, ObjectId, StringFormat};
+use crate::bundled_converter::ConversionResult;
+use crate::pdf_outline::{bookmark, outline_root};
+use crate::pdf_security::open_pdf;
+
+/// Page attributes a page may inherit from its parent Pages node
+const INHERITED_KEYS: [&[u8]; 4] = [b"Resources", b"MediaBox", b"CropBox", b"Rotate"];
+
+fn reference(dict: &Dictionary, key: &[u8]) -> Option<ObjectId> {
+    dict.get(key).and_then(Object::as_reference).ok()
+}
+
+fn type_of(object: &Object) -> Option<&[u8]> {
+    let dict = match object {
+        Object::Dictionary(dict) => dict,
+        Object::Stream(stream) => &stream.dict,
+        _ => return None,
+    };
+    dict.get(b"Type").and_then(Object::as_name).ok()
+}
+
+/// Catalog of a loaded document
+pub fn catalog_id(doc: &Document) -> Option<ObjectId> {
+    reference(&doc.trailer, b"Root")
+}
+
+/// Page dictionary with inherited attributes copied down, so it renders the same once re-parented
+fn flatten_inherited(doc: &Document, page_id: ObjectId) -> Dictionary {
+    let mut page = doc.get_dictionary(page_id).cloned().unwrap_or_default();
+    let mut parent = reference(&page, b"Parent");
+    let mut depth = 0;
+    while let Some(parent_id) = parent {
+        let Ok(node) = doc.get_dictionary(parent_id) else { break };
+        for key in INHERITED_KEYS {
+            if !page.has(key) {
+                if let Ok(value) = node.get(key) {
+                    page.set(key, value.clone());
+                }
+            }
+        }
+        parent = reference(node, b"Parent");
+        depth += 1;
+        if depth > 64 {
+            break; // Malformed (cyclic) page tree
+        }
+    }
+    page
+}
+
+/// Outline root and its top-level bookmark items, in order
+fn outline_items(doc: &Document) -> (Option<ObjectId>, Vec<ObjectId>) {
+    let root = catalog_id(doc)
+        .and_then(|id| doc.get_dictionary(id).ok())
+        .and_then(|catalog| reference(catalog, b"Outlines"));
+    let mut items = Vec::new();
+    let mut next = root
+        .and_then(|id| doc.get_dictionary(id).ok())
+        .and_then(|outlines| reference(outlines, b"First"));
+    while let Some(id) = next {
+        if items.contains(&id) {
+            break;
+        }
+        items.push(id);
+        next = doc.get_dictionary(id).ok().and_then(|item| reference(item, b"Next"));
+    }
+    (root, items)
+}
+
+/// Name tree category (Dests, EmbeddedFiles, JavaScript, ...) -> key -> value
+type NameTrees = BTreeMap<Vec<u8>, BTreeMap<Vec<u8>, Object>>;
+
+/// Old name -> new name for the destinations of one source
+#[derive(Default)]
+struct Renames {
+    strings: HashMap<Vec<u8>, Vec<u8>>,     // Name tree keys, referenced as strings
+    names: HashMap<Vec<u8>, Vec<u8>>,       // Catalog /Dests keys (PDF 1.1), referenced as names
+}
+
+fn resolve<'a>(doc: &'a Document, object: &'a Object) -> &'a Object {
+    match object {
+        Object::Reference(id) => doc.get_object(*id).unwrap_or(object),
+        _ => object,
+    }
+}
+
+/// Every key/value of a name tree; the ids of its nodes are added to `nodes`
+fn flatten_name_tree(doc: &Document, node: &Object, nodes: &mut Vec<ObjectId>, entries: &mut Vec<(Vec<u8>, Object)>) {
+    if let Object::Reference(id) = node {
+        if nodes.contains(id) {
+            return; // Malformed (cyclic) tree
+        }
+        nodes.push(*id);
+    }
+    let Ok(dict) = resolve(doc, node).as_dict() else { return };
+    if let Ok(pairs) = dict.get(b"Names").and_then(Object::as_array) {
+        for pair in pairs.chunks(2) {
+            if let [key, value] = pair {
+                if let Ok(key) = resolve(doc, key).as_str() {
+                    entries.push((key.to_vec(), value.clone()));
+                }
+            }
+        }
+    }
+    if let Ok(kids) = dict.get(b"Kids").and_then(Object::as_array) {
+        for kid in kids {
+            flatten_name_tree(doc, kid, nodes, entries);
+        }
+    }
+}
+
+/// `key` with "_<file number>" appended (or a higher number while that is taken too);
+/// UTF-16 keys get a UTF-16 suffix
+fn unique_key(key: &[u8], file_number: usize, taken: impl Fn(&[u8]) -> bool) -> Vec<u8> {
+    let utf16 = key.starts_with(&[0xFE, 0xFF]);
+    (file_number..)
+        .map(|n| {
+            let suffix = format!("_{}", n);
+            let mut candidate = key.to_vec();
+            if utf16 {
+                candidate.extend(suffix.encode_utf16().flat_map(u16::to_be_bytes));
+            } else {
+                candidate.extend(suffix.bytes());
+            }
+            candidate
+        })
+        .find(|candidate| !taken(candidate))
+        .unwrap_or_else(|| key.to_vec())
+}
+
+/// Move a source's name trees and /Dests dictionary into the merged ones. Returns the renamed
+/// destinations and the ids of the source's tree nodes (rebuilt, so not carried over).
+fn take_names(doc: &Document, file_number: usize, trees: &mut NameTrees, dests: &mut Dictionary) -> (Renames, Vec<ObjectId>) {
+    let mut renames = Renames::default();
+    let mut nodes = Vec::new();
+    let Some(catalog) = catalog_id(doc).and_then(|id| doc.get_dictionary(id).ok()) else {
+        return (renames, nodes);
+    };
+
+    if let Ok(names) = catalog.get(b"Names") {
+        if let Object::Reference(id) = names {
+            nodes.push(*id);
+        }
+        if let Ok(categories) = resolve(doc, names).as_dict() {
+            for (category, root) in categories.iter() {
+                let mut entries = Vec::new();
+                flatten_name_tree(doc, root, &mut nodes, &mut entries);
+                let tree = trees.entry(category.clone()).or_default();
+                for (key, value) in entries {
+                    let key = if tree.contains_key(&key) {
+                        let renamed = unique_key(&key, file_number, |k| tree.contains_key(k));
+                        if category.as_slice() == b"Dests" {
+                            renames.strings.insert(key, renamed.clone());
+                        }
+                        renamed
+                    } else {
+                        key
+                    };
+                    tree.insert(key, value);
+                }
+            }
+        }
+    }
+
+    if let Ok(old_dests) = catalog.get(b"Dests") {
+        if let Object::Reference(id) = old_dests {
+            nodes.push(*id);
+        }
+        if let Ok(old_dests) = resolve(doc, old_dests).as_dict() {
+            for (name, value) in old_dests.iter() {
+                let name = if dests.has(name) {
+                    let renamed = unique_key(name, file_number, |k| dests.has(k));
+                    renames.names.insert(name.clone(), renamed.clone());
+                    renamed
+                } else {
+                    name.clone()
+                };
+                dests.set(name, value.clone());
+            }
+        }
+    }
+    (renames, nodes)
+}
+
+/// Point links (/Dest), bookmarks and GoTo actions (/D) at renamed destinations
+fn rename_destinations(object: &mut Object, renames: &Renames) {
+    let dict = match object {
+        Object::Dictionary(dict) => dict,
+        Object::Stream(stream) => &mut stream.dict,
+        Object::Array(items) => {
+            for item in items {
+                rename_destinations(item, renames);
+            }
+            return;
+        }
+        _ => return,
+    };
+    for (key, value) in dict.iter_mut() {
+        let is_destination = key.as_slice() == b"Dest" || key.as_slice() == b"D";
+        match value {
+            Object::String(name, _) if is_destination => {
+                if let Some(renamed) = renames.strings.get(name.as_slice()) {
+                    *name = renamed.clone();
+                }
+            }
+            Object::Name(name) if is_destination => {
+                if let Some(renamed) = renames.names.get(name.as_slice()) {
+                    *name = renamed.clone();
+                }
+            }
+            _ => rename_destinations(value, renames),
+        }
+    }
+}
+
+/// Merge multiple PDF files into one, keeping each source's bookmarks.
+/// With `bookmark_per_file` every source gets a bookmark named after the file, holding its own bookmarks.
+/// `password` is tried on every protected input; the merged file is not protected.
+pub fn merge_pdfs(
+    input_paths: Vec<String>,
+    output_path: String,
+    password: Option<String>,
+    bookmark_per_file: bool,
+) -> Result<ConversionResult, String> {
+    if input_paths.len() < 2 {
+        return Err("Need at least 2 PDFs to merge".to_string());
+    }
+
+    info!("📄 Merging {} PDFs (bundled)", input_paths.len());
+
+    let mut merged = Document::with_version("1.5");
+    let mut next_id = 1;
+    let mut pages: Vec<(ObjectId, Dictionary)> = Vec::new();
+    // (file title, first page, top-level bookmarks) per source
+    let mut sources: Vec<(String, ObjectId, Vec<ObjectId>)> = Vec::new();
+    let mut name_trees = NameTrees::new();
+    let mut dests = Dictionary::new();
+
+    for (index, path) in input_paths.iter().enumerate() {
+        let mut doc = open_pdf(path, password.as_deref())?;
+        doc.renumber_objects_with(next_id);
+        next_id = doc.max_id + 1;
+
+        let source_pages: Vec<ObjectId> = doc.get_pages().into_values().collect();
+        if source_pages.is_empty() {
+            return Err(format!("{} has no pages", path));
+        }
+        let (renames, name_nodes) = take_names(&doc, index + 1, &mut name_trees, &mut dests);
+        let renamed = !renames.strings.is_empty() || !renames.names.is_empty();
+        for page_id in &source_pages {
+            let mut page = Object::Dictionary(flatten_inherited(&doc, *page_id));
+            if renamed {
+                rename_destinations(&mut page, &renames);
+            }
+            if let Object::Dictionary(page) = page {
+                pages.push((*page_id, page));
+            }
+        }
+        let (outline_root, items) = outline_items(&doc);
+        let title = Path::new(path).file_stem().and_then(|s| s.to_str()).unwrap_or(path).to_string();
+        sources.push((title, source_pages[0], items));
+
+        // Everything except the old catalog, page tree, outline root and name trees carries over as-is
+        for (id, mut object) in doc.objects {
+            let skip = source_pages.contains(&id)
+                || Some(id) == outline_root
+                || name_nodes.contains(&id)
+                || matches!(type_of(&object), Some(b"Catalog" | b"Pages" | b"XRef" | b"ObjStm"));
+            if skip {
+                continue;
+            }
+            if renamed {
+                rename_destinations(&mut object, &renames);
+            }
+            merged.objects.insert(id, object);
+        }
+    }
+    merged.max_id = next_id;
+
+    let pages_id = merged.new_object_id();
+    let kids: Vec<Object> = pages.iter().map(|(id, _)| Object::Reference(*id)).collect();
+    let page_count = kids.len();
+    for (id, mut page) in pages {
+        page.set("Parent", pages_id);
+
+        merged.objects.insert(id, Object::Dictionary(page));
+    }
+    merged.objects.insert(pages_id, Object::Dictionary(dictionary! {
+        "Type" => "Pages",
+        "Kids" => kids,
+        "Count" => page_count as i64,
+    }));
+
+    let mut catalog = dictionary! {
+        "Type" => "Catalog",
+        "Pages" => pages_id,
+    };
+    let bookmarks: Vec<ObjectId> = if bookmark_per_file {
+        sources.iter()
+            .map(|(title, first_page, items)| bookmark(&mut merged, title, Some(*first_page), items))
+            .collect()
+    } else {
+        sources.into_iter().flat_map(|(_, _, items)| items).collect()
+    };
+    if let Some(outlines_id) = outline_root(&mut merged, &bookmarks) {
+        catalog.set("Outlines", outlines_id);
+        catalog.set("PageMode", "UseOutlines");
+    }
+    // One flat leaf per name tree; BTreeMap order is the byte order the tree requires
+    if !name_trees.is_empty() {
+        let mut names = Dictionary::new();
+        for (category, entries) in name_trees {
+            let pairs: Vec<Object> = entries.into_iter()
+                .flat_map(|(key, value)| [Object::String(key, StringFormat::Literal), value])
+                .collect();
+            names.set(category, merged.add_object(dictionary! { "Names" => pairs }));
+        }
+        catalog.set("Names", names);
+    }
+    if !dests.is_empty() {
+        catalog.set("Dests", merged.add_object(dests));
+    }
+    let catalog_id = merged.add_object(catalog);
+    merged.trailer.set("Root", catalog_id);
+    merged.compress();
+
+    merged.save(&output_path)
+        .map_err(|e| format!("Failed to save merged PDF: {}", e))?;
+
+    let output_size = fs::metadata(&output_path).map(|m| m.len()).ok();
+
+    info!("✅ PDFs merged: {} ({} pages)", output_path, page_count);
+    Ok(ConversionResult {
+        success: true,
+        output_path,
+        message: format!("Successfully merged {} PDFs ({} pages)", input_paths.len(), page_count),
+        output_size,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tool_process::WorkDir;
+
+    /// Word ("Save as PDF") layout: every object packed into an object stream, a cross-reference
+    /// stream instead of an xref table, tagged pages, headings exported as `_Toc` destinations in
+    /// a /Names tree with an intermediate node, and a TOC link that refers to one by string
+    fn word_pdf(toc_name: &str) -> Vec<u8> {
+        let objects = [
+            "<< /Type /Catalog /Pages 2 0 R /Names 3 0 R /MarkInfo << /Marked true >> /Lang (en-IN) >>".to_string(),
+            "<< /Type /Pages /Kids [6 0 R 7 0 R] /Count 2 >>".to_string(),
+            "<< /Dests 4 0 R >>".to_string(),
+            "<< /Kids [5 0 R] >>".to_string(),
+            format!("<< /Limits [({0}) ({0})] /Names [({0}) [7 0 R /XYZ 72 720 0]] >>", toc_name),
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 612 792] /Resources << >> /Annots [8 0 R] /StructParents 0 >>".to_string(),
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 612 792] /Resources << >> /StructParents 1 >>".to_string(),
+            format!("<< /Type /Annot /Subtype /Link /Rect [72 700 300 714] /Border [0 0 0] /Dest ({}) >>", toc_name),
+        ];
+        let (mut header, mut body) = (String::new(), String::new());
+        for (i, object) in objects.iter().enumerate() {
+            header.push_str(&format!("{} {} ", i + 1, body.len()));
+            body.push_str(object);
+            body.push('\n');
+        }
+
+        let mut pdf = b"%PDF-1.7\n%\xB5\xB5\xB5\xB5\n".to_vec();
+        let object_stream_offset = pdf.len();
+        pdf.extend(format!(
+            "9 0 obj\n<< /Type /ObjStm /N {} /First {} /Length {} >>\nstream\n{}{}\nendstream\nendobj\n",
+            objects.len(), header.len(), header.len() + body.len(), header, body
+        ).bytes());
+
+        // W [1 2 1]: type, offset or object stream number, generation or index in the stream
+        let xref_offset = pdf.len();
+        let mut entries = vec![[0u8, 0, 0, 255]];
+        entries.extend((0..objects.len()).map(|i| [2, 0, 9, i as u8]));
+        for offset in [object_stream_offset, xref_offset] {
+            entries.push([1, (offset >> 8) as u8, offset as u8, 0]);
+        }
+        let entries = entries.concat();
+        pdf.extend(format!("10 0 obj\n<< /Type /XRef /Size 11 /W [1 2 1] /Root 1 0 R /Length {} >>\nstream\n", entries.len()).bytes());
+        pdf.extend(entries);
+        pdf.extend(format!("\nendstream\nendobj\nstartxref\n{}\n%%EOF\n", xref_offset).bytes());
+        pdf
+    }
+
+    /// LibreOffice (Writer export with bookmarks) layout: xref table, heading destinations in a
+    /// /Names tree split over leaves with /Limits, and a link and a bookmark using GoTo actions
+    fn libreoffice_pdf(heading: &str) -> Document {
+        let mut doc = Document::with_version("1.6");
+        let pages_id = doc.new_object_id();
+        let page_ids: Vec<ObjectId> = (0..2)
+            .map(|_| doc.add_object(dictionary! {
+                "Type" => "Page",
+                "Parent" => pages_id,
+                "MediaBox" => vec![0.into(), 0.into(), 595.into(), 842.into()],
+                "Resources" => dictionary! {},
+            }))
+            .collect();
+        doc.objects.insert(pages_id, Object::Dictionary(dictionary! {
+            "Type" => "Pages",
+            "Kids" => page_ids.iter().map(|id| Object::Reference(*id)).collect::<Vec<_>>(),
+            "Count" => 2,
+        }));
+
+        let go_to = || dictionary! { "S" => "GoTo", "D" => Object::string_literal(heading) };
+        let first_leaf = doc.add_object(dictionary! {
+            "Limits" => vec![Object::string_literal("Contents"), Object::string_literal("Contents")],
+            "Names" => vec![Object::string_literal("Contents"), vec![page_ids[0].into(), "Fit".into()].into()],
+        });
+        let second_leaf = doc.add_object(dictionary! {
+            "Limits" => vec![Object::string_literal(heading), Object::string_literal(heading)],
+            "Names" => vec![Object::string_literal(heading), vec![page_ids[1].into(), "XYZ".into(), 57.into(), 785.into(), 0.into()].into()],
+        });
+        let dests = doc.add_object(dictionary! { "Kids" => vec![first_leaf.into(), second_leaf.into()] });
+        let link = doc.add_object(dictionary! {
+            "Type" => "Annot",
+            "Subtype" => "Link",
+            "Rect" => vec![57.into(), 700.into(), 200.into(), 714.into()],
+            "A" => go_to(),
+        });
+        doc.get_dictionary_mut(page_ids[0]).unwrap().set("Annots", vec![Object::Reference(link)]);
+
+        let outlines = doc.new_object_id();
+        let item = doc.add_object(dictionary! { "Title" => Object::string_literal(heading), "Parent" => outlines, "A" => go_to() });
+        doc.objects.insert(outlines, Object::Dictionary(dictionary! { "Type" => "Outlines", "First" => item, "Last" => item, "Count" => 1 }));
+
+        let catalog = doc.add_object(dictionary! {
+            "Type" => "Catalog",
+            "Pages" => pages_id,
+            "Outlines" => outlines,
+            "Names" => dictionary! { "Dests" => dests },
+        });
+        let info = doc.add_object(dictionary! { "Producer" => Object::string_literal("LibreOffice 7.6") });
+        doc.trailer.set("Root", catalog);
+        doc.trailer.set("Info", info);
+        doc
+    }
+
+    /// Scanner ("scan to PDF") layout: one JPEG per page drawn full-page, MediaBox and Resources
+    /// inherited from the page tree, no outline and no names
+    fn scanner_pdf() -> Document {
+        let mut doc = Document::with_version("1.4");
+        let image = doc.add_object(lopdf::Stream::new(dictionary! {
+            "Type" => "XObject",
+            "Subtype" => "Image",
+            "Width" => 1,
+            "Height" => 1,
+            "ColorSpace" => "DeviceGray",
+            "BitsPerComponent" => 8,
+            "Filter" => "DCTDecode",
+        }, vec![0xFF, 0xD8, 0xFF, 0xD9]));
+        let content = doc.add_object(lopdf::Stream::new(dictionary! {}, b"q 595 0 0 842 0 0 cm /Im0 Do Q".to_vec()));
+        let pages_id = doc.new_object_id();
+        let page = doc.add_object(dictionary! { "Type" => "Page", "Parent" => pages_id, "Contents" => content });
+        doc.objects.insert(pages_id, Object::Dictionary(dictionary! {
+            "Type" => "Pages",
+            "Kids" => vec![page.into()],
+            "Count" => 1,
+            "MediaBox" => vec![0.into(), 0.into(), 595.into(), 842.into()],
+            "Resources" => dictionary! { "XObject" => dictionary! { "Im0" => image } },
+        }));
+        let catalog = doc.add_object(dictionary! { "Type" => "Catalog", "Pages" => pages_id });
+        doc.trailer.set("Root", catalog);
+        doc
+    }
+
+    /// Older (PDF 1.1 style) layout: destinations in the catalog /Dests dictionary, links by name
+    fn dests_dictionary_pdf() -> Document {
+        let mut doc = Document::with_version("1.3");
+        let pages_id = doc.new_object_id();
+        let page = doc.add_object(dictionary! {
+            "Type" => "Page",
+            "Parent" => pages_id,
+            "MediaBox" => vec![0.into(), 0.into(), 612.into(), 792.into()],
+            "Annots" => vec![Object::Dictionary(dictionary! { "Subtype" => "Link", "Dest" => Object::Name(b"intro".to_vec()) })],
+        });
+        doc.objects.insert(pages_id, Object::Dictionary(dictionary! { "Type" => "Pages", "Kids" => vec![page.into()], "Count" => 1 }));
+        let catalog = doc.add_object(dictionary! {
+            "Type" => "Catalog",
+            "Pages" => pages_id,
+            "Dests" => dictionary! { "intro" => vec![page.into(), "Fit".into()] },
+        });
+        doc.trailer.set("Root", catalog);
+        doc
+    }
+
+    fn save(work: &WorkDir, name: &str, mut doc: Document) -> String {
+        let path = work.path().join(name);
+        doc.save(&path).unwrap();
+        path.to_string_lossy().to_string()
+    }
+
+    fn merge(work: &WorkDir, inputs: Vec<String>) -> Document {
+        let output = work.path().join("merged.pdf").to_string_lossy().to_string();
+        merge_pdfs(inputs, output.clone(), None, false).unwrap();
+        Document::load(&output).unwrap()
+    }
+
+    /// Key -> destination array of the merged /Names /Dests tree
+    fn named_dests(doc: &Document) -> BTreeMap<String, Vec<Object>> {
+        let catalog = doc.get_dictionary(catalog_id(doc).unwrap()).unwrap();
+        let names = catalog.get(b"Names").map(|n| resolve(doc, n).as_dict().unwrap().clone()).unwrap_or_default();
+        let mut entries = Vec::new();
+        if let Ok(root) = names.get(b"Dests") {
+            flatten_name_tree(doc, root, &mut Vec::new(), &mut entries);
+        }
+        entries.into_iter()
+            .map(|(key, value)| {
+                let value = resolve(doc, &value).clone();
+                let array = value.as_dict().and_then(|d| d.get(b"D")).unwrap_or(&value).as_array().unwrap().clone();
+                (String::from_utf8(key).unwrap(), array)
+            })
+            .collect()
+    }
+
+    /// 1-based number of the page a destination array points at
+    fn page_number(doc: &Document, dest: &[Object]) -> u32 {
+        let target = dest[0].as_reference().unwrap();
+        doc.get_pages().into_iter().find(|(_, id)| *id == target).map(|(number, _)| number).unwrap()
+    }
+
+    fn link_target(doc: &Document, page: u32) -> Vec<u8> {
+        let page = doc.get_dictionary(doc.get_pages()[&page]).unwrap();
+        let annot = page.get(b"Annots").unwrap().as_array().unwrap()[0].clone();
+        let annot = resolve(doc, &annot).as_dict().unwrap().clone();
+        let dest = match annot.get(b"A") {
+            Ok(action) => action.as_dict().unwrap().get(b"D").unwrap().clone(),
+            Err(_) => annot.get(b"Dest").unwrap().clone(),
+        };
+        match dest {
+            Object::String(name, _) | Object::Name(name) => name,
+            other => panic!("unexpected destination {:?}", other),
+        }
+    }
+
+    #[test]
+    fn word_files_keep_their_toc_links() {
+        let work = WorkDir::create("merge-test").unwrap();
+        let first = work.path().join("first.pdf");
+        let second = work.path().join("second.pdf");
+        fs::write(&first, word_pdf("_Toc1")).unwrap();
+        fs::write(&second, word_pdf("_Toc1")).unwrap();
+
+        let merged = merge(&work, vec![first.to_string_lossy().to_string(), second.to_string_lossy().to_string()]);
+        let dests = named_dests(&merged);
+        assert_eq!(dests.keys().collect::<Vec<_>>(), ["_Toc1", "_Toc1_2"]);
+        assert_eq!(page_number(&merged, &dests["_Toc1"]), 2);
+        assert_eq!(page_number(&merged, &dests["_Toc1_2"]), 4);
+        assert_eq!(link_target(&merged, 1), b"_Toc1");
+        assert_eq!(link_target(&merged, 3), b"_Toc1_2");
+    }
+
+    #[test]
+    fn libreoffice_bookmarks_and_links_follow_renamed_destinations() {
+        let work = WorkDir::create("merge-test").unwrap();
+        let first = save(&work, "first.pdf", libreoffice_pdf("Introduction"));
+        let second = save(&work, "second.pdf", libreoffice_pdf("Introduction"));
+
+        let merged = merge(&work, vec![first, second]);
+        let dests = named_dests(&merged);
+        assert_eq!(dests.keys().collect::<Vec<_>>(), ["Contents", "Contents_2", "Introduction", "Introduction_2"]);
+        assert_eq!(page_number(&merged, &dests["Introduction"]), 2);
+        assert_eq!(page_number(&merged, &dests["Introduction_2"]), 4);
+        assert_eq!(link_target(&merged, 3), b"Introduction_2");
+
+        let (_, items) = outline_items(&merged);
+        let targets: Vec<Object> = items.iter()
+            .map(|id| merged.get_dictionary(*id).unwrap().get(b"A").unwrap().as_dict().unwrap().get(b"D").unwrap().clone())
+            .collect();
+        assert_eq!(targets, [Object::string_literal("Introduction"), Object::string_literal("Introduction_2")]);
+    }
+
+    #[test]
+    fn scanned_pages_keep_inherited_size_and_image() {
+        let work = WorkDir::create("merge-test").unwrap();
+        let scan = save(&work, "scan.pdf", scanner_pdf());
+        let word = work.path().join("word.pdf");
+        fs::write(&word, word_pdf("_Toc7")).unwrap();
+
+        let merged = merge(&work, vec![scan, word.to_string_lossy().to_string()]);
+        assert_eq!(merged.get_pages().len(), 3);
+        let page = merged.get_dictionary(merged.get_pages()[&1]).unwrap();
+        assert!(page.has(b"MediaBox"));
+        let resources = resolve(&merged, page.get(b"Resources").unwrap()).as_dict().unwrap();
+        assert!(resources.get(b"XObject").unwrap().as_dict().unwrap().has(b"Im0"));
+
+        let dests = named_dests(&merged);
+        assert_eq!(dests.keys().collect::<Vec<_>>(), ["_Toc7"]);
+        assert_eq!(page_number(&merged, &dests["_Toc7"]), 3);
+    }
+
+    #[test]
+    fn catalog_dests_dictionary_is_merged_and_renamed() {
+        let work = WorkDir::create("merge-test").unwrap();
+        let first = save(&work, "first.pdf", dests_dictionary_pdf());
+        let second = save(&work, "second.pdf", dests_dictionary_pdf());
+
+        let merged = merge(&work, vec![first, second]);
+        let catalog = merged.get_dictionary(catalog_id(&merged).unwrap()).unwrap();
+        let dests = resolve(&merged, catalog.get(b"Dests").unwrap()).as_dict().unwrap();
+        assert!(dests.has(b"intro") && dests.has(b"intro_2"));
+        assert_eq!(page_number(&merged, dests.get(b"intro_2").unwrap().as_array().unwrap()), 2);
+        assert_eq!(link_target(&merged, 2), b"intro_2");
+    }
+}
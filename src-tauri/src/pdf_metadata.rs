@@ -0,0 +1,175 @@
+//! PDF Metadata - read and edit the document information dictionary (title, author, dates)
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use log::info;
+use lopdf::{dictionary, Dictionary, Document, Object, Stream, StringFormat};
+use crate::bundled_converter::ConversionResult;
+use crate::pdf_merge::catalog_id;
+use crate::pdf_pdfa::{build_xmp, pdfa_claim};
+use crate::pdf_security::open_pdf;
+
+/// Document information fields. When editing, None keeps a field and "" clears it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PdfMetadata {
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub subject: Option<String>,
+    pub keywords: Option<String>,
+    pub creator: Option<String>,            // Application that made the original document
+    pub producer: Option<String>,           // Library that wrote the PDF
+    pub creation_date: Option<String>,      // ISO 8601, e.g. 2024-01-15T10:30:00+05:30
+    pub modification_date: Option<String>,
+}
+
+/// Decode a PDF text string (UTF-16BE with BOM, UTF-8 with BOM, or PDFDocEncoding as Latin-1)
+pub fn decode_text(bytes: &[u8]) -> String {
+    if let Some(utf16) = bytes.strip_prefix(&[0xFE, 0xFF]) {
+        let units: Vec<u16> = utf16.chunks_exact(2).map(|c| u16::from_be_bytes([c[0], c[1]])).collect();
+        String::from_utf16_lossy(&units)
+    } else if let Some(utf8) = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]) {
+        String::from_utf8_lossy(utf8).to_string()
+    } else {
+        bytes.iter().map(|b| *b as char).collect()
+    }
+}
+
+/// Encode a PDF text string; anything beyond ASCII is written as UTF-16BE so Tamil names survive
+pub fn encode_text(text: &str) -> Object {
+    if text.is_ascii() {
+        Object::String(text.as_bytes().to_vec(), StringFormat::Literal)
+    } else {
+        let mut bytes = vec![0xFE, 0xFF];
+        for unit in text.encode_utf16() {
+            bytes.extend(unit.to_be_bytes());
+        }
+        Object::String(bytes, StringFormat::Hexadecimal)
+    }
+}
+
+/// PDF date string (D:YYYYMMDDHHmmSS+HH'mm')
+pub fn pdf_date<Tz: chrono::TimeZone>(time: &chrono::DateTime<Tz>) -> String
+where
+    Tz::Offset: std::fmt::Display,
+{
+    format!("D:{}{}'", time.format("%Y%m%d%H%M%S"), time.format("%:z").to_string().replace(':', "'"))
+}
+
+/// "D:20240115103000+05'30'" -> "2024-01-15T10:30:00+05:30"; missing parts default per the spec
+pub fn pdf_date_to_iso(raw: &str) -> String {
+    let value = raw.trim_start_matches("D:");
+    let digits: String = value.chars().take_while(|c| c.is_ascii_digit()).collect();
+    if digits.len() < 4 {
+        return raw.to_string();
+    }
+    let part = |start: usize, default: &'static str| digits.get(start..start + 2).unwrap_or(default).to_string();
+    let zone = match value[digits.len()..].chars().next() {
+        Some('Z') => "Z".to_string(),
+        Some(sign @ ('+' | '-')) => {
+            let offset: String = value[digits.len() + 1..].chars().filter(|c| c.is_ascii_digit()).collect();
+            format!("{}{}:{}", sign, offset.get(0..2).unwrap_or("00"), offset.get(2..4).unwrap_or("00"))
+        }
+        _ => String::new(),
+    };
+    format!("{}-{}-{}T{}:{}:{}{}", &digits[0..4], part(4, "01"), part(6, "01"),
+        part(8, "00"), part(10, "00"), part(12, "00"), zone)
+}
+
+/// Accepts RFC 3339 or a plain YYYY-MM-DD
+fn iso_to_pdf_date(value: &str) -> Result<String, String> {
+    if let Ok(time) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Ok(pdf_date(&time));
+    }
+    chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map(|date| format!("D:{}000000", date.format("%Y%m%d")))
+        .map_err(|_| format!("Invalid date: {} (use YYYY-MM-DD or RFC 3339)", value))
+}
+
+pub fn info_dict(doc: &Document) -> Option<&Dictionary> {
+    match doc.trailer.get(b"Info").ok()? {
+        Object::Reference(id) => doc.get_dictionary(*id).ok(),
+        Object::Dictionary(dict) => Some(dict),
+        _ => None,
+    }
+}
+
+pub fn read_metadata(doc: &Document) -> PdfMetadata {
+    let text = |key: &[u8]| {
+        info_dict(doc)?.get(key).and_then(Object::as_str).ok()
+            .map(decode_text)
+            .filter(|value| !value.trim().is_empty())
+    };
+    PdfMetadata {
+        title: text(b"Title"),
+        author: text(b"Author"),
+        subject: text(b"Subject"),
+        keywords: text(b"Keywords"),
+        creator: text(b"Creator"),
+        producer: text(b"Producer"),
+        creation_date: text(b"CreationDate").map(|d| pdf_date_to_iso(&d)),
+        modification_date: text(b"ModDate").map(|d| pdf_date_to_iso(&d)),
+    }
+}
+
+/// Write `fields` into the document information dictionary and the XMP packet. ModDate is always set to now.
+pub fn set_pdf_metadata(
+    input_path: String,
+    output_path: String,
+    fields: PdfMetadata,
+    password: Option<String>,
+) -> Result<ConversionResult, String> {
+    info!("🏷️ Updating PDF metadata: {}", input_path);
+
+    let mut doc = open_pdf(&input_path, password.as_deref())?;
+    let mut info = info_dict(&doc).cloned().unwrap_or_default();
+
+    let text_fields: [(&[u8], &Option<String>); 6] = [
+        (b"Title", &fields.title),
+        (b"Author", &fields.author),
+        (b"Subject", &fields.subject),
+        (b"Keywords", &fields.keywords),
+        (b"Creator", &fields.creator),
+        (b"Producer", &fields.producer),
+    ];
+    for (key, value) in text_fields {
+        match value.as_deref().map(str::trim) {
+            Some("") => { info.remove(key); }
+            Some(value) => info.set(key, encode_text(value)),
+            None => {}
+        }
+    }
+    match fields.creation_date.as_deref().map(str::trim) {
+        Some("") => { info.remove(b"CreationDate"); }
+        Some(value) => info.set("CreationDate", Object::string_literal(iso_to_pdf_date(value)?)),
+        None => {}
+    }
+    info.set("ModDate", Object::string_literal(pdf_date(&chrono::Local::now())));
+
+    let info_id = doc.add_object(info);
+    doc.trailer.set("Info", info_id);
+
+    // Readers prefer XMP over the info dictionary, so the packet is rewritten from the new values;
+    // a PDF/A declaration is kept (archives reject PDF/A files without XMP)
+    let claim = pdfa_claim(&doc);
+    let xmp = build_xmp(&read_metadata(&doc), claim.as_ref().map(|(part, level)| (part.as_str(), level.as_str())));
+    let xmp_id = doc.add_object(
+        Stream::new(dictionary! { "Type" => "Metadata", "Subtype" => "XML" }, xmp.into_bytes()).with_compression(false),
+    );
+    if let Some(catalog) = catalog_id(&doc).and_then(|id| doc.get_object_mut(id).and_then(Object::as_dict_mut).ok()) {
+        catalog.set("Metadata", xmp_id);
+    }
+
+    doc.save(&output_path)
+        .map_err(|e| format!("Failed to save PDF: {}", e))?;
+
+    let output_size = fs::metadata(&output_path).map(|m| m.len()).ok();
+
+    info!("✅ PDF metadata updated: {}", output_path);
+    Ok(ConversionResult {
+        success: true,
+        output_path,
+        message: "PDF metadata updated".to_string(),
+        output_size,
+    })
+}
@@ -0,0 +1,233 @@
+//! PDF Outline - read a document's bookmarks and write new ones
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use log::info;
+use lopdf::{dictionary, Dictionary, Document, Object, ObjectId};
+use crate::bundled_converter::ConversionResult;
+use crate::pdf_merge::catalog_id;
+use crate::pdf_metadata::{decode_text, encode_text};
+use crate::pdf_pages::resolve;
+use crate::pdf_security::open_pdf;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutlineItem {
+    pub title: String,
+    #[serde(default)]
+    pub page: Option<usize>,            // 1-based target page (None: external link/action when read, plain heading when written)
+    #[serde(default)]
+    pub children: Vec<OutlineItem>,
+}
+
+/// Link `items` in order as the children of `parent` (an outline root or another bookmark)
+pub fn attach_children(doc: &mut Document, parent: ObjectId, items: &[ObjectId]) {
+    for (i, id) in items.iter().enumerate() {
+        if let Ok(item) = doc.get_object_mut(*id).and_then(Object::as_dict_mut) {
+            item.set("Parent", parent);
+            item.remove(b"Prev");
+            item.remove(b"Next");
+            if i > 0 {
+                item.set("Prev", items[i - 1]);
+            }
+            if let Some(next) = items.get(i + 1) {
+                item.set("Next", *next);
+            }
+        }
+    }
+    if let Ok(parent) = doc.get_object_mut(parent).and_then(Object::as_dict_mut) {
+        match (items.first(), items.last()) {
+            (Some(first), Some(last)) => {
+                parent.set("First", *first);
+                parent.set("Last", *last);
+                parent.set("Count", items.len() as i64);
+            }
+            _ => {
+                parent.remove(b"First");
+                parent.remove(b"Last");
+                parent.remove(b"Count");
+            }
+        }
+    }
+}
+
+/// New outline root holding `items` as its top-level bookmarks
+pub fn outline_root(doc: &mut Document, items: &[ObjectId]) -> Option<ObjectId> {
+    if items.is_empty() {
+        return None;
+    }
+    let root_id = doc.add_object(dictionary! { "Type" => "Outlines" });
+    attach_children(doc, root_id, items);
+    Some(root_id)
+}
+
+/// Bookmark that opens `page_id` fitted to the window (none: a heading that goes nowhere), with
+/// `children` nested under it
+pub fn bookmark(doc: &mut Document, title: &str, page_id: Option<ObjectId>, children: &[ObjectId]) -> ObjectId {
+    let mut item = dictionary! { "Title" => encode_text(title) };
+    if let Some(page_id) = page_id {
+        item.set("Dest", vec![page_id.into(), "Fit".into()]);
+    }
+    let id = doc.add_object(item);
+    attach_children(doc, id, children);
+    id
+}
+
+/// Look a named destination up in the catalog's Dests dictionary or the Names/Dests name tree
+fn named_destination(doc: &Document, name: &[u8]) -> Option<Object> {
+    let catalog = doc.get_dictionary(catalog_id(doc)?).ok()?;
+    if let Some(found) = catalog.get(b"Dests").ok()
+        .and_then(|d| resolve(doc, d).as_dict().ok())
+        .and_then(|dests| dests.get(name).ok())
+    {
+        return Some(found.clone());
+    }
+    let tree = catalog.get(b"Names").ok()
+        .and_then(|n| resolve(doc, n).as_dict().ok())
+        .and_then(|names| names.get(b"Dests").ok())?;
+    search_name_tree(doc, resolve(doc, tree).as_dict().ok()?, name, 0)
+}
+
+fn search_name_tree(doc: &Document, node: &Dictionary, name: &[u8], depth: usize) -> Option<Object> {
+    if let Ok(pairs) = node.get(b"Names").and_then(Object::as_array) {
+        for pair in pairs.chunks(2) {
+            if let [key, value] = pair {
+                if resolve(doc, key).as_str().ok() == Some(name) {
+                    return Some(value.clone());
+                }
+            }
+        }
+    }
+    if depth > 16 {
+        return None;
+    }
+    node.get(b"Kids").and_then(Object::as_array).ok()?
+        .iter()
+        .filter_map(|kid| resolve(doc, kid).as_dict().ok())
+        .find_map(|kid| search_name_tree(doc, kid, name, depth + 1))
+}
+
+/// Page a bookmark opens, following Dest, GoTo actions and named destinations
+fn target_page(doc: &Document, item: &Dictionary, pages: &HashMap<ObjectId, usize>) -> Option<usize> {
+    let mut destination = match item.get(b"Dest") {
+        Ok(dest) => resolve(doc, dest).clone(),
+        Err(_) => {
+            let action = resolve(doc, item.get(b"A").ok()?).as_dict().ok()?;
+            if action.get(b"S").and_then(Object::as_name).ok() != Some(b"GoTo".as_slice()) {
+                return None;
+            }
+            resolve(doc, action.get(b"D").ok()?).clone()
+        }
+    };
+    for _ in 0..4 {
+        destination = match destination {
+            Object::Array(ref target) => return pages.get(&target.first()?.as_reference().ok()?).copied(),
+            Object::Dictionary(ref dict) => resolve(doc, dict.get(b"D").ok()?).clone(),
+            Object::String(ref name, _) | Object::Name(ref name) => resolve(doc, &named_destination(doc, name)?).clone(),
+            _ => return None,
+        };
+    }
+    None
+}
+
+fn read_items(
+    doc: &Document,
+    first: Option<ObjectId>,
+    pages: &HashMap<ObjectId, usize>,
+    seen: &mut HashSet<ObjectId>,
+) -> Vec<OutlineItem> {
+    let mut items = Vec::new();
+    let mut next = first;
+    while let Some(id) = next {
+        if !seen.insert(id) {
+            break; // Cyclic outline
+        }
+        let Ok(dict) = doc.get_dictionary(id) else { break };
+        let title = dict.get(b"Title").ok()
+            .and_then(|t| resolve(doc, t).as_str().ok())
+            .map(decode_text)
+            .unwrap_or_default();
+        let first_child = dict.get(b"First").and_then(Object::as_reference).ok();
+        items.push(OutlineItem {
+            title,
+            page: target_page(doc, dict, pages),
+            children: read_items(doc, first_child, pages, seen),
+        });
+        next = dict.get(b"Next").and_then(Object::as_reference).ok();
+    }
+    items
+}
+
+/// The document's bookmark tree (empty when it has none)
+pub fn get_pdf_outline(input_path: String, password: Option<String>) -> Result<Vec<OutlineItem>, String> {
+    let doc = open_pdf(&input_path, password.as_deref())?;
+    let pages: HashMap<ObjectId, usize> = doc.get_pages().into_iter()
+        .map(|(number, id)| (id, number as usize))
+        .collect();
+    let first = catalog_id(&doc)
+        .and_then(|id| doc.get_dictionary(id).ok())
+        .and_then(|catalog| catalog.get(b"Outlines").ok())
+        .and_then(|root| resolve(&doc, root).as_dict().ok())
+        .and_then(|root| root.get(b"First").and_then(Object::as_reference).ok());
+    Ok(read_items(&doc, first, &pages, &mut HashSet::new()))
+}
+
+fn write_items(doc: &mut Document, items: &[OutlineItem], pages: &[ObjectId]) -> Result<Vec<ObjectId>, String> {
+    let mut ids = Vec::with_capacity(items.len());
+    for item in items {
+        // Items without a page are plain headings that only group their children
+        let page_id = item.page
+            .map(|page| pages.get(page.wrapping_sub(1)).copied()
+                .ok_or_else(|| format!("Bookmark \"{}\" points at page {}, outside 1-{}", item.title, page, pages.len())))
+            .transpose()?;
+        let children = write_items(doc, &item.children, pages)?;
+        ids.push(bookmark(doc, &item.title, page_id, &children));
+    }
+    Ok(ids)
+}
+
+/// Replace the document's bookmarks with `items` (an empty list removes them)
+pub fn set_pdf_outline(
+    input_path: String,
+    output_path: String,
+    items: Vec<OutlineItem>,
+    password: Option<String>,
+) -> Result<ConversionResult, String> {
+    info!("🔖 Writing {} bookmarks to {}", items.len(), output_path);
+
+    let mut doc = open_pdf(&input_path, password.as_deref())?;
+    let pages: Vec<ObjectId> = doc.get_pages().into_values().collect();
+    let catalog = catalog_id(&doc).ok_or("PDF has no catalog")?;
+
+    let top_level = write_items(&mut doc, &items, &pages)?;
+    let root = outline_root(&mut doc, &top_level);
+    let catalog = doc.get_object_mut(catalog)
+        .and_then(Object::as_dict_mut)
+        .map_err(|e| format!("Invalid PDF catalog: {}", e))?;
+    match root {
+        Some(root) => {
+            catalog.set("Outlines", root);
+            catalog.set("PageMode", "UseOutlines");
+        }
+        None => {
+            catalog.remove(b"Outlines");
+            catalog.remove(b"PageMode");
+        }
+    }
+
+    // Drop the previous outline's objects
+    doc.prune_objects();
+    doc.compress();
+    doc.save(&output_path)
+        .map_err(|e| format!("Failed to save PDF: {}", e))?;
+
+    let output_size = fs::metadata(&output_path).map(|m| m.len()).ok();
+
+    info!("✅ Bookmarks written: {}", output_path);
+    Ok(ConversionResult {
+        success: true,
+        output_path,
+        message: format!("Wrote {} bookmarks", items.len()),
+        output_size,
+    })
+}
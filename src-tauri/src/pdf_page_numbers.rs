@@ -0,0 +1,119 @@
+//! PDF Page Numbers - stamp "Page X of Y" plus optional header/footer text onto existing pages
+//! Handy after merging many documents into one submission bundle.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use log::info;
+use lopdf::content::Operation;
+use lopdf::Object;
+use crate::bundled_converter::ConversionResult;
+use crate::pdf_pages::{add_page_resource, overlay_page, page_box, select_pages, standard_font};
+use crate::pdf_security::open_pdf;
+use crate::pdf_writer::{pdf_text, text_width};
+
+const FONT_NAME: &str = "AtPnFont";
+const EDGE_MARGIN: f32 = 24.0;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PageNumberOptions {
+    pub input_path: String,
+    pub output_path: String,
+    #[serde(default)]
+    pub format: Option<String>,        // {n} and {total} placeholders, default "Page {n} of {total}"
+    #[serde(default)]
+    pub position: Option<String>,      // top|bottom _ left|center|right, default bottom_center
+    #[serde(default)]
+    pub start_at: Option<u32>,         // Number printed on the first stamped page
+    #[serde(default)]
+    pub pages: Option<String>,         // "3-"; every page when empty
+    #[serde(default)]
+    pub header: Option<String>,        // Top centre of each stamped page
+    #[serde(default)]
+    pub footer: Option<String>,        // Bottom left of each stamped page
+    #[serde(default)]
+    pub font_size: Option<f32>,
+    #[serde(default)]
+    pub password: Option<String>,
+}
+
+/// Baseline origin for `text` at a position like "bottom_right" within the page box
+fn place(text: &str, size: f32, position: &str, page: (f32, f32, f32, f32)) -> (f32, f32) {
+    let (x, y, width, height) = page;
+    let (vertical, horizontal) = position.split_once('_').unwrap_or(("bottom", "center"));
+    let text_x = match horizontal {
+        "left" => x + EDGE_MARGIN,
+        "right" => x + width - EDGE_MARGIN - text_width(text, size),
+        _ => x + (width - text_width(text, size)) / 2.0,
+    };
+    let text_y = match vertical {
+        "top" => y + height - EDGE_MARGIN - size,
+        _ => y + EDGE_MARGIN,
+    };
+    (text_x, text_y)
+}
+
+fn text_operations(text: &str, size: f32, origin: (f32, f32)) -> Vec<Operation> {
+    vec![
+        Operation::new("BT", vec![]),
+        Operation::new("Tf", vec![Object::Name(FONT_NAME.into()), size.into()]),
+        Operation::new("Td", vec![origin.0.into(), origin.1.into()]),
+        Operation::new("Tj", vec![pdf_text(text)]),
+        Operation::new("ET", vec![]),
+    ]
+}
+
+/// Stamp page numbers (and header/footer text) on the selected pages.
+/// Pages outside the selection keep their content and are not counted.
+pub fn stamp_page_numbers(options: PageNumberOptions) -> Result<ConversionResult, String> {
+    let format = options.format.as_deref().filter(|f| !f.trim().is_empty()).unwrap_or("Page {n} of {total}");
+    let position = options.position.as_deref().unwrap_or("bottom_center").to_lowercase();
+    let start_at = options.start_at.unwrap_or(1) as usize;
+    let size = options.font_size.unwrap_or(10.0).clamp(5.0, 36.0);
+    let header = options.header.as_deref().map(str::trim).filter(|h| !h.is_empty());
+    let footer = options.footer.as_deref().map(str::trim).filter(|f| !f.is_empty());
+
+    info!("🔢 Numbering pages of {} ({})", options.input_path, position);
+
+    let mut doc = open_pdf(&options.input_path, options.password.as_deref())?;
+    let pages = doc.get_pages();
+    let selected = select_pages(options.pages.as_deref(), pages.len())?;
+    if selected.is_empty() {
+        return Err("The PDF has no pages".to_string());
+    }
+    let total = start_at + selected.len() - 1;
+    let font_id = doc.add_object(standard_font("Helvetica"));
+
+    for (index, number) in selected.iter().enumerate() {
+        let page_id = pages[&(*number as u32)];
+        let page = page_box(&doc, page_id);
+        add_page_resource(&mut doc, page_id, "Font", FONT_NAME, font_id.into())?;
+
+        let label = format
+            .replace("{n}", &(start_at + index).to_string())
+            .replace("{total}", &total.to_string());
+        let mut operations = vec![Operation::new("q", vec![]), Operation::new("g", vec![0.2_f32.into()])];
+        operations.extend(text_operations(&label, size, place(&label, size, &position, page)));
+        if let Some(header) = header {
+            operations.extend(text_operations(header, size, place(header, size, "top_center", page)));
+        }
+        if let Some(footer) = footer {
+            operations.extend(text_operations(footer, size, place(footer, size, "bottom_left", page)));
+        }
+        operations.push(Operation::new("Q", vec![]));
+        overlay_page(&mut doc, page_id, operations)?;
+    }
+
+    doc.compress();
+    doc.save(&options.output_path)
+        .map_err(|e| format!("Failed to save numbered PDF: {}", e))?;
+
+    let output_size = fs::metadata(&options.output_path).map(|m| m.len()).ok();
+
+    info!("✅ Numbered {} pages: {}", selected.len(), options.output_path);
+    Ok(ConversionResult {
+        success: true,
+        output_path: options.output_path,
+        message: format!("Numbered {} pages", selected.len()),
+        output_size,
+    })
+}
@@ -0,0 +1,139 @@
+//! PDF Pages - page selection, page boxes and content overlays shared by the PDF tools
+
+use lopdf::content::{Content, Operation};
+use lopdf::{dictionary, Dictionary, Document, Object, ObjectId, Stream};
+
+/// Parse a page selection like "1-3,5,8-" (1-based, "8-" runs to the end); empty selects every page
+pub fn select_pages(spec: Option<&str>, page_count: usize) -> Result<Vec<usize>, String> {
+    let Some(spec) = spec.map(str::trim).filter(|s| !s.is_empty()) else {
+        return Ok((1..=page_count).collect());
+    };
+    let mut pages = Vec::new();
+    for part in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let parse = |value: &str, default: usize| -> Result<usize, String> {
+            let value = value.trim();
+            if value.is_empty() {
+                return Ok(default);
+            }
+            value.parse().map_err(|_| format!("Invalid page selection: {}", part))
+        };
+        let (start, end) = match part.split_once('-') {
+            Some((start, end)) => (parse(start, 1)?, parse(end, page_count)?),
+            None => {
+                let page = parse(part, 0)?;
+                (page, page)
+            }
+        };
+        if start == 0 || start > end || end > page_count {
+            return Err(format!("Page selection {} is outside 1-{}", part, page_count));
+        }
+        pages.extend(start..=end);
+    }
+    pages.sort_unstable();
+    pages.dedup();
+    Ok(pages)
+}
+
+/// Attribute from the page or the nearest Pages ancestor that defines it
+pub fn inherited<'a>(doc: &'a Document, page_id: ObjectId, key: &[u8]) -> Option<&'a Object> {
+    let mut node = Some(page_id);
+    for _ in 0..64 {
+        let dict = doc.get_dictionary(node?).ok()?;
+        if let Ok(value) = dict.get(key) {
+            return Some(value);
+        }
+        node = dict.get(b"Parent").and_then(Object::as_reference).ok();
+    }
+    None
+}
+
+/// Follow a reference to the object it points at
+pub fn resolve<'a>(doc: &'a Document, object: &'a Object) -> &'a Object {
+    match object {
+        Object::Reference(id) => doc.get_object(*id).unwrap_or(object),
+        _ => object,
+    }
+}
+
+fn resolve_dict(doc: &Document, object: &Object) -> Dictionary {
+    match object {
+        Object::Reference(id) => doc.get_dictionary(*id).cloned().unwrap_or_default(),
+        Object::Dictionary(dict) => dict.clone(),
+        _ => Dictionary::new(),
+    }
+}
+
+/// Visible area of a page as (x, y, width, height), from the CropBox or MediaBox
+pub fn page_box(doc: &Document, page_id: ObjectId) -> (f32, f32, f32, f32) {
+    for key in [b"CropBox".as_slice(), b"MediaBox".as_slice()] {
+        if let Some(Object::Array(values)) = inherited(doc, page_id, key) {
+            let n: Vec<f32> = values.iter().filter_map(|v| v.as_float().ok()).collect();
+            if n.len() == 4 {
+                return (n[0].min(n[2]), n[1].min(n[3]), (n[2] - n[0]).abs(), (n[3] - n[1]).abs());
+            }
+        }
+    }
+    (0.0, 0.0, 595.0, 842.0)
+}
+
+/// Standard 14 font (not embedded, so text is limited to WinAnsi)
+pub fn standard_font(base_font: &str) -> Dictionary {
+    dictionary! {
+        "Type" => "Font",
+        "Subtype" => "Type1",
+        "BaseFont" => base_font,
+        "Encoding" => "WinAnsiEncoding",
+    }
+}
+
+/// Add a named entry (font, graphics state, image) to a page's resources.
+/// The resource dictionary is copied onto the page, so pages that shared it are unaffected.
+pub fn add_page_resource(
+    doc: &mut Document,
+    page_id: ObjectId,
+    category: &str,
+    name: &str,
+    resource: Object,
+) -> Result<(), String> {
+    let mut resources = inherited(doc, page_id, b"Resources")
+        .map(|r| resolve_dict(doc, r))
+        .unwrap_or_default();
+    let mut entries = resources.get(category.as_bytes())
+        .map(|e| resolve_dict(doc, e))
+        .unwrap_or_default();
+    entries.set(name, resource);
+    resources.set(category, entries);
+    doc.get_object_mut(page_id)
+        .and_then(Object::as_dict_mut)
+        .map_err(|e| format!("Invalid page object: {}", e))?
+        .set("Resources", resources);
+    Ok(())
+}
+
+/// Draw `operations` on top of a page. The existing content is wrapped in q/Q so
+/// whatever graphics state it leaves behind cannot shift or recolour the overlay.
+pub fn overlay_page(doc: &mut Document, page_id: ObjectId, operations: Vec<Operation>) -> Result<(), String> {
+    let mut contents = match doc.get_dictionary(page_id).ok().and_then(|page| page.get(b"Contents").ok()) {
+        Some(Object::Array(list)) => list.clone(),
+        Some(Object::Reference(id)) => match doc.get_object(*id) {
+            Ok(Object::Array(list)) => list.clone(),
+            _ => vec![Object::Reference(*id)],
+        },
+        _ => Vec::new(),
+    };
+
+    let mut overlay = vec![Operation::new("Q", vec![])];
+    overlay.extend(operations);
+    let overlay = Content { operations: overlay }.encode()
+        .map_err(|e| format!("Failed to encode page overlay: {}", e))?;
+    let open_id = doc.add_object(Stream::new(dictionary! {}, b"q\n".to_vec()));
+    let overlay_id = doc.add_object(Stream::new(dictionary! {}, overlay));
+    contents.insert(0, open_id.into());
+    contents.push(overlay_id.into());
+
+    doc.get_object_mut(page_id)
+        .and_then(Object::as_dict_mut)
+        .map_err(|e| format!("Invalid page object: {}", e))?
+        .set("Contents", contents);
+    Ok(())
+}
@@ -0,0 +1,291 @@
+//! PDF/A - basic archival checks and a best-effort conversion to PDF/A-2B for records retention
+//! The checks cover what usually gets archive uploads rejected (encryption, unembedded fonts,
+//! missing XMP metadata or output intent, JavaScript); this is not a full ISO 19005 validator.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use log::info;
+use lopdf::{dictionary, Document, Object, ObjectId, Stream};
+use crate::bundled_converter::ConversionResult;
+use crate::pdf_icc::add_srgb_output_intent;
+use crate::pdf_merge::catalog_id;
+use crate::pdf_metadata::{info_dict, pdf_date, read_metadata, PdfMetadata};
+use crate::pdf_pages::resolve;
+use crate::pdf_security::{ensure_file_id, open_pdf};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PdfaReport {
+    pub compliant: bool,                    // Passed every basic check (issues is empty)
+    pub claimed: Option<String>,            // Conformance declared in the XMP metadata, e.g. "PDF/A-2B"
+    pub encrypted: bool,
+    pub has_xmp_metadata: bool,
+    pub has_output_intent: bool,
+    pub unembedded_fonts: Vec<String>,
+    pub issues: Vec<String>,                // Why the file fails, in plain words
+}
+
+fn is_script(object: &Object) -> bool {
+    object.as_dict().ok().and_then(|d| d.get(b"S").and_then(Object::as_name).ok()) == Some(b"JavaScript".as_slice())
+}
+
+/// Value of an XMP property written either as an attribute or as an element
+fn xmp_property(xmp: &str, name: &str) -> Option<String> {
+    if let Some(start) = xmp.find(&format!("{}=\"", name)) {
+        return xmp[start + name.len() + 2..].split('"').next().map(str::to_string);
+    }
+    let start = xmp.find(&format!("<{}>", name))? + name.len() + 2;
+    xmp[start..].split('<').next().map(|value| value.trim().to_string())
+}
+
+fn xmp_packet(doc: &Document) -> Option<String> {
+    let catalog = doc.get_dictionary(catalog_id(doc)?).ok()?;
+    let stream = resolve(doc, catalog.get(b"Metadata").ok()?).as_stream().ok()?;
+    let bytes = stream.decompressed_content().unwrap_or_else(|_| stream.content.clone());
+    Some(String::from_utf8_lossy(&bytes).to_string())
+}
+
+fn has_output_intent(doc: &Document) -> bool {
+    catalog_id(doc)
+        .and_then(|id| doc.get_dictionary(id).ok())
+        .and_then(|catalog| catalog.get(b"OutputIntents").ok())
+        .and_then(|intents| resolve(doc, intents).as_array().ok())
+        .is_some_and(|intents| intents.iter().any(|intent| {
+            resolve(doc, intent).as_dict().ok()
+                .and_then(|d| d.get(b"S").and_then(Object::as_name).ok()) == Some(b"GTS_PDFA1".as_slice())
+        }))
+}
+
+/// Base names of fonts without an embedded font program (Type 3 glyphs are plain PDF drawing)
+fn unembedded_fonts(doc: &Document) -> Vec<String> {
+    let mut names = Vec::new();
+    for object in doc.objects.values() {
+        let Ok(font) = object.as_dict() else { continue };
+        if font.get(b"Type").and_then(Object::as_name).ok() != Some(b"Font".as_slice()) {
+            continue;
+        }
+        // Type0 fonts are judged by their descendant font, which is a font object of its own
+        if matches!(font.get(b"Subtype").and_then(Object::as_name), Ok(b"Type0" | b"Type3")) {
+            continue;
+        }
+        let embedded = font.get(b"FontDescriptor").ok()
+            .and_then(|d| resolve(doc, d).as_dict().ok())
+            .is_some_and(|d| d.has(b"FontFile") || d.has(b"FontFile2") || d.has(b"FontFile3"));
+        if !embedded {
+            let name = font.get(b"BaseFont").and_then(Object::as_name)
+                .map(|n| String::from_utf8_lossy(n).to_string())
+                .unwrap_or_else(|_| "(unnamed)".to_string());
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+    }
+    names
+}
+
+fn inspect(doc: &Document, encrypted: bool) -> PdfaReport {
+    let xmp = xmp_packet(doc);
+    let claimed = pdfa_claim(doc).map(|(part, conformance)| format!("PDF/A-{}{}", part, conformance.to_uppercase()));
+    let has_output_intent = has_output_intent(doc);
+    let unembedded_fonts = unembedded_fonts(doc);
+
+    let mut issues = Vec::new();
+    if encrypted {
+        issues.push("The file is encrypted".to_string());
+    }
+    if xmp.is_none() {
+        issues.push("No XMP metadata".to_string());
+    } else if claimed.is_none() {
+        issues.push("XMP metadata does not declare PDF/A conformance".to_string());
+    }
+    if !has_output_intent {
+        issues.push("No PDF/A output intent (colour profile)".to_string());
+    }
+    if !unembedded_fonts.is_empty() {
+        issues.push(format!("Fonts not embedded: {}", unembedded_fonts.join(", ")));
+    }
+    if doc.objects.values().any(is_script) {
+        issues.push("Contains JavaScript".to_string());
+    }
+    if doc.trailer.get(b"ID").is_err() {
+        issues.push("No file identifier".to_string());
+    }
+
+    PdfaReport {
+        compliant: issues.is_empty(),
+        claimed,
+        encrypted,
+        has_xmp_metadata: xmp.is_some(),
+        has_output_intent,
+        unembedded_fonts,
+        issues,
+    }
+}
+
+/// Check a PDF against the basic PDF/A requirements
+pub fn check_pdfa(input_path: String, password: Option<String>) -> Result<PdfaReport, String> {
+    info!("🗄️ Checking PDF/A: {}", input_path);
+
+    // Files with an empty user password load already decrypted, so the encryption state tells them apart
+    let (doc, encrypted) = match Document::load(&input_path) {
+        Ok(doc) if !doc.is_encrypted() => {
+            let encrypted = doc.encryption_state.is_some();
+            (doc, encrypted)
+        }
+        _ => (open_pdf(&input_path, password.as_deref())?, true),
+    };
+    let report = inspect(&doc, encrypted);
+
+    info!("✅ PDF/A check: {} ({} issues)", input_path, report.issues.len());
+    Ok(report)
+}
+
+/// Drop document, page and field scripts (PDF/A forbids JavaScript)
+fn remove_javascript(doc: &mut Document) {
+    let scripts: HashSet<ObjectId> = doc.objects.iter()
+        .filter(|(_, object)| is_script(object))
+        .map(|(id, _)| *id)
+        .collect();
+    for object in doc.objects.values_mut() {
+        let Ok(dict) = object.as_dict_mut() else { continue };
+        dict.remove(b"AA");
+        dict.remove(b"JavaScript"); // Names dictionary entry
+        for key in [b"A".as_slice(), b"OpenAction".as_slice()] {
+            let script = match dict.get(key) {
+                Ok(Object::Reference(id)) => scripts.contains(id),
+                Ok(action) => is_script(action),
+                Err(_) => false,
+            };
+            if script {
+                dict.remove(key);
+            }
+        }
+    }
+}
+
+pub fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// The PDF/A part and conformance level declared in the XMP metadata, e.g. ("2", "B")
+pub fn pdfa_claim(doc: &Document) -> Option<(String, String)> {
+    let xmp = xmp_packet(doc)?;
+    let part = xmp_property(&xmp, "pdfaid:part")?;
+    Some((part, xmp_property(&xmp, "pdfaid:conformance").unwrap_or_default()))
+}
+
+/// XMP packet mirroring the information dictionary, declaring `claim` (part, conformance) if given
+pub fn build_xmp(metadata: &PdfMetadata, claim: Option<(&str, &str)>) -> String {
+    let mut properties = Vec::new();
+    if let Some((part, conformance)) = claim {
+        properties.push(format!("<pdfaid:part>{}</pdfaid:part>", escape_xml(part)));
+        if !conformance.is_empty() {
+            properties.push(format!("<pdfaid:conformance>{}</pdfaid:conformance>", escape_xml(conformance)));
+        }
+    }
+    let alt = |tag: &str, value: &str| {
+        format!("<{0}><rdf:Alt><rdf:li xml:lang=\"x-default\">{1}</rdf:li></rdf:Alt></{0}>", tag, escape_xml(value))
+    };
+    if let Some(title) = &metadata.title {
+        properties.push(alt("dc:title", title));
+    }
+    if let Some(author) = &metadata.author {
+        properties.push(format!("<dc:creator><rdf:Seq><rdf:li>{}</rdf:li></rdf:Seq></dc:creator>", escape_xml(author)));
+    }
+    if let Some(subject) = &metadata.subject {
+        properties.push(alt("dc:description", subject));
+    }
+    let simple: [(&str, &Option<String>); 5] = [
+        ("pdf:Keywords", &metadata.keywords),
+        ("pdf:Producer", &metadata.producer),
+        ("xmp:CreatorTool", &metadata.creator),
+        ("xmp:CreateDate", &metadata.creation_date),
+        ("xmp:ModifyDate", &metadata.modification_date),
+    ];
+    for (tag, value) in simple {
+        if let Some(value) = value {
+            properties.push(format!("<{0}>{1}</{0}>", tag, escape_xml(value)));
+        }
+    }
+
+    let body = properties.join("\n");
+    [
+        "<?xpacket begin=\"\u{feff}\" id=\"W5M0MpCehiHzreSzNTczkc9d\"?>",
+        "<x:xmpmeta xmlns:x=\"adobe:ns:meta/\">",
+        "<rdf:RDF xmlns:rdf=\"http://www.w3.org/1999/02/22-rdf-syntax-ns#\">",
+        "<rdf:Description rdf:about=\"\" xmlns:dc=\"http://purl.org/dc/elements/1.1/\" \
+         xmlns:pdf=\"http://ns.adobe.com/pdf/1.3/\" xmlns:xmp=\"http://ns.adobe.com/xap/1.0/\" \
+         xmlns:pdfaid=\"http://www.aiim.org/pdfa/ns/id/\">",
+        body.as_str(),
+        "</rdf:Description>",
+        "</rdf:RDF>",
+        "</x:xmpmeta>",
+        "<?xpacket end=\"w\"?>",
+    ].join("\n")
+}
+
+/// Best-effort conversion to PDF/A-2B: removes encryption and JavaScript, writes XMP metadata and
+/// an sRGB output intent. The file only declares PDF/A-2B when it then passes the basic checks;
+/// fonts that are not embedded cannot be fixed here and are named in the message.
+pub fn convert_to_pdfa(
+    input_path: String,
+    output_path: String,
+    password: Option<String>,
+) -> Result<ConversionResult, String> {
+    info!("🗄️ Converting to PDF/A-2B: {}", input_path);
+
+    let mut doc = open_pdf(&input_path, password.as_deref())?;
+    doc.encryption_state = None;
+    if doc.version.as_str() > "1.7" {
+        doc.version = "1.7".to_string();
+    }
+    ensure_file_id(&mut doc);
+    remove_javascript(&mut doc);
+    doc.prune_objects();
+
+    let mut info = info_dict(&doc).cloned().unwrap_or_default();
+    info.set("ModDate", Object::string_literal(pdf_date(&chrono::Local::now())));
+    let info_id = doc.add_object(info);
+    doc.trailer.set("Info", info_id);
+
+    // Metadata streams must stay unfiltered in PDF/A
+    let metadata = read_metadata(&doc);
+    let xmp = Stream::new(dictionary! { "Type" => "Metadata", "Subtype" => "XML" }, build_xmp(&metadata, Some(("2", "B"))).into_bytes())
+        .with_compression(false);
+    let xmp_id = doc.add_object(xmp);
+    let intent_id = (!has_output_intent(&doc)).then(|| add_srgb_output_intent(&mut doc));
+
+    let catalog_id = catalog_id(&doc).ok_or("PDF has no catalog")?;
+    let catalog = doc.get_object_mut(catalog_id)
+        .and_then(Object::as_dict_mut)
+        .map_err(|e| format!("Invalid PDF catalog: {}", e))?;
+    catalog.set("Metadata", xmp_id);
+    if let Some(intent_id) = intent_id {
+        catalog.set("OutputIntents", vec![Object::Reference(intent_id)]);
+    }
+
+    // Checked with the declaration in place; a file that still fails must not claim conformance
+    let report = inspect(&doc, false);
+    if !report.compliant {
+        if let Ok(Object::Stream(xmp)) = doc.get_object_mut(xmp_id) {
+            xmp.set_plain_content(build_xmp(&metadata, None).into_bytes());
+        }
+    }
+    doc.compress();
+    doc.save(&output_path)
+        .map_err(|e| format!("Failed to save PDF/A: {}", e))?;
+
+    let output_size = fs::metadata(&output_path).map(|m| m.len()).ok();
+
+    info!("✅ PDF/A conversion done: {} ({} issues left)", output_path, report.issues.len());
+    Ok(ConversionResult {
+        success: true,
+        output_path,
+        message: if report.compliant {
+            "Converted to PDF/A-2B".to_string()
+        } else {
+            format!("Not marked as PDF/A-2B; still failing: {}", report.issues.join("; "))
+        },
+        output_size,
+    })
+}
@@ -0,0 +1,97 @@
+//! PDF Render - turn PDF pages into PNG/JPEG images with PDFium (no LibreOffice/ImageMagick needed)
+//! The PDFium library ships in the app resources (src-tauri/pdfium/<platform>, see the README there);
+//! a copy next to the executable or a system-wide install is used as a fallback.
+
+use std::path::{Path, PathBuf};
+use log::info;
+use pdfium_render::prelude::*;
+use tauri::{AppHandle, Manager};
+use crate::pdf_pages::select_pages;
+use crate::pdf_security::{PASSWORD_REQUIRED, WRONG_PASSWORD};
+
+const DEFAULT_DPI: u32 = 150;
+
+fn library_name() -> &'static str {
+    if cfg!(windows) {
+        "pdfium.dll"
+    } else if cfg!(target_os = "macos") {
+        "libpdfium.dylib"
+    } else {
+        "libpdfium.so"
+    }
+}
+
+fn bind_pdfium(app: &AppHandle) -> Result<Pdfium, String> {
+    let mut candidates: Vec<PathBuf> = Vec::new();
+    if let Ok(resources) = app.path().resource_dir() {
+        candidates.push(resources.join("pdfium").join(library_name()));
+    }
+    if let Some(exe_dir) = std::env::current_exe().ok().and_then(|exe| exe.parent().map(Path::to_path_buf)) {
+        candidates.push(exe_dir.join(library_name()));
+    }
+    for library in candidates.iter().filter(|path| path.is_file()) {
+        if let Ok(bindings) = Pdfium::bind_to_library(library.to_string_lossy().to_string()) {
+            return Ok(Pdfium::new(bindings));
+        }
+    }
+    Pdfium::bind_to_system_library()
+        .map(Pdfium::new)
+        .map_err(|e| format!("PDF renderer (PDFium) not available: {:?}", e))
+}
+
+/// Render pages to `<output_dir>/<name>_page_001.<format>`. `pages` is a selection like "1-3,5"
+/// (every page when empty); `format` is png (default) or jpg.
+pub fn pdf_to_images(
+    app: AppHandle,
+    input_path: String,
+    output_dir: String,
+    dpi: Option<u32>,
+    format: Option<String>,
+    pages: Option<String>,
+    password: Option<String>,
+) -> Result<Vec<String>, String> {
+    let dpi = dpi.unwrap_or(DEFAULT_DPI).clamp(36, 600);
+    let password = password.filter(|p| !p.is_empty());
+    let extension = match format.as_deref().map(str::to_lowercase).as_deref() {
+        None | Some("png") => "png",
+        Some("jpg") | Some("jpeg") => "jpg",
+        Some(other) => return Err(format!("Unsupported image format: {} (use png or jpg)", other)),
+    };
+    std::fs::create_dir_all(&output_dir)
+        .map_err(|e| format!("Failed to create output folder: {}", e))?;
+
+    let pdfium = bind_pdfium(&app)?;
+    let document = pdfium.load_pdf_from_file(&input_path, password.as_deref())
+        .map_err(|e| match e {
+            PdfiumError::PdfiumLibraryInternalError(PdfiumInternalError::PasswordError) => if password.is_some() {
+                format!("{} for {}", WRONG_PASSWORD, input_path)
+            } else {
+                format!("{}: {}", PASSWORD_REQUIRED, input_path)
+            },
+            other => format!("Failed to load {}: {:?}", input_path, other),
+        })?;
+
+    let document_pages = document.pages();
+    let selected = select_pages(pages.as_deref(), document_pages.len() as usize)?;
+    let stem = Path::new(&input_path).file_stem().and_then(|s| s.to_str()).unwrap_or("page");
+    let config = PdfRenderConfig::new().scale_page_by_factor(dpi as f32 / 72.0);
+
+    info!("🖼️ Rendering {} pages of {} at {} dpi", selected.len(), input_path, dpi);
+
+    let mut outputs = Vec::with_capacity(selected.len());
+    for number in selected {
+        let page = document_pages.get((number - 1) as u16)
+            .map_err(|e| format!("Failed to read page {}: {:?}", number, e))?;
+        let image = page.render_with_config(&config)
+            .map_err(|e| format!("Failed to render page {}: {:?}", number, e))?
+            .as_image()
+            .into_rgb8();
+        let path = Path::new(&output_dir).join(format!("{}_page_{:03}.{}", stem, number, extension));
+        image.save(&path)
+            .map_err(|e| format!("Failed to save page {}: {}", number, e))?;
+        outputs.push(path.to_string_lossy().to_string());
+    }
+
+    info!("✅ Rendered {} pages to {}", outputs.len(), output_dir);
+    Ok(outputs)
+}
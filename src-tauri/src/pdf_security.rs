@@ -0,0 +1,148 @@
+//! PDF Security - password protection with the standard security handler (AES-128 / AES-256)
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::sync::Arc;
+use log::info;
+use lopdf::encryption::crypt_filters::{Aes128CryptFilter, Aes256CryptFilter, CryptFilter};
+use lopdf::{Document, EncryptionState, EncryptionVersion, Object, Permissions, StringFormat};
+use rand::RngCore;
+use crate::bundled_converter::ConversionResult;
+
+/// Map permission names from the UI to PDF permission flags
+fn parse_permissions(names: &[String]) -> Result<Permissions, String> {
+    let mut permissions = Permissions::empty();
+    for name in names {
+        permissions |= match name.to_lowercase().as_str() {
+            "print" => Permissions::PRINTABLE,
+            "print_high" => Permissions::PRINTABLE | Permissions::PRINTABLE_IN_HIGH_QUALITY,
+            "modify" => Permissions::MODIFIABLE,
+            "copy" => Permissions::COPYABLE,
+            "annotate" => Permissions::ANNOTABLE,
+            "fill_forms" => Permissions::FILLABLE,
+            "accessibility" => Permissions::COPYABLE_FOR_ACCESSIBILITY,
+            "assemble" => Permissions::ASSEMBLABLE,
+            other => return Err(format!("Unknown permission: {}", other)),
+        };
+    }
+    Ok(permissions)
+}
+
+/// Error prefixes the UI uses to tell a password prompt apart from a damaged file
+pub const PASSWORD_REQUIRED: &str = "PDF is password protected";
+pub const WRONG_PASSWORD: &str = "Wrong password";
+
+pub fn is_password_error(error: &str) -> bool {
+    error.starts_with(PASSWORD_REQUIRED) || error.starts_with(WRONG_PASSWORD)
+}
+
+/// Load a PDF, decrypting it with `password` when it is protected.
+/// Every PDF operation that reads an input goes through here.
+pub fn open_pdf(path: &str, password: Option<&str>) -> Result<Document, String> {
+    let mut doc = match Document::load(path) {
+        Ok(doc) => doc,
+        Err(lopdf::Error::Decryption(_)) => return Err(format!("{}: {}", PASSWORD_REQUIRED, path)),
+        Err(e) => return Err(format!("Failed to load {}: {} (the file may be damaged or not a PDF)", path, e)),
+    };
+    // Files with an empty user password are decrypted on load; anything left needs the password
+    if doc.is_encrypted() {
+        match password.filter(|p| !p.is_empty()) {
+            Some(password) => doc.decrypt(password)
+                .map_err(|_| format!("{} for {}", WRONG_PASSWORD, path))?,
+            None => return Err(format!("{}: {}", PASSWORD_REQUIRED, path)),
+        }
+        doc.trailer.remove(b"Encrypt");
+    }
+    Ok(doc)
+}
+
+fn random_bytes<const N: usize>() -> [u8; N] {
+    let mut bytes = [0u8; N];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    bytes
+}
+
+/// AES-128 and PDF/A need the file identifier; documents from some generators have none
+pub fn ensure_file_id(doc: &mut Document) {
+    if doc.trailer.get(b"ID").is_err() {
+        let id = random_bytes::<16>().to_vec();
+        doc.trailer.set("ID", vec![
+            Object::String(id.clone(), StringFormat::Hexadecimal),
+            Object::String(id, StringFormat::Hexadecimal),
+        ]);
+    }
+}
+
+/// Password-protect a PDF. `permissions` lists what a user-password holder may do
+/// (print, print_high, modify, copy, annotate, fill_forms, accessibility, assemble);
+/// None allows everything. Without an owner password a random one is used, so the
+/// restrictions cannot be lifted. `key_length` is 128 or 256 (default).
+pub fn encrypt_pdf(
+    input_path: String,
+    output_path: String,
+    user_password: String,
+    owner_password: Option<String>,
+    permissions: Option<Vec<String>>,
+    key_length: Option<u32>,
+) -> Result<ConversionResult, String> {
+    let permissions = match permissions {
+        Some(names) => parse_permissions(&names)?,
+        None => Permissions::all(),
+    };
+    let owner_password = owner_password
+        .filter(|p| !p.is_empty())
+        .unwrap_or_else(|| random_bytes::<16>().iter().map(|b| format!("{:02x}", b)).collect());
+    let key_length = key_length.unwrap_or(256);
+
+    info!("🔒 Encrypting {} (AES-{})", input_path, key_length);
+
+    let mut doc = open_pdf(&input_path, None)?;
+    ensure_file_id(&mut doc);
+
+    let state = match key_length {
+        128 => {
+            let filter: Arc<dyn CryptFilter> = Arc::new(Aes128CryptFilter);
+            EncryptionState::try_from(EncryptionVersion::V4 {
+                document: &doc,
+                encrypt_metadata: true,
+                crypt_filters: BTreeMap::from([(b"StdCF".to_vec(), filter)]),
+                stream_filter: b"StdCF".to_vec(),
+                string_filter: b"StdCF".to_vec(),
+                owner_password: &owner_password,
+                user_password: &user_password,
+                permissions,
+            })
+        }
+        256 => {
+            let filter: Arc<dyn CryptFilter> = Arc::new(Aes256CryptFilter);
+            let file_key = random_bytes::<32>();
+            EncryptionState::try_from(EncryptionVersion::V5 {
+                encrypt_metadata: true,
+                crypt_filters: BTreeMap::from([(b"StdCF".to_vec(), filter)]),
+                file_encryption_key: &file_key,
+                stream_filter: b"StdCF".to_vec(),
+                string_filter: b"StdCF".to_vec(),
+                owner_password: &owner_password,
+                user_password: &user_password,
+                permissions,
+            })
+        }
+        other => return Err(format!("Unsupported key length: {} (use 128 or 256)", other)),
+    }
+    .map_err(|e| format!("Failed to set up encryption: {}", e))?;
+
+    doc.encrypt(&state)
+        .map_err(|e| format!("Failed to encrypt PDF: {}", e))?;
+    doc.save(&output_path)
+        .map_err(|e| format!("Failed to save encrypted PDF: {}", e))?;
+
+    let output_size = fs::metadata(&output_path).map(|m| m.len()).ok();
+
+    info!("✅ PDF encrypted: {}", output_path);
+    Ok(ConversionResult {
+        success: true,
+        output_path,
+        message: format!("PDF protected with AES-{}", key_length),
+        output_size,
+    })
+}
@@ -0,0 +1,168 @@
+//! PDF Watermark - stamp diagonal text ("CONFIDENTIAL") or a logo over selected pages
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use log::info;
+use lopdf::content::Operation;
+use lopdf::{dictionary, Document, Object, ObjectId, Stream};
+use crate::bundled_converter::ConversionResult;
+use crate::pdf_pages::{add_page_resource, overlay_page, page_box, select_pages, standard_font};
+use crate::pdf_security::open_pdf;
+use crate::pdf_writer::{pdf_text, text_width};
+
+/// Resource names used for the stamp; unusual enough not to clash with a page's own
+const FONT_NAME: &str = "AtWmFont";
+const STATE_NAME: &str = "AtWmGs";
+const IMAGE_NAME: &str = "AtWmImg";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatermarkOptions {
+    pub input_path: String,
+    pub output_path: String,
+    #[serde(default)]
+    pub text: Option<String>,          // e.g. "CONFIDENTIAL"
+    #[serde(default)]
+    pub image_path: Option<String>,    // PNG/JPEG logo, used instead of text
+    #[serde(default)]
+    pub position: Option<String>,      // diagonal (default), center, top, bottom
+    #[serde(default)]
+    pub opacity: Option<f32>,          // 0-1, default 0.25
+    #[serde(default)]
+    pub pages: Option<String>,         // "1-3,5"; every page when empty
+    #[serde(default)]
+    pub password: Option<String>,
+}
+
+/// Embed a logo as an RGB image with its alpha channel as a soft mask
+fn image_xobject(doc: &mut Document, path: &str) -> Result<(ObjectId, f32, f32), String> {
+    let rgba = image::open(path)
+        .map_err(|e| format!("Failed to load watermark image: {}", e))?
+        .to_rgba8();
+    let (width, height) = rgba.dimensions();
+    let rgb: Vec<u8> = rgba.pixels().flat_map(|p| [p[0], p[1], p[2]]).collect();
+    let alpha: Vec<u8> = rgba.pixels().map(|p| p[3]).collect();
+
+    let mask_id = doc.add_object(Stream::new(dictionary! {
+        "Type" => "XObject",
+        "Subtype" => "Image",
+        "Width" => width as i64,
+        "Height" => height as i64,
+        "ColorSpace" => "DeviceGray",
+        "BitsPerComponent" => 8,
+    }, alpha));
+    let image_id = doc.add_object(Stream::new(dictionary! {
+        "Type" => "XObject",
+        "Subtype" => "Image",
+        "Width" => width as i64,
+        "Height" => height as i64,
+        "ColorSpace" => "DeviceRGB",
+        "BitsPerComponent" => 8,
+        "SMask" => mask_id,
+    }, rgb));
+    Ok((image_id, width as f32, height as f32))
+}
+
+/// Text stamp: centred on the page (rotated along the diagonal), or along the top/bottom edge
+fn text_operations(text: &str, position: &str, page: (f32, f32, f32, f32)) -> Vec<Operation> {
+    let (x, y, width, height) = page;
+    let unit_width = text_width(text, 1.0).max(1.0);
+    let (angle, span, cx, cy) = match position {
+        "top" => (0.0, width * 0.5, x + width / 2.0, y + height - 36.0),
+        "bottom" => (0.0, width * 0.5, x + width / 2.0, y + 36.0),
+        "center" => (0.0, width * 0.7, x + width / 2.0, y + height / 2.0),
+        _ => (height.atan2(width), width.hypot(height) * 0.7, x + width / 2.0, y + height / 2.0),
+    };
+    let size = (span / unit_width).clamp(10.0, 160.0);
+    let (sin, cos) = angle.sin_cos();
+
+    vec![
+        Operation::new("q", vec![]),
+        Operation::new("gs", vec![Object::Name(STATE_NAME.into())]),
+        Operation::new("g", vec![0.6_f32.into()]),
+        Operation::new("cm", vec![cos.into(), sin.into(), (-sin).into(), cos.into(), cx.into(), cy.into()]),
+        Operation::new("BT", vec![]),
+        Operation::new("Tf", vec![Object::Name(FONT_NAME.into()), size.into()]),
+        Operation::new("Td", vec![(-unit_width * size / 2.0).into(), (-size * 0.35).into()]),
+        Operation::new("Tj", vec![pdf_text(text)]),
+        Operation::new("ET", vec![]),
+        Operation::new("Q", vec![]),
+    ]
+}
+
+/// Logo stamp, scaled to half the page width (a quarter along the top/bottom edge); never rotated
+fn image_operations(image_size: (f32, f32), position: &str, page: (f32, f32, f32, f32)) -> Vec<Operation> {
+    let (x, y, width, height) = page;
+    let draw_width = if matches!(position, "top" | "bottom") { width * 0.25 } else { width * 0.5 };
+    let draw_height = draw_width * image_size.1 / image_size.0.max(1.0);
+    let left = x + (width - draw_width) / 2.0;
+    let bottom = match position {
+        "top" => y + height - draw_height - 24.0,
+        "bottom" => y + 24.0,
+        _ => y + (height - draw_height) / 2.0,
+    };
+
+    vec![
+        Operation::new("q", vec![]),
+        Operation::new("gs", vec![Object::Name(STATE_NAME.into())]),
+        Operation::new("cm", vec![draw_width.into(), 0.into(), 0.into(), draw_height.into(), left.into(), bottom.into()]),
+        Operation::new("Do", vec![Object::Name(IMAGE_NAME.into())]),
+        Operation::new("Q", vec![]),
+    ]
+}
+
+/// Stamp text or a logo over the selected pages
+pub fn watermark_pdf(options: WatermarkOptions) -> Result<ConversionResult, String> {
+    let text = options.text.as_deref().map(str::trim).filter(|t| !t.is_empty());
+    let image_path = options.image_path.as_deref().filter(|p| !p.is_empty());
+    if text.is_none() && image_path.is_none() {
+        return Err("Enter watermark text or choose an image".to_string());
+    }
+    let position = options.position.as_deref().unwrap_or("diagonal").to_lowercase();
+    let opacity = options.opacity.unwrap_or(0.25).clamp(0.05, 1.0);
+
+    info!("💧 Watermarking {} ({})", options.input_path, position);
+
+    let mut doc = open_pdf(&options.input_path, options.password.as_deref())?;
+    let pages = doc.get_pages();
+    let selected = select_pages(options.pages.as_deref(), pages.len())?;
+
+    let state_id = doc.add_object(dictionary! {
+        "Type" => "ExtGState",
+        "ca" => opacity,
+        "CA" => opacity,
+    });
+    let (stamp_id, category, name, image_size) = match image_path {
+        Some(path) => {
+            let (image_id, width, height) = image_xobject(&mut doc, path)?;
+            (image_id, "XObject", IMAGE_NAME, (width, height))
+        }
+        None => (doc.add_object(standard_font("Helvetica-Bold")), "Font", FONT_NAME, (0.0, 0.0)),
+    };
+
+    for number in &selected {
+        let page_id = pages[&(*number as u32)];
+        let page = page_box(&doc, page_id);
+        add_page_resource(&mut doc, page_id, "ExtGState", STATE_NAME, state_id.into())?;
+        add_page_resource(&mut doc, page_id, category, name, stamp_id.into())?;
+        let operations = match (image_path, text) {
+            (Some(_), _) => image_operations(image_size, &position, page),
+            (None, Some(text)) => text_operations(text, &position, page),
+            (None, None) => unreachable!("checked above"),
+        };
+        overlay_page(&mut doc, page_id, operations)?;
+    }
+
+    doc.compress();
+    doc.save(&options.output_path)
+        .map_err(|e| format!("Failed to save watermarked PDF: {}", e))?;
+
+    let output_size = fs::metadata(&options.output_path).map(|m| m.len()).ok();
+
+    info!("✅ Watermark added to {} pages: {}", selected.len(), options.output_path);
+    Ok(ConversionResult {
+        success: true,
+        output_path: options.output_path,
+        message: format!("Watermark added to {} pages", selected.len()),
+        output_size,
+    })
+}
@@ -0,0 +1,359 @@
+//! PDF Writer - minimal text/table layout on top of lopdf
+//! Uses the standard Helvetica fonts (no embedding), so text is limited to WinAnsiEncoding
+//! (Latin-1 plus the euro sign, curly quotes, dashes and a few more)
+
+use lopdf::content::{Content, Operation};
+use log::warn;
+use lopdf::{dictionary, Document, Object, ObjectId, Stream, StringFormat};
+
+pub const A4_PORTRAIT: (f32, f32) = (595.0, 842.0);
+
+const MARGIN: f32 = 40.0;
+const FOOTER_SIZE: f32 = 8.0;
+
+/// Characters WinAnsiEncoding puts at 0x80-0x9F, where Latin-1 has control codes
+/// (0x81, 0x8D, 0x8F, 0x90 and 0x9D are unused)
+const WIN_ANSI_HIGH: [(u8, char); 27] = [
+    (0x80, '€'), (0x82, '‚'), (0x83, 'ƒ'), (0x84, '„'), (0x85, '…'), (0x86, '†'), (0x87, '‡'),
+    (0x88, 'ˆ'), (0x89, '‰'), (0x8A, 'Š'), (0x8B, '‹'), (0x8C, 'Œ'), (0x8E, 'Ž'), (0x91, '‘'),
+    (0x92, '’'), (0x93, '“'), (0x94, '”'), (0x95, '•'), (0x96, '–'), (0x97, '—'), (0x98, '˜'),
+    (0x99, '™'), (0x9A, 'š'), (0x9B, '›'), (0x9C, 'œ'), (0x9E, 'ž'), (0x9F, 'Ÿ'),
+];
+
+/// WinAnsiEncoding byte for a character, if it has one
+fn win_ansi_byte(c: char) -> Option<u8> {
+    match c as u32 {
+        0x20..=0x7E | 0xA0..=0xFF => Some(c as u8),
+        _ => WIN_ANSI_HIGH.iter().find(|(_, mapped)| *mapped == c).map(|(byte, _)| *byte),
+    }
+}
+
+/// Encode text as WinAnsi bytes; tabs and line breaks become spaces, and other characters the
+/// encoding lacks become '?' (logged, since the text then differs from what was asked for)
+pub fn pdf_text(text: &str) -> Object {
+    let mut missing: Vec<char> = Vec::new();
+    let bytes = text.chars()
+        .map(|c| match c {
+            '\t' | '\n' | '\r' => b' ',
+            '\u{2212}' | '\u{2010}' | '\u{2011}' => b'-',   // Minus sign and hyphens
+            _ => win_ansi_byte(c).unwrap_or_else(|| {
+                if !missing.contains(&c) {
+                    missing.push(c);
+                }
+                b'?'
+            }),
+        })
+        .collect();
+    if !missing.is_empty() {
+        warn!("Characters not available in the standard PDF fonts were replaced with '?': {}", missing.iter().collect::<String>());
+    }
+    Object::String(bytes, StringFormat::Literal)
+}
+
+/// Approximate Helvetica advance width (in 1/1000 em) - good enough for wrapping and centering
+fn char_width(c: char) -> f32 {
+    match c {
+        'i' | 'j' | 'l' | '.' | ',' | '\'' | '|' | ':' | ';' | '!' => 278.0,
+        ' ' | 'f' | 't' | 'I' | '(' | ')' | '[' | ']' | '/' => 300.0,
+        'r' => 333.0,
+        'm' | 'M' | 'W' => 833.0,
+        'w' => 722.0,
+        'A'..='Z' => 667.0,
+        '0'..='9' => 556.0,
+        _ => 556.0,
+    }
+}
+
+pub fn text_width(text: &str, size: f32) -> f32 {
+    text.chars().map(char_width).sum::<f32>() * size / 1000.0
+}
+
+/// Word-wrap text to fit a width
+pub fn wrap_text(text: &str, size: f32, max_width: f32) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    for word in text.split_whitespace() {
+        let candidate = if current.is_empty() { word.to_string() } else { format!("{} {}", current, word) };
+        if text_width(&candidate, size) > max_width && !current.is_empty() {
+            lines.push(std::mem::replace(&mut current, word.to_string()));
+        } else {
+            current = candidate;
+        }
+    }
+    if !current.is_empty() || lines.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
+
+pub struct PdfWriter {
+    doc: Document,
+    pages_id: ObjectId,
+    page_size: (f32, f32),
+    pages: Vec<Vec<Operation>>,
+    y: f32,
+    footer: Option<String>,
+    images: Vec<ObjectId>,
+}
+
+impl PdfWriter {
+    pub fn new(page_size: (f32, f32)) -> Self {
+        let mut doc = Document::with_version("1.5");
+        let pages_id = doc.new_object_id();
+        let mut writer = PdfWriter {
+            doc,
+            pages_id,
+            page_size,
+            pages: Vec::new(),
+            y: 0.0,
+            footer: None,
+            images: Vec::new(),
+        };
+        writer.new_page();
+        writer
+    }
+
+    /// Footer text printed on every page next to "Page X of Y"
+    pub fn set_footer(&mut self, footer: &str) {
+        self.footer = Some(footer.to_string());
+    }
+
+    pub fn width(&self) -> f32 { self.page_size.0 }
+    pub fn content_width(&self) -> f32 { self.page_size.0 - 2.0 * MARGIN }
+    pub fn left(&self) -> f32 { MARGIN }
+    pub fn cursor(&self) -> f32 { self.y }
+
+    pub fn new_page(&mut self) {
+        self.pages.push(Vec::new());
+        self.y = self.page_size.1 - MARGIN;
+    }
+
+    /// Start a new page if less than `height` points remain above the bottom margin
+    pub fn ensure_space(&mut self, height: f32) {
+        if self.y - height < MARGIN + FOOTER_SIZE * 2.0 {
+            self.new_page();
+        }
+    }
+
+    pub fn move_down(&mut self, amount: f32) {
+        self.y -= amount;
+    }
+
+    fn ops(&mut self) -> &mut Vec<Operation> {
+        self.pages.last_mut().expect("writer always has a page")
+    }
+
+    /// Draw text at an absolute position (baseline)
+    pub fn text_at(&mut self, x: f32, y: f32, size: f32, bold: bool, text: &str) {
+        let font = if bold { "F2" } else { "F1" };
+        let ops = self.ops();
+        ops.push(Operation::new("BT", vec![]));
+        ops.push(Operation::new("Tf", vec![font.into(), size.into()]));
+        ops.push(Operation::new("Td", vec![x.into(), y.into()]));
+        ops.push(Operation::new("Tj", vec![pdf_text(text)]));
+        ops.push(Operation::new("ET", vec![]));
+    }
+
+    /// Fill a rectangle with an RGB colour (components 0.0-1.0)
+    pub fn fill_rect(&mut self, x: f32, y: f32, w: f32, h: f32, rgb: (f32, f32, f32)) {
+        let ops = self.ops();
+        ops.push(Operation::new("q", vec![]));
+        ops.push(Operation::new("rg", vec![rgb.0.into(), rgb.1.into(), rgb.2.into()]));
+        ops.push(Operation::new("re", vec![x.into(), y.into(), w.into(), h.into()]));
+        ops.push(Operation::new("f", vec![]));
+        ops.push(Operation::new("Q", vec![]));
+    }
+
+    /// Embed JPEG data once; returns the name to draw it with `image_at`
+    pub fn add_jpeg(&mut self, jpeg: Vec<u8>, width: u32, height: u32) -> String {
+        let image_id = self.doc.add_object(Stream::new(dictionary! {
+            "Type" => "XObject",
+            "Subtype" => "Image",
+            "Width" => width as i64,
+            "Height" => height as i64,
+            "ColorSpace" => "DeviceRGB",
+            "BitsPerComponent" => 8,
+            "Filter" => "DCTDecode",
+        }, jpeg).with_compression(false));
+        self.images.push(image_id);
+        format!("Im{}", self.images.len())
+    }
+
+    /// Draw an embedded image scaled into the box with lower-left corner (x, y)
+    pub fn image_at(&mut self, name: &str, x: f32, y: f32, w: f32, h: f32) {
+        let ops = self.ops();
+        ops.push(Operation::new("q", vec![]));
+        ops.push(Operation::new("cm", vec![w.into(), 0.into(), 0.into(), h.into(), x.into(), y.into()]));
+        ops.push(Operation::new("Do", vec![Object::Name(name.as_bytes().to_vec())]));
+        ops.push(Operation::new("Q", vec![]));
+    }
+
+    pub fn hline(&mut self, x1: f32, x2: f32, y: f32, width: f32) {
+        let ops = self.ops();
+        ops.push(Operation::new("q", vec![]));
+        ops.push(Operation::new("w", vec![width.into()]));
+        ops.push(Operation::new("m", vec![x1.into(), y.into()]));
+        ops.push(Operation::new("l", vec![x2.into(), y.into()]));
+        ops.push(Operation::new("S", vec![]));
+        ops.push(Operation::new("Q", vec![]));
+    }
+
+    /// Flowing, word-wrapped paragraph at the cursor
+    pub fn paragraph(&mut self, text: &str, size: f32, bold: bool) {
+        let line_height = size * 1.35;
+        for line in wrap_text(text, size, self.content_width()) {
+            self.ensure_space(line_height);
+            self.y -= size;
+            let (x, y) = (self.left(), self.y);
+            self.text_at(x, y, size, bold, &line);
+            self.y -= line_height - size;
+        }
+    }
+
+    /// Word-wrapped text starting `indent` points in, with an optional marker (bullet, number)
+    /// in the gutter before the first line
+    pub fn indented(&mut self, indent: f32, marker: &str, text: &str, size: f32, bold: bool) {
+        let line_height = size * 1.35;
+        let left = MARGIN + indent;
+        for (index, line) in wrap_text(text, size, self.content_width() - indent).iter().enumerate() {
+            self.ensure_space(line_height);
+            self.y -= size;
+            let y = self.y;
+            if index == 0 && !marker.is_empty() {
+                self.text_at(left - text_width(marker, size) - 4.0, y, size, false, marker);
+            }
+            self.text_at(left, y, size, bold, line);
+            self.y -= line_height - size;
+        }
+    }
+
+    /// Centered single line at the cursor
+    pub fn centered(&mut self, text: &str, size: f32, bold: bool) {
+        self.ensure_space(size * 1.35);
+        self.y -= size;
+        let x = (self.width() - text_width(text, size)) / 2.0;
+        let y = self.y;
+        self.text_at(x.max(MARGIN), y, size, bold, text);
+        self.y -= size * 0.35;
+    }
+
+    /// Simple grid table; header is repeated when the table breaks across pages.
+    /// `widths` are relative column weights.
+    pub fn table(&mut self, headers: &[&str], rows: &[Vec<String>], widths: &[f32], bold_last_row: bool) {
+        let size = 9.0;
+        let row_height = 16.0;
+        let total: f32 = widths.iter().sum();
+        let columns: Vec<f32> = widths.iter().map(|w| w / total * self.content_width()).collect();
+
+        let draw_header = |writer: &mut PdfWriter| {
+            let (left, width, y) = (writer.left(), writer.content_width(), writer.y);
+            writer.fill_rect(left, y - row_height, width, row_height, (0.85, 0.88, 0.95));
+            let mut x = left;
+            for (i, header) in headers.iter().enumerate() {
+                writer.text_at(x + 3.0, y - row_height + 5.0, size, true, header);
+                x += columns[i];
+            }
+            writer.y -= row_height;
+        };
+
+        self.ensure_space(row_height * 2.0);
+        draw_header(self);
+
+        for (index, row) in rows.iter().enumerate() {
+            if self.y - row_height < MARGIN + FOOTER_SIZE * 2.0 {
+                self.new_page();
+                draw_header(self);
+            }
+            let bold = bold_last_row && index == rows.len() - 1;
+            let (left, y) = (self.left(), self.y);
+            let mut x = left;
+            for (i, cell) in row.iter().enumerate().take(columns.len()) {
+                // Truncate cells that would overflow their column
+                let mut text = cell.clone();
+                while text.len() > 1 && text_width(&text, size) > columns[i] - 6.0 {
+                    text.pop();
+                }
+                self.text_at(x + 3.0, y - row_height + 5.0, size, bold, &text);
+                x += columns[i];
+            }
+            self.y -= row_height;
+            let right = left + self.content_width();
+            self.hline(left, right, self.y, 0.3);
+        }
+    }
+
+    /// Finalize the document (fonts, page tree, footers) and write it to disk
+    pub fn save(mut self, output_path: &str) -> Result<(), String> {
+        let font_regular = self.doc.add_object(dictionary! {
+            "Type" => "Font",
+            "Subtype" => "Type1",
+            "BaseFont" => "Helvetica",
+            "Encoding" => "WinAnsiEncoding",
+        });
+        let font_bold = self.doc.add_object(dictionary! {
+            "Type" => "Font",
+            "Subtype" => "Type1",
+            "BaseFont" => "Helvetica-Bold",
+            "Encoding" => "WinAnsiEncoding",
+        });
+        let mut images = lopdf::Dictionary::new();
+        for (index, image_id) in self.images.iter().enumerate() {
+            images.set(format!("Im{}", index + 1), *image_id);
+        }
+        let resources_id = self.doc.add_object(dictionary! {
+            "Font" => dictionary! {
+                "F1" => font_regular,
+                "F2" => font_bold,
+            },
+            "XObject" => images,
+        });
+
+        let page_count = self.pages.len();
+        let (width, height) = self.page_size;
+        let pages = std::mem::take(&mut self.pages);
+        let mut kids: Vec<Object> = Vec::with_capacity(page_count);
+
+        for (index, mut operations) in pages.into_iter().enumerate() {
+            let label = format!("Page {} of {}", index + 1, page_count);
+            let footer_ops = [
+                (MARGIN, self.footer.clone().unwrap_or_default()),
+                (width - MARGIN - text_width(&label, FOOTER_SIZE), label),
+            ];
+            for (x, text) in footer_ops {
+                operations.push(Operation::new("BT", vec![]));
+                operations.push(Operation::new("Tf", vec!["F1".into(), FOOTER_SIZE.into()]));
+                operations.push(Operation::new("Td", vec![x.into(), (MARGIN / 2.0).into()]));
+                operations.push(Operation::new("Tj", vec![pdf_text(&text)]));
+                operations.push(Operation::new("ET", vec![]));
+            }
+
+            let content = Content { operations }.encode()
+                .map_err(|e| format!("Failed to encode page content: {}", e))?;
+            let content_id = self.doc.add_object(Stream::new(dictionary! {}, content));
+            let page_id = self.doc.add_object(dictionary! {
+                "Type" => "Page",
+                "Parent" => self.pages_id,
+                "Contents" => content_id,
+                "Resources" => resources_id,
+                "MediaBox" => vec![0.into(), 0.into(), width.into(), height.into()],
+            });
+            kids.push(page_id.into());
+        }
+
+        self.doc.objects.insert(self.pages_id, Object::Dictionary(dictionary! {
+            "Type" => "Pages",
+            "Kids" => kids,
+            "Count" => page_count as i64,
+        }));
+        let catalog_id = self.doc.add_object(dictionary! {
+            "Type" => "Catalog",
+            "Pages" => self.pages_id,
+        });
+        self.doc.trailer.set("Root", catalog_id);
+        self.doc.compress();
+
+        self.doc.save(output_path)
+            .map_err(|e| format!("Failed to save PDF: {}", e))?;
+        Ok(())
+    }
+}
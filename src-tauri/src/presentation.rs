@@ -0,0 +1,87 @@
+//! Presentation - lecture slides (PPTX, PPT, ODP) to PDF or one PNG per slide
+//! LibreOffice exports the PDF; for images the PDF goes through the PDFium page renderer and is
+//! then thrown away, leaving <name>_slide_001.png, ... ready to upload to the LMS.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use log::info;
+use tauri::AppHandle;
+use crate::document_converter::convert_with_libreoffice;
+use crate::file_sniff::{sniff, FileKind};
+use crate::pdf_render::pdf_to_images;
+use crate::tool_process::WorkDir;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PresentationMode {
+    #[default]
+    Pdf,
+    Images,     // One PNG per slide
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PresentationResult {
+    pub output_paths: Vec<String>,      // The PDF, or the slide images in order
+    pub message: String,
+}
+
+/// Convert a presentation into `output_dir`; `dpi` (default 150) sets the slide image size
+pub async fn convert_presentation(
+    app: AppHandle,
+    input_path: String,
+    mode: PresentationMode,
+    output_dir: String,
+    dpi: Option<u32>,
+) -> Result<PresentationResult, String> {
+    let kind = sniff(&input_path)?;
+    if !matches!(kind, FileKind::Pptx | FileKind::Odp | FileKind::Ole) {
+        return Err(format!("Not a presentation: file is actually {}", kind.label()));
+    }
+    std::fs::create_dir_all(&output_dir)
+        .map_err(|e| format!("Failed to create output folder: {}", e))?;
+
+    info!("📄 Converting presentation to {:?}: {}", mode, input_path);
+
+    if mode == PresentationMode::Pdf {
+        let pdf = convert_with_libreoffice(input_path, "pdf".to_string(), output_dir).await?;
+        if pdf.output_size.is_none() {
+            return Err("LibreOffice produced no PDF".to_string());
+        }
+        return Ok(PresentationResult {
+            message: "Presentation converted to PDF".to_string(),
+            output_paths: vec![pdf.output_path],
+        });
+    }
+
+    let work = WorkDir::create("slides")?;
+    let pdf = convert_with_libreoffice(input_path.clone(), "pdf".to_string(), work.path().to_string_lossy().to_string()).await?;
+    if pdf.output_size.is_none() {
+        return Err("LibreOffice produced no PDF".to_string());
+    }
+    let pages = tokio::task::spawn_blocking(move || {
+        pdf_to_images(app, pdf.output_path, output_dir, dpi, Some("png".to_string()), None, None)
+    })
+    .await
+    .map_err(|e| format!("Task error: {}", e))??;
+
+    // <name>_page_001.png -> <name>_slide_001.png; only the suffix, as the name may contain "_page_" too
+    let mut slides = Vec::with_capacity(pages.len());
+    for page in pages {
+        let file_name = Path::new(&page).file_name().and_then(|n| n.to_str());
+        let slide = match file_name.and_then(|name| name.rfind("_page_").map(|at| (name, at))) {
+            Some((name, at)) => Path::new(&page)
+                .with_file_name(format!("{}_slide_{}", &name[..at], &name[at + "_page_".len()..]))
+                .to_string_lossy()
+                .to_string(),
+            None => page.clone(),
+        };
+        std::fs::rename(&page, &slide).map_err(|e| format!("Failed to name slide image: {}", e))?;
+        slides.push(slide);
+    }
+
+    info!("✅ Exported {} slides", slides.len());
+    Ok(PresentationResult {
+        message: format!("Exported {} slides as PNG", slides.len()),
+        output_paths: slides,
+    })
+}
@@ -0,0 +1,174 @@
+//! Report Generator - branded monthly attendance report PDF built from attendance summaries
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use log::info;
+use crate::attendance_summary::{summarize_month, SummaryOptions, UserMonthSummary};
+use crate::bundled_converter::ConversionResult;
+use crate::holidays::load_calendar;
+use crate::pdf_writer::{PdfWriter, A4_PORTRAIT};
+use crate::zkteco_client::{AttendanceRecord, DeviceUser};
+
+const DEFAULT_INSTITUTION: &str = "Alagappa Institutions";
+const UNASSIGNED_DEPARTMENT: &str = "General";
+
+const MONTH_NAMES: [&str; 12] = [
+    "January", "February", "March", "April", "May", "June",
+    "July", "August", "September", "October", "November", "December",
+];
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReportFilters {
+    pub institution_name: Option<String>,
+    pub late_after: Option<String>,                 // HH:MM
+    #[serde(default)]
+    pub departments: HashMap<String, String>,       // user_id -> department
+    pub include_departments: Option<Vec<String>>,   // Only these departments
+    pub user_ids: Option<Vec<u32>>,                 // Only these users
+    pub users: Option<Vec<DeviceUser>>,             // Device user list, so users without punches are listed
+}
+
+fn attendance_percent(present: u32, absent: u32) -> String {
+    let working = present + absent;
+    if working == 0 {
+        "-".to_string()
+    } else {
+        format!("{:.1}%", present as f64 * 100.0 / working as f64)
+    }
+}
+
+fn summary_row(user: &UserMonthSummary) -> Vec<String> {
+    vec![
+        user.user_id.to_string(),
+        user.user_name.clone(),
+        user.present.to_string(),
+        user.late.to_string(),
+        user.absent.to_string(),
+        user.holidays.to_string(),
+        attendance_percent(user.present, user.absent),
+    ]
+}
+
+/// Render the monthly attendance report as a PDF
+pub fn generate_attendance_report_pdf(
+    month: String,
+    records: Vec<AttendanceRecord>,
+    filters: ReportFilters,
+    output_path: String,
+) -> Result<ConversionResult, String> {
+    let calendar = load_calendar()?;
+    let options = SummaryOptions { month, late_after: filters.late_after.clone(), users: filters.users.clone() };
+    let summary = summarize_month(&records, &options, &calendar)?;
+
+    // Group users by department, applying filters
+    let mut sections: BTreeMap<String, Vec<&UserMonthSummary>> = BTreeMap::new();
+    for user in &summary.users {
+        if let Some(ids) = &filters.user_ids {
+            if !ids.contains(&user.user_id) {
+                continue;
+            }
+        }
+        let department = filters.departments
+            .get(&user.user_id.to_string())
+            .cloned()
+            .unwrap_or_else(|| UNASSIGNED_DEPARTMENT.to_string());
+        if let Some(include) = &filters.include_departments {
+            if !include.contains(&department) {
+                continue;
+            }
+        }
+        sections.entry(department).or_default().push(user);
+    }
+
+    if sections.is_empty() {
+        return Err("No attendance data matches the selected month and filters".to_string());
+    }
+
+    let staff_count: usize = sections.values().map(|users| users.len()).sum();
+    info!("📄 Generating attendance report for {} ({} staff, {} departments)",
+        summary.month, staff_count, sections.len());
+
+    let institution = filters.institution_name.as_deref().unwrap_or(DEFAULT_INSTITUTION);
+    let month_title = format!("{} {}", MONTH_NAMES[(summary.month_number - 1) as usize], summary.year);
+
+    let mut pdf = PdfWriter::new(A4_PORTRAIT);
+    pdf.set_footer(&format!("{} - Attendance Report {}", institution, month_title));
+
+    // Institution header
+    pdf.centered(institution, 18.0, true);
+    pdf.centered(&format!("Monthly Attendance Report - {}", month_title), 12.0, false);
+    pdf.centered(&format!("Generated on {}", chrono::Local::now().format("%d %b %Y %H:%M")), 8.0, false);
+    pdf.move_down(6.0);
+    let (left, right, y) = (pdf.left(), pdf.left() + pdf.content_width(), pdf.cursor());
+    pdf.hline(left, right, y, 1.0);
+    pdf.move_down(12.0);
+
+    let headers = ["ID", "Name", "Present", "Late", "Absent", "Holidays", "Attendance"];
+    let widths: [f32; 7] = [1.0, 4.0, 1.2, 1.0, 1.2, 1.3, 1.5];
+    let (mut total_present, mut total_late, mut total_absent, mut total_holidays) = (0, 0, 0, 0);
+
+    for (department, users) in &sections {
+        pdf.ensure_space(60.0);
+        pdf.paragraph(&format!("{} ({} staff)", department, users.len()), 12.0, true);
+        pdf.move_down(4.0);
+
+        let mut rows: Vec<Vec<String>> = users.iter().map(|u| summary_row(u)).collect();
+        let present: u32 = users.iter().map(|u| u.present).sum();
+        let late: u32 = users.iter().map(|u| u.late).sum();
+        let absent: u32 = users.iter().map(|u| u.absent).sum();
+        let holidays: u32 = users.iter().map(|u| u.holidays).sum();
+        rows.push(vec![
+            String::new(),
+            "Department total".to_string(),
+            present.to_string(),
+            late.to_string(),
+            absent.to_string(),
+            holidays.to_string(),
+            attendance_percent(present, absent),
+        ]);
+
+        pdf.table(&headers, &rows, &widths, true);
+        pdf.move_down(16.0);
+
+        total_present += present;
+        total_late += late;
+        total_absent += absent;
+        total_holidays += holidays;
+    }
+
+    // Institution-wide totals
+    pdf.ensure_space(120.0);
+    pdf.paragraph("Summary", 12.0, true);
+    pdf.move_down(4.0);
+    let totals = vec![
+        vec!["Staff".to_string(), staff_count.to_string()],
+        vec!["Departments".to_string(), sections.len().to_string()],
+        vec!["Present days (incl. late)".to_string(), total_present.to_string()],
+        vec!["Late arrivals".to_string(), total_late.to_string()],
+        vec!["Absent days".to_string(), total_absent.to_string()],
+        vec!["Holiday / weekly-off days".to_string(), total_holidays.to_string()],
+        vec!["Overall attendance".to_string(), attendance_percent(total_present, total_absent)],
+    ];
+    pdf.table(&["Metric", "Value"], &totals, &[3.0, 2.0], true);
+
+    if !summary.holidays.is_empty() {
+        pdf.move_down(12.0);
+        pdf.paragraph("Holidays this month", 10.0, true);
+        for holiday in &summary.holidays {
+            pdf.paragraph(&format!("{} - {}", holiday.date, holiday.name), 9.0, false);
+        }
+    }
+
+    pdf.save(&output_path)?;
+
+    let output_size = fs::metadata(&output_path).map(|m| m.len()).ok();
+
+    info!("✅ Attendance report generated: {}", output_path);
+    Ok(ConversionResult {
+        success: true,
+        output_path,
+        message: format!("Report generated for {} staff", staff_count),
+        output_size,
+    })
+}
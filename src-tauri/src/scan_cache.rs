@@ -0,0 +1,132 @@
+//! Scan Cache - remembers the last network scan and diffs new scans against it
+
+use serde::{Deserialize, Serialize};
+use log::{info, warn};
+use crate::app_storage::{load_json, save_json};
+use crate::device_scanner::{scan_network, BiometricDevice, ScanOptions};
+use tauri::AppHandle;
+
+const SCAN_CACHE_FILE: &str = "last_scan.json";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScanCache {
+    pub scanned_at: Option<String>,  // RFC 3339
+    pub devices: Vec<BiometricDevice>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceChange {
+    pub before: BiometricDevice,
+    pub after: BiometricDevice,
+    pub changes: Vec<String>,        // Human-readable, e.g. "firmware: 6.60 -> 6.70"
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanDiff {
+    pub previous_scan_at: Option<String>,
+    pub scanned_at: String,
+    pub appeared: Vec<BiometricDevice>,
+    pub disappeared: Vec<BiometricDevice>,
+    pub changed: Vec<DeviceChange>,
+    pub unchanged: usize,
+}
+
+/// Stable identity across scans: serial number, then MAC, then IP (DHCP can move a terminal)
+fn identity(device: &BiometricDevice) -> String {
+    if let Some(serial) = device.serial_number.as_ref().filter(|s| !s.is_empty()) {
+        return format!("sn:{}", serial);
+    }
+    if device.mac != "Unknown" && !device.mac.is_empty() {
+        return format!("mac:{}", device.mac);
+    }
+    format!("ip:{}", device.ip)
+}
+
+fn describe_changes(before: &BiometricDevice, after: &BiometricDevice) -> Vec<String> {
+    let show = |v: &Option<String>| v.clone().unwrap_or_else(|| "-".to_string());
+    let mut changes = Vec::new();
+    if before.ip != after.ip {
+        changes.push(format!("ip: {} -> {}", before.ip, after.ip));
+    }
+    if before.firmware_version != after.firmware_version {
+        changes.push(format!("firmware: {} -> {}", show(&before.firmware_version), show(&after.firmware_version)));
+    }
+    if before.device_name != after.device_name {
+        changes.push(format!("name: {} -> {}", show(&before.device_name), show(&after.device_name)));
+    }
+    if before.open_ports != after.open_ports {
+        changes.push(format!("ports: {:?} -> {:?}", before.open_ports, after.open_ports));
+    }
+    changes
+}
+
+pub fn load_last_scan() -> Result<ScanCache, String> {
+    load_json(SCAN_CACHE_FILE)
+}
+
+pub fn save_last_scan(devices: &[BiometricDevice]) -> Result<(), String> {
+    save_json(SCAN_CACHE_FILE, &ScanCache {
+        scanned_at: Some(chrono::Local::now().to_rfc3339()),
+        devices: devices.to_vec(),
+    })
+}
+
+/// Compare two device lists by identity
+pub fn diff_scans(previous: &ScanCache, current: &[BiometricDevice], scanned_at: String) -> ScanDiff {
+    let mut appeared = Vec::new();
+    let mut changed = Vec::new();
+    let mut unchanged = 0;
+
+    for device in current {
+        let key = identity(device);
+        match previous.devices.iter().find(|d| identity(d) == key) {
+            None => appeared.push(device.clone()),
+            Some(before) => {
+                let changes = describe_changes(before, device);
+                if changes.is_empty() {
+                    unchanged += 1;
+                } else {
+                    changed.push(DeviceChange { before: before.clone(), after: device.clone(), changes });
+                }
+            }
+        }
+    }
+
+    let disappeared = previous.devices.iter()
+        .filter(|d| {
+            let key = identity(d);
+            !current.iter().any(|c| identity(c) == key)
+        })
+        .cloned()
+        .collect();
+
+    ScanDiff {
+        previous_scan_at: previous.scanned_at.clone(),
+        scanned_at,
+        appeared,
+        disappeared,
+        changed,
+        unchanged,
+    }
+}
+
+/// Regular scan that also refreshes the cache (a failed save doesn't fail the scan)
+pub async fn scan_and_remember(options: ScanOptions, app: Option<AppHandle>) -> Result<Vec<BiometricDevice>, String> {
+    let devices = scan_network(options, app).await?;
+    if let Err(e) = save_last_scan(&devices) {
+        warn!("Could not store scan result: {}", e);
+    }
+    Ok(devices)
+}
+
+/// Scan again, report what changed since the last scan, then store the new result
+pub async fn rescan_and_diff(options: ScanOptions, app: Option<AppHandle>) -> Result<ScanDiff, String> {
+    let previous = load_last_scan()?;
+    let devices = scan_network(options, app).await?;
+    let diff = diff_scans(&previous, &devices, chrono::Local::now().to_rfc3339());
+    save_last_scan(&devices)?;
+
+    info!("🔍 Scan diff: {} new, {} gone, {} changed, {} unchanged",
+        diff.appeared.len(), diff.disappeared.len(), diff.changed.len(), diff.unchanged);
+    Ok(diff)
+}
@@ -0,0 +1,184 @@
+//! Scan Enhance - turn phone photos of documents into clean black-on-white pages
+//! Steps, each optional: background whitening (divide by a blurred estimate of the paper, which
+//! removes shadows and uneven light), deskew (the angle whose horizontal projection gives the
+//! sharpest text-line peaks), despeckle (3x3 median) and a final level stretch or Otsu
+//! black-and-white threshold. Output is grayscale, ready for images-to-PDF.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use log::info;
+use image::imageops::{self, FilterType};
+use image::{DynamicImage, GrayImage, Luma};
+use imageproc::contrast::otsu_level;
+use imageproc::filter::median_filter;
+use imageproc::geometric_transformations::{rotate_about_center, Interpolation};
+use crate::bundled_converter::{save_image, ConversionResult};
+use crate::image_edit::open_oriented;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScanOptions {
+    pub whiten: Option<bool>,           // Flatten the paper to white (default true)
+    pub deskew: Option<bool>,           // Straighten text lines, up to 10 degrees (default true)
+    pub despeckle: Option<bool>,        // Remove dust and sensor noise (default true)
+    pub black_and_white: Option<bool>,  // Pure black/white output instead of grayscale (default false)
+    pub quality: Option<u8>,            // JPEG quality
+}
+
+/// Shrink so the longest side is at most `max_side`
+fn downscale(gray: &GrayImage, max_side: f32) -> GrayImage {
+    let scale = (gray.width().max(gray.height()) as f32 / max_side).max(1.0);
+    let width = ((gray.width() as f32 / scale) as u32).max(1);
+    let height = ((gray.height() as f32 / scale) as u32).max(1);
+    imageops::resize(gray, width, height, FilterType::Triangle)
+}
+
+/// Paper brightness around each pixel: downscale, max-filter away the text, blur, upscale
+fn background(gray: &GrayImage) -> GrayImage {
+    let (width, height) = gray.dimensions();
+    let small = downscale(gray, 400.0);
+
+    let radius = 3i64;
+    let (sw, sh) = (small.width() as i64, small.height() as i64);
+    let dilated = GrayImage::from_fn(small.width(), small.height(), |x, y| {
+        let mut brightest = 0;
+        for dy in -radius..=radius {
+            for dx in -radius..=radius {
+                let (nx, ny) = (x as i64 + dx, y as i64 + dy);
+                if nx >= 0 && ny >= 0 && nx < sw && ny < sh {
+                    brightest = brightest.max(small.get_pixel(nx as u32, ny as u32)[0]);
+                }
+            }
+        }
+        Luma([brightest])
+    });
+    let blurred = imageops::blur(&dilated, 4.0);
+    imageops::resize(&blurred, width, height, FilterType::Triangle)
+}
+
+fn whiten(gray: &GrayImage) -> GrayImage {
+    let paper = background(gray);
+    GrayImage::from_fn(gray.width(), gray.height(), |x, y| {
+        let value = gray.get_pixel(x, y)[0] as f32;
+        let bg = paper.get_pixel(x, y)[0].max(1) as f32;
+        Luma([(value / bg * 255.0).min(255.0) as u8])
+    })
+}
+
+/// Sum of squared row counts of dark points projected at `angle` (radians)
+fn profile_score(points: &[(f32, f32)], angle: f32, rows: usize) -> f64 {
+    let (sin, cos) = angle.sin_cos();
+    let offset = rows as f32;
+    let mut histogram = vec![0u32; rows * 2];
+    for &(x, y) in points {
+        let row = (y * cos - x * sin + offset).round();
+        if row >= 0.0 && (row as usize) < histogram.len() {
+            histogram[row as usize] += 1;
+        }
+    }
+    histogram.iter().map(|&count| (count as f64).powi(2)).sum()
+}
+
+/// Skew of the text lines in radians (positive = lines run down to the right)
+fn detect_skew(gray: &GrayImage) -> f32 {
+    let small = downscale(gray, 800.0);
+    let level = otsu_level(&small);
+    let (cx, cy) = (small.width() as f32 / 2.0, small.height() as f32 / 2.0);
+    let points: Vec<(f32, f32)> = small.enumerate_pixels()
+        .filter(|(_, _, p)| p[0] < level)
+        .map(|(x, y, _)| (x as f32 - cx, y as f32 - cy))
+        .collect();
+    if points.len() < 100 {
+        return 0.0;
+    }
+    let rows = (small.width() + small.height()) as usize;
+
+    let best = |from: f32, to: f32, step: f32| {
+        let steps = ((to - from) / step).round() as i32;
+        (0..=steps)
+            .map(|i| (from + i as f32 * step).to_radians())
+            .map(|angle| (angle, profile_score(&points, angle, rows)))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(angle, _)| angle.to_degrees())
+            .unwrap_or(0.0)
+    };
+    // Coarse search, then refine around the best coarse angle
+    let coarse = best(-10.0, 10.0, 0.5);
+    best(coarse - 0.5, coarse + 0.5, 0.05).to_radians()
+}
+
+/// Brightness below which `percent` of the pixels fall
+fn percentile(gray: &GrayImage, percent: u64) -> f32 {
+    let mut histogram = [0u64; 256];
+    for p in gray.pixels() {
+        histogram[p[0] as usize] += 1;
+    }
+    let total: u64 = histogram.iter().sum();
+    let mut seen = 0;
+    histogram.iter()
+        .position(|&count| {
+            seen += count;
+            seen * 100 >= total * percent
+        })
+        .unwrap_or(255) as f32
+}
+
+/// Stretch levels so the darkest 1% become black and the paper (most of a page, so the
+/// median brightness) becomes white
+fn stretch(gray: &mut GrayImage) {
+    let black = percentile(gray, 1);
+    let white = (percentile(gray, 50) * 0.97).max(black + 1.0);
+    for p in gray.pixels_mut() {
+        p[0] = ((p[0] as f32 - black) / (white - black) * 255.0).clamp(0.0, 255.0) as u8;
+    }
+}
+
+/// Clean up a photographed or scanned page
+pub fn enhance_scan(input_path: String, output_path: String, options: ScanOptions) -> Result<ConversionResult, String> {
+    info!("🖼️ Enhancing scan (bundled)");
+
+    let mut gray = open_oriented(&input_path)?.to_luma8();
+    let mut steps = Vec::new();
+
+    if options.whiten.unwrap_or(true) {
+        gray = whiten(&gray);
+        steps.push("whitened".to_string());
+    }
+    if options.deskew.unwrap_or(true) {
+        let skew = detect_skew(&gray);
+        if skew.abs().to_degrees() >= 0.1 {
+            gray = rotate_about_center(&gray, -skew, Interpolation::Bilinear, Luma([255]));
+            steps.push(format!("deskewed {:.1}°", skew.to_degrees()));
+        }
+    }
+    if options.despeckle.unwrap_or(true) {
+        gray = median_filter(&gray, 1, 1);
+        steps.push("despeckled".to_string());
+    }
+    if options.black_and_white.unwrap_or(false) {
+        let level = otsu_level(&gray);
+        for p in gray.pixels_mut() {
+            p[0] = if p[0] > level { 255 } else { 0 };
+        }
+        steps.push("black and white".to_string());
+    } else {
+        stretch(&mut gray);
+    }
+
+    save_image(&DynamicImage::ImageLuma8(gray), &output_path, options.quality)?;
+
+    let output_size = fs::metadata(&output_path).map(|m| m.len()).ok();
+
+    info!("✅ Scan enhanced: {}", output_path);
+    let message = if steps.is_empty() {
+        "Scan levels adjusted".to_string()
+    } else {
+        format!("Scan {}", steps.join(", "))
+    };
+    Ok(ConversionResult {
+        success: true,
+        output_path,
+        message,
+        output_size,
+    })
+}
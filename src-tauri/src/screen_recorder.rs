@@ -0,0 +1,260 @@
+//! Screen Recorder - record the screen (optionally a region, webcam overlay and microphone) to MP4
+//! Capture uses ffmpeg's platform grabbers: avfoundation (macOS), gdigrab + dshow (Windows),
+//! x11grab + pulse + v4l2 (Linux). One recording at a time; stopping sends "q" so the MP4 is finalised.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio::io::AsyncWriteExt;
+use tokio::process::{Child, Command as TokioCommand};
+use log::{info, warn};
+use crate::media_converter::ConversionResult;
+use crate::ffmpeg_manager::ffmpeg_path;
+
+/// How long ffmpeg gets to flush and close the file after "q"
+const STOP_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Grace period to catch devices that fail to open right after start
+const STARTUP_CHECK: Duration = Duration::from_millis(800);
+
+static RECORDING: Mutex<Option<RecorderSlot>> = Mutex::new(None);
+
+/// Taken before ffmpeg is spawned, so a second start can't race the startup check
+enum RecorderSlot {
+    Starting(String),               // Output path
+    Recording(Box<ActiveRecording>),
+}
+
+struct ActiveRecording {
+    child: Child,
+    output_path: String,
+    log_path: PathBuf,
+    started: Instant,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct CaptureRegion {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordingOptions {
+    pub output_path: String,
+    pub region: Option<CaptureRegion>,  // Whole screen when None
+    #[serde(default = "default_fps")]
+    pub fps: u32,
+    pub display: Option<String>,        // macOS: "Capture screen 0"; Linux: ":0.0"
+    pub webcam: Option<String>,         // Device name (macOS/Windows) or /dev/videoN (Linux)
+    pub microphone: Option<String>,     // Device name; Linux: pulse source ("default")
+}
+
+fn default_fps() -> u32 {
+    30
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordingStatus {
+    pub recording: bool,
+    pub output_path: Option<String>,
+    pub elapsed_secs: f64,
+}
+
+/// Screen, webcam and microphone inputs for this platform; returns true if the screen input
+/// still needs cropping to the region (avfoundation can't grab a region itself)
+fn add_inputs(cmd: &mut TokioCommand, options: &RecordingOptions) -> bool {
+    let fps = options.fps.clamp(1, 60).to_string();
+    let mut needs_crop = false;
+
+    if cfg!(target_os = "macos") {
+        let screen = options.display.clone().unwrap_or_else(|| "Capture screen 0".to_string());
+        cmd.arg("-f").arg("avfoundation").arg("-capture_cursor").arg("1").arg("-framerate").arg(&fps);
+        cmd.arg("-i").arg(format!("{}:none", screen));
+        needs_crop = options.region.is_some();
+        if let Some(cam) = &options.webcam {
+            cmd.arg("-f").arg("avfoundation").arg("-framerate").arg("30").arg("-i").arg(format!("{}:none", cam));
+        }
+        if let Some(mic) = &options.microphone {
+            cmd.arg("-f").arg("avfoundation").arg("-i").arg(format!("none:{}", mic));
+        }
+    } else if cfg!(target_os = "windows") {
+        cmd.arg("-f").arg("gdigrab").arg("-framerate").arg(&fps);
+        if let Some(r) = &options.region {
+            cmd.arg("-offset_x").arg(r.x.to_string()).arg("-offset_y").arg(r.y.to_string());
+            cmd.arg("-video_size").arg(format!("{}x{}", r.width, r.height));
+        }
+        cmd.arg("-i").arg("desktop");
+        if let Some(cam) = &options.webcam {
+            cmd.arg("-f").arg("dshow").arg("-i").arg(format!("video={}", cam));
+        }
+        if let Some(mic) = &options.microphone {
+            cmd.arg("-f").arg("dshow").arg("-i").arg(format!("audio={}", mic));
+        }
+    } else {
+        let display = options.display.clone()
+            .or_else(|| std::env::var("DISPLAY").ok())
+            .unwrap_or_else(|| ":0.0".to_string());
+        cmd.arg("-f").arg("x11grab").arg("-framerate").arg(&fps);
+        match &options.region {
+            Some(r) => {
+                cmd.arg("-video_size").arg(format!("{}x{}", r.width, r.height));
+                cmd.arg("-i").arg(format!("{}+{},{}", display, r.x, r.y));
+            }
+            None => {
+                cmd.arg("-i").arg(display);
+            }
+        }
+        if let Some(cam) = &options.webcam {
+            cmd.arg("-f").arg("v4l2").arg("-i").arg(cam);
+        }
+        if let Some(mic) = &options.microphone {
+            cmd.arg("-f").arg("pulse").arg("-i").arg(mic);
+        }
+    }
+    needs_crop
+}
+
+/// Video filter graph: optional crop, then the webcam scaled into the bottom-right corner
+fn filter_graph(options: &RecordingOptions, needs_crop: bool) -> String {
+    let mut graph = match (&options.region, needs_crop) {
+        (Some(r), true) => format!("[0:v]crop={}:{}:{}:{}", r.width, r.height, r.x, r.y),
+        _ => "[0:v]null".to_string(),
+    };
+    if options.webcam.is_some() {
+        graph.push_str("[screen];[1:v]scale=320:-2[cam];[screen][cam]overlay=W-w-20:H-h-20");
+    }
+    graph.push_str(",scale=trunc(iw/2)*2:trunc(ih/2)*2,format=yuv420p[out]");
+    graph
+}
+
+fn log_tail(path: &Path) -> String {
+    let text = std::fs::read_to_string(path).unwrap_or_default();
+    let lines: Vec<&str> = text.lines().rev().take(5).collect();
+    lines.into_iter().rev().collect::<Vec<_>>().join("\n")
+}
+
+/// Spawn ffmpeg and wait out the startup check
+async fn spawn_recorder(options: &RecordingOptions) -> Result<ActiveRecording, String> {
+    let log_path = std::env::temp_dir().join("screen_recording.log");
+    let log_file = std::fs::File::create(&log_path)
+        .map_err(|e| format!("Failed to create recorder log: {}", e))?;
+
+    let mut cmd = TokioCommand::new(ffmpeg_path());
+    cmd.arg("-hide_banner").arg("-loglevel").arg("error").arg("-y");
+    // Live grabbers drop frames if the input queue is too small
+    cmd.arg("-thread_queue_size").arg("512");
+    let needs_crop = add_inputs(&mut cmd, options);
+
+    cmd.arg("-filter_complex").arg(filter_graph(options, needs_crop));
+    cmd.arg("-map").arg("[out]");
+    if options.microphone.is_some() {
+        let mic_input = if options.webcam.is_some() { 2 } else { 1 };
+        cmd.arg("-map").arg(format!("{}:a", mic_input));
+        cmd.arg("-c:a").arg("aac").arg("-b:a").arg("128k");
+    }
+    cmd.arg("-c:v").arg("libx264").arg("-preset").arg("veryfast").arg("-crf").arg("23");
+    cmd.arg("-movflags").arg("+faststart");
+    cmd.arg(&options.output_path);
+
+    cmd.stdin(Stdio::piped()).stdout(Stdio::null()).stderr(Stdio::from(log_file));
+    cmd.kill_on_drop(true);
+    let mut child = cmd.spawn()
+        .map_err(|e| format!("FFmpeg execution failed: {}", e))?;
+
+    tokio::time::sleep(STARTUP_CHECK).await;
+    if let Ok(Some(_)) = child.try_wait() {
+        return Err(format!("Recording failed to start: {}", log_tail(&log_path)));
+    }
+
+    Ok(ActiveRecording {
+        child,
+        output_path: options.output_path.clone(),
+        log_path,
+        started: Instant::now(),
+    })
+}
+
+pub async fn start_recording(options: RecordingOptions) -> Result<RecordingStatus, String> {
+    {
+        let mut slot = RECORDING.lock().map_err(|_| "Recorder state poisoned".to_string())?;
+        if slot.is_some() {
+            return Err("A recording is already in progress".to_string());
+        }
+        *slot = Some(RecorderSlot::Starting(options.output_path.clone()));
+    }
+
+    let started = spawn_recorder(&options).await;
+    let mut slot = RECORDING.lock().map_err(|_| "Recorder state poisoned".to_string())?;
+    match started {
+        Ok(active) => *slot = Some(RecorderSlot::Recording(Box::new(active))),
+        Err(e) => {
+            *slot = None;
+            return Err(e);
+        }
+    }
+
+    info!("⏺️ Recording started: {}", options.output_path);
+    Ok(RecordingStatus {
+        recording: true,
+        output_path: Some(options.output_path),
+        elapsed_secs: 0.0,
+    })
+}
+
+pub async fn stop_recording() -> Result<ConversionResult, String> {
+    let recording = {
+        let mut slot = RECORDING.lock().map_err(|_| "Recorder state poisoned".to_string())?;
+        match slot.take() {
+            Some(RecorderSlot::Recording(active)) => *active,
+            Some(starting) => {
+                *slot = Some(starting);
+                return Err("The recording is still starting - try again in a moment".to_string());
+            }
+            None => return Err("No recording in progress".to_string()),
+        }
+    };
+    let ActiveRecording { mut child, output_path, log_path, started } = recording;
+
+    // "q" makes ffmpeg stop grabbing and write the MP4 index; killing would leave it unplayable
+    if let Some(mut stdin) = child.stdin.take() {
+        let _ = stdin.write_all(b"q").await;
+    }
+    if tokio::time::timeout(STOP_TIMEOUT, child.wait()).await.is_err() {
+        warn!("Recorder did not stop in time, killing ffmpeg");
+        let _ = child.kill().await;
+    }
+
+    let output_size = std::fs::metadata(&output_path).map(|m| m.len()).ok();
+    if output_size.unwrap_or(0) == 0 {
+        return Err(format!("Recording was not saved: {}", log_tail(&log_path)));
+    }
+
+    info!("⏹️ Recording saved: {} ({:.0}s)", output_path, started.elapsed().as_secs_f64());
+    Ok(ConversionResult {
+        success: true,
+        output_path,
+        message: "Recording saved successfully".to_string(),
+        output_size,
+    })
+}
+
+pub fn recording_status() -> RecordingStatus {
+    let guard = RECORDING.lock().ok();
+    match guard.as_ref().and_then(|r| r.as_ref()) {
+        Some(RecorderSlot::Recording(active)) => RecordingStatus {
+            recording: true,
+            output_path: Some(active.output_path.clone()),
+            elapsed_secs: active.started.elapsed().as_secs_f64(),
+        },
+        Some(RecorderSlot::Starting(output_path)) => RecordingStatus {
+            recording: true,
+            output_path: Some(output_path.clone()),
+            elapsed_secs: 0.0,
+        },
+        None => RecordingStatus { recording: false, output_path: None, elapsed_secs: 0.0 },
+    }
+}
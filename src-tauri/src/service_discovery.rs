@@ -0,0 +1,156 @@
+//! Service Discovery - mDNS and SSDP announcements on the local segment
+//! Complements the TCP port sweep: newer terminals and printers answer these without any port probing.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::time::Duration;
+use tokio::net::UdpSocket;
+use log::{debug, info};
+
+const MDNS_ADDR: &str = "224.0.0.251:5353";
+const SSDP_ADDR: &str = "239.255.255.250:1900";
+const MDNS_SERVICES_QUERY: &str = "_services._dns-sd._udp.local";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiscoveredService {
+    pub ip: String,
+    pub method: String,        // "mdns" or "ssdp"
+    pub name: Option<String>,  // Service instance / SERVER header
+}
+
+/// Build a DNS PTR query with the "unicast response" bit so replies come straight back to our socket
+fn mdns_query(name: &str) -> Vec<u8> {
+    let mut packet = vec![0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0]; // id 0, flags 0, 1 question
+    for label in name.split('.') {
+        packet.push(label.len() as u8);
+        packet.extend_from_slice(label.as_bytes());
+    }
+    packet.push(0);
+    packet.extend_from_slice(&12u16.to_be_bytes());     // PTR
+    packet.extend_from_slice(&0x8001u16.to_be_bytes()); // QU + class IN
+    packet
+}
+
+/// Read a (possibly compressed) DNS name; returns the name and the offset after it
+fn read_name(packet: &[u8], mut offset: usize) -> Option<(String, usize)> {
+    let mut labels = Vec::new();
+    let mut end = None;
+    for _ in 0..32 {
+        let len = *packet.get(offset)? as usize;
+        if len == 0 {
+            return Some((labels.join("."), end.unwrap_or(offset + 1)));
+        }
+        if len & 0xC0 == 0xC0 {
+            let pointer = ((len & 0x3F) << 8) | *packet.get(offset + 1)? as usize;
+            end.get_or_insert(offset + 2);
+            offset = pointer;
+            continue;
+        }
+        let label = packet.get(offset + 1..offset + 1 + len)?;
+        labels.push(String::from_utf8_lossy(label).to_string());
+        offset += 1 + len;
+    }
+    None
+}
+
+/// Pull the most descriptive name out of an mDNS response (first PTR target, else first answer name)
+fn parse_mdns_name(packet: &[u8]) -> Option<String> {
+    let questions = u16::from_be_bytes([*packet.get(4)?, *packet.get(5)?]);
+    let answers = u16::from_be_bytes([*packet.get(6)?, *packet.get(7)?]);
+    let mut offset = 12;
+    for _ in 0..questions {
+        offset = read_name(packet, offset)?.1 + 4;
+    }
+
+    let mut first_name = None;
+    for _ in 0..answers {
+        let (name, next) = read_name(packet, offset)?;
+        let rtype = u16::from_be_bytes([*packet.get(next)?, *packet.get(next + 1)?]);
+        let rdlength = u16::from_be_bytes([*packet.get(next + 8)?, *packet.get(next + 9)?]) as usize;
+        let rdata = next + 10;
+        if rtype == 12 {
+            if let Some((target, _)) = read_name(packet, rdata) {
+                return Some(target);
+            }
+        }
+        first_name.get_or_insert(name);
+        offset = rdata + rdlength;
+    }
+    first_name
+}
+
+fn parse_ssdp_server(response: &str) -> Option<String> {
+    response.lines()
+        .filter_map(|line| line.split_once(':'))
+        .find(|(key, _)| key.trim().eq_ignore_ascii_case("SERVER"))
+        .map(|(_, value)| value.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// Send one probe and collect every reply until the listen window closes
+async fn probe(target: &str, payload: &[u8], listen: Duration) -> Vec<(SocketAddr, Vec<u8>)> {
+    let socket = match UdpSocket::bind("0.0.0.0:0").await {
+        Ok(s) => s,
+        Err(e) => {
+            debug!("Discovery socket bind failed: {}", e);
+            return Vec::new();
+        }
+    };
+    if let Err(e) = socket.send_to(payload, target).await {
+        debug!("Discovery probe to {} failed: {}", target, e);
+        return Vec::new();
+    }
+
+    let mut replies = Vec::new();
+    let mut buf = [0u8; 4096];
+    let deadline = tokio::time::Instant::now() + listen;
+    while let Ok(Ok((len, from))) = tokio::time::timeout_at(deadline, socket.recv_from(&mut buf)).await {
+        replies.push((from, buf[..len].to_vec()));
+    }
+    replies
+}
+
+async fn discover_mdns(listen: Duration) -> Vec<DiscoveredService> {
+    probe(MDNS_ADDR, &mdns_query(MDNS_SERVICES_QUERY), listen).await
+        .into_iter()
+        .map(|(from, packet)| DiscoveredService {
+            ip: from.ip().to_string(),
+            method: "mdns".to_string(),
+            name: parse_mdns_name(&packet),
+        })
+        .collect()
+}
+
+async fn discover_ssdp(listen: Duration) -> Vec<DiscoveredService> {
+    let search = format!(
+        "M-SEARCH * HTTP/1.1\r\nHOST: {}\r\nMAN: \"ssdp:discover\"\r\nMX: {}\r\nST: ssdp:all\r\n\r\n",
+        SSDP_ADDR,
+        listen.as_secs().max(1)
+    );
+    probe(SSDP_ADDR, search.as_bytes(), listen).await
+        .into_iter()
+        .map(|(from, packet)| DiscoveredService {
+            ip: from.ip().to_string(),
+            method: "ssdp".to_string(),
+            name: parse_ssdp_server(&String::from_utf8_lossy(&packet)),
+        })
+        .collect()
+}
+
+/// Run mDNS and SSDP discovery in parallel; one entry per (ip, method)
+pub async fn discover_services(listen: Duration) -> Vec<DiscoveredService> {
+    let (mdns, ssdp) = tokio::join!(discover_mdns(listen), discover_ssdp(listen));
+
+    let mut unique: HashMap<(IpAddr, String), DiscoveredService> = HashMap::new();
+    for service in mdns.into_iter().chain(ssdp) {
+        let Ok(ip) = service.ip.parse::<IpAddr>() else { continue };
+        let entry = unique.entry((ip, service.method.clone())).or_insert_with(|| service.clone());
+        if entry.name.is_none() {
+            entry.name = service.name;
+        }
+    }
+
+    info!("📡 Service discovery: {} announcement(s)", unique.len());
+    unique.into_values().collect()
+}
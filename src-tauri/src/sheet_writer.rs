@@ -0,0 +1,184 @@
+//! Sheet Writer - typed, formatted worksheets for the CSV/JSON to Excel converters
+//! Text cells are typed on the way in (numbers, dates, booleans) so Excel can sort and sum them.
+
+use std::sync::OnceLock;
+use chrono::{Datelike, NaiveDate, NaiveDateTime, Timelike};
+use regex::Regex;
+use rust_xlsxwriter::{Color, ExcelDateTime, Format, FormatBorder, Worksheet, XlsxError};
+
+const MIN_COLUMN_WIDTH: usize = 8;
+const MAX_COLUMN_WIDTH: usize = 60;
+const DATE_FORMATS: [&str; 4] = ["%Y-%m-%d", "%d/%m/%Y", "%d-%m-%Y", "%d.%m.%Y"];
+const DATETIME_FORMATS: [&str; 4] = ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M", "%d/%m/%Y %H:%M:%S"];
+
+/// Thousands grouping (1,250,000) or Indian lakh grouping (12,50,000), with optional decimals
+static GROUPED: OnceLock<Regex> = OnceLock::new();
+
+/// A single decimal comma (3,5 or 12,75)
+static DECIMAL_COMMA: OnceLock<Regex> = OnceLock::new();
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CellValue {
+    Empty,
+    Text(String),
+    Number(f64),
+    Bool(bool),
+    Date(NaiveDate),
+    DateTime(NaiveDateTime),
+}
+
+impl CellValue {
+    /// Width the value takes up when displayed, in characters
+    fn display_width(&self) -> usize {
+        match self {
+            CellValue::Empty => 0,
+            CellValue::Text(text) => text.lines().map(|l| l.chars().count()).max().unwrap_or(0),
+            CellValue::Number(number) => number.to_string().len(),
+            CellValue::Bool(_) => 5,
+            CellValue::Date(_) => 10,
+            CellValue::DateTime(_) => 19,
+        }
+    }
+}
+
+pub fn xlsx_err(e: XlsxError) -> String {
+    format!("Failed to write workbook: {}", e)
+}
+
+/// A number with no commas, valid digit grouping, or a decimal comma; anything else
+/// ("1,2,3", "1,23,4567") is not a number
+fn parse_number(text: &str) -> Option<f64> {
+    if !text.contains(',') {
+        return text.parse().ok();
+    }
+    let grouped = GROUPED.get_or_init(|| {
+        Regex::new(r"^[-+]?(\d{1,3}(,\d{3})+|\d{1,2}(,\d{2})*,\d{3})(\.\d+)?$").expect("valid regex")
+    });
+    if grouped.is_match(text) {
+        return text.replace(',', "").parse().ok();
+    }
+    let decimal_comma = DECIMAL_COMMA.get_or_init(|| Regex::new(r"^[-+]?\d+,\d+$").expect("valid regex"));
+    if decimal_comma.is_match(text) {
+        return text.replace(',', ".").parse().ok();
+    }
+    None
+}
+
+/// Type a text value. Codes with leading zeros (roll numbers, PIN codes) and long digit strings
+/// (phone, Aadhaar numbers) stay text so nothing is lost to Excel's 15-digit precision.
+pub fn detect(text: &str) -> CellValue {
+    let trimmed = text.trim();
+    if trimmed.is_empty() {
+        return CellValue::Empty;
+    }
+    let digits = trimmed.trim_start_matches(['-', '+']);
+    let leading_zero = digits.len() > 1 && digits.starts_with('0') && !digits.starts_with("0.");
+    let long_integer = digits.len() > 15 && digits.chars().all(|c| c.is_ascii_digit());
+    if !leading_zero && !long_integer {
+        if let Some(number) = parse_number(trimmed).filter(|n| n.is_finite()) {
+            return CellValue::Number(number);
+        }
+    }
+    match trimmed.to_lowercase().as_str() {
+        "true" => return CellValue::Bool(true),
+        "false" => return CellValue::Bool(false),
+        _ => {}
+    }
+    for format in DATETIME_FORMATS {
+        if let Ok(datetime) = NaiveDateTime::parse_from_str(trimmed, format) {
+            return CellValue::DateTime(datetime);
+        }
+    }
+    for format in DATE_FORMATS {
+        if let Ok(date) = NaiveDate::parse_from_str(trimmed, format) {
+            return CellValue::Date(date);
+        }
+    }
+    CellValue::Text(text.to_string())
+}
+
+fn excel_date(date: NaiveDate) -> Result<ExcelDateTime, XlsxError> {
+    ExcelDateTime::from_ymd(date.year() as u16, date.month() as u8, date.day() as u8)
+}
+
+/// Write one cell; dates outside Excel's range (before 1900) fall back to text
+pub fn write_cell(worksheet: &mut Worksheet, row: u32, col: u16, value: &CellValue) -> Result<(), XlsxError> {
+    match value {
+        CellValue::Empty => {}
+        CellValue::Text(text) => {
+            worksheet.write_string(row, col, text)?;
+        }
+        CellValue::Number(number) => {
+            worksheet.write_number(row, col, *number)?;
+        }
+        CellValue::Bool(flag) => {
+            worksheet.write_boolean(row, col, *flag)?;
+        }
+        CellValue::Date(date) => match excel_date(*date) {
+            Ok(excel) => {
+                worksheet.write_datetime_with_format(row, col, &excel, &Format::new().set_num_format("yyyy-mm-dd"))?;
+            }
+            Err(_) => {
+                worksheet.write_string(row, col, date.to_string())?;
+            }
+        },
+        CellValue::DateTime(datetime) => {
+            let excel = excel_date(datetime.date())
+                .and_then(|d| d.and_hms(datetime.hour() as u16, datetime.minute() as u8, datetime.second() as f64));
+            match excel {
+                Ok(excel) => {
+                    worksheet.write_datetime_with_format(row, col, &excel, &Format::new().set_num_format("yyyy-mm-dd hh:mm:ss"))?;
+                }
+                Err(_) => {
+                    worksheet.write_string(row, col, datetime.to_string())?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Write a header row (bold, shaded) and typed rows, size columns to their content
+/// and optionally freeze the header so it stays visible while scrolling
+pub fn write_table(
+    worksheet: &mut Worksheet,
+    headers: &[String],
+    rows: &[Vec<CellValue>],
+    freeze_header: bool,
+) -> Result<(), XlsxError> {
+    let header_format = Format::new()
+        .set_bold()
+        .set_background_color(Color::RGB(0xD9E1F2))
+        .set_border(FormatBorder::Thin);
+    let first_row = if headers.is_empty() { 0 } else { 1 };
+    let columns = rows.iter().map(Vec::len).chain([headers.len()]).max().unwrap_or(0);
+    let mut widths = vec![0usize; columns];
+
+    for (col, header) in headers.iter().enumerate() {
+        worksheet.write_string_with_format(0, col as u16, header, &header_format)?;
+        widths[col] = header.chars().count();
+    }
+    for (index, row) in rows.iter().enumerate() {
+        for (col, value) in row.iter().enumerate() {
+            write_cell(worksheet, first_row + index as u32, col as u16, value)?;
+            widths[col] = widths[col].max(value.display_width());
+        }
+    }
+    for (col, width) in widths.into_iter().enumerate() {
+        let width = width.clamp(MIN_COLUMN_WIDTH, MAX_COLUMN_WIDTH) + 2;
+        worksheet.set_column_width(col as u16, width as f64)?;
+    }
+    if freeze_header && first_row == 1 {
+        worksheet.set_freeze_panes(1, 0)?;
+    }
+    Ok(())
+}
+
+/// Excel sheet names: at most 31 characters, none of []:*?/\
+pub fn sheet_name(name: &str) -> String {
+    let cleaned: String = name.chars()
+        .map(|c| if "[]:*?/\\".contains(c) { '_' } else { c })
+        .take(31)
+        .collect();
+    if cleaned.trim().is_empty() { "Sheet1".to_string() } else { cleaned }
+}
@@ -0,0 +1,277 @@
+//! Spreadsheet Diff - compare two versions of a sheet (xlsx/xls/ods/csv) row by row
+//! Rows are matched on key columns (e.g. roll number), or by position when no key is given.
+//! The report lists added, removed and changed rows with the changed cells; it can also be
+//! saved as JSON or as a workbook with added rows green, removed rows red and changed cells yellow.
+
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use log::info;
+use rust_xlsxwriter::{Color, Format, Workbook, Worksheet, XlsxError};
+use crate::excel_reader::read_table;
+use crate::sheet_writer::{detect, write_table, xlsx_err, CellValue};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DiffOptions {
+    pub key_columns: Vec<String>,       // Identify a row, e.g. ["Roll No"]; empty matches rows by position
+    pub sheet_index: Option<usize>,     // Sheet of both workbooks to compare (first by default)
+    pub output_path: Option<String>,    // Also save the report: .xlsx (highlighted) or .json
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RowEntry {
+    pub key: String,
+    pub row: usize,                     // Spreadsheet row number in its file (header is row 1)
+    pub values: Vec<String>,            // One per report column
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CellChange {
+    pub column: String,
+    pub old_value: String,
+    pub new_value: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RowChange {
+    pub key: String,
+    pub old_row: usize,
+    pub new_row: usize,
+    pub cells: Vec<CellChange>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiffReport {
+    pub columns: Vec<String>,           // Columns of both files, old file's order first
+    pub added_columns: Vec<String>,
+    pub removed_columns: Vec<String>,
+    pub added_rows: Vec<RowEntry>,
+    pub removed_rows: Vec<RowEntry>,
+    pub changed_rows: Vec<RowChange>,
+    pub unchanged_rows: usize,
+    pub duplicate_keys: Vec<String>,    // Keys found more than once; only the first row is compared
+    pub output_path: Option<String>,
+}
+
+struct Side {
+    rows: Vec<Vec<String>>,             // Aligned to the report columns
+    keys: Vec<String>,
+    present: Vec<usize>,                // Report columns this file has
+}
+
+fn load_side(
+    path: &str,
+    sheet_index: Option<usize>,
+    columns: &mut Vec<String>,
+    key_columns: &[String],
+) -> Result<(Side, Vec<String>), String> {
+    let (headers, rows) = read_table(path, sheet_index).map_err(|e| format!("{}: {}", path, e))?;
+    let mut new_columns = Vec::new();
+    let positions: Vec<usize> = headers.iter()
+        .map(|name| match columns.iter().position(|c| c.eq_ignore_ascii_case(name.trim())) {
+            Some(index) => index,
+            None => {
+                columns.push(name.trim().to_string());
+                new_columns.push(name.trim().to_string());
+                columns.len() - 1
+            }
+        })
+        .collect();
+
+    let key_positions = key_columns.iter()
+        .map(|key| {
+            headers.iter()
+                .position(|h| h.trim().eq_ignore_ascii_case(key.trim()))
+                .map(|i| positions[i])
+                .ok_or_else(|| format!("Key column {} not found in {}", key, path))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut aligned = Vec::with_capacity(rows.len());
+    let mut keys = Vec::with_capacity(rows.len());
+    for (index, row) in rows.into_iter().enumerate() {
+        let mut values = vec![String::new(); columns.len()];
+        for (value, position) in row.into_iter().zip(&positions) {
+            values[*position] = value.trim().to_string();
+        }
+        keys.push(if key_positions.is_empty() {
+            format!("row {}", index + 2)
+        } else {
+            key_positions.iter().map(|&p| values[p].as_str()).collect::<Vec<_>>().join(" | ")
+        });
+        aligned.push(values);
+    }
+    Ok((Side { rows: aligned, keys, present: positions }, new_columns))
+}
+
+/// Write a value keeping numbers numeric, with a highlight format
+fn write_highlighted(worksheet: &mut Worksheet, row: u32, col: u16, value: &str, format: &Format) -> Result<(), XlsxError> {
+    match detect(value) {
+        CellValue::Number(number) => worksheet.write_number_with_format(row, col, number, format)?,
+        _ => worksheet.write_string_with_format(row, col, value, format)?,
+    };
+    Ok(())
+}
+
+fn save_workbook(report: &DiffReport, new_side: &Side, path: &str) -> Result<(), String> {
+    let added = Format::new().set_background_color(Color::RGB(0xC6EFCE));
+    let removed = Format::new().set_background_color(Color::RGB(0xFFC7CE)).set_font_strikethrough();
+    let changed = Format::new().set_background_color(Color::RGB(0xFFEB9C));
+
+    let status: HashMap<&str, &RowChange> = report.changed_rows.iter().map(|c| (c.key.as_str(), c)).collect();
+    let added_keys: HashSet<&str> = report.added_rows.iter().map(|r| r.key.as_str()).collect();
+
+    let mut headers = vec!["Status".to_string()];
+    headers.extend(report.columns.iter().cloned());
+    let mut rows: Vec<Vec<CellValue>> = Vec::new();
+    for (key, values) in new_side.keys.iter().zip(&new_side.rows) {
+        let label = if added_keys.contains(&key.as_str()) { "added" } else if status.contains_key(key.as_str()) { "changed" } else { "" };
+        rows.push(std::iter::once(CellValue::Text(label.to_string())).chain(values.iter().map(|v| detect(v))).collect());
+    }
+    for entry in &report.removed_rows {
+        rows.push(std::iter::once(CellValue::Text("removed".to_string())).chain(entry.values.iter().map(|v| detect(v))).collect());
+    }
+
+    let mut workbook = Workbook::new();
+    let worksheet = workbook.add_worksheet().set_name("Diff").map_err(xlsx_err)?;
+    write_table(worksheet, &headers, &rows, true).map_err(xlsx_err)?;
+
+    // Highlight on top of the typed table: row i of `rows` is sheet row i + 1
+    for (i, (key, values)) in new_side.keys.iter().zip(&new_side.rows).enumerate() {
+        let row = i as u32 + 1;
+        if added_keys.contains(&key.as_str()) {
+            for (col, value) in values.iter().enumerate() {
+                write_highlighted(worksheet, row, col as u16 + 1, value, &added).map_err(xlsx_err)?;
+            }
+        } else if let Some(change) = status.get(key.as_str()) {
+            for cell in &change.cells {
+                if let Some(col) = report.columns.iter().position(|c| *c == cell.column) {
+                    write_highlighted(worksheet, row, col as u16 + 1, &cell.new_value, &changed).map_err(xlsx_err)?;
+                }
+            }
+        }
+    }
+    for (i, entry) in report.removed_rows.iter().enumerate() {
+        let row = (new_side.rows.len() + i) as u32 + 1;
+        for (col, value) in entry.values.iter().enumerate() {
+            write_highlighted(worksheet, row, col as u16 + 1, value, &removed).map_err(xlsx_err)?;
+        }
+    }
+
+    let changes: Vec<Vec<CellValue>> = report.changed_rows.iter()
+        .flat_map(|change| change.cells.iter().map(move |cell| vec![
+            CellValue::Text(change.key.clone()),
+            CellValue::Text(cell.column.clone()),
+            detect(&cell.old_value),
+            detect(&cell.new_value),
+        ]))
+        .collect();
+    let change_headers = ["Key", "Column", "Old value", "New value"].map(String::from);
+    let worksheet = workbook.add_worksheet().set_name("Changes").map_err(xlsx_err)?;
+    write_table(worksheet, &change_headers, &changes, true).map_err(xlsx_err)?;
+
+    workbook.save(path).map_err(xlsx_err)
+}
+
+/// Compare `old_path` with `new_path`
+pub fn diff_spreadsheets(old_path: String, new_path: String, options: DiffOptions) -> Result<DiffReport, String> {
+    info!("📊 Comparing {} with {}", old_path, new_path);
+
+    let mut columns = Vec::new();
+    let (mut old_side, _) = load_side(&old_path, options.sheet_index, &mut columns, &options.key_columns)?;
+    let old_columns = columns.len();
+    let (new_side, added_columns) = load_side(&new_path, options.sheet_index, &mut columns, &options.key_columns)?;
+    for row in &mut old_side.rows {
+        row.resize(columns.len(), String::new());
+    }
+    let removed_columns: Vec<String> = (0..old_columns)
+        .filter(|c| !new_side.present.contains(c))
+        .map(|c| columns[c].clone())
+        .collect();
+
+    let mut duplicate_keys = Vec::new();
+    let mut old_index: HashMap<&str, usize> = HashMap::new();
+    for (i, key) in old_side.keys.iter().enumerate() {
+        match old_index.entry(key) {
+            Entry::Occupied(_) => duplicate_keys.push(key.clone()),
+            Entry::Vacant(slot) => {
+                slot.insert(i);
+            }
+        }
+    }
+    let mut seen_new: HashMap<&str, usize> = HashMap::new();
+
+    let (mut added_rows, mut changed_rows, mut unchanged_rows) = (Vec::new(), Vec::new(), 0);
+    for (j, key) in new_side.keys.iter().enumerate() {
+        match seen_new.entry(key) {
+            Entry::Occupied(_) => {
+                duplicate_keys.push(key.clone());
+                continue;
+            }
+            Entry::Vacant(slot) => {
+                slot.insert(j);
+            }
+        }
+        let new_values = &new_side.rows[j];
+        let Some(&i) = old_index.get(key.as_str()) else {
+            added_rows.push(RowEntry { key: key.clone(), row: j + 2, values: new_values.clone() });
+            continue;
+        };
+        let cells: Vec<CellChange> = columns.iter().enumerate()
+            .filter(|(c, column)| !added_columns.contains(column) && !removed_columns.contains(column) && old_side.rows[i][*c] != new_values[*c])
+            .map(|(c, column)| CellChange {
+                column: column.clone(),
+                old_value: old_side.rows[i][c].clone(),
+                new_value: new_values[c].clone(),
+            })
+            .collect();
+        if cells.is_empty() {
+            unchanged_rows += 1;
+        } else {
+            changed_rows.push(RowChange { key: key.clone(), old_row: i + 2, new_row: j + 2, cells });
+        }
+    }
+    let removed_rows: Vec<RowEntry> = old_side.keys.iter().enumerate()
+        .filter(|(i, key)| !seen_new.contains_key(key.as_str()) && old_index.get(key.as_str()) == Some(i))
+        .map(|(i, key)| RowEntry { key: key.clone(), row: i + 2, values: old_side.rows[i].clone() })
+        .collect();
+    duplicate_keys.sort();
+    duplicate_keys.dedup();
+
+    let mut report = DiffReport {
+        columns,
+        added_columns,
+        removed_columns,
+        added_rows,
+        removed_rows,
+        changed_rows,
+        unchanged_rows,
+        duplicate_keys,
+        output_path: None,
+    };
+
+    if let Some(output_path) = options.output_path.filter(|p| !p.is_empty()) {
+        let ext = Path::new(&output_path).extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
+        match ext.as_str() {
+            "xlsx" => save_workbook(&report, &new_side, &output_path)?,
+            "json" => {
+                let json = serde_json::to_string_pretty(&report)
+                    .map_err(|e| format!("Failed to serialize JSON: {}", e))?;
+                fs::write(&output_path, json).map_err(|e| format!("Failed to write JSON: {}", e))?;
+            }
+            _ => return Err(format!("Unsupported report format: {} (use xlsx or json)", ext)),
+        }
+        report.output_path = Some(output_path);
+    }
+
+    info!(
+        "✅ Diff: {} added, {} removed, {} changed rows",
+        report.added_rows.len(),
+        report.removed_rows.len(),
+        report.changed_rows.len()
+    );
+    Ok(report)
+}
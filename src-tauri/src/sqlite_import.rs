@@ -0,0 +1,210 @@
+//! SQLite Import - load CSV/Excel files into tables of one .db file and query it with SQL
+//! Each file becomes a table (replaced if it exists) named after the file unless given a name.
+//! Column types come from the data: INTEGER, REAL or TEXT; dates are stored as ISO text
+//! (2024-06-30) so they sort and compare correctly in SQL.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use log::info;
+use rusqlite::types::{Value as SqlValue, ValueRef};
+use rusqlite::{params_from_iter, Connection};
+use crate::bundled_converter::ConversionResult;
+use crate::excel_reader::read_table;
+use crate::sheet_writer::{detect, CellValue};
+
+/// Rows returned by a query; the result says when more were left out
+const MAX_QUERY_ROWS: usize = 1000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryResult {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<serde_json::Value>>,
+    pub rows_affected: usize,           // For INSERT/UPDATE/DELETE; 0 for queries
+    pub truncated: bool,                // More than MAX_QUERY_ROWS rows matched
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum ColumnType {
+    Integer,
+    Real,
+    Date,
+    Text,
+}
+
+impl ColumnType {
+    fn sql(self) -> &'static str {
+        match self {
+            ColumnType::Integer => "INTEGER",
+            ColumnType::Real => "REAL",
+            ColumnType::Date | ColumnType::Text => "TEXT",
+        }
+    }
+}
+
+fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// Table or column name from free text; empty names get `fallback`, repeats get _2, _3...
+fn unique_name(name: &str, fallback: &str, used: &mut HashSet<String>) -> String {
+    let base: String = name.trim().chars()
+        .map(|c| if c.is_alphanumeric() || c == '_' { c } else { '_' })
+        .collect();
+    let base = if base.trim_matches('_').is_empty() { fallback.to_string() } else { base };
+    let mut candidate = base.clone();
+    let mut n = 2;
+    while !used.insert(candidate.to_lowercase()) {
+        candidate = format!("{}_{}", base, n);
+        n += 1;
+    }
+    candidate
+}
+
+fn column_type(rows: &[Vec<String>], column: usize) -> ColumnType {
+    let mut kind: Option<ColumnType> = None;
+    for value in rows.iter().filter_map(|row| row.get(column)) {
+        let cell = match detect(value) {
+            CellValue::Empty => continue,
+            CellValue::Number(n) if n.fract() == 0.0 && n.abs() < 9e15 && !value.contains('.') => ColumnType::Integer,
+            CellValue::Number(_) => ColumnType::Real,
+            CellValue::Date(_) | CellValue::DateTime(_) => ColumnType::Date,
+            CellValue::Text(_) | CellValue::Bool(_) => return ColumnType::Text,
+        };
+        kind = Some(match (kind, cell) {
+            (None, cell) => cell,
+            (Some(a), b) if a == b => a,
+            (Some(ColumnType::Integer), ColumnType::Real) | (Some(ColumnType::Real), ColumnType::Integer) => ColumnType::Real,
+            _ => return ColumnType::Text,
+        });
+    }
+    kind.unwrap_or(ColumnType::Text)
+}
+
+fn sql_value(value: &str, kind: ColumnType) -> SqlValue {
+    match (detect(value), kind) {
+        (CellValue::Empty, _) => SqlValue::Null,
+        (CellValue::Number(n), ColumnType::Integer) => SqlValue::Integer(n as i64),
+        (CellValue::Number(n), ColumnType::Real) => SqlValue::Real(n),
+        (CellValue::Date(date), ColumnType::Date) => SqlValue::Text(date.format("%Y-%m-%d").to_string()),
+        (CellValue::DateTime(datetime), ColumnType::Date) => SqlValue::Text(datetime.format("%Y-%m-%d %H:%M:%S").to_string()),
+        _ => SqlValue::Text(value.to_string()),
+    }
+}
+
+fn sql_err(e: rusqlite::Error) -> String {
+    format!("Database error: {}", e)
+}
+
+/// Load `inputs` into `output_db` (created if missing); `table_names[i]` names the table for `inputs[i]`
+pub fn import_to_sqlite(
+    inputs: Vec<String>,
+    output_db: String,
+    table_names: Vec<String>,
+) -> Result<ConversionResult, String> {
+    if inputs.is_empty() {
+        return Err("No files to import".to_string());
+    }
+
+    info!("📊 Importing {} files into {}", inputs.len(), output_db);
+
+    let mut conn = Connection::open(&output_db).map_err(sql_err)?;
+    let mut used_tables = HashSet::new();
+    let mut summary = Vec::new();
+
+    for (i, path) in inputs.iter().enumerate() {
+        let (headers, rows) = read_table(path, None).map_err(|e| format!("{}: {}", path, e))?;
+        let stem = Path::new(path).file_stem().and_then(|s| s.to_str()).unwrap_or("table");
+        let table = unique_name(table_names.get(i).map(String::as_str).unwrap_or(stem), "table", &mut used_tables);
+
+        let mut used_columns = HashSet::new();
+        let columns: Vec<(String, ColumnType)> = headers.iter().enumerate()
+            .map(|(c, name)| (unique_name(name, &format!("column{}", c + 1), &mut used_columns), column_type(&rows, c)))
+            .collect();
+        if columns.is_empty() {
+            return Err(format!("{}: no columns found", path));
+        }
+
+        let tx = conn.transaction().map_err(sql_err)?;
+        let definition = columns.iter()
+            .map(|(name, kind)| format!("{} {}", quote_identifier(name), kind.sql()))
+            .collect::<Vec<_>>()
+            .join(", ");
+        tx.execute_batch(&format!(
+            "DROP TABLE IF EXISTS {0}; CREATE TABLE {0} ({1});",
+            quote_identifier(&table),
+            definition
+        ))
+        .map_err(sql_err)?;
+        {
+            let placeholders = vec!["?"; columns.len()].join(", ");
+            let mut insert = tx
+                .prepare(&format!("INSERT INTO {} VALUES ({})", quote_identifier(&table), placeholders))
+                .map_err(sql_err)?;
+            for row in &rows {
+                let values = columns.iter().enumerate()
+                    .map(|(c, (_, kind))| sql_value(row.get(c).map(String::as_str).unwrap_or(""), *kind));
+                insert.execute(params_from_iter(values)).map_err(sql_err)?;
+            }
+        }
+        tx.commit().map_err(sql_err)?;
+        summary.push(format!("{} ({} rows)", table, rows.len()));
+    }
+
+    let output_size = fs::metadata(&output_db).map(|m| m.len()).ok();
+
+    info!("✅ Imported into {}: {}", output_db, summary.join(", "));
+    Ok(ConversionResult {
+        success: true,
+        output_path: output_db,
+        message: format!("Imported tables: {}", summary.join(", ")),
+        output_size,
+    })
+}
+
+fn json_value(value: ValueRef) -> serde_json::Value {
+    match value {
+        ValueRef::Null => serde_json::Value::Null,
+        ValueRef::Integer(n) => serde_json::Value::from(n),
+        ValueRef::Real(n) => serde_json::Number::from_f64(n).map(serde_json::Value::Number).unwrap_or(serde_json::Value::Null),
+        ValueRef::Text(text) => serde_json::Value::String(String::from_utf8_lossy(text).to_string()),
+        ValueRef::Blob(bytes) => serde_json::Value::String(format!("<{} bytes>", bytes.len())),
+    }
+}
+
+/// Run one SQL statement against an existing database
+pub fn query_sqlite(db_path: String, sql: String) -> Result<QueryResult, String> {
+    if !Path::new(&db_path).exists() {
+        return Err(format!("Database not found: {}", db_path));
+    }
+
+    info!("📊 Running query on {}", db_path);
+
+    let conn = Connection::open(&db_path).map_err(sql_err)?;
+    let mut statement = conn.prepare(sql.trim().trim_end_matches(';')).map_err(sql_err)?;
+    let columns: Vec<String> = statement.column_names().into_iter().map(str::to_string).collect();
+
+    if columns.is_empty() {
+        let rows_affected = statement.execute([]).map_err(sql_err)?;
+        return Ok(QueryResult { columns, rows: Vec::new(), rows_affected, truncated: false });
+    }
+
+    let mut rows = Vec::new();
+    let mut truncated = false;
+    let mut cursor = statement.query([]).map_err(sql_err)?;
+    while let Some(row) = cursor.next().map_err(sql_err)? {
+        if rows.len() == MAX_QUERY_ROWS {
+            truncated = true;
+            break;
+        }
+        let values = (0..columns.len())
+            .map(|c| row.get_ref(c).map(json_value))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(sql_err)?;
+        rows.push(values);
+    }
+
+    info!("✅ Query returned {} rows", rows.len());
+    Ok(QueryResult { columns, rows, rows_affected: 0, truncated })
+}
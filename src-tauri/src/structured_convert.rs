@@ -0,0 +1,187 @@
+//! Structured Convert - YAML, TOML, JSON, XML and CSV into one another
+//! Everything goes through a JSON value: YAML files with several documents become an array,
+//! TOML dates become text, and TOML output drops nulls (TOML has none). CSV output flattens
+//! records like json_to_csv; CSV input gives one object of text values per row.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Number, Value};
+use std::fs;
+use std::path::Path;
+use log::info;
+use crate::bundled_converter::ConversionResult;
+use crate::csv_dialect::{decode, read_csv, write_csv, CsvDialect};
+use crate::json_flatten::{flatten_records, JsonToCsvOptions};
+use crate::xml_convert::{parse_xml, render_xml, XmlOptions};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DataFormat {
+    Json,
+    Yaml,
+    Toml,
+    Xml,
+    Csv,
+}
+
+impl DataFormat {
+    fn from_path(path: &str) -> Result<Self, String> {
+        let ext = Path::new(path)
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("")
+            .to_lowercase();
+        match ext.as_str() {
+            "json" => Ok(DataFormat::Json),
+            "yaml" | "yml" => Ok(DataFormat::Yaml),
+            "toml" => Ok(DataFormat::Toml),
+            "xml" => Ok(DataFormat::Xml),
+            "csv" => Ok(DataFormat::Csv),
+            _ => Err(format!("Unsupported format: {}", ext)),
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            DataFormat::Json => "JSON",
+            DataFormat::Yaml => "YAML",
+            DataFormat::Toml => "TOML",
+            DataFormat::Xml => "XML",
+            DataFormat::Csv => "CSV",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StructuredOptions {
+    pub csv: JsonToCsvOptions,          // Flattening when writing CSV
+    pub xml: XmlOptions,                // Attribute/element mapping when reading or writing XML
+}
+
+fn from_toml(value: toml::Value) -> Value {
+    match value {
+        toml::Value::String(text) => Value::String(text),
+        toml::Value::Integer(number) => Value::Number(number.into()),
+        toml::Value::Float(number) => Number::from_f64(number).map(Value::Number).unwrap_or_else(|| Value::String(number.to_string())),
+        toml::Value::Boolean(flag) => Value::Bool(flag),
+        toml::Value::Datetime(datetime) => Value::String(datetime.to_string()),
+        toml::Value::Array(items) => Value::Array(items.into_iter().map(from_toml).collect()),
+        toml::Value::Table(table) => Value::Object(table.into_iter().map(|(k, v)| (k, from_toml(v))).collect()),
+    }
+}
+
+fn to_toml(value: &Value) -> Option<toml::Value> {
+    match value {
+        Value::Null => None,
+        Value::Bool(flag) => Some(toml::Value::Boolean(*flag)),
+        Value::Number(number) => Some(match number.as_i64() {
+            Some(integer) => toml::Value::Integer(integer),
+            None => toml::Value::Float(number.as_f64().unwrap_or_default()),
+        }),
+        Value::String(text) => Some(toml::Value::String(text.clone())),
+        Value::Array(items) => Some(toml::Value::Array(items.iter().filter_map(to_toml).collect())),
+        Value::Object(fields) => Some(toml::Value::Table(
+            fields.iter().filter_map(|(k, v)| to_toml(v).map(|v| (k.clone(), v))).collect(),
+        )),
+    }
+}
+
+fn read_text(path: &str) -> Result<String, String> {
+    let bytes = fs::read(path).map_err(|e| format!("Failed to read file: {}", e))?;
+    decode(&bytes, None).map(|(text, _)| text)
+}
+
+/// Read a file of the given format as a JSON value
+fn read_value(path: &str, format: DataFormat, options: &StructuredOptions) -> Result<Value, String> {
+    match format {
+        DataFormat::Json => serde_json::from_str(&read_text(path)?).map_err(|e| format!("Failed to parse JSON: {}", e)),
+        DataFormat::Yaml => {
+            let text = read_text(path)?;
+            let mut documents = serde_norway::Deserializer::from_str(&text)
+                .map(Value::deserialize)
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| format!("Failed to parse YAML: {}", e))?;
+            Ok(match documents.len() {
+                0 => Value::Null,
+                1 => documents.remove(0),
+                _ => Value::Array(documents),
+            })
+        }
+        DataFormat::Toml => {
+            let table: toml::Table = toml::from_str(&read_text(path)?).map_err(|e| format!("Failed to parse TOML: {}", e))?;
+            Ok(from_toml(toml::Value::Table(table)))
+        }
+        DataFormat::Xml => parse_xml(&read_text(path)?, &options.xml),
+        DataFormat::Csv => {
+            let table = read_csv(path, &CsvDialect::default())?;
+            let records = table.rows.into_iter()
+                .map(|row| {
+                    let record: Map<String, Value> = table.headers.iter().cloned()
+                        .zip(row.into_iter().map(Value::String))
+                        .collect();
+                    Value::Object(record)
+                })
+                .collect();
+            Ok(Value::Array(records))
+        }
+    }
+}
+
+/// Write a JSON value in the given format
+fn write_value(path: &str, value: &Value, format: DataFormat, options: &StructuredOptions) -> Result<(), String> {
+    let text = match format {
+        DataFormat::Json => serde_json::to_string_pretty(value).map_err(|e| format!("Failed to serialize JSON: {}", e))?,
+        DataFormat::Yaml => serde_norway::to_string(value).map_err(|e| format!("Failed to serialize YAML: {}", e))?,
+        DataFormat::Toml => {
+            let Some(toml::Value::Table(table)) = to_toml(value) else {
+                return Err("TOML needs an object at the top level, not a list or single value".to_string());
+            };
+            toml::to_string_pretty(&table).map_err(|e| format!("Failed to serialize TOML: {}", e))?
+        }
+        DataFormat::Xml => render_xml(value, &options.xml),
+        DataFormat::Csv => {
+            let records = match value {
+                Value::Array(records) => records.clone(),
+                other => vec![other.clone()],
+            };
+            let (headers, rows) = flatten_records(&records, &options.csv)?;
+            let mut output = Vec::with_capacity(rows.len() + 1);
+            output.push(headers);
+            output.extend(rows);
+            return write_csv(path, &output, &CsvDialect::default());
+        }
+    };
+    fs::write(path, text).map_err(|e| format!("Failed to write file: {}", e))
+}
+
+/// Convert between structured formats; `from`/`to` default to the file extensions
+pub fn convert_structured(
+    input_path: String,
+    output_path: String,
+    from: Option<DataFormat>,
+    to: Option<DataFormat>,
+    options: StructuredOptions,
+) -> Result<ConversionResult, String> {
+    let from = match from {
+        Some(format) => format,
+        None => DataFormat::from_path(&input_path)?,
+    };
+    let to = match to {
+        Some(format) => format,
+        None => DataFormat::from_path(&output_path)?,
+    };
+    info!("📊 Converting {} to {} (bundled)", from.label(), to.label());
+
+    let value = read_value(&input_path, from, &options)?;
+    write_value(&output_path, &value, to, &options)?;
+
+    let output_size = fs::metadata(&output_path).map(|m| m.len()).ok();
+
+    info!("✅ {} converted to {}: {}", from.label(), to.label(), output_path);
+    Ok(ConversionResult {
+        success: true,
+        output_path,
+        message: format!("{} converted to {}", from.label(), to.label()),
+        output_size,
+    })
+}
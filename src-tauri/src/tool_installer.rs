@@ -0,0 +1,265 @@
+//! Tool Installer - download portable Pandoc and qpdf builds into app data
+//! Release assets come from the projects' GitHub releases (jgm/pandoc, qpdf/qpdf) and are checked
+//! against the SHA-256 digest GitHub publishes for each asset before extraction. The document
+//! converter resolves the binaries through managed_path(), preferring them over PATH, so a
+//! finished install shows up in check_tools() straight away.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use log::{info, warn};
+use tauri::{AppHandle, Emitter};
+use crate::app_storage::app_data_dir;
+use crate::document_converter::{check_tools, ToolStatus};
+use crate::ffmpeg_manager::{exe_name, find_file};
+use crate::tool_process::WorkDir;
+
+pub const EVENT_INSTALL: &str = "tools://install_progress";
+
+const MANAGED_DIR: &str = "tools";
+
+/// Emit at most one progress event per this many bytes
+const PROGRESS_STEP: u64 = 512 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ManagedTool {
+    Pandoc,
+    Qpdf,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolInstallProgress {
+    pub tool: ManagedTool,
+    pub stage: String,              // downloading, verifying, extracting, done
+    pub downloaded: u64,
+    pub total: Option<u64>,
+    pub percentage: Option<f32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReleaseAsset {
+    name: String,
+    browser_download_url: String,
+    digest: Option<String>,         // "sha256:<hex>"
+}
+
+#[derive(Debug, Deserialize)]
+struct Release {
+    assets: Vec<ReleaseAsset>,
+}
+
+impl ManagedTool {
+    fn binary(&self) -> &'static str {
+        match self {
+            ManagedTool::Pandoc => "pandoc",
+            ManagedTool::Qpdf => "qpdf",
+        }
+    }
+
+    fn repository(&self) -> &'static str {
+        match self {
+            ManagedTool::Pandoc => "jgm/pandoc",
+            ManagedTool::Qpdf => "qpdf/qpdf",
+        }
+    }
+
+    /// End of the release asset name for this platform (None where no portable build is published)
+    fn asset_suffix(&self) -> Option<&'static str> {
+        let windows = cfg!(all(target_os = "windows", target_arch = "x86_64"));
+        let linux_x64 = cfg!(all(target_os = "linux", target_arch = "x86_64"));
+        match self {
+            ManagedTool::Pandoc if windows => Some("-windows-x86_64.zip"),
+            ManagedTool::Pandoc if linux_x64 => Some("-linux-amd64.tar.gz"),
+            ManagedTool::Pandoc if cfg!(all(target_os = "linux", target_arch = "aarch64")) => Some("-linux-arm64.tar.gz"),
+            ManagedTool::Pandoc if cfg!(all(target_os = "macos", target_arch = "aarch64")) => Some("-arm64-macOS.zip"),
+            ManagedTool::Pandoc if cfg!(all(target_os = "macos", target_arch = "x86_64")) => Some("-x86_64-macOS.zip"),
+            ManagedTool::Qpdf if windows => Some("-msvc64.zip"),
+            ManagedTool::Qpdf if linux_x64 => Some("-bin-linux-x86_64.zip"),
+            _ => None,
+        }
+    }
+
+    pub fn install_supported(&self) -> bool {
+        self.asset_suffix().is_some()
+    }
+}
+
+fn managed_dir(tool: ManagedTool) -> Option<PathBuf> {
+    app_data_dir().ok().map(|dir| dir.join(MANAGED_DIR).join(tool.binary()))
+}
+
+/// The installed binary, if any (builds keep it in a bin/ folder next to its libraries)
+pub fn managed_path(tool: ManagedTool) -> Option<PathBuf> {
+    managed_dir(tool)
+        .filter(|dir| dir.is_dir())
+        .and_then(|dir| find_file(&dir, &exe_name(tool.binary())))
+}
+
+fn emit(app: &AppHandle, tool: ManagedTool, stage: &str, downloaded: u64, total: Option<u64>) {
+    let percentage = total.filter(|t| *t > 0).map(|t| (downloaded as f64 / t as f64 * 100.0) as f32);
+    let _ = app.emit(EVENT_INSTALL, ToolInstallProgress {
+        tool,
+        stage: stage.to_string(),
+        downloaded,
+        total,
+        percentage,
+    });
+}
+
+/// Unpack a .zip in-process (GNU tar cannot read zip) or anything else with tar
+async fn extract(archive: &Path, destination: &Path) -> Result<(), String> {
+    if archive.extension().and_then(|e| e.to_str()) == Some("zip") {
+        let (archive, destination) = (archive.to_path_buf(), destination.to_path_buf());
+        return tokio::task::spawn_blocking(move || {
+            let file = std::fs::File::open(&archive).map_err(|e| format!("Failed to open download: {}", e))?;
+            zip::ZipArchive::new(file)
+                .and_then(|mut zip| zip.extract(&destination))
+                .map_err(|e| format!("Failed to extract archive: {}", e))
+        })
+        .await
+        .map_err(|e| format!("Task error: {}", e))?;
+    }
+    let output = tokio::process::Command::new("tar")
+        .arg("-xf").arg(archive)
+        .arg("-C").arg(destination)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run tar: {}", e))?;
+    if !output.status.success() {
+        return Err(format!("Failed to extract archive: {}", String::from_utf8_lossy(&output.stderr)));
+    }
+    Ok(())
+}
+
+/// Extract the archive into `destination` and make the tool's binary executable; returns the
+/// binary's path below `destination`
+async fn unpack_into(archive: &Path, destination: &Path, name: &str) -> Result<PathBuf, String> {
+    extract(archive, destination).await?;
+    let binary = find_file(destination, &exe_name(name))
+        .ok_or_else(|| format!("{} not found in the downloaded archive", exe_name(name)))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&binary, std::fs::Permissions::from_mode(0o755))
+            .map_err(|e| format!("Failed to make {} executable: {}", name, e))?;
+    }
+    Ok(binary.strip_prefix(destination).map(Path::to_path_buf).unwrap_or(binary))
+}
+
+/// Download, verify and unpack the latest release of `tool` into app data
+pub async fn install(app: AppHandle, tool: ManagedTool) -> Result<Vec<ToolStatus>, String> {
+    let name = tool.binary();
+    let suffix = tool.asset_suffix().ok_or_else(|| format!(
+        "No portable {} build for this platform - install it with your package manager (e.g. `brew install {}`) and restart",
+        name, name
+    ))?;
+    let target_dir = managed_dir(tool).ok_or("Could not find app data directory")?;
+
+    let client = reqwest::Client::builder()
+        .connect_timeout(Duration::from_secs(15))
+        .user_agent("alagappa-tools")
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let release: Release = client.get(format!("https://api.github.com/repos/{}/releases/latest", tool.repository()))
+        .send().await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Failed to look up the latest {} release: {}", name, e))?
+        .json().await
+        .map_err(|e| format!("Failed to read {} release: {}", name, e))?;
+    let asset = release.assets.into_iter()
+        .find(|a| a.name.ends_with(suffix))
+        .ok_or_else(|| format!("The latest {} release has no *{} download", name, suffix))?;
+    let expected = asset.digest.as_deref()
+        .and_then(|d| d.strip_prefix("sha256:"))
+        .map(str::to_lowercase)
+        .ok_or_else(|| format!("No checksum published for {}", asset.name))?;
+
+    info!("⬇️ Downloading {}: {}", name, asset.name);
+    let mut response = client.get(&asset.browser_download_url)
+        .send().await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Failed to download {}: {}", name, e))?;
+    let total = response.content_length();
+
+    // Own folder per install, so two installs running at once don't share files
+    let staging = WorkDir::create(&format!("{}-download", name))?;
+    let archive_path = staging.path().join(&asset.name);
+
+    let mut file = std::fs::File::create(&archive_path)
+        .map_err(|e| format!("Failed to save download: {}", e))?;
+    let mut hasher = Sha256::new();
+    let mut downloaded = 0;
+    let mut last_emit = 0;
+    emit(&app, tool, "downloading", 0, total);
+    while let Some(chunk) = response.chunk().await.map_err(|e| format!("Download interrupted: {}", e))? {
+        hasher.update(&chunk);
+        file.write_all(&chunk)
+            .map_err(|e| format!("Failed to save download: {}", e))?;
+        downloaded += chunk.len() as u64;
+        if downloaded - last_emit >= PROGRESS_STEP {
+            last_emit = downloaded;
+            emit(&app, tool, "downloading", downloaded, total);
+        }
+    }
+    drop(file);
+
+    emit(&app, tool, "verifying", downloaded, total);
+    let actual: String = hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect();
+    if actual != expected {
+        return Err(format!("Checksum mismatch for {} - download discarded", asset.name));
+    }
+
+    // Unpack next to the destination (same disk, so it can be renamed into place); the previous
+    // version stays until the new one is complete
+    emit(&app, tool, "extracting", downloaded, total);
+    let staging_name = staging.path().file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let partial = target_dir.with_file_name(format!("{}.{}.partial", name, staging_name));
+    std::fs::create_dir_all(&partial)
+        .map_err(|e| format!("Failed to create {} folder: {}", name, e))?;
+    let binary = match unpack_into(&archive_path, &partial, name).await {
+        Ok(binary) => target_dir.join(binary),
+        Err(e) => {
+            let _ = std::fs::remove_dir_all(&partial);
+            return Err(e);
+        }
+    };
+
+    // Replace any previous version as a whole: qpdf's binary needs the libraries next to it
+    let previous = target_dir.with_file_name(format!("{}.{}.old", name, staging_name));
+    let had_previous = target_dir.exists();
+    if had_previous {
+        std::fs::rename(&target_dir, &previous)
+            .map_err(|e| format!("Failed to replace the installed {}: {}", name, e))?;
+    }
+    if let Err(e) = std::fs::rename(&partial, &target_dir) {
+        if had_previous {
+            let _ = std::fs::rename(&previous, &target_dir);
+        }
+        let _ = std::fs::remove_dir_all(&partial);
+        return Err(format!("Failed to install {}: {}", name, e));
+    }
+    if had_previous {
+        if let Err(e) = std::fs::remove_dir_all(&previous) {
+            warn!("Could not remove the previous {}: {}", name, e);
+        }
+    }
+    drop(staging);
+    emit(&app, tool, "done", downloaded, total);
+
+    info!("✅ Managed {} installed: {}", name, binary.display());
+    tokio::task::spawn_blocking(check_tools).await.map_err(|e| format!("Task error: {}", e))
+}
+
+/// Delete the managed copy (falls back to the tool on PATH)
+pub fn uninstall(tool: ManagedTool) -> Result<Vec<ToolStatus>, String> {
+    if let Some(dir) = managed_dir(tool).filter(|d| d.exists()) {
+        std::fs::remove_dir_all(&dir)
+            .map_err(|e| format!("Failed to remove managed {}: {}", tool.binary(), e))?;
+        info!("🗑️ Managed {} removed", tool.binary());
+    }
+    Ok(check_tools())
+}
@@ -0,0 +1,149 @@
+//! Tool Process - run external converters (soffice, pandoc, wkhtmltopdf, qpdf/pdftk, LaTeX) with a time limit
+//! A converter stuck on a dialog or a broken file is killed together with its child processes,
+//! its partial output is deleted and the error starts with TIMED_OUT. Limits are per tool and
+//! saved in the app data directory.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::{Output, Stdio};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::process::Command as TokioCommand;
+use tokio::task::JoinHandle;
+use log::warn;
+use crate::app_storage::{load_json, save_json};
+
+const TIMEOUTS_FILE: &str = "tool_timeouts.json";
+
+/// Prefix of the error returned when a tool runs past its limit
+pub const TIMED_OUT: &str = "Timed out";
+
+/// Prefix of the error returned when the tool's binary cannot be found
+pub const NOT_INSTALLED: &str = "Not installed";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Tool {
+    LibreOffice,
+    Pandoc,
+    Wkhtmltopdf,
+    PdfMerge,
+    Latex,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ToolTimeouts {
+    pub libreoffice_secs: Option<u64>,  // Default 300; 0 = no limit
+    pub pandoc_secs: Option<u64>,       // Default 120
+    pub wkhtmltopdf_secs: Option<u64>,  // Default 120
+    pub pdf_merge_secs: Option<u64>,    // qpdf/pdftk, default 120
+    pub latex_secs: Option<u64>,        // Per pass; default 300 (tectonic downloads packages on first use)
+}
+
+impl ToolTimeouts {
+    pub fn limit(&self, tool: Tool) -> Option<Duration> {
+        let secs = match tool {
+            Tool::LibreOffice => self.libreoffice_secs.unwrap_or(300),
+            Tool::Pandoc => self.pandoc_secs.unwrap_or(120),
+            Tool::Wkhtmltopdf => self.wkhtmltopdf_secs.unwrap_or(120),
+            Tool::PdfMerge => self.pdf_merge_secs.unwrap_or(120),
+            Tool::Latex => self.latex_secs.unwrap_or(300),
+        };
+        (secs > 0).then_some(Duration::from_secs(secs))
+    }
+}
+
+pub fn load_timeouts() -> Result<ToolTimeouts, String> {
+    load_json(TIMEOUTS_FILE)
+}
+
+pub fn save_timeouts(timeouts: ToolTimeouts) -> Result<ToolTimeouts, String> {
+    save_json(TIMEOUTS_FILE, &timeouts)?;
+    Ok(timeouts)
+}
+
+/// Temporary folder for the intermediate files of a tool pipeline, deleted when dropped
+pub struct WorkDir(PathBuf);
+
+impl WorkDir {
+    pub fn create(purpose: &str) -> Result<Self, String> {
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or(0);
+        let path = std::env::temp_dir().join(format!("alagappa-{}-{}-{}", purpose, std::process::id(), nanos));
+        std::fs::create_dir_all(&path).map_err(|e| format!("Failed to create temporary folder: {}", e))?;
+        Ok(WorkDir(path))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for WorkDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+fn drain<R: AsyncRead + Unpin + Send + 'static>(pipe: Option<R>) -> JoinHandle<Vec<u8>> {
+    tokio::spawn(async move {
+        let mut buffer = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut buffer).await;
+        }
+        buffer
+    })
+}
+
+/// Kill a process and everything it started (soffice forks a second process, for one)
+fn kill_tree(pid: u32) {
+    #[cfg(windows)]
+    let (program, args) = ("taskkill", vec!["/T".to_string(), "/F".to_string(), "/PID".to_string(), pid.to_string()]);
+    // The child leads its own process group (see run_tool), so the group id is its pid
+    #[cfg(not(windows))]
+    let (program, args) = ("kill", vec!["-KILL".to_string(), format!("-{}", pid)]);
+    if let Err(e) = std::process::Command::new(program).args(&args).output() {
+        warn!("Failed to stop process tree {}: {}", pid, e);
+    }
+}
+
+/// Run `cmd` to completion, or kill it after the tool's configured limit and remove `outputs`
+pub async fn run_tool(mut cmd: TokioCommand, tool: Tool, name: &str, outputs: &[&Path]) -> Result<Output, String> {
+    let limit = load_timeouts().unwrap_or_default().limit(tool);
+
+    cmd.stdout(Stdio::piped()).stderr(Stdio::piped()).stdin(Stdio::null());
+    cmd.kill_on_drop(true);
+    #[cfg(unix)]
+    cmd.process_group(0);
+    let program = cmd.as_std().get_program().to_string_lossy().to_string();
+    let mut child = cmd.spawn().map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => format!("{}: {} (`{}` was not found)", NOT_INSTALLED, name, program),
+        _ => format!("Failed to start {}: {}", name, e),
+    })?;
+    let pid = child.id();
+    let stdout = drain(child.stdout.take());
+    let stderr = drain(child.stderr.take());
+
+    let status = match limit {
+        Some(limit) => match tokio::time::timeout(limit, child.wait()).await {
+            Ok(status) => status,
+            Err(_) => {
+                if let Some(pid) = pid {
+                    kill_tree(pid);
+                }
+                let _ = child.kill().await;
+                for output in outputs {
+                    let _ = std::fs::remove_file(output);
+                }
+                return Err(format!("{}: {} did not finish within {} s and was stopped", TIMED_OUT, name, limit.as_secs()));
+            }
+        },
+        None => child.wait().await,
+    }
+    .map_err(|e| format!("Failed to run {}: {}", name, e))?;
+
+    Ok(Output {
+        status,
+        stdout: stdout.await.unwrap_or_default(),
+        stderr: stderr.await.unwrap_or_default(),
+    })
+}
@@ -0,0 +1,90 @@
+//! Tool Registry - user settings for external converter binaries
+//! A custom path per binary (a LibreOffice outside PATH, a second FFmpeg, ...) is used before the
+//! managed downloads and PATH, and `pdf_merge_order` decides which engine merges PDFs first.
+//! Saved in the app data directory.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use crate::app_storage::{load_json, save_json};
+
+const REGISTRY_FILE: &str = "tool_registry.json";
+
+/// Binaries a custom path can be set for
+pub const KNOWN_TOOLS: &[&str] = &[
+    "soffice", "pandoc", "wkhtmltopdf", "qpdf", "pdftk", "ffmpeg", "ffprobe", "tectonic", "xelatex", "pdflatex",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PdfMergeEngine {
+    Qpdf,
+    Pdftk,
+    Bundled,    // Built-in merge (lopdf), no external tool
+}
+
+impl PdfMergeEngine {
+    pub fn label(&self) -> &'static str {
+        match self {
+            PdfMergeEngine::Qpdf => "qpdf",
+            PdfMergeEngine::Pdftk => "pdftk",
+            PdfMergeEngine::Bundled => "the built-in merger",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ToolRegistry {
+    pub paths: BTreeMap<String, String>,                // "soffice" -> "/opt/libreoffice7.6/program/soffice"
+    pub pdf_merge_order: Option<Vec<PdfMergeEngine>>,   // Default: qpdf, then pdftk
+}
+
+pub fn load_registry() -> Result<ToolRegistry, String> {
+    load_json(REGISTRY_FILE)
+}
+
+/// Validate and save; empty paths are dropped, unknown tools and missing files rejected
+pub fn save_registry(mut registry: ToolRegistry) -> Result<ToolRegistry, String> {
+    registry.paths = registry.paths.into_iter()
+        .map(|(tool, path)| (tool.trim().to_lowercase(), path.trim().to_string()))
+        .filter(|(_, path)| !path.is_empty())
+        .collect();
+    for (tool, path) in &registry.paths {
+        if !KNOWN_TOOLS.contains(&tool.as_str()) {
+            return Err(format!("Unknown tool: {} (expected one of {})", tool, KNOWN_TOOLS.join(", ")));
+        }
+        if !Path::new(path).is_file() {
+            return Err(format!("{} not found: {}", tool, path));
+        }
+    }
+    if let Some(order) = registry.pdf_merge_order.as_mut() {
+        let mut seen = Vec::new();
+        order.retain(|engine| {
+            let first = !seen.contains(engine);
+            seen.push(*engine);
+            first
+        });
+        if order.is_empty() {
+            registry.pdf_merge_order = None;
+        }
+    }
+    save_json(REGISTRY_FILE, &registry)?;
+    Ok(registry)
+}
+
+/// The user's path for `tool`, if set and still present
+pub fn custom_path(tool: &str) -> Option<PathBuf> {
+    load_registry().ok()?
+        .paths
+        .get(tool)
+        .map(PathBuf::from)
+        .filter(|path| path.is_file())
+}
+
+pub fn pdf_merge_order() -> Vec<PdfMergeEngine> {
+    load_registry().ok()
+        .and_then(|registry| registry.pdf_merge_order)
+        .filter(|order| !order.is_empty())
+        .unwrap_or_else(|| vec![PdfMergeEngine::Qpdf, PdfMergeEngine::Pdftk])
+}
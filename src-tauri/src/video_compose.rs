@@ -0,0 +1,84 @@
+//! Video Compose - combine two recordings into one frame: picture-in-picture or side-by-side
+//! The usual hybrid-lecture setup is slides/screen as the first input and the presenter camera second.
+
+use std::path::Path;
+use log::info;
+use crate::ffmpeg_runner::{ffmpeg_command, run_ffmpeg, MediaJob};
+use crate::media_converter::ConversionResult;
+
+/// Output height for side-by-side (both inputs are scaled to it)
+const STACK_HEIGHT: u32 = 720;
+
+/// Width of the inset as a fraction of the main picture, and its margin in pixels
+const PIP_SCALE: f64 = 0.28;
+const PIP_MARGIN: u32 = 24;
+
+/// Filter graph for a layout; the main picture's length decides the output length
+fn layout_graph(layout: &str) -> Result<String, String> {
+    match layout.to_lowercase().as_str() {
+        "pip" | "picture_in_picture" => Ok(format!(
+            "[1:v][0:v]scale2ref=w=main_w*{scale}:h=ow/dar[inset][main];\
+             [main][inset]overlay=W-w-{m}:H-h-{m}:eof_action=pass,format=yuv420p[out]",
+            scale = PIP_SCALE, m = PIP_MARGIN
+        )),
+        "side_by_side" | "sbs" => Ok(format!(
+            "[0:v]scale=-2:{h},setsar=1[left];[1:v]scale=-2:{h},setsar=1[right];\
+             [left][right]hstack=inputs=2,format=yuv420p[out]",
+            h = STACK_HEIGHT
+        )),
+        "stacked" | "top_bottom" => Ok(
+            "[1:v][0:v]scale2ref=w=main_w:h=ow/dar[bottom][top];\
+             [top][bottom]vstack=inputs=2,scale=trunc(iw/2)*2:trunc(ih/2)*2,format=yuv420p[out]".to_string()
+        ),
+        other => Err(format!("Unknown layout: {} (use pip, side_by_side or stacked)", other)),
+    }
+}
+
+/// Compose exactly two inputs. Audio is taken from the first (main) input.
+pub async fn compose(
+    layout: String,
+    inputs: Vec<String>,
+    output_path: String,
+    job: MediaJob,
+) -> Result<ConversionResult, String> {
+    if inputs.len() != 2 {
+        return Err("Composition needs exactly two inputs (main, then inset/right)".to_string());
+    }
+    for path in &inputs {
+        if !Path::new(path).exists() {
+            return Err(format!("Input file not found: {}", path));
+        }
+    }
+    let graph = layout_graph(&layout)?;
+
+    info!("🧩 Composing {} + {} ({}) -> {}", inputs[0], inputs[1], layout, output_path);
+
+    let job = job.with_duration_of(&inputs[0]).await.with_output(&output_path);
+    let mut cmd = ffmpeg_command();
+    cmd.arg("-y");
+    for input in &inputs {
+        cmd.arg("-i").arg(input);
+    }
+    cmd.arg("-filter_complex").arg(graph);
+    cmd.arg("-map").arg("[out]");
+    cmd.arg("-map").arg("0:a:0?");
+    cmd.arg("-c:v").arg("libx264");
+    cmd.arg("-crf").arg("20");
+    cmd.arg("-preset").arg("medium");
+    cmd.arg("-c:a").arg("aac");
+    cmd.arg("-b:a").arg("160k");
+    cmd.arg("-shortest");
+    cmd.arg(&output_path);
+
+    run_ffmpeg(cmd, &job).await
+        .map_err(|error| format!("Composition failed: {}", error))?;
+
+    let output_size = std::fs::metadata(&output_path).map(|m| m.len()).ok();
+    info!("✅ Composition written: {}", output_path);
+    Ok(ConversionResult {
+        success: true,
+        output_path,
+        message: "Videos composed successfully".to_string(),
+        output_size,
+    })
+}
@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 use std::process::Command;
 use std::path::Path;
 use tokio::process::Command as TokioCommand;
+use crate::ffmpeg_manager::{ffmpeg_path, ffprobe_path};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VideoConversionOptions {
@@ -25,7 +26,7 @@ pub struct ConversionProgress {
 
 // Check if FFmpeg is available on the system
 pub fn check_ffmpeg_available() -> Result<String, String> {
-    let output = Command::new("ffmpeg")
+    let output = Command::new(ffmpeg_path())
         .arg("-version")
         .output()
         .map_err(|e| format!("FFmpeg not found: {}. Please install FFmpeg first.", e))?;
@@ -40,7 +41,7 @@ pub fn check_ffmpeg_available() -> Result<String, String> {
 
 // Get video file information
 pub async fn get_video_info(input_path: &str) -> Result<serde_json::Value, String> {
-    let output = Command::new("ffprobe")
+    let output = Command::new(ffprobe_path())
         .arg("-v")
         .arg("quiet")
         .arg("-print_format")
@@ -69,7 +70,7 @@ pub async fn convert_video(options: VideoConversionOptions) -> Result<String, St
     }
 
     // Build FFmpeg command
-    let mut cmd = TokioCommand::new("ffmpeg");
+    let mut cmd = TokioCommand::new(ffmpeg_path());
     
     // Input file
     cmd.arg("-i").arg(&options.input_path);
@@ -201,7 +202,7 @@ pub async fn extract_audio(
         return Err(format!("Input file not found: {}", input_path));
     }
     
-    let mut cmd = TokioCommand::new("ffmpeg");
+    let mut cmd = TokioCommand::new(ffmpeg_path());
     cmd.arg("-i").arg(&input_path);
     cmd.arg("-vn"); // No video
     cmd.arg("-y"); // Overwrite
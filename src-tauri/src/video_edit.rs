@@ -0,0 +1,245 @@
+//! Video Edit - cut/trim style edits on a single input (FFmpeg)
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use tokio::process::Command as TokioCommand;
+use log::info;
+use crate::ffmpeg_manager::{ffmpeg_path, ffprobe_path};
+use crate::ffmpeg_runner::{ffmpeg_command, parse_clock, probe_duration, run_ffmpeg, MediaJob};
+use crate::media_converter::ConversionResult;
+
+/// Seconds of video sampled by cropdetect
+const CROPDETECT_SAMPLE_SECS: f64 = 20.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct CropRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+fn require_input(input_path: &str) -> Result<(), String> {
+    if Path::new(input_path).exists() {
+        Ok(())
+    } else {
+        Err(format!("Input file not found: {}", input_path))
+    }
+}
+
+fn finished(output_path: String, message: &str) -> ConversionResult {
+    let output_size = std::fs::metadata(&output_path).map(|m| m.len()).ok();
+    info!("✅ {}: {}", message, output_path);
+    ConversionResult {
+        success: true,
+        output_path,
+        message: message.to_string(),
+        output_size,
+    }
+}
+
+/// Cut [start, end] out of a video. Timestamps are "HH:MM:SS(.ms)" or seconds.
+/// Stream copy is instant and lossless but snaps to keyframes; re-encode is frame-accurate.
+pub async fn trim_video(
+    input_path: String,
+    output_path: String,
+    start: String,
+    end: String,
+    reencode: bool,
+    job: MediaJob,
+) -> Result<ConversionResult, String> {
+    require_input(&input_path)?;
+
+    let start_secs = parse_clock(&start).ok_or_else(|| format!("Invalid start time: {}", start))?;
+    let end_secs = parse_clock(&end).ok_or_else(|| format!("Invalid end time: {}", end))?;
+    if end_secs <= start_secs {
+        return Err("End time must be after start time".to_string());
+    }
+
+    info!("✂️ Trimming video: {} [{} - {}] ({})", input_path, start, end,
+        if reencode { "re-encode" } else { "stream copy" });
+
+    let mut job = job.with_output(&output_path);
+    job.total_secs = Some(end_secs - start_secs);
+
+    let mut cmd = ffmpeg_command();
+    cmd.arg("-y");
+    if reencode {
+        // Output seeking: decode from the start so the cut lands on the exact frame
+        cmd.arg("-i").arg(&input_path);
+        cmd.arg("-ss").arg(format!("{:.3}", start_secs));
+        cmd.arg("-to").arg(format!("{:.3}", end_secs));
+        cmd.arg("-c:v").arg("libx264");
+        cmd.arg("-crf").arg("18");
+        cmd.arg("-preset").arg("medium");
+        cmd.arg("-c:a").arg("aac");
+    } else {
+        // Input seeking + stream copy: no decoding, cut snaps to the previous keyframe
+        cmd.arg("-ss").arg(format!("{:.3}", start_secs));
+        cmd.arg("-to").arg(format!("{:.3}", end_secs));
+        cmd.arg("-i").arg(&input_path);
+        cmd.arg("-c").arg("copy");
+        cmd.arg("-map").arg("0");
+        cmd.arg("-avoid_negative_ts").arg("make_zero");
+    }
+    cmd.arg(&output_path);
+
+    run_ffmpeg(cmd, &job).await
+        .map_err(|error| format!("Trim failed: {}", error))?;
+
+    Ok(finished(output_path, "Video trimmed successfully"))
+}
+
+/// Parse the "crop=W:H:X:Y" that cropdetect prints on each analysed frame
+fn parse_crop(line: &str) -> Option<CropRect> {
+    let value = line.split("crop=").nth(1)?.split_whitespace().next()?;
+    let parts: Vec<u32> = value.split(':').map(|p| p.parse().ok()).collect::<Option<_>>()?;
+    match parts.as_slice() {
+        [width, height, x, y] => Some(CropRect { x: *x, y: *y, width: *width, height: *height }),
+        _ => None,
+    }
+}
+
+/// Analyse black borders and suggest a crop rectangle (the most common cropdetect result).
+/// Samples a stretch from 10% into the video to skip black intro frames.
+pub async fn detect_crop(input_path: &str) -> Result<CropRect, String> {
+    require_input(input_path)?;
+
+    let start = probe_duration(input_path).await.map(|d| d * 0.1).unwrap_or(0.0);
+    let output = TokioCommand::new(ffmpeg_path())
+        .arg("-hide_banner")
+        .arg("-ss").arg(format!("{:.3}", start))
+        .arg("-i").arg(input_path)
+        .arg("-t").arg(CROPDETECT_SAMPLE_SECS.to_string())
+        .arg("-vf").arg("cropdetect=limit=24:round=2:reset=0")
+        .arg("-an")
+        .arg("-f").arg("null")
+        .arg("-")
+        .output()
+        .await
+        .map_err(|e| format!("FFmpeg execution failed: {}", e))?;
+
+    let mut votes: HashMap<CropRect, u32> = HashMap::new();
+    for rect in String::from_utf8_lossy(&output.stderr).lines().filter_map(parse_crop) {
+        *votes.entry(rect).or_default() += 1;
+    }
+    let rect = votes.into_iter()
+        .max_by_key(|(_, count)| *count)
+        .map(|(rect, _)| rect)
+        .ok_or("Crop detection found no frames to analyse")?;
+
+    info!("🔲 Detected crop for {}: {}x{} at {},{}", input_path, rect.width, rect.height, rect.x, rect.y);
+    Ok(rect)
+}
+
+/// Crop to `rect`, or to the detected black-border rectangle when `rect` is None
+pub async fn crop_video(
+    input_path: String,
+    output_path: String,
+    rect: Option<CropRect>,
+    job: MediaJob,
+) -> Result<ConversionResult, String> {
+    require_input(&input_path)?;
+
+    let rect = match rect {
+        Some(rect) => rect,
+        None => detect_crop(&input_path).await?,
+    };
+    if rect.width == 0 || rect.height == 0 {
+        return Err("Crop width and height must be greater than zero".to_string());
+    }
+
+    info!("🔲 Cropping video: {} -> {} ({}x{} at {},{})", input_path, output_path,
+        rect.width, rect.height, rect.x, rect.y);
+
+    let job = job.with_duration_of(&input_path).await.with_output(&output_path);
+    let mut cmd = ffmpeg_command();
+    cmd.arg("-y");
+    cmd.arg("-i").arg(&input_path);
+    cmd.arg("-vf").arg(format!("crop={}:{}:{}:{}", rect.width, rect.height, rect.x, rect.y));
+    cmd.arg("-c:v").arg("libx264");
+    cmd.arg("-crf").arg("18");
+    cmd.arg("-preset").arg("medium");
+    cmd.arg("-c:a").arg("copy");
+    cmd.arg(&output_path);
+
+    run_ffmpeg(cmd, &job).await
+        .map_err(|error| format!("Crop failed: {}", error))?;
+
+    Ok(finished(output_path, "Video cropped successfully"))
+}
+
+/// Split a tempo factor into atempo stages, each within the filter's 0.5-2.0 range
+fn atempo_chain(factor: f64) -> String {
+    let mut remaining = factor;
+    let mut stages = Vec::new();
+    while remaining > 2.0 {
+        stages.push("atempo=2.0".to_string());
+        remaining /= 2.0;
+    }
+    while remaining < 0.5 {
+        stages.push("atempo=0.5".to_string());
+        remaining /= 0.5;
+    }
+    stages.push(format!("atempo={:.6}", remaining));
+    stages.join(",")
+}
+
+async fn probe_sample_rate(input_path: &str) -> Option<u32> {
+    let output = TokioCommand::new(ffprobe_path())
+        .arg("-v").arg("error")
+        .arg("-select_streams").arg("a:0")
+        .arg("-show_entries").arg("stream=sample_rate")
+        .arg("-of").arg("default=noprint_wrappers=1:nokey=1")
+        .arg(input_path)
+        .output()
+        .await
+        .ok()?;
+    String::from_utf8_lossy(&output.stdout).trim().parse().ok()
+}
+
+/// Speed a video up or down (1.5 = 50% faster). With `keep_pitch` the audio is time-stretched
+/// (atempo); without it the audio is resampled so pitch shifts with speed, like tape.
+pub async fn change_speed(
+    input_path: String,
+    output_path: String,
+    factor: f64,
+    keep_pitch: bool,
+    job: MediaJob,
+) -> Result<ConversionResult, String> {
+    require_input(&input_path)?;
+    if !(0.1..=10.0).contains(&factor) {
+        return Err("Speed factor must be between 0.1 and 10".to_string());
+    }
+
+    info!("⏩ Changing speed: {} -> {} ({}x, keep pitch: {})", input_path, output_path, factor, keep_pitch);
+
+    let audio_filter = if keep_pitch {
+        atempo_chain(factor)
+    } else {
+        let rate = probe_sample_rate(&input_path).await.unwrap_or(44100);
+        format!("asetrate={},aresample={}", (rate as f64 * factor).round() as u32, rate)
+    };
+
+    let mut job = job.with_duration_of(&input_path).await.with_output(&output_path);
+    job.total_secs = job.total_secs.map(|total| total / factor);
+
+    let mut cmd = ffmpeg_command();
+    cmd.arg("-y");
+    cmd.arg("-i").arg(&input_path);
+    cmd.arg("-map").arg("0:v:0");
+    cmd.arg("-map").arg("0:a:0?");
+    cmd.arg("-filter:v").arg(format!("setpts=PTS/{:.6}", factor));
+    cmd.arg("-filter:a").arg(audio_filter);
+    cmd.arg("-c:v").arg("libx264");
+    cmd.arg("-crf").arg("20");
+    cmd.arg("-preset").arg("medium");
+    cmd.arg("-c:a").arg("aac");
+    cmd.arg(&output_path);
+
+    run_ffmpeg(cmd, &job).await
+        .map_err(|error| format!("Speed change failed: {}", error))?;
+
+    Ok(finished(output_path, "Video speed changed successfully"))
+}
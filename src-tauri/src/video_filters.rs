@@ -0,0 +1,22 @@
+//! Video Filters - cleanup filter presets (denoise, sharpen) shared by the conversion paths
+
+/// Temporal/spatial noise reduction. low/medium use hqdn3d (fast); high uses nlmeans, which is
+/// much slower but handles the heavy grain of DV-tape and VHS digitizations.
+pub fn denoise_filter(level: &str) -> Result<&'static str, String> {
+    match level.to_lowercase().as_str() {
+        "low" => Ok("hqdn3d=2:1.5:3:2.25"),
+        "medium" => Ok("hqdn3d=4:3:6:4.5"),
+        "high" => Ok("nlmeans=s=3.5:p=7:r=15"),
+        other => Err(format!("Unknown denoise level: {} (use low, medium or high)", other)),
+    }
+}
+
+/// Luma unsharp mask, applied after scaling so the output resolution is what gets sharpened
+pub fn sharpen_filter(level: &str) -> Result<&'static str, String> {
+    match level.to_lowercase().as_str() {
+        "low" => Ok("unsharp=5:5:0.4:5:5:0.0"),
+        "medium" => Ok("unsharp=5:5:0.8:5:5:0.0"),
+        "high" => Ok("unsharp=5:5:1.3:5:5:0.0"),
+        other => Err(format!("Unknown sharpen level: {} (use low, medium or high)", other)),
+    }
+}
@@ -0,0 +1,148 @@
+//! Video Frames - grab still images (thumbnails, preview strips) from a video via FFmpeg
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tokio::process::Command as TokioCommand;
+use log::info;
+use crate::ffmpeg_manager::ffmpeg_path;
+use crate::ffmpeg_runner::{ffmpeg_command, parse_clock, probe_duration, run_ffmpeg, MediaJob};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum FrameMode {
+    Timestamp { time: String },   // One frame at "HH:MM:SS(.ms)" or seconds
+    Count { count: u32 },         // N thumbnails evenly spaced across the video
+    PerSecond,                    // One frame per second of video
+}
+
+/// Expand an ffmpeg image pattern ("thumb_%03d.jpg", "frame_%d.png") for frame number n
+fn numbered_path(pattern: &str, n: u32) -> String {
+    let Some(start) = pattern.find('%') else {
+        return pattern.to_string();
+    };
+    let Some(len) = pattern[start..].find('d') else {
+        return pattern.to_string();
+    };
+    let width: usize = pattern[start + 1..start + len].trim_start_matches('0').parse().unwrap_or(0);
+    format!("{}{:0width$}{}", &pattern[..start], n, &pattern[start + len + 1..], width = width)
+}
+
+/// Extract frames to `output_pattern` and return the written image paths in order.
+/// The pattern uses ffmpeg numbering, e.g. "C:/out/thumb_%03d.jpg" (numbering starts at 1).
+pub async fn extract_frames(
+    input_path: String,
+    output_pattern: String,
+    mode: FrameMode,
+    mut job: MediaJob,
+) -> Result<Vec<String>, String> {
+    if !Path::new(&input_path).exists() {
+        return Err(format!("Input file not found: {}", input_path));
+    }
+    if let Some(dir) = Path::new(&output_pattern).parent().filter(|d| !d.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create output folder: {}", e))?;
+    }
+
+    let duration = probe_duration(&input_path).await;
+    job.total_secs = duration;
+
+    info!("🖼️ Extracting frames: {} -> {} ({:?})", input_path, output_pattern, mode);
+
+    let mut cmd = ffmpeg_command();
+    cmd.arg("-y");
+    let expected = match &mode {
+        FrameMode::Timestamp { time } => {
+            let secs = parse_clock(time).ok_or_else(|| format!("Invalid timestamp: {}", time))?;
+            if duration.is_some_and(|d| secs > d) {
+                return Err(format!("Timestamp {} is past the end of the video", time));
+            }
+            job.total_secs = None;
+            cmd.arg("-ss").arg(format!("{:.3}", secs));
+            cmd.arg("-i").arg(&input_path);
+            cmd.arg("-frames:v").arg("1");
+            1
+        }
+        FrameMode::Count { count } => {
+            let count = (*count).max(1);
+            let total = duration.filter(|d| *d > 0.0)
+                .ok_or("Could not read the video duration")?;
+            cmd.arg("-i").arg(&input_path);
+            cmd.arg("-vf").arg(format!("fps={}/{:.3}", count, total));
+            cmd.arg("-frames:v").arg(count.to_string());
+            count
+        }
+        FrameMode::PerSecond => {
+            cmd.arg("-i").arg(&input_path);
+            cmd.arg("-vf").arg("fps=1");
+            duration.map(|d| d.ceil() as u32 + 1).unwrap_or(u32::MAX)
+        }
+    };
+    cmd.arg("-q:v").arg("2");
+    cmd.arg(&output_pattern);
+
+    run_ffmpeg(cmd, &job).await
+        .map_err(|error| format!("Frame extraction failed: {}", error))?;
+
+    let frames: Vec<String> = (1..=expected)
+        .map(|n| numbered_path(&output_pattern, n))
+        .take_while(|path| Path::new(path).is_file())
+        .collect();
+    if frames.is_empty() {
+        return Err("No frames were written".to_string());
+    }
+
+    info!("✅ Extracted {} frames", frames.len());
+    Ok(frames)
+}
+
+/// Width of scrub previews - small enough to decode and encode in well under 100 ms
+const PREVIEW_WIDTH: u32 = 640;
+
+/// Previews older than this are deleted (the UI has long since loaded them)
+const PREVIEW_MAX_AGE: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Grab one frame at `timestamp` as a JPEG in the temp folder and return its path.
+/// Seeks before -i (keyframe seek + decode to the exact frame), so it stays fast on long files.
+/// Each call writes a new file (so the webview never shows a cached image); stale previews are removed.
+pub async fn preview_frame(input_path: String, timestamp: String) -> Result<String, String> {
+    if !Path::new(&input_path).exists() {
+        return Err(format!("Input file not found: {}", input_path));
+    }
+    let secs = parse_clock(&timestamp).ok_or_else(|| format!("Invalid timestamp: {}", timestamp))?;
+
+    let dir = std::env::temp_dir().join("alagappa-previews");
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create preview folder: {}", e))?;
+    if let Ok(entries) = std::fs::read_dir(&dir) {
+        for entry in entries.flatten() {
+            let stale = entry.metadata()
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|modified| modified.elapsed().ok())
+                .is_some_and(|age| age > PREVIEW_MAX_AGE);
+            if stale {
+                let _ = std::fs::remove_file(entry.path());
+            }
+        }
+    }
+    let output = dir.join(format!("preview-{}.jpg", chrono::Local::now().timestamp_millis()));
+
+    let result = TokioCommand::new(ffmpeg_path())
+        .arg("-hide_banner")
+        .arg("-loglevel").arg("error")
+        .arg("-ss").arg(format!("{:.3}", secs))
+        .arg("-i").arg(&input_path)
+        .arg("-frames:v").arg("1")
+        .arg("-vf").arg(format!("scale={}:-2", PREVIEW_WIDTH))
+        .arg("-q:v").arg("4")
+        .arg("-y")
+        .arg(&output)
+        .output()
+        .await
+        .map_err(|e| format!("FFmpeg execution failed: {}", e))?;
+
+    if !result.status.success() || !output.is_file() {
+        return Err(format!("Preview failed: {}", String::from_utf8_lossy(&result.stderr).trim()));
+    }
+    Ok(output.to_string_lossy().to_string())
+}
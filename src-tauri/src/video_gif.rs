@@ -0,0 +1,120 @@
+//! Video GIF - clean GIF export using FFmpeg's two-pass palettegen/paletteuse pipeline
+//! Pass 1 builds an optimal 256-colour palette for the clip, pass 2 maps frames onto it.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use log::info;
+use crate::ffmpeg_runner::{ffmpeg_command, parse_clock, probe_duration, run_ffmpeg, MediaJob};
+use crate::media_converter::ConversionResult;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GifOptions {
+    pub input_path: String,
+    pub output_path: String,
+    #[serde(default = "default_fps")]
+    pub fps: u32,
+    #[serde(default = "default_width")]
+    pub width: u32,                  // Height follows the aspect ratio
+    #[serde(default)]
+    pub loop_count: i32,             // 0 = loop forever, -1 = play once, N = repeat N times
+    pub start: Option<String>,       // "HH:MM:SS(.ms)" or seconds
+    pub duration: Option<String>,    // Length of the clip, same format
+}
+
+fn default_fps() -> u32 {
+    12
+}
+
+fn default_width() -> u32 {
+    480
+}
+
+impl GifOptions {
+    /// Defaults used when a plain conversion targets .gif
+    pub fn for_paths(input_path: String, output_path: String) -> Self {
+        GifOptions {
+            input_path,
+            output_path,
+            fps: default_fps(),
+            width: default_width(),
+            loop_count: 0,
+            start: None,
+            duration: None,
+        }
+    }
+}
+
+/// Add the -ss/-t input options for the selected clip
+fn add_clip_args(cmd: &mut tokio::process::Command, start: Option<f64>, duration: Option<f64>) {
+    if let Some(start) = start {
+        cmd.arg("-ss").arg(format!("{:.3}", start));
+    }
+    if let Some(duration) = duration {
+        cmd.arg("-t").arg(format!("{:.3}", duration));
+    }
+}
+
+pub async fn video_to_gif(options: GifOptions, mut job: MediaJob) -> Result<ConversionResult, String> {
+    if !Path::new(&options.input_path).exists() {
+        return Err(format!("Input file not found: {}", options.input_path));
+    }
+
+    let parse = |value: &Option<String>, what: &str| -> Result<Option<f64>, String> {
+        value.as_deref()
+            .filter(|v| !v.is_empty())
+            .map(|v| parse_clock(v).ok_or_else(|| format!("Invalid {}: {}", what, v)))
+            .transpose()
+    };
+    let start = parse(&options.start, "start time")?;
+    let duration = parse(&options.duration, "duration")?;
+
+    let fps = options.fps.clamp(1, 50);
+    let width = options.width.max(16);
+    let base_filter = format!("fps={},scale={}:-1:flags=lanczos", fps, width);
+
+    job.total_secs = match duration {
+        Some(duration) => Some(duration),
+        None => probe_duration(&options.input_path).await.map(|total| (total - start.unwrap_or(0.0)).max(0.0)),
+    };
+    let palette = std::env::temp_dir().join(format!("{}-palette.png", job.id));
+    let palette_path = palette.to_string_lossy().to_string();
+    // Registered for both passes so a cancel between them stops the second one
+    let job = job.with_output(&options.output_path).with_output(&palette_path).registered()?;
+
+    info!("🎞️ Creating GIF: {} -> {} ({} fps, {}px)", options.input_path, options.output_path, fps, width);
+
+    // Pass 1: palette tuned to the moving parts of the clip
+    let mut cmd = ffmpeg_command();
+    cmd.arg("-y");
+    add_clip_args(&mut cmd, start, duration);
+    cmd.arg("-i").arg(&options.input_path);
+    cmd.arg("-vf").arg(format!("{},palettegen=stats_mode=diff", base_filter));
+    cmd.arg(&palette_path);
+    run_ffmpeg(cmd, &job).await
+        .map_err(|error| format!("GIF palette generation failed: {}", error))?;
+
+    // Pass 2: map frames onto the palette, only re-dithering the changed rectangle
+    let mut cmd = ffmpeg_command();
+    cmd.arg("-y");
+    add_clip_args(&mut cmd, start, duration);
+    cmd.arg("-i").arg(&options.input_path);
+    cmd.arg("-i").arg(&palette_path);
+    cmd.arg("-lavfi").arg(format!(
+        "{}[x];[x][1:v]paletteuse=dither=bayer:bayer_scale=5:diff_mode=rectangle",
+        base_filter
+    ));
+    cmd.arg("-loop").arg(options.loop_count.to_string());
+    cmd.arg(&options.output_path);
+    let result = run_ffmpeg(cmd, &job).await;
+    let _ = std::fs::remove_file(&palette);
+    result.map_err(|error| format!("GIF encoding failed: {}", error))?;
+
+    let output_size = std::fs::metadata(&options.output_path).map(|m| m.len()).ok();
+    info!("✅ GIF created: {}", options.output_path);
+    Ok(ConversionResult {
+        success: true,
+        output_path: options.output_path,
+        message: "GIF created successfully".to_string(),
+        output_size,
+    })
+}
@@ -0,0 +1,147 @@
+//! Video HLS - package a video as a multi-bitrate HLS ladder (master.m3u8 + one folder per rendition)
+//! Renditions are encoded one after another; "media://hls_rendition" marks each start so the UI can
+//! label the "media://progress" stream (which restarts at 0% per rendition).
+
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::path::Path;
+use log::info;
+use tauri::Emitter;
+use crate::ffmpeg_runner::{ffmpeg_command, run_ffmpeg, MediaJob};
+use crate::media_converter::{get_media_info, ConversionResult};
+
+pub const EVENT_RENDITION: &str = "media://hls_rendition";
+
+const SEGMENT_SECS: u32 = 6;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HlsRendition {
+    pub name: String,           // Folder name, e.g. "720p"
+    pub height: u32,            // Width follows the aspect ratio
+    pub video_bitrate: u32,     // kbit/s
+    pub audio_bitrate: u32,     // kbit/s
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HlsRenditionEvent {
+    pub job_id: String,
+    pub rendition: String,
+    pub index: usize,           // 1-based
+    pub total: usize,
+}
+
+fn rendition(name: &str, height: u32, video_bitrate: u32, audio_bitrate: u32) -> HlsRendition {
+    HlsRendition { name: name.to_string(), height, video_bitrate, audio_bitrate }
+}
+
+/// Standard 1080/720/480 ladder
+pub fn default_renditions() -> Vec<HlsRendition> {
+    vec![
+        rendition("1080p", 1080, 5000, 192),
+        rendition("720p", 720, 2800, 128),
+        rendition("480p", 480, 1400, 96),
+    ]
+}
+
+fn even(value: f64) -> u32 {
+    ((value / 2.0).round() as u32).max(1) * 2
+}
+
+pub async fn video_to_hls(
+    input_path: String,
+    output_dir: String,
+    renditions: Option<Vec<HlsRendition>>,
+    job: MediaJob,
+) -> Result<ConversionResult, String> {
+    let info = get_media_info(&input_path).await?;
+    let (source_width, source_height) = match (info.width, info.height) {
+        (Some(w), Some(h)) if w > 0 && h > 0 => (w, h),
+        _ => return Err(format!("No video stream in {}", info.file_name)),
+    };
+    let has_audio = info.tracks.iter().any(|t| t.codec_type == "audio");
+
+    // Never upscale: drop rungs above the source, but always keep at least the smallest one
+    let mut ladder = renditions.filter(|r| !r.is_empty()).unwrap_or_else(default_renditions);
+    ladder.sort_by_key(|r| std::cmp::Reverse(r.height));
+    let smallest = ladder.last().cloned();
+    ladder.retain(|r| r.height <= source_height);
+    if ladder.is_empty() {
+        ladder.extend(smallest.map(|r| HlsRendition { height: source_height, ..r }));
+    }
+
+    // One registration for the whole ladder so a cancel between renditions still stops it; every
+    // rendition encodes the full input, so the duration probed above serves them all
+    let mut job = job.registered()?;
+    job.total_secs = info.duration;
+    if !Path::new(&output_dir).exists() {
+        job = job.with_output(&output_dir);
+    }
+    std::fs::create_dir_all(&output_dir)
+        .map_err(|e| format!("Failed to create output folder: {}", e))?;
+
+    info!("📺 Packaging HLS: {} -> {} ({} renditions)", input_path, output_dir, ladder.len());
+
+    let mut master = String::from("#EXTM3U\n#EXT-X-VERSION:3\n");
+    for (i, rung) in ladder.iter().enumerate() {
+        let rung_dir = Path::new(&output_dir).join(&rung.name);
+        std::fs::create_dir_all(&rung_dir)
+            .map_err(|e| format!("Failed to create rendition folder: {}", e))?;
+        // Cancelling removes every rendition folder so far - segments and playlists alike
+        job = job.with_output(&rung_dir.to_string_lossy());
+        let playlist = rung_dir.join("index.m3u8");
+        let segments = rung_dir.join("segment_%03d.ts");
+        let width = even(source_width as f64 * rung.height as f64 / source_height as f64);
+
+        if let Some(app) = &job.app {
+            let _ = app.emit(EVENT_RENDITION, HlsRenditionEvent {
+                job_id: job.id.clone(),
+                rendition: rung.name.clone(),
+                index: i + 1,
+                total: ladder.len(),
+            });
+        }
+        info!("📺 Rendition {}/{}: {} ({}x{})", i + 1, ladder.len(), rung.name, width, rung.height);
+
+        let mut cmd = ffmpeg_command();
+        cmd.arg("-y");
+        cmd.arg("-i").arg(&input_path);
+        cmd.arg("-map").arg("0:v:0");
+        cmd.arg("-map").arg("0:a:0?");
+        cmd.arg("-vf").arg(format!("scale={}:{}", width, rung.height));
+        cmd.arg("-c:v").arg("libx264");
+        cmd.arg("-preset").arg("veryfast");
+        cmd.arg("-b:v").arg(format!("{}k", rung.video_bitrate));
+        cmd.arg("-maxrate").arg(format!("{}k", rung.video_bitrate * 107 / 100));
+        cmd.arg("-bufsize").arg(format!("{}k", rung.video_bitrate * 3 / 2));
+        // Keyframe every segment so each rendition can switch at segment boundaries
+        cmd.arg("-force_key_frames").arg(format!("expr:gte(t,n_forced*{})", SEGMENT_SECS));
+        cmd.arg("-c:a").arg("aac");
+        cmd.arg("-b:a").arg(format!("{}k", rung.audio_bitrate));
+        cmd.arg("-ac").arg("2");
+        cmd.arg("-f").arg("hls");
+        cmd.arg("-hls_time").arg(SEGMENT_SECS.to_string());
+        cmd.arg("-hls_playlist_type").arg("vod");
+        cmd.arg("-hls_segment_filename").arg(&segments);
+        cmd.arg(&playlist);
+
+        run_ffmpeg(cmd, &job).await
+            .map_err(|error| format!("HLS rendition {} failed: {}", rung.name, error))?;
+
+        let bandwidth = (rung.video_bitrate + if has_audio { rung.audio_bitrate } else { 0 }) * 1000;
+        let _ = writeln!(master, "#EXT-X-STREAM-INF:BANDWIDTH={},RESOLUTION={}x{}", bandwidth, width, rung.height);
+        let _ = writeln!(master, "{}/index.m3u8", rung.name);
+    }
+
+    let master_path = Path::new(&output_dir).join("master.m3u8");
+    std::fs::write(&master_path, master)
+        .map_err(|e| format!("Failed to write master playlist: {}", e))?;
+
+    let output_path = master_path.to_string_lossy().to_string();
+    info!("✅ HLS ready: {}", output_path);
+    Ok(ConversionResult {
+        success: true,
+        output_path,
+        message: format!("HLS packaged with {} renditions", ladder.len()),
+        output_size: None,
+    })
+}
@@ -0,0 +1,102 @@
+//! Video Quality - objective comparison of an encode against its original (PSNR, SSIM, VMAF)
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use log::info;
+use tokio::process::Command as TokioCommand;
+use crate::ffmpeg_manager::ffmpeg_path;
+use crate::ffmpeg_runner::{ffmpeg_command, run_ffmpeg_with_log, MediaJob};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QualityScores {
+    pub psnr: Option<f64>,         // dB, average over all planes (40+ is visually lossless)
+    pub ssim: Option<f64>,         // 0-1, "All" score
+    pub vmaf: Option<f64>,         // 0-100 mean
+    pub vmaf_available: bool,      // ffmpeg was built with libvmaf
+}
+
+async fn vmaf_available() -> bool {
+    TokioCommand::new(ffmpeg_path())
+        .arg("-hide_banner")
+        .arg("-filters")
+        .output()
+        .await
+        .map(|o| String::from_utf8_lossy(&o.stdout).contains("libvmaf"))
+        .unwrap_or(false)
+}
+
+/// Number right after `label` on the last log line containing it
+fn last_value(log: &str, label: &str) -> Option<f64> {
+    log.lines()
+        .rev()
+        .find_map(|line| line.split(label).nth(1))
+        .and_then(|rest| rest.split_whitespace().next())
+        .and_then(|value| value.parse().ok())
+}
+
+/// Compare `encoded` against `original`. `metrics` picks any of "psnr", "ssim", "vmaf"
+/// (empty = all available). The encode is scaled to the original's size before comparing.
+pub async fn compare_quality(
+    original: String,
+    encoded: String,
+    metrics: Vec<String>,
+    job: MediaJob,
+) -> Result<QualityScores, String> {
+    for path in [&original, &encoded] {
+        if !Path::new(path).exists() {
+            return Err(format!("Input file not found: {}", path));
+        }
+    }
+
+    let vmaf_available = vmaf_available().await;
+    let wanted = |metric: &str| metrics.is_empty() || metrics.iter().any(|m| m.eq_ignore_ascii_case(metric));
+    let mut filters: Vec<&str> = Vec::new();
+    if wanted("psnr") { filters.push("psnr"); }
+    if wanted("ssim") { filters.push("ssim"); }
+    if wanted("vmaf") {
+        if vmaf_available {
+            filters.push("libvmaf");
+        } else if !metrics.is_empty() {
+            return Err("VMAF requested but this FFmpeg build has no libvmaf".to_string());
+        }
+    }
+    if filters.is_empty() {
+        return Err("No metrics selected (use psnr, ssim or vmaf)".to_string());
+    }
+
+    info!("📐 Comparing {} against {} ({})", encoded, original, filters.join(", "));
+
+    // Each metric filter passes the distorted frames through, so they chain; the reference is split
+    let mut graph = format!(
+        "[1:v]setpts=PTS-STARTPTS[d_in];[0:v]setpts=PTS-STARTPTS[r_in];\
+         [d_in][r_in]scale2ref=flags=bicubic[d0][r_all];[r_all]split={}",
+        filters.len()
+    );
+    for i in 0..filters.len() {
+        graph.push_str(&format!("[r{}]", i));
+    }
+    for (i, filter) in filters.iter().enumerate() {
+        graph.push_str(&format!(";[d{}][r{}]{}[d{}]", i, i, filter, i + 1));
+    }
+
+    let job = job.with_duration_of(&original).await;
+    let mut cmd = ffmpeg_command();
+    cmd.arg("-i").arg(&original);
+    cmd.arg("-i").arg(&encoded);
+    cmd.arg("-filter_complex").arg(graph);
+    cmd.arg("-map").arg(format!("[d{}]", filters.len()));
+    cmd.arg("-f").arg("null").arg("-");
+
+    let log = run_ffmpeg_with_log(cmd, &job).await
+        .map_err(|error| format!("Quality comparison failed: {}", error))?;
+
+    let scores = QualityScores {
+        psnr: if filters.contains(&"psnr") { last_value(&log, "average:") } else { None },
+        ssim: if filters.contains(&"ssim") { last_value(&log, "All:") } else { None },
+        vmaf: if filters.contains(&"libvmaf") { last_value(&log, "VMAF score:") } else { None },
+        vmaf_available,
+    };
+
+    info!("✅ Quality: PSNR {:?}, SSIM {:?}, VMAF {:?}", scores.psnr, scores.ssim, scores.vmaf);
+    Ok(scores)
+}
@@ -0,0 +1,90 @@
+//! Video Scenes - find scene cuts and split a long recording into pieces at given timestamps
+
+use std::path::Path;
+use log::info;
+use crate::ffmpeg_runner::{ffmpeg_command, run_ffmpeg, run_ffmpeg_with_log, MediaJob};
+
+/// Scene score threshold used when the caller passes none (0-1, higher = only hard cuts)
+const DEFAULT_SCENE_THRESHOLD: f64 = 0.4;
+
+/// Timestamps (seconds) where the picture changes by more than `threshold`
+pub async fn detect_scenes(input_path: String, threshold: Option<f64>, job: MediaJob) -> Result<Vec<f64>, String> {
+    if !Path::new(&input_path).exists() {
+        return Err(format!("Input file not found: {}", input_path));
+    }
+    let threshold = threshold.unwrap_or(DEFAULT_SCENE_THRESHOLD).clamp(0.01, 1.0);
+
+    info!("🎬 Detecting scenes in {} (threshold {})", input_path, threshold);
+
+    let job = job.with_duration_of(&input_path).await;
+    let mut cmd = ffmpeg_command();
+    cmd.arg("-i").arg(&input_path);
+    cmd.arg("-an");
+    // showinfo logs one line (with pts_time) for every frame that passes the scene filter
+    cmd.arg("-vf").arg(format!("select='gt(scene,{})',showinfo", threshold));
+    cmd.arg("-f").arg("null").arg("-");
+
+    let log = run_ffmpeg_with_log(cmd, &job).await
+        .map_err(|error| format!("Scene detection failed: {}", error))?;
+
+    let scenes: Vec<f64> = log.lines()
+        .filter(|line| line.contains("Parsed_showinfo"))
+        .filter_map(|line| line.split("pts_time:").nth(1))
+        .filter_map(|rest| rest.split_whitespace().next()?.parse().ok())
+        .collect();
+
+    info!("✅ Found {} scene changes", scenes.len());
+    Ok(scenes)
+}
+
+/// Split into consecutive pieces at `timestamps` (stream copy, cuts snap to keyframes).
+/// Pieces are written as output_dir/<input name>_001.<ext>, _002, ...
+pub async fn split_at(
+    input_path: String,
+    timestamps: Vec<f64>,
+    output_dir: String,
+    job: MediaJob,
+) -> Result<Vec<String>, String> {
+    let input = Path::new(&input_path);
+    if !input.exists() {
+        return Err(format!("Input file not found: {}", input_path));
+    }
+    let mut cuts: Vec<f64> = timestamps.into_iter().filter(|t| *t > 0.0).collect();
+    cuts.sort_by(|a, b| a.total_cmp(b));
+    cuts.dedup();
+    if cuts.is_empty() {
+        return Err("No split points given".to_string());
+    }
+    std::fs::create_dir_all(&output_dir)
+        .map_err(|e| format!("Failed to create output folder: {}", e))?;
+
+    let stem = input.file_stem().and_then(|s| s.to_str()).unwrap_or("part");
+    let extension = input.extension().and_then(|e| e.to_str()).unwrap_or("mp4");
+    let pattern = Path::new(&output_dir).join(format!("{}_%03d.{}", stem, extension));
+
+    info!("✂️ Splitting {} at {} points -> {}", input_path, cuts.len(), output_dir);
+
+    let job = job.with_duration_of(&input_path).await;
+    let mut cmd = ffmpeg_command();
+    cmd.arg("-y");
+    cmd.arg("-i").arg(&input_path);
+    cmd.arg("-map").arg("0");
+    cmd.arg("-c").arg("copy");
+    cmd.arg("-f").arg("segment");
+    cmd.arg("-segment_times").arg(cuts.iter().map(|t| format!("{:.3}", t)).collect::<Vec<_>>().join(","));
+    cmd.arg("-segment_start_number").arg("1");
+    cmd.arg("-reset_timestamps").arg("1");
+    cmd.arg(&pattern);
+
+    run_ffmpeg(cmd, &job).await
+        .map_err(|error| format!("Split failed: {}", error))?;
+
+    let outputs: Vec<String> = (1..=cuts.len() + 1)
+        .map(|n| Path::new(&output_dir).join(format!("{}_{:03}.{}", stem, n, extension)))
+        .filter(|path| path.is_file())
+        .map(|path| path.to_string_lossy().to_string())
+        .collect();
+
+    info!("✅ Split into {} files", outputs.len());
+    Ok(outputs)
+}
@@ -0,0 +1,301 @@
+//! XML Convert - XML ⇄ JSON for portals that only take or give XML
+//! Elements become keys, repeated elements become arrays, attributes become "@name" keys and
+//! text next to attributes or children goes under "#text". json_to_xml reverses the mapping, so
+//! a file converted to JSON and back keeps its structure (not its comments or formatting).
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fs;
+use log::info;
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use crate::bundled_converter::ConversionResult;
+use crate::csv_dialect::decode;
+use crate::pdf_pdfa::escape_xml;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct XmlOptions {
+    pub attribute_prefix: Option<String>,   // Marks attribute keys, default "@"; "" maps attributes like child elements
+    pub text_key: Option<String>,           // Key for element text next to attributes/children, default "#text"
+    pub always_array: Option<bool>,         // xml_to_json: every child element becomes an array (default false)
+    pub root_name: Option<String>,          // json_to_xml: root element when the JSON has no single top-level key, default "root"
+    pub item_name: Option<String>,          // json_to_xml: element for items of a top-level or nested array, default "item"
+}
+
+impl XmlOptions {
+    fn prefix(&self) -> &str {
+        self.attribute_prefix.as_deref().unwrap_or("@")
+    }
+
+    fn text_key(&self) -> &str {
+        self.text_key.as_deref().filter(|k| !k.is_empty()).unwrap_or("#text")
+    }
+}
+
+struct Frame {
+    name: String,
+    fields: Map<String, Value>,
+    text: String,
+}
+
+/// Add a child under `name`, turning repeated names into arrays
+fn insert_child(fields: &mut Map<String, Value>, name: String, value: Value, always_array: bool) {
+    match fields.get_mut(&name) {
+        Some(Value::Array(items)) => items.push(value),
+        Some(existing) => {
+            let first = existing.take();
+            *existing = Value::Array(vec![first, value]);
+        }
+        None if always_array => {
+            fields.insert(name, Value::Array(vec![value]));
+        }
+        None => {
+            fields.insert(name, value);
+        }
+    }
+}
+
+fn finish(frame: Frame, text_key: &str) -> Value {
+    let Frame { mut fields, text, .. } = frame;
+    if fields.is_empty() {
+        return Value::String(text);
+    }
+    if !text.is_empty() {
+        fields.insert(text_key.to_string(), Value::String(text));
+    }
+    Value::Object(fields)
+}
+
+fn open_frame(element: &quick_xml::events::BytesStart, prefix: &str) -> Result<Frame, String> {
+    let mut fields = Map::new();
+    for attribute in element.attributes() {
+        let attribute = attribute.map_err(|e| format!("Invalid attribute: {}", e))?;
+        let key = String::from_utf8_lossy(attribute.key.as_ref()).to_string();
+        // Namespace declarations only matter to XML tools
+        if key == "xmlns" || key.starts_with("xmlns:") {
+            continue;
+        }
+        let value = attribute.unescape_value().map_err(|e| format!("Invalid attribute value: {}", e))?;
+        fields.insert(format!("{}{}", prefix, key), Value::String(value.to_string()));
+    }
+    Ok(Frame {
+        name: String::from_utf8_lossy(element.name().as_ref()).to_string(),
+        fields,
+        text: String::new(),
+    })
+}
+
+/// Parse an XML document into JSON: {"root": {...}}
+pub fn parse_xml(text: &str, options: &XmlOptions) -> Result<Value, String> {
+    let mut reader = Reader::from_str(text);
+    reader.config_mut().trim_text(true);
+
+    let always_array = options.always_array.unwrap_or(false);
+    let mut stack: Vec<Frame> = Vec::new();
+    let mut document = Map::new();
+
+    loop {
+        let event = reader.read_event()
+            .map_err(|e| format!("Failed to parse XML at byte {}: {}", reader.buffer_position(), e))?;
+        match event {
+            Event::Start(element) => stack.push(open_frame(&element, options.prefix())?),
+            Event::Empty(element) => {
+                let frame = open_frame(&element, options.prefix())?;
+                let name = frame.name.clone();
+                let value = finish(frame, options.text_key());
+                match stack.last_mut() {
+                    Some(parent) => insert_child(&mut parent.fields, name, value, always_array),
+                    None => insert_child(&mut document, name, value, false),
+                }
+            }
+            Event::Text(text) => {
+                let text = text.unescape().map_err(|e| format!("Invalid text: {}", e))?;
+                if let Some(frame) = stack.last_mut() {
+                    if !frame.text.is_empty() {
+                        frame.text.push(' ');
+                    }
+                    frame.text.push_str(&text);
+                }
+            }
+            Event::CData(data) => {
+                if let Some(frame) = stack.last_mut() {
+                    frame.text.push_str(&String::from_utf8_lossy(&data.into_inner()));
+                }
+            }
+            Event::End(_) => {
+                let frame = stack.pop().ok_or("Unexpected closing tag")?;
+                let name = frame.name.clone();
+                let value = finish(frame, options.text_key());
+                match stack.last_mut() {
+                    Some(parent) => insert_child(&mut parent.fields, name, value, always_array),
+                    None => insert_child(&mut document, name, value, false),
+                }
+            }
+            Event::Eof => break,
+            // Declarations, comments, processing instructions and doctypes carry no data
+            _ => {}
+        }
+    }
+
+    if let Some(frame) = stack.last() {
+        return Err(format!("Unclosed element: <{}>", frame.name));
+    }
+    if document.is_empty() {
+        return Err("XML has no root element".to_string());
+    }
+    Ok(Value::Object(document))
+}
+
+/// A JSON key as an XML element name: letters, digits, - _ . : and not starting with a digit
+fn element_name(key: &str, fallback: &str) -> String {
+    let mut name: String = key.trim().chars()
+        .map(|c| if c.is_alphanumeric() || "-_.:".contains(c) { c } else { '_' })
+        .collect();
+    if name.is_empty() {
+        return fallback.to_string();
+    }
+    if !name.starts_with(|c: char| c.is_alphabetic() || c == '_') {
+        name.insert(0, '_');
+    }
+    name
+}
+
+fn scalar(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+struct XmlWriter<'a> {
+    options: &'a XmlOptions,
+    out: String,
+}
+
+impl XmlWriter<'_> {
+    fn element(&mut self, name: &str, value: &Value, depth: usize) {
+        let options = self.options;
+        let indent = "  ".repeat(depth);
+        let (prefix, text_key) = (options.prefix(), options.text_key());
+        match value {
+            Value::Array(items) => {
+                for item in items {
+                    if item.is_array() {
+                        // Arrays of arrays need a wrapper per inner array
+                        self.out.push_str(&format!("{}<{}>\n", indent, name));
+                        self.element(options.item_name.as_deref().unwrap_or("item"), item, depth + 1);
+                        self.out.push_str(&format!("{}</{}>\n", indent, name));
+                    } else {
+                        self.element(name, item, depth);
+                    }
+                }
+            }
+            Value::Object(fields) => {
+                let mut attributes = String::new();
+                let mut text = String::new();
+                let mut children = Vec::new();
+                for (key, field) in fields {
+                    if key == text_key {
+                        text = scalar(field);
+                    } else if !prefix.is_empty() && key.starts_with(prefix) && !field.is_object() && !field.is_array() {
+                        let attribute = element_name(&key[prefix.len()..], "attribute");
+                        attributes.push_str(&format!(" {}=\"{}\"", attribute, escape_xml(&scalar(field))));
+                    } else {
+                        children.push((element_name(key, "item"), field));
+                    }
+                }
+                if children.is_empty() && text.is_empty() {
+                    self.out.push_str(&format!("{}<{}{}/>\n", indent, name, attributes));
+                } else if children.is_empty() {
+                    self.out.push_str(&format!("{}<{}{}>{}</{}>\n", indent, name, attributes, escape_xml(&text), name));
+                } else {
+                    self.out.push_str(&format!("{}<{}{}>\n", indent, name, attributes));
+                    if !text.is_empty() {
+                        self.out.push_str(&format!("{}  {}\n", indent, escape_xml(&text)));
+                    }
+                    for (child, field) in children {
+                        self.element(&child, field, depth + 1);
+                    }
+                    self.out.push_str(&format!("{}</{}>\n", indent, name));
+                }
+            }
+            Value::Null => self.out.push_str(&format!("{}<{}/>\n", indent, name)),
+            other => self.out.push_str(&format!("{}<{}>{}</{}>\n", indent, name, escape_xml(&scalar(other)), name)),
+        }
+    }
+}
+
+/// Render JSON as an indented XML document
+pub fn render_xml(value: &Value, options: &XmlOptions) -> String {
+    let mut writer = XmlWriter {
+        options,
+        out: String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n"),
+    };
+    let root = element_name(options.root_name.as_deref().unwrap_or("root"), "root");
+    // {"name": {...}} is already a document with one root element
+    let single = match value {
+        Value::Object(fields) if fields.len() == 1 => fields.iter().next().filter(|(_, field)| !field.is_array()),
+        _ => None,
+    };
+    match (single, value) {
+        (Some((key, field)), _) => writer.element(&element_name(key, &root), field, 0),
+        (None, Value::Array(items)) => {
+            let wrapped = Value::Object(Map::from_iter([(
+                options.item_name.clone().unwrap_or_else(|| "item".to_string()),
+                Value::Array(items.clone()),
+            )]));
+            writer.element(&root, &wrapped, 0);
+        }
+        (None, other) => writer.element(&root, other, 0),
+    }
+    writer.out
+}
+
+/// Convert an XML file to JSON
+pub fn xml_to_json(input_path: String, output_path: String, options: XmlOptions) -> Result<ConversionResult, String> {
+    info!("📊 Converting XML to JSON (bundled)");
+
+    let bytes = fs::read(&input_path).map_err(|e| format!("Failed to read XML: {}", e))?;
+    let (text, _) = decode(&bytes, None)?;
+    let value = parse_xml(&text, &options)?;
+
+    let json = serde_json::to_string_pretty(&value)
+        .map_err(|e| format!("Failed to serialize JSON: {}", e))?;
+    fs::write(&output_path, json)
+        .map_err(|e| format!("Failed to write JSON: {}", e))?;
+
+    let output_size = fs::metadata(&output_path).map(|m| m.len()).ok();
+
+    info!("✅ XML converted to JSON: {}", output_path);
+    Ok(ConversionResult {
+        success: true,
+        output_path,
+        message: "XML converted to JSON".to_string(),
+        output_size,
+    })
+}
+
+/// Convert a JSON file to XML
+pub fn json_to_xml(input_path: String, output_path: String, options: XmlOptions) -> Result<ConversionResult, String> {
+    info!("📊 Converting JSON to XML (bundled)");
+
+    let content = fs::read_to_string(&input_path)
+        .map_err(|e| format!("Failed to read JSON: {}", e))?;
+    let value: Value = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse JSON: {}", e))?;
+
+    fs::write(&output_path, render_xml(&value, &options))
+        .map_err(|e| format!("Failed to write XML: {}", e))?;
+
+    let output_size = fs::metadata(&output_path).map(|m| m.len()).ok();
+
+    info!("✅ JSON converted to XML: {}", output_path);
+    Ok(ConversionResult {
+        success: true,
+        output_path,
+        message: "JSON converted to XML".to_string(),
+        output_size,
+    })
+}
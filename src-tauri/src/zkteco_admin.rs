@@ -0,0 +1,159 @@
+//! ZKTeco Admin - device operations that change state (delete user, clear data, door relay)
+//! Kept apart from the read-only fetch path in zkteco_client.rs
+
+use serde::{Deserialize, Serialize};
+use log::{info, warn};
+use crate::admin_auth::require_admin;
+use crate::device_profiles::find_profile;
+use crate::zkteco_client::{ZKClient, CMD_ACK_OK};
+
+const CMD_DELETE_USER: u16 = 18;
+const CMD_CLEAR_DATA: u16 = 14;      // Users, fingerprints and attendance logs
+const CMD_REFRESHDATA: u16 = 1013;   // Apply changes to the device's in-memory tables
+const CMD_UNLOCK: u16 = 31;          // Pulse the door relay
+const CMD_DOORSTATE_RRQ: u16 = 75;   // Read lock state (access-control models only)
+
+/// Upper bound for a remote unlock pulse
+const MAX_UNLOCK_SECONDS: u32 = 60;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DoorState {
+    pub supported: bool,  // false on attendance-only models
+    pub open: bool,
+}
+
+impl ZKClient {
+    fn refresh_data(&mut self) -> Result<(), String> {
+        let (cmd, _) = self.send_command(CMD_REFRESHDATA, &[])?;
+        if cmd == CMD_ACK_OK { Ok(()) } else { Err(format!("Failed to refresh device data: cmd={}", cmd)) }
+    }
+
+    fn delete_user(&mut self, uid: u32) -> Result<(), String> {
+        let uid = u16::try_from(uid).map_err(|_| format!("Invalid device uid: {}", uid))?;
+        let (cmd, _) = self.send_command(CMD_DELETE_USER, &uid.to_le_bytes())?;
+        if cmd != CMD_ACK_OK {
+            return Err(format!("Device refused to delete uid {}: cmd={}", uid, cmd));
+        }
+        self.refresh_data()
+    }
+
+    fn unlock(&mut self, seconds: u32) -> Result<(), String> {
+        // Duration is sent in tenths of a second
+        let (cmd, _) = self.send_command(CMD_UNLOCK, &(seconds * 10).to_le_bytes())?;
+        if cmd == CMD_ACK_OK { Ok(()) } else { Err(format!("Device refused to unlock: cmd={}", cmd)) }
+    }
+
+    fn door_state(&mut self) -> Result<DoorState, String> {
+        let (cmd, data) = self.send_command(CMD_DOORSTATE_RRQ, &[])?;
+        if cmd != CMD_ACK_OK {
+            return Ok(DoorState { supported: false, open: false });
+        }
+        // Some firmwares return a state byte, others only ACK while the lock is open (pyzk behaviour)
+        let open = data.first().map(|b| *b != 0).unwrap_or(true);
+        Ok(DoorState { supported: true, open })
+    }
+
+    fn clear_data(&mut self) -> Result<(), String> {
+        let (cmd, _) = self.send_command(CMD_CLEAR_DATA, &[])?;
+        if cmd != CMD_ACK_OK {
+            return Err(format!("Device refused to clear data: cmd={}", cmd));
+        }
+        self.refresh_data()
+    }
+}
+
+/// Remove one enrolled user (by internal device uid, not badge number)
+pub async fn delete_device_user(ip: &str, port: u16, uid: u32) -> Result<String, String> {
+    let ip = ip.to_string();
+
+    tokio::task::spawn_blocking(move || {
+        let mut client = ZKClient::connect(&ip, port)?;
+
+        let users = client.get_users().unwrap_or_default();
+        let name = users.iter().find(|u| u.uid == uid).map(|u| u.name.clone());
+        if !users.is_empty() && name.is_none() {
+            let _ = client.disconnect();
+            return Err(format!("No user with uid {} on device", uid));
+        }
+
+        // Re-enable and disconnect on every path, or the terminal stays locked
+        let result = client.disable_device().and_then(|_| client.delete_user(uid));
+        let _ = client.disconnect();
+        result?;
+
+        let label = name.unwrap_or_else(|| format!("uid {}", uid));
+        info!("🗑️ Deleted user {} from {}", label, ip);
+        Ok(format!("Deleted user {}", label))
+    })
+    .await
+    .map_err(|e| format!("Task error: {}", e))?
+}
+
+/// Wipe all users, fingerprints and attendance logs from a terminal. Needs the admin password;
+/// `confirm_serial` must also match the device's serial number - guards against wiping the wrong unit.
+pub async fn clear_all_data(ip: &str, port: u16, confirm_serial: String, admin_password: String) -> Result<String, String> {
+    let ip = ip.to_string();
+
+    tokio::task::spawn_blocking(move || {
+        require_admin(Some(&admin_password))?;
+        let mut client = ZKClient::connect(&ip, port)?;
+
+        let serial = client.get_serial_number();
+        if serial.is_empty() || serial.trim() != confirm_serial.trim() {
+            let _ = client.disconnect();
+            return Err("Serial number confirmation does not match this device - nothing was cleared".to_string());
+        }
+
+        warn!("⚠️ Clearing all data on device {} (S/N: {})", ip, serial);
+        let result = client.disable_device().and_then(|_| client.clear_data());
+        let _ = client.disconnect();
+        result?;
+
+        info!("✅ Device {} cleared", serial);
+        Ok(format!("All users and attendance data cleared from {}", serial))
+    })
+    .await
+    .map_err(|e| format!("Task error: {}", e))?
+}
+
+fn require_door_control(ip: &str, port: u16) -> Result<(), String> {
+    match find_profile(ip, port)? {
+        Some(profile) if profile.allow_door_control => Ok(()),
+        Some(profile) => Err(format!("Door control is disabled for {} - enable it in the device profile", profile.name)),
+        None => Err(format!("No device profile for {}:{} - save one with door control enabled first", ip, port)),
+    }
+}
+
+/// Pulse the door relay. Needs the admin password, and only allowed for devices whose profile
+/// enables door control (which itself needs the admin password to turn on).
+pub async fn unlock_door(ip: &str, port: u16, seconds: u32, admin_password: String) -> Result<String, String> {
+    let seconds = seconds.clamp(1, MAX_UNLOCK_SECONDS);
+    let ip = ip.to_string();
+
+    tokio::task::spawn_blocking(move || {
+        require_admin(Some(&admin_password))?;
+        require_door_control(&ip, port)?;
+        let mut client = ZKClient::connect(&ip, port)?;
+        let result = client.unlock(seconds);
+        let _ = client.disconnect();
+        result?;
+
+        info!("🚪 Door unlocked on {} for {}s", ip, seconds);
+        Ok(format!("Door unlocked for {} seconds", seconds))
+    })
+    .await
+    .map_err(|e| format!("Task error: {}", e))?
+}
+
+pub async fn get_door_state(ip: &str, port: u16) -> Result<DoorState, String> {
+    let ip = ip.to_string();
+
+    tokio::task::spawn_blocking(move || {
+        let mut client = ZKClient::connect(&ip, port)?;
+        let state = client.door_state();
+        let _ = client.disconnect();
+        state
+    })
+    .await
+    .map_err(|e| format!("Task error: {}", e))?
+}
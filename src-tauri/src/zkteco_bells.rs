@@ -0,0 +1,176 @@
+//! ZKTeco Bells - read/write the terminal's bell (period) schedule
+//! Bells are stored as device options, one slot per bell:
+//!   BellTime<n>  = "HH:MM"
+//!   BellDelay<n> = ring duration in seconds
+//!   BellEnable<n> = 0/1
+//! These names are not the same on every firmware, so nothing is written blindly: every option a
+//! write touches must already be reported by the terminal, and after writing each one is read back.
+//! If the terminal stored something else, the previous values are restored and the write fails.
+
+use serde::{Deserialize, Serialize};
+use chrono::NaiveTime;
+use log::info;
+use crate::zkteco_client::ZKClient;
+
+/// (option name, value) pairs
+type OptionValues = Vec<(String, String)>;
+
+const BELL_SLOTS: u8 = 16;
+const DEFAULT_RING_SECONDS: u32 = 5;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BellEntry {
+    pub slot: u8,            // 1-based bell slot on the device
+    pub enabled: bool,
+    pub time: String,        // HH:MM
+    pub duration_secs: u32,  // Ring duration
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BellSchedule {
+    pub bells: Vec<BellEntry>,
+}
+
+impl ZKClient {
+    fn read_bells(&mut self) -> Result<Vec<BellEntry>, String> {
+        let mut bells = Vec::new();
+        for slot in 1..=BELL_SLOTS {
+            let time = self.get_option(&format!("BellTime{}", slot)).unwrap_or_default();
+            if time.is_empty() {
+                continue;
+            }
+            let duration_secs = self.get_option(&format!("BellDelay{}", slot))
+                .ok()
+                .and_then(|v| v.trim().parse().ok())
+                .unwrap_or(DEFAULT_RING_SECONDS);
+            let enabled = self.get_option(&format!("BellEnable{}", slot))
+                .map(|v| v.trim() != "0")
+                .unwrap_or(true);
+            bells.push(BellEntry { slot, enabled, time, duration_secs });
+        }
+        Ok(bells)
+    }
+
+    /// Options (and values) the new schedule consists of. Slots not in it are disabled rather than
+    /// left ringing.
+    fn bell_options(bells: &[BellEntry]) -> OptionValues {
+        let mut options = Vec::new();
+        for slot in 1..=BELL_SLOTS {
+            match bells.iter().find(|b| b.slot == slot) {
+                Some(bell) => {
+                    options.push((format!("BellTime{}", slot), bell.time.clone()));
+                    options.push((format!("BellDelay{}", slot), bell.duration_secs.to_string()));
+                    options.push((format!("BellEnable{}", slot), if bell.enabled { "1" } else { "0" }.to_string()));
+                }
+                None => options.push((format!("BellEnable{}", slot), "0".to_string())),
+            }
+        }
+        options
+    }
+
+    fn write_options(&mut self, options: &OptionValues) -> Result<(), String> {
+        for (name, value) in options {
+            self.set_option(name, value)?;
+        }
+        self.refresh_options()
+    }
+
+    /// Options whose stored value differs from the one written, as "name=stored"
+    fn mismatched_options(&mut self, options: &OptionValues) -> Result<Vec<String>, String> {
+        let mut mismatched = Vec::new();
+        for (name, expected) in options {
+            let stored = self.query_option(name)?.unwrap_or_default();
+            // "8:00" and "08:00" are the same bell time
+            let same_time = || -> Option<bool> {
+                Some(NaiveTime::parse_from_str(stored.trim(), "%H:%M").ok()? == NaiveTime::parse_from_str(expected, "%H:%M").ok()?)
+            };
+            if stored.trim() != expected && same_time() != Some(true) {
+                mismatched.push(format!("{}={}", name, stored.trim()));
+            }
+        }
+        Ok(mismatched)
+    }
+
+    fn write_bells(&mut self, bells: &[BellEntry]) -> Result<(), String> {
+        let options = Self::bell_options(bells);
+
+        // Refuse before touching anything if the firmware does not know one of the options
+        let mut previous = Vec::with_capacity(options.len());
+        for (name, _) in &options {
+            match self.query_option(name)? {
+                Some(value) => previous.push((name.clone(), value)),
+                None => return Err(format!(
+                    "Device does not report the {} option - its firmware stores bells differently, so nothing was written",
+                    name
+                )),
+            }
+        }
+
+        self.write_options(&options)?;
+        let mismatched = self.mismatched_options(&options)?;
+        if !mismatched.is_empty() {
+            let restored = self.write_options(&previous);
+            return Err(format!(
+                "Device did not keep the bell schedule (read back {}); {}",
+                mismatched.join(", "),
+                match restored {
+                    Ok(()) => "the previous schedule was restored".to_string(),
+                    Err(e) => format!("restoring the previous schedule failed: {}", e),
+                }
+            ));
+        }
+        Ok(())
+    }
+}
+
+fn validate_schedule(schedule: &BellSchedule) -> Result<(), String> {
+    for bell in &schedule.bells {
+        if bell.slot == 0 || bell.slot > BELL_SLOTS {
+            return Err(format!("Bell slot must be 1-{}, got {}", BELL_SLOTS, bell.slot));
+        }
+        NaiveTime::parse_from_str(&bell.time, "%H:%M")
+            .map_err(|_| format!("Invalid bell time '{}', expected HH:MM", bell.time))?;
+        if schedule.bells.iter().filter(|b| b.slot == bell.slot).count() > 1 {
+            return Err(format!("Bell slot {} is used more than once", bell.slot));
+        }
+    }
+    Ok(())
+}
+
+pub async fn get_bell_schedule(ip: &str, port: u16) -> Result<BellSchedule, String> {
+    let ip = ip.to_string();
+
+    tokio::task::spawn_blocking(move || {
+        let mut client = ZKClient::connect(&ip, port)?;
+        let bells = client.read_bells();
+        let _ = client.disconnect();
+        let bells = bells?;
+
+        if bells.is_empty() {
+            return Err("Device did not report a bell schedule (not supported on this model/firmware)".to_string());
+        }
+        info!("🔔 Read {} bells from {}", bells.len(), ip);
+        Ok(BellSchedule { bells })
+    })
+    .await
+    .map_err(|e| format!("Task error: {}", e))?
+}
+
+pub async fn set_bell_schedule(ip: &str, port: u16, schedule: BellSchedule) -> Result<BellSchedule, String> {
+    validate_schedule(&schedule)?;
+    let ip = ip.to_string();
+
+    tokio::task::spawn_blocking(move || {
+        let mut client = ZKClient::connect(&ip, port)?;
+        let result = client.disable_device()
+            .and_then(|_| client.write_bells(&schedule.bells))
+            .and_then(|_| client.read_bells());
+        let _ = client.disconnect();
+        let bells = result?;
+
+        info!("🔔 Wrote {} bells to {}", schedule.bells.len(), ip);
+        Ok(BellSchedule { bells })
+    })
+    .await
+    .map_err(|e| format!("Task error: {}", e))?
+}
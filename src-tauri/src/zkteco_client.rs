@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::io::{Read, Write};
-use std::net::TcpStream;
+use std::net::{SocketAddr, TcpStream};
 use std::time::Duration;
 use chrono::{DateTime, Local, TimeZone};
 use log::{debug, info, warn};
@@ -11,10 +11,39 @@ pub struct AttendanceRecord {
     pub user_id: u32,
     pub user_name: String,
     pub timestamp: String,  // ISO format for sorting
-    pub status: u8,         // Raw status from device
+    pub status: u8,         // Raw status from device (verify mode)
     pub punch: u8,          // Raw punch from device
     pub date: String,       // YYYY-MM-DD
     pub time: String,       // HH:MM:SS
+    #[serde(default)]
+    pub device_serial: Option<String>,  // Source terminal (set after fetch)
+    #[serde(default)]
+    pub device_ip: Option<String>,      // Set for terminals fetched over the network
+    #[serde(default)]
+    pub serial_port: Option<String>,    // Set for terminals fetched over RS232/RS485 ("/dev/ttyUSB0", "COM3")
+    #[serde(default)]
+    pub work_code: Option<u32>,         // Work code entered at the terminal (16-byte records only)
+    #[serde(default)]
+    pub verify_method: String,          // Fingerprint, Face, Card, Password, ...
+}
+
+/// Human-readable verify mode for the attendance status byte
+fn verify_method_name(code: u8) -> String {
+    match code {
+        0 | 3 => "Password".to_string(),
+        1 => "Fingerprint".to_string(),
+        2 | 4 => "Card".to_string(),
+        15 => "Face".to_string(),
+        25 => "Palm".to_string(),
+        other => format!("Other ({})", other),
+    }
+}
+
+/// A user enrolled on the device, keyed like the attendance records
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceUser {
+    pub user_id: u32,
+    pub name: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -29,14 +58,22 @@ pub struct DeviceInfo {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AttendanceResponse {
     pub device_info: DeviceInfo,
+    pub device_user_count: u32,     // Users reported by the device
+    pub device_record_count: u32,   // Records reported by the device (CMD_GET_FREE_SIZES)
+    pub parsed_count: usize,        // Records actually parsed
+    pub fetch_duration_ms: u64,
+    pub transport: String,          // "tcp" or "serial"
+    pub warnings: Vec<String>,      // Fetch diagnostics (unknown users, count mismatch, ...)
+    #[serde(default)]
+    pub users: Vec<DeviceUser>,     // Everyone enrolled, also those without punches
     pub records: Vec<AttendanceRecord>,
 }
 
 #[derive(Debug, Clone)]
-struct User {
-    uid: u32,
-    user_id: String,
-    name: String,
+pub(crate) struct User {
+    pub(crate) uid: u32,
+    pub(crate) user_id: String,
+    pub(crate) name: String,
 }
 
 // ZKTeco protocol constants (from pyzk const.py)
@@ -51,7 +88,7 @@ const CMD_ATTLOG_RRQ: u16 = 13;
 const CMD_PREPARE_DATA: u16 = 1500;
 const CMD_DATA: u16 = 1501;
 const CMD_FREE_DATA: u16 = 1502;
-const CMD_ACK_OK: u16 = 2000;
+pub(crate) const CMD_ACK_OK: u16 = 2000;
 #[allow(dead_code)]
 const CMD_ACK_ERROR: u16 = 2001;
 #[allow(dead_code)]
@@ -62,6 +99,8 @@ const CMD_GET_FREE_SIZES: u16 = 50;
 const CMD_DATA_WRRQ: u16 = 1503;  // Buffered data request
 const CMD_DATA_RDY: u16 = 1504;   // Read chunk
 const CMD_OPTIONS_RRQ: u16 = 11;  // Get option value
+const CMD_OPTIONS_WRQ: u16 = 12;  // Set option value
+const CMD_REFRESHOPTION: u16 = 1014; // Apply written options
 const CMD_VERSION: u16 = 1100;    // Get firmware version
 const CMD_SERIALNUMBER: u16 = 1101; // Get serial number (alternative)
 
@@ -75,19 +114,180 @@ const FCT_ATTLOG: i32 = 1;
 #[allow(dead_code)]
 const FCT_USER: i32 = 5;
 
-struct ZKClient {
-    stream: TcpStream,
+/// Serial connection strings look like "serial:/dev/ttyUSB0:115200" or "serial:COM3:9600"
+const SERIAL_PREFIX: &str = "serial:";
+const DEFAULT_BAUD_RATE: u32 = 115200;
+
+/// Silence on a serial line that ends a reply (serial frames carry no length)
+const SERIAL_IDLE_GAP: Duration = Duration::from_millis(50);
+
+/// Byte transport under the ZK packet layer: Ethernet socket or RS232/RS485 line.
+/// The packet code reads and writes TCP frames (0x5050 0x7D82 + length header); the serial line
+/// translates them to serial frames, which are the bare packet as sent over UDP.
+enum ZkStream {
+    Tcp(TcpStream),
+    Serial(SerialLine),
+}
+
+struct SerialLine {
+    port: Box<dyn serialport::SerialPort>,
+    timeout: Duration,      // Wait for the first byte of a reply
+    outgoing: Vec<u8>,      // TCP-framed bytes not yet forming a whole packet
+    incoming: Vec<u8>,      // Last reply re-framed for the packet code
+    read_pos: usize,
+}
+
+impl SerialLine {
+    fn new(port: Box<dyn serialport::SerialPort>) -> Self {
+        let timeout = port.timeout();
+        SerialLine { port, timeout, outgoing: Vec::new(), incoming: Vec::new(), read_pos: 0 }
+    }
+
+    /// Read one reply: wait up to `timeout` for it to start, then until the line goes quiet
+    fn receive_frame(&mut self) -> std::io::Result<Vec<u8>> {
+        let mut frame = Vec::new();
+        let mut chunk = [0u8; 1024];
+        self.port.set_timeout(self.timeout)?;
+        let n = self.port.read(&mut chunk)?;
+        frame.extend_from_slice(&chunk[..n]);
+
+        self.port.set_timeout(SERIAL_IDLE_GAP)?;
+        let result = loop {
+            match self.port.read(&mut chunk) {
+                Ok(0) => break Ok(()),
+                Ok(n) => frame.extend_from_slice(&chunk[..n]),
+                Err(e) if e.kind() == std::io::ErrorKind::TimedOut => break Ok(()),
+                Err(e) => break Err(e),
+            }
+        };
+        self.port.set_timeout(self.timeout)?;
+        result.map(|_| frame)
+    }
+}
+
+impl Read for SerialLine {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.read_pos >= self.incoming.len() {
+            let frame = self.receive_frame()?;
+            self.incoming.clear();
+            self.incoming.extend_from_slice(&MACHINE_PREPARE_DATA_1.to_le_bytes());
+            self.incoming.extend_from_slice(&MACHINE_PREPARE_DATA_2.to_le_bytes());
+            self.incoming.extend_from_slice(&(frame.len() as u32).to_le_bytes());
+            self.incoming.extend_from_slice(&frame);
+            self.read_pos = 0;
+        }
+        let n = buf.len().min(self.incoming.len() - self.read_pos);
+        buf[..n].copy_from_slice(&self.incoming[self.read_pos..self.read_pos + n]);
+        self.read_pos += n;
+        Ok(n)
+    }
+}
+
+impl Write for SerialLine {
+    /// Collect TCP-framed bytes and send each complete packet without its 8-byte TCP header
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.outgoing.extend_from_slice(buf);
+        while self.outgoing.len() >= 8 {
+            let length = u32::from_le_bytes([self.outgoing[4], self.outgoing[5], self.outgoing[6], self.outgoing[7]]) as usize;
+            if self.outgoing.len() < 8 + length {
+                break;
+            }
+            self.port.write_all(&self.outgoing[8..8 + length])?;
+            self.outgoing.drain(..8 + length);
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.port.flush()
+    }
+}
+
+impl ZkStream {
+    fn transport_name(&self) -> &'static str {
+        match self {
+            ZkStream::Tcp(_) => "tcp",
+            ZkStream::Serial(_) => "serial",
+        }
+    }
+
+    fn read_timeout(&self) -> std::io::Result<Option<Duration>> {
+        match self {
+            ZkStream::Tcp(s) => s.read_timeout(),
+            ZkStream::Serial(s) => Ok(Some(s.timeout)),
+        }
+    }
+
+    fn set_read_timeout(&mut self, timeout: Option<Duration>) -> std::io::Result<()> {
+        match self {
+            ZkStream::Tcp(s) => s.set_read_timeout(timeout),
+            ZkStream::Serial(s) => {
+                s.timeout = timeout.unwrap_or(Duration::from_secs(3600));
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Read for ZkStream {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self {
+            ZkStream::Tcp(s) => s.read(buf),
+            ZkStream::Serial(s) => s.read(buf),
+        }
+    }
+}
+
+impl Write for ZkStream {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            ZkStream::Tcp(s) => s.write(buf),
+            ZkStream::Serial(s) => s.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            ZkStream::Tcp(s) => s.flush(),
+            ZkStream::Serial(s) => s.flush(),
+        }
+    }
+}
+
+/// Build a socket address from an IPv4/IPv6 literal ("10.0.0.5", "fd00::5", "[fe80::5%3]")
+pub(crate) fn socket_addr(ip: &str, port: u16) -> Result<SocketAddr, String> {
+    let host = ip.trim().trim_start_matches('[').trim_end_matches(']');
+    let addr = if host.contains(':') { format!("[{}]:{}", host, port) } else { format!("{}:{}", host, port) };
+    addr.parse().map_err(|e| format!("Invalid address {}: {}", ip, e))
+}
+
+/// Parse "serial:<path>[:<baud>]" into (path, baud)
+fn parse_serial_target(target: &str) -> Option<(String, u32)> {
+    let spec = target.strip_prefix(SERIAL_PREFIX)?;
+    Some(match spec.rsplit_once(':').map(|(path, baud)| (path, baud.parse::<u32>())) {
+        Some((path, Ok(baud_rate))) => (path.to_string(), baud_rate),
+        _ => (spec.to_string(), DEFAULT_BAUD_RATE),
+    })
+}
+
+pub(crate) struct ZKClient {
+    stream: ZkStream,
     session_id: u16,
     reply_id: u16,
 }
 
 impl ZKClient {
-    fn connect(ip: &str, port: u16) -> Result<Self, String> {
+    /// Connect over TCP (ip + port) or serial ("serial:/dev/ttyUSB0:115200" - port is ignored)
+    pub(crate) fn connect(ip: &str, port: u16) -> Result<Self, String> {
+        if let Some((path, baud_rate)) = parse_serial_target(ip) {
+            return Self::connect_serial(&path, baud_rate);
+        }
+
         info!("Connecting to {}:{}...", ip, port);
-        let addr = format!("{}:{}", ip, port);
+        let addr = socket_addr(ip, port)?;
         
         let stream = TcpStream::connect_timeout(
-            &addr.parse().map_err(|e| format!("Invalid address: {}", e))?,
+            &addr,
             Duration::from_secs(10)
         ).map_err(|e| format!("Failed to connect to {}: {}", addr, e))?;
         
@@ -98,7 +298,7 @@ impl ZKClient {
             .map_err(|e| format!("Failed to set write timeout: {}", e))?;
         
         let mut client = ZKClient {
-            stream,
+            stream: ZkStream::Tcp(stream),
             session_id: 0,
             reply_id: USHRT_MAX - 1,
         };
@@ -107,6 +307,25 @@ impl ZKClient {
         
         Ok(client)
     }
+
+    fn connect_serial(path: &str, baud_rate: u32) -> Result<Self, String> {
+        info!("Connecting to serial {} @ {} baud...", path, baud_rate);
+
+        let port = serialport::new(path, baud_rate)
+            .timeout(Duration::from_secs(30))
+            .open()
+            .map_err(|e| format!("Failed to open serial port {}: {}", path, e))?;
+
+        let mut client = ZKClient {
+            stream: ZkStream::Serial(SerialLine::new(port)),
+            session_id: 0,
+            reply_id: USHRT_MAX - 1,
+        };
+
+        client.do_handshake()?;
+
+        Ok(client)
+    }
     
     /// Calculate checksum (matching pyzk __create_checksum exactly)
     fn calc_checksum(data: &[u8]) -> u16 {
@@ -179,7 +398,7 @@ impl ZKClient {
     }
     
     /// Send command and receive response
-    fn send_command(&mut self, command: u16, command_string: &[u8]) -> Result<(u16, Vec<u8>), String> {
+    pub(crate) fn send_command(&mut self, command: u16, command_string: &[u8]) -> Result<(u16, Vec<u8>), String> {
         let buf = self.create_header(command, command_string);
         let top = self.create_tcp_top(&buf);
         
@@ -321,7 +540,7 @@ impl ZKClient {
         }
     }
     
-    fn disable_device(&mut self) -> Result<(), String> {
+    pub(crate) fn disable_device(&mut self) -> Result<(), String> {
         let (cmd, _) = self.send_command(CMD_DISABLEDEVICE, &[])?;
         if cmd == CMD_ACK_OK { Ok(()) } else { Err(format!("Failed to disable device: cmd={}", cmd)) }
     }
@@ -343,7 +562,12 @@ impl ZKClient {
     }
     
     /// Get a device option value
-    fn get_option(&mut self, option: &str) -> Result<String, String> {
+    pub(crate) fn get_option(&mut self, option: &str) -> Result<String, String> {
+        Ok(self.query_option(option)?.unwrap_or_default())
+    }
+
+    /// Read a device option; None when the firmware does not know the option at all
+    pub(crate) fn query_option(&mut self, option: &str) -> Result<Option<String>, String> {
         let mut cmd_data = option.as_bytes().to_vec();
         cmd_data.push(0x00); // null terminate
         
@@ -356,15 +580,29 @@ impl ZKClient {
             
             if let Some(pos) = response.find('=') {
                 let value = response[pos + 1..].to_string();
-                Ok(value)
+                Ok(Some(value))
             } else {
-                Ok(response.to_string())
+                Ok(Some(response.to_string()))
             }
         } else {
-            Ok(String::new())
+            Ok(None)
         }
     }
     
+    /// Set a device option ("key=value\0"); call refresh_options() afterwards to apply
+    pub(crate) fn set_option(&mut self, option: &str, value: &str) -> Result<(), String> {
+        let mut cmd_data = format!("{}={}", option, value).into_bytes();
+        cmd_data.push(0x00);
+
+        let (cmd, _) = self.send_command(CMD_OPTIONS_WRQ, &cmd_data)?;
+        if cmd == CMD_ACK_OK { Ok(()) } else { Err(format!("Device rejected option {}: cmd={}", option, cmd)) }
+    }
+
+    pub(crate) fn refresh_options(&mut self) -> Result<(), String> {
+        let (cmd, _) = self.send_command(CMD_REFRESHOPTION, &[])?;
+        if cmd == CMD_ACK_OK { Ok(()) } else { Err(format!("Failed to apply options: cmd={}", cmd)) }
+    }
+    
     /// Get device information (name, firmware, serial, etc.)
     /// Get firmware version using direct command
     fn get_firmware_version(&mut self) -> String {
@@ -388,7 +626,7 @@ impl ZKClient {
     }
     
     /// Get serial number using direct command or options
-    fn get_serial_number(&mut self) -> String {
+    pub(crate) fn get_serial_number(&mut self) -> String {
         // Try direct serial number command (CMD_SERIALNUMBER = 1101)
         if let Ok((cmd, data)) = self.send_command(CMD_SERIALNUMBER, &[]) {
             if cmd == CMD_ACK_OK && !data.is_empty() {
@@ -431,7 +669,7 @@ impl ZKClient {
         }
     }
     
-    fn enable_device(&mut self) -> Result<(), String> {
+    pub(crate) fn enable_device(&mut self) -> Result<(), String> {
         let (cmd, _) = self.send_command(CMD_ENABLEDEVICE, &[])?;
         if cmd == CMD_ACK_OK { Ok(()) } else { Err(format!("Failed to enable device: cmd={}", cmd)) }
     }
@@ -752,7 +990,7 @@ impl ZKClient {
             .unwrap_or_else(|| Local::now())
     }
     
-    fn get_users(&mut self) -> Result<Vec<User>, String> {
+    pub(crate) fn get_users(&mut self) -> Result<Vec<User>, String> {
         let (data, _) = self.read_with_buffer_pyzk(CMD_USERTEMP_RRQ, FCT_USER)?;
         let mut users = Vec::new();
         
@@ -964,6 +1202,11 @@ impl ZKClient {
                         punch,
                         date: dt.format("%Y-%m-%d").to_string(),
                         time: dt.format("%H:%M:%S").to_string(),
+                        device_serial: None,
+                        device_ip: None,
+                        serial_port: None,
+                        work_code: None,
+                        verify_method: verify_method_name(status),
                     });
                     
                     offset += 8;
@@ -982,7 +1225,7 @@ impl ZKClient {
                     let status = record[8];
                     let punch = record[9];
                     // reserved 2 bytes
-                    // workcode 4 bytes
+                    let work_code = u32::from_le_bytes([record[12], record[13], record[14], record[15]]);
                     
                     // Log first attendance record for debugging
                     if !sample_logged {
@@ -1006,6 +1249,11 @@ impl ZKClient {
                         punch,
                         date: dt.format("%Y-%m-%d").to_string(),
                         time: dt.format("%H:%M:%S").to_string(),
+                        device_serial: None,
+                        device_ip: None,
+                        serial_port: None,
+                        work_code: Some(work_code),
+                        verify_method: verify_method_name(status),
                     });
                     
                     offset += 16;
@@ -1062,6 +1310,11 @@ impl ZKClient {
                             punch,
                             date: dt.format("%Y-%m-%d").to_string(),
                             time: dt.format("%H:%M:%S").to_string(),
+                            device_serial: None,
+                            device_ip: None,
+                            serial_port: None,
+                            work_code: None,
+                            verify_method: verify_method_name(status),
                         });
                     }
                     
@@ -1074,7 +1327,7 @@ impl ZKClient {
         Ok(records)
     }
     
-    fn disconnect(&mut self) -> Result<(), String> {
+    pub(crate) fn disconnect(&mut self) -> Result<(), String> {
         let _ = self.enable_device();
         let _ = self.send_command(CMD_EXIT, &[]);
         info!("Disconnected");
@@ -1082,6 +1335,7 @@ impl ZKClient {
     }
 }
 
+/// `ip` may also be a serial target ("serial:/dev/ttyUSB0:115200"); `port` is then ignored
 pub async fn connect_and_fetch_attendance(
     ip: &str,
     port: u16,
@@ -1089,27 +1343,72 @@ pub async fn connect_and_fetch_attendance(
     let ip = ip.to_string();
     
     tokio::task::spawn_blocking(move || {
+        let started = std::time::Instant::now();
+        let mut warnings = Vec::new();
         let mut client = ZKClient::connect(&ip, port)?;
+        let transport = client.stream.transport_name();
         
         // Get device info first
         let device_info = client.get_device_info();
         
         if let Err(e) = client.disable_device() {
             warn!("Failed to disable device: {}", e);
+            warnings.push(format!("Could not lock device during download: {}", e));
         }
         
-        let (_, _, record_count) = client.read_sizes().unwrap_or((0, 0, 0));
+        let (user_count, _, record_count) = client.read_sizes().unwrap_or((0, 0, 0));
         
-        let users = client.get_users().unwrap_or_else(|_| Vec::new());
+        let users = client.get_users().unwrap_or_else(|e| {
+            warnings.push(format!("Could not read user list, names unavailable: {}", e));
+            Vec::new()
+        });
         info!("Users: {}, Expected records: {}", users.len(), record_count);
         
-        let records = client.get_attendance(&users, record_count)?;
+        let mut records = client.get_attendance(&users, record_count)?;
         info!("Fetched {} attendance records", records.len());
+
+        // Tag every record with its source terminal for multi-device merging
+        let serial = Some(device_info.serial_number.clone()).filter(|s| !s.is_empty());
+        let serial_port = parse_serial_target(&ip).map(|(path, _)| path);
+        let device_ip = Some(ip.clone()).filter(|_| serial_port.is_none());
+        for record in &mut records {
+            record.device_serial = serial.clone();
+            record.device_ip = device_ip.clone();
+            record.serial_port = serial_port.clone();
+        }
         
         client.disconnect()?;
+
+        if record_count as usize != records.len() {
+            warnings.push(format!("Device reported {} records but {} were parsed", record_count, records.len()));
+        }
+
+        let mut unknown_users: Vec<u32> = records.iter()
+            .filter(|r| r.user_name.starts_with("ID: "))
+            .map(|r| r.user_id)
+            .collect();
+        unknown_users.sort_unstable();
+        unknown_users.dedup();
+        if !unknown_users.is_empty() {
+            let preview: Vec<String> = unknown_users.iter().take(10).map(|id| id.to_string()).collect();
+            warnings.push(format!("{} user(s) not found on device: {}{}",
+                unknown_users.len(),
+                preview.join(", "),
+                if unknown_users.len() > 10 { ", ..." } else { "" }
+            ));
+        }
         
         Ok(AttendanceResponse {
             device_info,
+            device_user_count: user_count,
+            device_record_count: record_count,
+            parsed_count: records.len(),
+            fetch_duration_ms: started.elapsed().as_millis() as u64,
+            transport: transport.to_string(),
+            warnings,
+            users: users.iter()
+                .map(|user| DeviceUser { user_id: user.user_id.parse().unwrap_or(user.uid), name: user.name.clone() })
+                .collect(),
             records,
         })
     })
@@ -1126,11 +1425,11 @@ pub async fn get_device_info_quick(ip: &str, port: u16) -> Option<DeviceInfo> {
     
     let result = tokio::task::spawn_blocking(move || {
         // Quick connect with shorter timeout
-        let addr = format!("{}:{}", ip, port_copy);
+        let addr = socket_addr(&ip, port_copy).ok()?;
         info!("🔌 Fetching device info from {}", addr);
         
         let stream = match std::net::TcpStream::connect_timeout(
-            &addr.parse().ok()?,
+            &addr,
             std::time::Duration::from_secs(5)
         ) {
             Ok(s) => s,
@@ -1144,7 +1443,7 @@ pub async fn get_device_info_quick(ip: &str, port: u16) -> Option<DeviceInfo> {
         stream.set_write_timeout(Some(std::time::Duration::from_secs(5))).ok()?;
         
         let mut client = ZKClient {
-            stream,
+            stream: ZkStream::Tcp(stream),
             session_id: 0,
             reply_id: USHRT_MAX - 1,
         };
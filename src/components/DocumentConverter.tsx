@@ -261,6 +261,7 @@ export default function DocumentConverter() {
       const convResult = await invoke<ConversionResult>("bundled_merge_pdfs", {
         inputPaths: pdfFiles,
         outputPath,
+        bookmarkPerFile: true,
       });
       setResult(convResult);
     } catch (err) {